@@ -0,0 +1,621 @@
+//! Delegate listing and bid rules to an auctioneer to run timed auctions.
+//! A listing under an auctioneer carries a start and end time, a reserve price, a minimum bid increment and an anti-sniping extension. Bids that break those rules are rejected and the highest bid is the only one that can be matched once the auction ends.
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+use anchor_spl::token::TokenAccount;
+use solana_program::program_memory::sol_memset;
+
+use crate::{constants::*, id, utils::*, AuctionHouse, ErrorCode};
+
+pub const AUCTIONEER_SIZE: usize = 8 + // key
+32 + // auction_house
+32 + // auctioneer_authority
+1; // bump
+
+/// Scope record delegating the listing and bid rules of an Auction House to an auctioneer authority.
+#[account]
+pub struct Auctioneer {
+    pub auction_house: Pubkey,
+    pub auctioneer_authority: Pubkey,
+    pub bump: u8,
+}
+
+pub const LISTING_CONFIG_SIZE: usize = 8 + // key
+32 + // auctioneer
+32 + // seller
+32 + // token_account
+8 + // token_size
+8 + // start_time
+8 + // end_time
+8 + // reserve_price
+8 + // min_bid_increment
+4 + // time_ext_period
+4 + // time_ext_delta
+1 + 32 + 32 + 8 + // highest_bid
+1; // bump
+
+/// Highest bid placed on an auctioneer listing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct HighestBid {
+    pub buyer: Pubkey,
+    pub buyer_trade_state: Pubkey,
+    pub price: u64,
+}
+
+/// Auction rules of a listing, at a PDA with the seeds:
+/// "listing_config", <AUCTION_HOUSE_PUBKEY>, <TOKEN_ACCOUNT_PUBKEY>.
+#[account]
+pub struct ListingConfig {
+    pub auctioneer: Pubkey,
+    pub seller: Pubkey,
+    pub token_account: Pubkey,
+    pub token_size: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub reserve_price: u64,
+    pub min_bid_increment: u64,
+    /// Bids placed within this many seconds of `end_time` extend the auction.
+    pub time_ext_period: u32,
+    /// Number of seconds a late bid adds to `end_time`.
+    pub time_ext_delta: u32,
+    pub highest_bid: Option<HighestBid>,
+    pub bump: u8,
+}
+
+pub const ESCROW_COMMITMENT_SIZE: usize = 8 + // key
+8 + // amount
+1; // bump
+
+/// Funds of a buyer escrow backing highest bids that were not settled yet, at a PDA with the seeds:
+/// "escrow_commitment", <AUCTION_HOUSE_PUBKEY>, <WALLET_PUBKEY>.
+#[account]
+pub struct EscrowCommitment {
+    pub amount: u64,
+    pub bump: u8,
+}
+
+/// Accounts for the [`delegate_auctioneer` handler](fn.delegate_auctioneer.html).
+#[derive(Accounts)]
+#[instruction(auctioneer_bump: u8)]
+pub struct DelegateAuctioneer<'info> {
+    #[account(mut, seeds = [PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], bump = auction_house.bump, has_one = authority)]
+    pub auction_house: Account<'info, AuctionHouse>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(init, seeds = [AUCTIONEER.as_bytes(), auction_house.key().as_ref(), auctioneer_authority.key().as_ref()], bump = auctioneer_bump, space = AUCTIONEER_SIZE, payer = authority)]
+    pub ah_auctioneer_pda: Account<'info, Auctioneer>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Hand the listing and bid rules of an Auction House to an auctioneer authority.
+///
+/// Auctions are listed at a price of zero and settled by the Auction House authority repricing the listing to the winning bid, so the Auction House must allow sale price changes.
+pub fn delegate_auctioneer<'info>(
+    ctx: Context<'_, '_, '_, 'info, DelegateAuctioneer<'info>>,
+    auctioneer_bump: u8,
+) -> ProgramResult {
+    let auction_house = &mut ctx.accounts.auction_house;
+    let auctioneer_authority = &ctx.accounts.auctioneer_authority;
+    let ah_auctioneer_pda = &mut ctx.accounts.ah_auctioneer_pda;
+
+    if auction_house.has_auctioneer {
+        return Err(ErrorCode::AuctioneerAlreadyDelegated.into());
+    }
+
+    if !auction_house.can_change_sale_price {
+        return Err(ErrorCode::AuctioneerRequiresSalePriceChanges.into());
+    }
+
+    ah_auctioneer_pda.auction_house = auction_house.key();
+    ah_auctioneer_pda.auctioneer_authority = auctioneer_authority.key();
+    ah_auctioneer_pda.bump = auctioneer_bump;
+
+    auction_house.has_auctioneer = true;
+    auction_house.auctioneer_address = ah_auctioneer_pda.key();
+
+    Ok(())
+}
+
+/// Accounts for the [`undelegate_auctioneer` handler](fn.undelegate_auctioneer.html).
+#[derive(Accounts)]
+pub struct UndelegateAuctioneer<'info> {
+    #[account(mut, seeds = [PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], bump = auction_house.bump, has_one = authority, constraint = auction_house.auctioneer_address == ah_auctioneer_pda.key())]
+    pub auction_house: Account<'info, AuctionHouse>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [AUCTIONEER.as_bytes(), auction_house.key().as_ref(), auctioneer_authority.key().as_ref()], bump = ah_auctioneer_pda.bump, close = authority)]
+    pub ah_auctioneer_pda: Account<'info, Auctioneer>,
+}
+
+/// Take the listing and bid rules of an Auction House back from its auctioneer, closing the `auctioneer` scope account.
+///
+/// Listing configs still open afterwards no longer apply, so the auctions of the Auction House should be settled first.
+pub fn undelegate_auctioneer<'info>(
+    ctx: Context<'_, '_, '_, 'info, UndelegateAuctioneer<'info>>,
+) -> ProgramResult {
+    let auction_house = &mut ctx.accounts.auction_house;
+
+    if !auction_house.has_auctioneer {
+        return Err(ErrorCode::AuctioneerNotDelegated.into());
+    }
+
+    auction_house.has_auctioneer = false;
+    auction_house.auctioneer_address = Pubkey::default();
+
+    Ok(())
+}
+
+/// Accounts for the [`create_listing_config` handler](fn.create_listing_config.html).
+#[derive(Accounts)]
+#[instruction(listing_config_bump: u8)]
+pub struct CreateListingConfig<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub token_account: Account<'info, TokenAccount>,
+    pub auctioneer_authority: Signer<'info>,
+    #[account(seeds = [PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], bump = auction_house.bump, constraint = auction_house.auctioneer_address == ah_auctioneer_pda.key())]
+    pub auction_house: Account<'info, AuctionHouse>,
+    #[account(seeds = [AUCTIONEER.as_bytes(), auction_house.key().as_ref(), auctioneer_authority.key().as_ref()], bump = ah_auctioneer_pda.bump)]
+    pub ah_auctioneer_pda: Account<'info, Auctioneer>,
+    pub free_seller_trade_state: UncheckedAccount<'info>,
+    #[account(init, seeds = [LISTING_CONFIG.as_bytes(), auction_house.key().as_ref(), token_account.key().as_ref()], bump = listing_config_bump, space = LISTING_CONFIG_SIZE, payer = wallet)]
+    pub listing_config: Account<'info, ListingConfig>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Put a free listing up for auction under the Auction House auctioneer.
+///
+/// The seller must already have listed `token_size` tokens at a price of zero; the auctioneer co-signs the rules of the auction.
+#[allow(clippy::too_many_arguments)]
+pub fn create_listing_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateListingConfig<'info>>,
+    listing_config_bump: u8,
+    token_size: u64,
+    start_time: i64,
+    end_time: i64,
+    reserve_price: u64,
+    min_bid_increment: u64,
+    time_ext_period: u32,
+    time_ext_delta: u32,
+) -> ProgramResult {
+    let wallet = &ctx.accounts.wallet;
+    let token_account = &ctx.accounts.token_account;
+    let auction_house = &ctx.accounts.auction_house;
+    let ah_auctioneer_pda = &ctx.accounts.ah_auctioneer_pda;
+    let free_seller_trade_state = &ctx.accounts.free_seller_trade_state;
+    let listing_config = &mut ctx.accounts.listing_config;
+
+    assert_is_ata(
+        &token_account.to_account_info(),
+        &wallet.key(),
+        &token_account.mint,
+    )?;
+
+    if free_seller_trade_state.data_is_empty() {
+        return Err(ErrorCode::TradeStateDoesntExist.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let wallet_key = wallet.key();
+    let token_account_key = token_account.key();
+    assert_derivation(
+        &id(),
+        &free_seller_trade_state.to_account_info(),
+        &[
+            PREFIX.as_bytes(),
+            wallet_key.as_ref(),
+            auction_house_key.as_ref(),
+            token_account_key.as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes(),
+        ],
+    )?;
+
+    if end_time <= start_time {
+        return Err(ErrorCode::InvalidAuctionWindow.into());
+    }
+
+    listing_config.auctioneer = ah_auctioneer_pda.key();
+    listing_config.seller = wallet_key;
+    listing_config.token_account = token_account_key;
+    listing_config.token_size = token_size;
+    listing_config.start_time = start_time;
+    listing_config.end_time = end_time;
+    listing_config.reserve_price = reserve_price;
+    listing_config.min_bid_increment = min_bid_increment;
+    listing_config.time_ext_period = time_ext_period;
+    listing_config.time_ext_delta = time_ext_delta;
+    listing_config.highest_bid = None;
+    listing_config.bump = listing_config_bump;
+
+    Ok(())
+}
+
+/// Accounts for the [`close_listing_config` handler](fn.close_listing_config.html).
+#[derive(Accounts)]
+pub struct CloseListingConfig<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(mut, has_one = seller, close = seller)]
+    pub listing_config: Account<'info, ListingConfig>,
+}
+
+/// Close the auction rules of a listing, returning the rent to the seller.
+///
+/// A listing config with a bid can only be closed by settling its auction.
+pub fn close_listing_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseListingConfig<'info>>,
+) -> ProgramResult {
+    if ctx.accounts.listing_config.highest_bid.is_some() {
+        return Err(ErrorCode::ListingConfigHasBids.into());
+    }
+
+    Ok(())
+}
+
+fn load_listing_config(
+    listing_config_info: &AccountInfo,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+) -> Result<ListingConfig, ProgramError> {
+    assert_owned_by(listing_config_info, &id())?;
+    assert_derivation(
+        &id(),
+        listing_config_info,
+        &[
+            LISTING_CONFIG.as_bytes(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+        ],
+    )?;
+
+    let listing_config_data = listing_config_info.try_borrow_data()?;
+    let mut listing_config_slice: &[u8] = &listing_config_data;
+    ListingConfig::try_deserialize(&mut listing_config_slice)
+}
+
+fn escrow_commitment_bump(
+    escrow_commitment_info: &AccountInfo,
+    auction_house: &Pubkey,
+    wallet: &Pubkey,
+) -> Result<u8, ProgramError> {
+    assert_derivation(
+        &id(),
+        escrow_commitment_info,
+        &[
+            ESCROW_COMMITMENT.as_bytes(),
+            auction_house.as_ref(),
+            wallet.as_ref(),
+        ],
+    )
+}
+
+fn load_escrow_commitment(
+    escrow_commitment_info: &AccountInfo,
+) -> Result<EscrowCommitment, ProgramError> {
+    assert_owned_by(escrow_commitment_info, &id())?;
+    let escrow_commitment_data = escrow_commitment_info.try_borrow_data()?;
+    let mut escrow_commitment_slice: &[u8] = &escrow_commitment_data;
+    EscrowCommitment::try_deserialize(&mut escrow_commitment_slice)
+}
+
+/// Amount of the escrow of `wallet` backing highest bids, zero before its first highest bid.
+pub fn get_escrow_commitment(
+    escrow_commitment_info: &AccountInfo,
+    auction_house: &Pubkey,
+    wallet: &Pubkey,
+) -> Result<u64, ProgramError> {
+    escrow_commitment_bump(escrow_commitment_info, auction_house, wallet)?;
+    if escrow_commitment_info.data_is_empty() {
+        return Ok(0);
+    }
+
+    Ok(load_escrow_commitment(escrow_commitment_info)?.amount)
+}
+
+/// Commit `amount` of the escrow of `wallet` to a highest bid, creating its escrow commitment on the first one.
+#[allow(clippy::too_many_arguments)]
+fn commit_escrow<'info>(
+    escrow_commitment_info: &AccountInfo<'info>,
+    auction_house: &Pubkey,
+    wallet: &Pubkey,
+    amount: u64,
+    fee_payer: &AccountInfo<'info>,
+    fee_seeds: &[&[u8]],
+    rent: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> ProgramResult {
+    let bump = escrow_commitment_bump(escrow_commitment_info, auction_house, wallet)?;
+    let mut escrow_commitment = if escrow_commitment_info.data_is_empty() {
+        create_or_allocate_account_raw(
+            id(),
+            escrow_commitment_info,
+            rent,
+            system_program,
+            fee_payer,
+            ESCROW_COMMITMENT_SIZE,
+            fee_seeds,
+            &[
+                ESCROW_COMMITMENT.as_bytes(),
+                auction_house.as_ref(),
+                wallet.as_ref(),
+                &[bump],
+            ],
+        )?;
+        EscrowCommitment { amount: 0, bump }
+    } else {
+        load_escrow_commitment(escrow_commitment_info)?
+    };
+
+    escrow_commitment.amount = escrow_commitment
+        .amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    escrow_commitment.try_serialize(&mut *escrow_commitment_info.try_borrow_mut_data()?)?;
+
+    Ok(())
+}
+
+/// Release `amount` of the escrow of `wallet` once the highest bid it backed is outbid, settled or canceled.
+pub fn release_escrow(
+    escrow_commitment_info: &AccountInfo,
+    auction_house: &Pubkey,
+    wallet: &Pubkey,
+    amount: u64,
+) -> ProgramResult {
+    escrow_commitment_bump(escrow_commitment_info, auction_house, wallet)?;
+    if escrow_commitment_info.data_is_empty() {
+        return Err(ErrorCode::MissingEscrowCommitment.into());
+    }
+
+    let mut escrow_commitment = load_escrow_commitment(escrow_commitment_info)?;
+    escrow_commitment.amount = escrow_commitment
+        .amount
+        .checked_sub(amount)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    escrow_commitment.try_serialize(&mut *escrow_commitment_info.try_borrow_mut_data()?)?;
+
+    Ok(())
+}
+
+/// Whether the token account is up for auction, that is whether `listing_config_info` is its listing config and was created.
+pub fn is_auction_listing(
+    listing_config_info: &AccountInfo,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+) -> Result<bool, ProgramError> {
+    assert_derivation(
+        &id(),
+        listing_config_info,
+        &[
+            LISTING_CONFIG.as_bytes(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+        ],
+    )?;
+
+    Ok(!listing_config_info.data_is_empty())
+}
+
+/// Check a bid against the rules of an auctioneer listing and record it as the highest bid, extending the auction if it lands in the anti-sniping period.
+///
+/// `auction_accounts` holds the listing config, the escrow commitment of `wallet` and, when there is a highest bid to beat, the escrow commitment of its buyer. The bid commits its price of the escrow and releases the one it outbids. Bids on a token account without a listing config follow the usual rules.
+///
+/// Returns the amount the escrow of `wallet` has to hold for the bid on top of the highest bids it already backs.
+#[allow(clippy::too_many_arguments)]
+pub fn assert_valid_auction_bid<'info>(
+    auction_accounts: &[AccountInfo<'info>],
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    wallet: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    buyer_price: u64,
+    token_size: u64,
+    fee_payer: &AccountInfo<'info>,
+    fee_seeds: &[&[u8]],
+    rent: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<u64, ProgramError> {
+    let auction_accounts = &mut auction_accounts.iter();
+    let listing_config_info =
+        next_account_info(auction_accounts).map_err(|_| ErrorCode::MissingListingConfig)?;
+    let escrow_commitment_info =
+        next_account_info(auction_accounts).map_err(|_| ErrorCode::MissingEscrowCommitment)?;
+    if !is_auction_listing(listing_config_info, auction_house, token_account)? {
+        return get_escrow_commitment(escrow_commitment_info, auction_house, wallet)?
+            .checked_add(buyer_price)
+            .ok_or_else(|| ErrorCode::NumericalOverflow.into());
+    }
+    let mut listing_config =
+        load_listing_config(listing_config_info, auction_house, token_account)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    if now < listing_config.start_time {
+        return Err(ErrorCode::AuctionNotStarted.into());
+    }
+    if now > listing_config.end_time {
+        return Err(ErrorCode::AuctionEnded.into());
+    }
+    if token_size != listing_config.token_size {
+        return Err(ErrorCode::InvalidTokenAmount.into());
+    }
+    if buyer_price < listing_config.reserve_price {
+        return Err(ErrorCode::BidBelowReservePrice.into());
+    }
+
+    if let Some(highest_bid) = &listing_config.highest_bid {
+        // Allow the current highest bid to be sent again with no issues
+        if highest_bid.buyer_trade_state == *buyer_trade_state {
+            return get_escrow_commitment(escrow_commitment_info, auction_house, wallet);
+        }
+        if buyer_price <= highest_bid.price
+            || buyer_price - highest_bid.price < listing_config.min_bid_increment
+        {
+            return Err(ErrorCode::BidBelowMinimumIncrement.into());
+        }
+
+        let outbid_escrow_commitment_info =
+            next_account_info(auction_accounts).map_err(|_| ErrorCode::MissingEscrowCommitment)?;
+        release_escrow(
+            outbid_escrow_commitment_info,
+            auction_house,
+            &highest_bid.buyer,
+            highest_bid.price,
+        )?;
+    }
+
+    if listing_config.end_time - now <= listing_config.time_ext_period as i64 {
+        listing_config.end_time = listing_config
+            .end_time
+            .checked_add(listing_config.time_ext_delta as i64)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+    }
+
+    commit_escrow(
+        escrow_commitment_info,
+        auction_house,
+        wallet,
+        buyer_price,
+        fee_payer,
+        fee_seeds,
+        rent,
+        system_program,
+    )?;
+
+    listing_config.highest_bid = Some(HighestBid {
+        buyer: *wallet,
+        buyer_trade_state: *buyer_trade_state,
+        price: buyer_price,
+    });
+
+    listing_config.try_serialize(&mut *listing_config_info.try_borrow_mut_data()?)?;
+
+    get_escrow_commitment(escrow_commitment_info, auction_house, wallet)
+}
+
+/// Check that canceling a trade state leaves the auction of its token account able to settle.
+///
+/// `auction_accounts` holds the listing config and, to cancel the highest bid, the seller trade state of the listing and the escrow commitment of the bidder. The seller cannot cancel a listing with a bid before its auction ends, and the highest bid can only be canceled once that listing is gone. Canceling it releases its escrow and lets the seller close the listing config.
+#[allow(clippy::too_many_arguments)]
+pub fn assert_auction_cancel_allowed(
+    auction_accounts: &[AccountInfo],
+    auction_house: &Pubkey,
+    treasury_mint: &Pubkey,
+    token_account: &Pubkey,
+    token_mint: &Pubkey,
+    wallet: &Pubkey,
+    trade_state: &Pubkey,
+    buyer_price: u64,
+    token_size: u64,
+) -> ProgramResult {
+    let auction_accounts = &mut auction_accounts.iter();
+    let listing_config_info =
+        next_account_info(auction_accounts).map_err(|_| ErrorCode::MissingListingConfig)?;
+    // the token account is not up for auction, or its auction was settled
+    if !is_auction_listing(listing_config_info, auction_house, token_account)? {
+        return Ok(());
+    }
+
+    let mut listing_config =
+        load_listing_config(listing_config_info, auction_house, token_account)?;
+    let highest_bid = match &listing_config.highest_bid {
+        Some(highest_bid) => highest_bid.clone(),
+        None => return Ok(()),
+    };
+
+    if highest_bid.buyer_trade_state == *trade_state {
+        let seller_trade_state = next_account_info(auction_accounts)
+            .map_err(|_| ErrorCode::HighestBidCannotBeCanceled)?;
+        assert_derivation(
+            &id(),
+            seller_trade_state,
+            &[
+                PREFIX.as_bytes(),
+                listing_config.seller.as_ref(),
+                auction_house.as_ref(),
+                token_account.as_ref(),
+                treasury_mint.as_ref(),
+                token_mint.as_ref(),
+                &0u64.to_le_bytes(),
+                &listing_config.token_size.to_le_bytes(),
+            ],
+        )?;
+        if !seller_trade_state.data_is_empty() {
+            return Err(ErrorCode::HighestBidCannotBeCanceled.into());
+        }
+
+        let escrow_commitment_info =
+            next_account_info(auction_accounts).map_err(|_| ErrorCode::MissingEscrowCommitment)?;
+        release_escrow(
+            escrow_commitment_info,
+            auction_house,
+            &highest_bid.buyer,
+            highest_bid.price,
+        )?;
+        listing_config.highest_bid = None;
+        listing_config.try_serialize(&mut *listing_config_info.try_borrow_mut_data()?)?;
+    } else if *wallet == listing_config.seller
+        && buyer_price == 0
+        && token_size == listing_config.token_size
+        && Clock::get()?.unix_timestamp <= listing_config.end_time
+    {
+        // the listing trade state delegates the token the auction is settled with
+        return Err(ErrorCode::AuctionActive.into());
+    }
+
+    Ok(())
+}
+
+/// Check that an auctioneer listing has ended and is being settled with its highest bid.
+#[inline(never)]
+pub fn assert_auction_settled(
+    listing_config_info: &AccountInfo,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    buyer_price: u64,
+    token_size: u64,
+) -> ProgramResult {
+    let listing_config = load_listing_config(listing_config_info, auction_house, token_account)?;
+
+    if Clock::get()?.unix_timestamp <= listing_config.end_time {
+        return Err(ErrorCode::AuctionActive.into());
+    }
+    if token_size != listing_config.token_size {
+        return Err(ErrorCode::InvalidTokenAmount.into());
+    }
+
+    match listing_config.highest_bid {
+        Some(highest_bid)
+            if highest_bid.buyer_trade_state == *buyer_trade_state
+                && highest_bid.price == buyer_price =>
+        {
+            Ok(())
+        }
+        _ => Err(ErrorCode::NotHighestBid.into()),
+    }
+}
+
+/// Close a settled listing config, returning the rent to the seller.
+pub fn close_settled_listing_config(
+    listing_config_info: &AccountInfo,
+    seller: &AccountInfo,
+) -> ProgramResult {
+    let curr_lamp = listing_config_info.lamports();
+    **listing_config_info.lamports.borrow_mut() = 0;
+
+    **seller.lamports.borrow_mut() = seller
+        .lamports()
+        .checked_add(curr_lamp)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    sol_memset(
+        *listing_config_info.try_borrow_mut_data()?,
+        0,
+        LISTING_CONFIG_SIZE,
+    );
+    Ok(())
+}
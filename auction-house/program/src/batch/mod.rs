@@ -0,0 +1,189 @@
+//! List and cancel many tokens in a single instruction.
+//! Batch instructions take parallel arrays of per-item accounts in `remaining_accounts`. The whole batch fails if any item is invalid, with `BatchItemFailed` and the index and error of the failing item in the logs.
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{cancel_logic, constants::*, sell_logic, utils::*, AuctionHouse, ErrorCode};
+
+/// Number of accounts of [`BatchSell`] before its remaining accounts.
+pub const BATCH_SELL_ACCOUNTS: usize = 8;
+
+/// Accounts for the [`batch_sell` handler](fn.batch_sell.html).
+///
+/// The remaining accounts are four parallel arrays of one account per listing, in this order: token accounts, metadata accounts, seller trade states and free seller trade states.
+#[derive(Accounts)]
+#[instruction(program_as_signer_bump: u8)]
+pub struct BatchSell<'info> {
+    /// User wallet account.
+    pub wallet: UncheckedAccount<'info>,
+    /// Auction House authority account.
+    pub authority: UncheckedAccount<'info>,
+    /// Auction House instance PDA account.
+    #[account(seeds = [PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], bump = auction_house.bump, has_one = authority, has_one = auction_house_fee_account)]
+    pub auction_house: Account<'info, AuctionHouse>,
+    /// Auction House instance fee account.
+    #[account(mut, seeds = [PREFIX.as_bytes(), auction_house.key().as_ref(), FEE_PAYER.as_bytes()], bump = auction_house.fee_payer_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [PREFIX.as_bytes(), SIGNER.as_bytes()], bump = program_as_signer_bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Create a sell bid for each listing of the batch, as the `sell` instruction does for one.
+pub fn batch_sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, BatchSell<'info>>,
+    _program_as_signer_bump: u8,
+    trade_state_bumps: Vec<u8>,
+    buyer_prices: Vec<u64>,
+    token_sizes: Vec<u64>,
+) -> ProgramResult {
+    let size = trade_state_bumps.len();
+    if buyer_prices.len() != size || token_sizes.len() != size {
+        return Err(ErrorCode::BatchLengthMismatch.into());
+    }
+    let arrays = split_batch_accounts(ctx.remaining_accounts, size, 4)?;
+
+    for index in 0..size {
+        batch_sell_item(
+            ctx.program_id,
+            ctx.accounts,
+            &arrays[0][index],
+            &arrays[1][index],
+            &arrays[2][index],
+            &arrays[3][index],
+            trade_state_bumps[index],
+            buyer_prices[index],
+            token_sizes[index],
+        )
+        .map_err(|error| batch_item_error(index, error))?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn batch_sell_item<'info>(
+    program_id: &Pubkey,
+    accounts: &BatchSell<'info>,
+    token_account: &AccountInfo<'info>,
+    metadata: &AccountInfo<'info>,
+    seller_trade_state: &AccountInfo<'info>,
+    free_seller_trade_state: &AccountInfo<'info>,
+    trade_state_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> ProgramResult {
+    let token_account = Account::<TokenAccount>::try_from(token_account)?;
+    let wallet_key = accounts.wallet.key();
+    let auction_house_key = accounts.auction_house.key();
+    let token_account_key = token_account.key();
+    let token_size_bytes = token_size.to_le_bytes();
+    let trade_state_seeds = |price_bytes| {
+        [
+            PREFIX.as_bytes(),
+            wallet_key.as_ref(),
+            auction_house_key.as_ref(),
+            token_account_key.as_ref(),
+            accounts.auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            price_bytes,
+            &token_size_bytes,
+        ]
+    };
+    let buyer_price_bytes = buyer_price.to_le_bytes();
+    let bump = assert_derivation(
+        program_id,
+        seller_trade_state,
+        &trade_state_seeds(&buyer_price_bytes),
+    )?;
+    if bump != trade_state_bump {
+        return Err(ErrorCode::DerivedKeyInvalid.into());
+    }
+    assert_derivation(
+        program_id,
+        free_seller_trade_state,
+        &trade_state_seeds(&0u64.to_le_bytes()),
+    )?;
+
+    sell_logic(
+        program_id,
+        &accounts.wallet,
+        &token_account,
+        &UncheckedAccount::try_from(metadata.clone()),
+        &accounts.authority,
+        &accounts.auction_house,
+        &accounts.auction_house_fee_account,
+        &UncheckedAccount::try_from(seller_trade_state.clone()),
+        &UncheckedAccount::try_from(free_seller_trade_state.clone()),
+        &accounts.token_program,
+        &accounts.system_program,
+        &accounts.program_as_signer,
+        &accounts.rent,
+        trade_state_bump,
+        buyer_price,
+        token_size,
+    )
+}
+
+/// Accounts for the [`batch_cancel` handler](fn.batch_cancel.html).
+///
+/// The remaining accounts are three parallel arrays of one account per bid or ask, in this order: token accounts, token mints and trade states. When the Auction House has an auctioneer, a fourth array holds the listing configs of the token accounts. The highest bid of an abandoned auction needs more accounts and has to be canceled on its own.
+#[derive(Accounts)]
+pub struct BatchCancel<'info> {
+    /// User wallet account.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+    /// Auction House instance PDA account.
+    #[account(seeds = [PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], bump = auction_house.bump, has_one = authority, has_one = auction_house_fee_account)]
+    pub auction_house: Account<'info, AuctionHouse>,
+    /// Auction House instance fee account.
+    #[account(mut, seeds = [PREFIX.as_bytes(), auction_house.key().as_ref(), FEE_PAYER.as_bytes()], bump = auction_house.fee_payer_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Cancel each bid or ask of the batch, as the `cancel` instruction does for one.
+pub fn batch_cancel<'info>(
+    ctx: Context<'_, '_, '_, 'info, BatchCancel<'info>>,
+    buyer_prices: Vec<u64>,
+    token_sizes: Vec<u64>,
+) -> ProgramResult {
+    let size = buyer_prices.len();
+    if token_sizes.len() != size {
+        return Err(ErrorCode::BatchLengthMismatch.into());
+    }
+    let has_auctioneer = ctx.accounts.auction_house.has_auctioneer;
+    let arrays = split_batch_accounts(
+        ctx.remaining_accounts,
+        size,
+        if has_auctioneer { 4 } else { 3 },
+    )?;
+
+    for index in 0..size {
+        Account::<TokenAccount>::try_from(&arrays[0][index])
+            .and_then(|token_account| {
+                cancel_logic(
+                    &ctx.accounts.wallet,
+                    &token_account,
+                    arrays[1][index].key,
+                    &ctx.accounts.authority,
+                    &ctx.accounts.auction_house,
+                    &ctx.accounts.auction_house_fee_account,
+                    &UncheckedAccount::try_from(arrays[2][index].clone()),
+                    &ctx.accounts.token_program,
+                    arrays.get(3).map_or(&[], |listing_configs| {
+                        std::slice::from_ref(&listing_configs[index])
+                    }),
+                    buyer_prices[index],
+                    token_sizes[index],
+                )
+            })
+            .map_err(|error| batch_item_error(index, error))?;
+    }
+
+    Ok(())
+}
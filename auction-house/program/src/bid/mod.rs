@@ -8,7 +8,10 @@ use anchor_lang::{
 use anchor_spl::token::{Mint, Token, TokenAccount};
 use solana_program::program_memory::sol_memset;
 
-use crate::{constants::*, utils::*, AuctionHouse, ErrorCode, TRADE_STATE_SIZE};
+use crate::{
+    auctioneer::assert_valid_auction_bid, constants::*, utils::*, AuctionHouse, ErrorCode,
+    TRADE_STATE_SIZE,
+};
 
 /// Accounts for the [`public_bid` handler](fn.public_bid.html).
 #[derive(Accounts)]
@@ -37,8 +40,8 @@ pub struct PublicBuy<'info> {
 
 /// Create a bid on a specific SPL token.
 /// Public bids are specific to the token itself, rather than the auction, and remain open indefinitely until either the user closes it or the requirements for the bid are met and it is matched with a counter bid and closed as a transaction.
-pub fn public_bid(
-    ctx: Context<PublicBuy>,
+pub fn public_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, PublicBuy<'info>>,
     trade_state_bump: u8,
     escrow_payment_bump: u8,
     buyer_price: u64,
@@ -64,6 +67,7 @@ pub fn public_bid(
         buyer_price,
         token_size,
         true,
+        ctx.remaining_accounts,
     )
 }
 
@@ -120,10 +124,13 @@ pub fn private_bid<'info>(
         buyer_price,
         token_size,
         false,
+        ctx.remaining_accounts,
     )
 }
 
 /// Handles the bid logic for both private and public bids.
+///
+/// When the Auction House has an auctioneer, the listing config of the token account and the escrow commitment of the wallet are expected as the first remaining accounts. When the listing config exists, the bid has to follow its rules and, when outbidding, the escrow commitment of the highest bidder is expected next. Either way the escrow is funded for the bid on top of the highest bids it already backs.
 pub fn bid_logic<'info>(
     wallet: Signer<'info>,
     payment_account: UncheckedAccount<'info>,
@@ -144,6 +151,7 @@ pub fn bid_logic<'info>(
     buyer_price: u64,
     token_size: u64,
     public: bool,
+    auction_accounts: &[AccountInfo<'info>],
 ) -> ProgramResult {
     assert_valid_trade_state(
         &wallet.key(),
@@ -170,74 +178,39 @@ pub fn bid_logic<'info>(
         &seeds,
     )?;
 
-    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    let escrow_amount = if auction_house.has_auctioneer {
+        assert_valid_auction_bid(
+            auction_accounts,
+            &auction_house_key,
+            &token_account.key(),
+            &wallet.key(),
+            &buyer_trade_state.key(),
+            buyer_price,
+            token_size,
+            &fee_payer,
+            fee_seeds,
+            &rent.to_account_info(),
+            &system_program.to_account_info(),
+        )?
+    } else {
+        buyer_price
+    };
 
-    let auction_house_key = auction_house.key();
-    let wallet_key = wallet.key();
-    let escrow_signer_seeds = [
-        PREFIX.as_bytes(),
-        auction_house_key.as_ref(),
-        wallet_key.as_ref(),
-        &[escrow_payment_bump],
-    ];
-    create_program_token_account_if_not_present(
+    fund_escrow_payment_account(
+        &wallet,
+        &payment_account,
+        &transfer_authority,
+        &treasury_mint,
         &escrow_payment_account,
-        &system_program,
+        &auction_house,
         &fee_payer,
+        fee_seeds,
         &token_program,
-        &treasury_mint,
-        &auction_house.to_account_info(),
+        &system_program,
         &rent,
-        &escrow_signer_seeds,
-        fee_seeds,
-        is_native,
+        escrow_payment_bump,
+        escrow_amount,
     )?;
-    if is_native {
-        assert_keys_equal(wallet.key(), payment_account.key())?;
-
-        if escrow_payment_account.lamports() < buyer_price {
-            let diff = buyer_price
-                .checked_sub(escrow_payment_account.lamports())
-                .ok_or(ErrorCode::NumericalOverflow)?;
-            invoke(
-                &system_instruction::transfer(
-                    &payment_account.key(),
-                    &escrow_payment_account.key(),
-                    diff,
-                ),
-                &[
-                    payment_account.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    system_program.to_account_info(),
-                ],
-            )?;
-        }
-    } else {
-        let escrow_payment_loaded: spl_token::state::Account =
-            assert_initialized(&escrow_payment_account)?;
-
-        if escrow_payment_loaded.amount < buyer_price {
-            let diff = buyer_price
-                .checked_sub(escrow_payment_loaded.amount)
-                .ok_or(ErrorCode::NumericalOverflow)?;
-            invoke(
-                &spl_token::instruction::transfer(
-                    &token_program.key(),
-                    &payment_account.key(),
-                    &escrow_payment_account.key(),
-                    &transfer_authority.key(),
-                    &[],
-                    diff,
-                )?,
-                &[
-                    transfer_authority.to_account_info(),
-                    payment_account.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    token_program.to_account_info(),
-                ],
-            )?;
-        }
-    }
     assert_metadata_valid(&metadata, &token_account)?;
 
     let ts_info = buyer_trade_state.to_account_info();
@@ -295,3 +268,91 @@ pub fn bid_logic<'info>(
     // Allow The same bid to be sent with no issues
     Ok(())
 }
+
+/// Creates the buyer escrow payment account if needed and tops it up to `amount` from the payment account.
+#[allow(clippy::too_many_arguments)]
+pub fn fund_escrow_payment_account<'info>(
+    wallet: &Signer<'info>,
+    payment_account: &UncheckedAccount<'info>,
+    transfer_authority: &UncheckedAccount<'info>,
+    treasury_mint: &Account<'info, Mint>,
+    escrow_payment_account: &UncheckedAccount<'info>,
+    auction_house: &Account<'info, AuctionHouse>,
+    fee_payer: &AccountInfo<'info>,
+    fee_seeds: &[&[u8]],
+    token_program: &Program<'info, Token>,
+    system_program: &Program<'info, System>,
+    rent: &Sysvar<'info, Rent>,
+    escrow_payment_bump: u8,
+    amount: u64,
+) -> ProgramResult {
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    let auction_house_key = auction_house.key();
+    let wallet_key = wallet.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        wallet_key.as_ref(),
+        &[escrow_payment_bump],
+    ];
+    create_program_token_account_if_not_present(
+        escrow_payment_account,
+        system_program,
+        fee_payer,
+        token_program,
+        treasury_mint,
+        &auction_house.to_account_info(),
+        rent,
+        &escrow_signer_seeds,
+        fee_seeds,
+        is_native,
+    )?;
+    if is_native {
+        assert_keys_equal(wallet.key(), payment_account.key())?;
+
+        if escrow_payment_account.lamports() < amount {
+            let diff = amount
+                .checked_sub(escrow_payment_account.lamports())
+                .ok_or(ErrorCode::NumericalOverflow)?;
+            invoke(
+                &system_instruction::transfer(
+                    &payment_account.key(),
+                    &escrow_payment_account.key(),
+                    diff,
+                ),
+                &[
+                    payment_account.to_account_info(),
+                    escrow_payment_account.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+            )?;
+        }
+    } else {
+        let escrow_payment_loaded: spl_token::state::Account =
+            assert_initialized(&escrow_payment_account)?;
+
+        if escrow_payment_loaded.amount < amount {
+            let diff = amount
+                .checked_sub(escrow_payment_loaded.amount)
+                .ok_or(ErrorCode::NumericalOverflow)?;
+            invoke(
+                &spl_token::instruction::transfer(
+                    &token_program.key(),
+                    &payment_account.key(),
+                    &escrow_payment_account.key(),
+                    &transfer_authority.key(),
+                    &[],
+                    diff,
+                )?,
+                &[
+                    transfer_authority.to_account_info(),
+                    payment_account.to_account_info(),
+                    escrow_payment_account.to_account_info(),
+                    token_program.to_account_info(),
+                ],
+            )?;
+        }
+    }
+    Ok(())
+}
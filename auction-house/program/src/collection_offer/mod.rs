@@ -0,0 +1,271 @@
+//! Create and cancel collection offers.
+//! A collection offer is a bid on *any* NFT of a collection, matched with any listing of an NFT whose metadata holds a verified `collection` equal to the offered collection mint.
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token::{Mint, Token};
+use mpl_token_metadata::state::Metadata;
+use solana_program::program_memory::sol_memset;
+
+use crate::{
+    auctioneer::get_escrow_commitment, bid::fund_escrow_payment_account, constants::*, utils::*,
+    AuctionHouse, ErrorCode, TRADE_STATE_SIZE,
+};
+
+/// Accounts for the [`collection_bid` handler](fn.collection_bid.html).
+#[derive(Accounts)]
+#[instruction(trade_state_bump: u8, escrow_payment_bump: u8, buyer_price: u64, token_size: u64)]
+pub struct CollectionBuy<'info> {
+    /// User wallet account.
+    pub wallet: Signer<'info>,
+    /// User SOL or SPL account to transfer funds from.
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+    /// SPL token account transfer authority.
+    pub transfer_authority: UncheckedAccount<'info>,
+    /// Auction House instance treasury mint account.
+    pub treasury_mint: Account<'info, Mint>,
+    /// Mint of the verified collection the offer is made on.
+    pub collection_mint: Account<'info, Mint>,
+    /// Buyer escrow payment account PDA.
+    #[account(mut, seeds = [PREFIX.as_bytes(), auction_house.key().as_ref(), wallet.key().as_ref()], bump = escrow_payment_bump)]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+    /// Auction House instance PDA account.
+    #[account(seeds = [PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], bump = auction_house.bump, has_one = authority, has_one = treasury_mint, has_one = auction_house_fee_account)]
+    pub auction_house: Account<'info, AuctionHouse>,
+    /// Auction House instance fee account.
+    #[account(mut, seeds = [PREFIX.as_bytes(), auction_house.key().as_ref(), FEE_PAYER.as_bytes()], bump = auction_house.fee_payer_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    /// Buyer trade state PDA account representing the collection offer.
+    #[account(mut, seeds = [PREFIX.as_bytes(), wallet.key().as_ref(), auction_house.key().as_ref(), COLLECTION_OFFER.as_bytes(), treasury_mint.key().as_ref(), collection_mint.key().as_ref(), buyer_price.to_le_bytes().as_ref(), token_size.to_le_bytes().as_ref()], bump = trade_state_bump)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Create a bid on any SPL token belonging to the verified collection of `collection_mint`.
+/// Collection offers remain open until either the user cancels it or it is matched with a listing of a collection member through `execute_collection_sale`.
+///
+/// The escrow is topped up to `buyer_price`, the price offered for `token_size` tokens of any member, and the buyer trade state is created if it does not exist yet, so the same offer can be sent again with no issues.
+/// When the Auction House has an auctioneer, the escrow commitment of the wallet is expected as the first remaining account and the escrow is funded for the offer on top of the highest bids it backs.
+pub fn collection_bid(
+    ctx: Context<CollectionBuy>,
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> ProgramResult {
+    let wallet = &ctx.accounts.wallet;
+    let collection_mint = &ctx.accounts.collection_mint;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    if token_size == 0 {
+        return Err(ErrorCode::InvalidTokenAmount.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+    let (fee_payer, fee_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    // the escrow has to keep paying for the highest bids it backs
+    let escrow_amount = if auction_house.has_auctioneer {
+        let escrow_commitment = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(ErrorCode::MissingEscrowCommitment)?;
+        get_escrow_commitment(escrow_commitment, &auction_house_key, &wallet.key())?
+            .checked_add(buyer_price)
+            .ok_or(ErrorCode::NumericalOverflow)?
+    } else {
+        buyer_price
+    };
+
+    fund_escrow_payment_account(
+        wallet,
+        &ctx.accounts.payment_account,
+        &ctx.accounts.transfer_authority,
+        &ctx.accounts.treasury_mint,
+        &ctx.accounts.escrow_payment_account,
+        auction_house,
+        &fee_payer,
+        fee_seeds,
+        &ctx.accounts.token_program,
+        system_program,
+        rent,
+        escrow_payment_bump,
+        escrow_amount,
+    )?;
+
+    let ts_info = buyer_trade_state.to_account_info();
+    if ts_info.data_is_empty() {
+        let wallet_key = wallet.key();
+        let collection_mint_key = collection_mint.key();
+        create_or_allocate_account_raw(
+            crate::id(),
+            &ts_info,
+            &rent.to_account_info(),
+            system_program,
+            &fee_payer,
+            TRADE_STATE_SIZE,
+            fee_seeds,
+            &[
+                PREFIX.as_bytes(),
+                wallet_key.as_ref(),
+                auction_house_key.as_ref(),
+                COLLECTION_OFFER.as_bytes(),
+                auction_house.treasury_mint.as_ref(),
+                collection_mint_key.as_ref(),
+                &buyer_price.to_le_bytes(),
+                &token_size.to_le_bytes(),
+                &[trade_state_bump],
+            ],
+        )?;
+        sol_memset(
+            *ts_info.try_borrow_mut_data()?,
+            trade_state_bump,
+            TRADE_STATE_SIZE,
+        );
+    }
+    // Allow The same bid to be sent with no issues
+    Ok(())
+}
+
+/// Accounts for the [`cancel_collection_bid` handler](fn.cancel_collection_bid.html).
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct CancelCollectionBuy<'info> {
+    /// User wallet account.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+    /// Collection mint account the offer was made on.
+    pub collection_mint: UncheckedAccount<'info>,
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+    /// Auction House instance PDA account.
+    #[account(seeds = [PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], bump = auction_house.bump, has_one = authority, has_one = auction_house_fee_account)]
+    pub auction_house: Account<'info, AuctionHouse>,
+    /// Auction House instance fee account.
+    #[account(mut, seeds = [PREFIX.as_bytes(), auction_house.key().as_ref(), FEE_PAYER.as_bytes()], bump = auction_house.fee_payer_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    /// Trade state PDA account representing the collection offer to be canceled.
+    #[account(mut)]
+    pub trade_state: UncheckedAccount<'info>,
+}
+
+/// Cancel a collection offer by transferring all lamports from the trade state account to the fee payer and setting the trade state account data to zero so it can be garbage collected.
+///
+/// `buyer_price` and `token_size` are the ones the offer was made with. Either the wallet or the Auction House authority has to sign. The escrow is left funded, its balance can be withdrawn separately.
+pub fn cancel_collection_bid(
+    ctx: Context<CancelCollectionBuy>,
+    buyer_price: u64,
+    token_size: u64,
+) -> ProgramResult {
+    let wallet = &ctx.accounts.wallet;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let trade_state = &ctx.accounts.trade_state;
+
+    let ts_bump = trade_state.try_borrow_data()?[0];
+    assert_valid_collection_trade_state(
+        &wallet.key(),
+        auction_house,
+        buyer_price,
+        token_size,
+        &trade_state.to_account_info(),
+        &ctx.accounts.collection_mint.key(),
+        ts_bump,
+    )?;
+    if !wallet.to_account_info().is_signer && !authority.to_account_info().is_signer {
+        return Err(ErrorCode::NoValidSignerPresent.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+    let (fee_payer, _) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let curr_lamp = trade_state.lamports();
+    **trade_state.lamports.borrow_mut() = 0;
+
+    **fee_payer.lamports.borrow_mut() = fee_payer
+        .lamports()
+        .checked_add(curr_lamp)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    sol_memset(*trade_state.try_borrow_mut_data()?, 0, TRADE_STATE_SIZE);
+    Ok(())
+}
+
+/// Checks that `trade_state` is the collection offer of `wallet` on `collection_mint` and returns its bump.
+pub fn assert_valid_collection_trade_state(
+    wallet: &Pubkey,
+    auction_house: &Account<AuctionHouse>,
+    buyer_price: u64,
+    token_size: u64,
+    trade_state: &AccountInfo,
+    collection_mint: &Pubkey,
+    ts_bump: u8,
+) -> Result<u8, ProgramError> {
+    let bump = assert_derivation(
+        &crate::id(),
+        trade_state,
+        &[
+            PREFIX.as_bytes(),
+            wallet.as_ref(),
+            auction_house.key().as_ref(),
+            COLLECTION_OFFER.as_bytes(),
+            auction_house.treasury_mint.as_ref(),
+            collection_mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+        ],
+    )?;
+    if bump != ts_bump {
+        return Err(ErrorCode::DerivedKeyInvalid.into());
+    }
+    Ok(bump)
+}
+
+/// Checks that `metadata` belongs to the collection of `collection_mint` and that the collection is verified.
+pub fn assert_verified_collection_member(
+    metadata: &AccountInfo,
+    collection_mint: &Pubkey,
+) -> ProgramResult {
+    let metadata = Metadata::from_account_info(metadata)?;
+    match metadata.collection {
+        Some(collection) if collection.key == *collection_mint => {
+            if !collection.verified {
+                return Err(ErrorCode::CollectionNotVerified.into());
+            }
+            Ok(())
+        }
+        _ => Err(ErrorCode::CollectionMismatch.into()),
+    }
+}
@@ -5,3 +5,7 @@ pub const SIGNER: &str = "signer";
 pub const PURCHASE_RECEIPT_PREFIX: &str = "purchase_receipt";
 pub const BID_RECEIPT_PREFIX: &str = "bid_receipt";
 pub const LISTING_RECEIPT_PREFIX: &str = "listing_receipt";
+pub const AUCTIONEER: &str = "auctioneer";
+pub const LISTING_CONFIG: &str = "listing_config";
+pub const COLLECTION_OFFER: &str = "collection_offer";
+pub const ESCROW_COMMITMENT: &str = "escrow_commitment";
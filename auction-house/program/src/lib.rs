@@ -2,12 +2,17 @@
 //! AuctionHouse is a protocol for marketplaces to implement a decentralized sales contract. It is simple, fast and very cheap. AuctionHouse is a Solana program available on Mainnet Beta and Devnet. Anyone can create an AuctionHouse and accept any SPL token they wish.
 //!
 //! Full docs can be found [here](https://docs.metaplex.com/auction-house/definition).
+pub mod auctioneer;
+pub mod batch;
 pub mod bid;
+pub mod collection_offer;
 pub mod constants;
 pub mod pda;
 pub mod receipt;
 pub mod utils;
-use crate::{bid::*, constants::*, receipt::*, utils::*};
+use crate::{
+    auctioneer::*, batch::*, bid::*, collection_offer::*, constants::*, receipt::*, utils::*,
+};
 use anchor_lang::{
     prelude::*,
     solana_program::{
@@ -20,6 +25,8 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token::{Mint, Token, TokenAccount},
 };
+use arrayref::array_ref;
+use solana_program::program_memory::sol_memset;
 use spl_token::instruction::{approve, revoke};
 
 anchor_lang::declare_id!("hausS13jsjafwWwGqZTUQRmWyvyxn9EQpqMwV1PBBmk");
@@ -28,7 +35,6 @@ anchor_lang::declare_id!("hausS13jsjafwWwGqZTUQRmWyvyxn9EQpqMwV1PBBmk");
 pub mod auction_house {
 
     use super::*;
-    use solana_program::program_memory::sol_memset;
 
     /// Withdraw `amount` from the Auction House Fee Account to a provided destination account.
     pub fn withdraw_from_fee<'info>(
@@ -199,6 +205,42 @@ pub mod auction_house {
         Ok(())
     }
 
+    /// Update the royalty policy of an Auction House instance.
+    pub fn update_royalty_settings<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateRoyaltySettings<'info>>,
+        enforce_royalties: Option<bool>,
+        min_royalty_basis_points: Option<u16>,
+        pay_royalties_on_free_transfers: Option<bool>,
+        free_transfer_royalty: Option<u64>,
+        creator_allow_list: Option<Vec<Pubkey>>,
+    ) -> ProgramResult {
+        let auction_house = &mut ctx.accounts.auction_house;
+
+        if let Some(enforce) = enforce_royalties {
+            auction_house.enforce_royalties = enforce;
+        }
+        if let Some(mrbp) = min_royalty_basis_points {
+            if mrbp > 10000 {
+                return Err(ErrorCode::InvalidBasisPoints.into());
+            }
+            auction_house.min_royalty_basis_points = mrbp;
+        }
+        if let Some(prft) = pay_royalties_on_free_transfers {
+            auction_house.pay_royalties_on_free_transfers = prft;
+        }
+        if let Some(ftr) = free_transfer_royalty {
+            auction_house.free_transfer_royalty = ftr;
+        }
+        if let Some(creators) = creator_allow_list {
+            if creators.len() > MAX_ALLOWED_CREATORS {
+                return Err(ErrorCode::TooManyAllowedCreators.into());
+            }
+            auction_house.creator_allow_list = creators;
+        }
+
+        Ok(())
+    }
+
     /// Create a new Auction House instance.
     pub fn create_auction_house<'info>(
         ctx: Context<'_, '_, '_, 'info, CreateAuctionHouse<'info>>,
@@ -296,6 +338,8 @@ pub mod auction_house {
     }
 
     /// Withdraw `amount` from the escrow payment account for your specific wallet.
+    ///
+    /// When the Auction House has an auctioneer, the escrow commitment of the wallet is expected as the first remaining account, even before the wallet places its first highest bid, and the escrow cannot go below the highest bids it backs.
     pub fn withdraw<'info>(
         ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
         escrow_payment_bump: u8,
@@ -335,6 +379,26 @@ pub mod auction_house {
             return Err(ErrorCode::NoValidSignerPresent.into());
         }
 
+        let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+        // the escrow has to keep paying for the highest bids it backs
+        if auction_house.has_auctioneer {
+            let escrow_commitment = ctx
+                .remaining_accounts
+                .first()
+                .ok_or(ErrorCode::MissingEscrowCommitment)?;
+            let committed =
+                get_escrow_commitment(escrow_commitment, &auction_house_key, &wallet_key)?;
+            let escrow_balance = if is_native {
+                escrow_payment_account.lamports()
+            } else {
+                Account::<TokenAccount>::try_from(&escrow_payment_account.to_account_info())?.amount
+            };
+            if escrow_balance.saturating_sub(amount) < committed {
+                return Err(ErrorCode::EscrowCommittedToHighestBid.into());
+            }
+        }
+
         let escrow_signer_seeds = [
             PREFIX.as_bytes(),
             auction_house_key.as_ref(),
@@ -350,8 +414,6 @@ pub mod auction_house {
             &seeds,
         )?;
 
-        let is_native = treasury_mint.key() == spl_token::native_mint::id();
-
         if !is_native {
             if receipt_account.data_is_empty() {
                 make_ata(
@@ -511,80 +573,31 @@ pub mod auction_house {
     }
 
     /// Cancel a bid or ask by revoking the token delegate, transferring all lamports from the trade state account to the fee payer, and setting the trade state account data to zero so it can be garbage collected.
+    ///
+    /// When the Auction House has an auctioneer, the listing config of the token account is expected as the first remaining account. The seller cannot cancel a listing with a bid before its auction ends, and the highest bid can only be canceled once that listing is gone, with the seller trade state and the escrow commitment of the bidder as the next remaining accounts.
     pub fn cancel<'info>(
         ctx: Context<'_, '_, '_, 'info, Cancel<'info>>,
         buyer_price: u64,
         token_size: u64,
     ) -> ProgramResult {
-        let wallet = &ctx.accounts.wallet;
-        let token_account = &ctx.accounts.token_account;
-        let token_mint = &ctx.accounts.token_mint;
-        let authority = &ctx.accounts.authority;
-        let auction_house = &ctx.accounts.auction_house;
-        let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
-        let trade_state = &ctx.accounts.trade_state;
-        let token_program = &ctx.accounts.token_program;
-        let ts_bump = trade_state.try_borrow_data()?[0];
-        assert_valid_trade_state(
-            &wallet.key(),
-            auction_house,
+        cancel_logic(
+            &ctx.accounts.wallet,
+            &ctx.accounts.token_account,
+            &ctx.accounts.token_mint.key(),
+            &ctx.accounts.authority,
+            &ctx.accounts.auction_house,
+            &ctx.accounts.auction_house_fee_account,
+            &ctx.accounts.trade_state,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
             buyer_price,
             token_size,
-            &trade_state.to_account_info(),
-            &token_account.mint.key(),
-            &token_account.key(),
-            ts_bump,
-        )?;
-        assert_keys_equal(token_mint.key(), token_account.mint)?;
-        if !wallet.to_account_info().is_signer && !authority.to_account_info().is_signer {
-            return Err(ErrorCode::NoValidSignerPresent.into());
-        }
-
-        let auction_house_key = auction_house.key();
-        let seeds = [
-            PREFIX.as_bytes(),
-            auction_house_key.as_ref(),
-            FEE_PAYER.as_bytes(),
-            &[auction_house.fee_payer_bump],
-        ];
-
-        let (fee_payer, _) = get_fee_payer(
-            authority,
-            auction_house,
-            wallet.to_account_info(),
-            auction_house_fee_account.to_account_info(),
-            &seeds,
-        )?;
-
-        if token_account.owner == wallet.key() && wallet.is_signer {
-            invoke(
-                &revoke(
-                    &token_program.key(),
-                    &token_account.key(),
-                    &wallet.key(),
-                    &[],
-                )
-                .unwrap(),
-                &[
-                    token_program.to_account_info(),
-                    token_account.to_account_info(),
-                    wallet.to_account_info(),
-                ],
-            )?;
-        }
-
-        let curr_lamp = trade_state.lamports();
-        **trade_state.lamports.borrow_mut() = 0;
-
-        **fee_payer.lamports.borrow_mut() = fee_payer
-            .lamports()
-            .checked_add(curr_lamp)
-            .ok_or(ErrorCode::NumericalOverflow)?;
-        sol_memset(*trade_state.try_borrow_mut_data()?, 0, TRADE_STATE_SIZE);
-        Ok(())
+        )
     }
 
     /// Execute sale between provided buyer and seller trade state accounts transferring funds to seller wallet and token to buyer wallet.
+    ///
+    /// When the Auction House has an auctioneer, the listing config of the token account and the escrow commitment of the buyer are expected as the first remaining accounts. When the listing config exists, the auction must have ended and only its highest bid can be matched. Either way the sale cannot leave the escrow below the other highest bids it backs.
     #[inline(never)]
     pub fn execute_sale<'info>(
         ctx: Context<'_, '_, '_, 'info, ExecuteSale<'info>>,
@@ -594,496 +607,930 @@ pub mod auction_house {
         buyer_price: u64,
         token_size: u64,
     ) -> ProgramResult {
-        let buyer = &ctx.accounts.buyer;
-        let seller = &ctx.accounts.seller;
-        let token_account = &ctx.accounts.token_account;
-        let token_mint = &ctx.accounts.token_mint;
-        let metadata = &ctx.accounts.metadata;
-        let treasury_mint = &ctx.accounts.treasury_mint;
-        let seller_payment_receipt_account = &ctx.accounts.seller_payment_receipt_account;
-        let buyer_receipt_token_account = &ctx.accounts.buyer_receipt_token_account;
-        let escrow_payment_account = &ctx.accounts.escrow_payment_account;
-        let authority = &ctx.accounts.authority;
-        let auction_house = &ctx.accounts.auction_house;
-        let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
-        let auction_house_treasury = &ctx.accounts.auction_house_treasury;
-        let buyer_trade_state = &ctx.accounts.buyer_trade_state;
-        let seller_trade_state = &ctx.accounts.seller_trade_state;
-        let free_trade_state = &ctx.accounts.free_trade_state;
-        let token_program = &ctx.accounts.token_program;
-        let system_program = &ctx.accounts.system_program;
-        let ata_program = &ctx.accounts.ata_program;
-        let program_as_signer = &ctx.accounts.program_as_signer;
-        let rent = &ctx.accounts.rent;
-
-        let metadata_clone = metadata.to_account_info();
-        let escrow_clone = escrow_payment_account.to_account_info();
-        let auction_house_clone = auction_house.to_account_info();
-        let ata_clone = ata_program.to_account_info();
-        let token_clone = token_program.to_account_info();
-        let sys_clone = system_program.to_account_info();
-        let rent_clone = rent.to_account_info();
-        let treasury_clone = auction_house_treasury.to_account_info();
-        let authority_clone = authority.to_account_info();
-        let buyer_receipt_clone = buyer_receipt_token_account.to_account_info();
-        let token_account_clone = token_account.to_account_info();
-
-        let is_native = treasury_mint.key() == spl_token::native_mint::id();
-
-        if buyer_price == 0 && !authority_clone.is_signer && !seller.is_signer {
-            return Err(ErrorCode::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into());
-        }
-
-        let token_account_mint = get_mint_from_token_account(&token_account_clone)?;
-
-        assert_keys_equal(token_mint.key(), token_account_mint)?;
-        let delegate = get_delegate_from_token_account(&token_account_clone)?;
-        if let Some(d) = delegate {
-            assert_keys_equal(program_as_signer.key(), d)?;
-        } else {
-            msg!("No delegate detected on token account.");
-            return Err(ErrorCode::BothPartiesNeedToAgreeToSale.into());
-        }
-        let buyer_ts_data = &mut buyer_trade_state.try_borrow_mut_data()?;
-        let seller_ts_data = &mut seller_trade_state.try_borrow_mut_data()?;
-        let ts_bump = buyer_ts_data[0];
-        assert_valid_trade_state(
-            &buyer.key(),
-            auction_house,
+        execute_sale_logic(
+            ctx.accounts,
+            ctx.remaining_accounts,
+            escrow_payment_bump,
+            program_as_signer_bump,
             buyer_price,
             token_size,
-            buyer_trade_state,
-            &token_mint.key(),
-            &token_account.key(),
-            ts_bump,
-        )?;
-        if ts_bump == 0 || buyer_ts_data.len() == 0 || seller_ts_data.len() == 0 {
-            return Err(ErrorCode::BothPartiesNeedToAgreeToSale.into());
-        }
-
-        let auction_house_key = auction_house.key();
-        let seeds = [
-            PREFIX.as_bytes(),
-            auction_house_key.as_ref(),
-            FEE_PAYER.as_bytes(),
-            &[auction_house.fee_payer_bump],
-        ];
+            buyer_price,
+            token_size,
+            None,
+        )
+    }
 
-        let wallet_to_use = if buyer.is_signer { buyer } else { seller };
+    /// Execute a partial sale of `partial_order_size` tokens from a listing of `token_size` tokens at `buyer_price`, matched with a bid of `partial_order_price` for those tokens.
+    ///
+    /// The seller trade state tracks the size left on the listing and is only closed once it reaches zero.
+    #[inline(never)]
+    pub fn execute_partial_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteSale<'info>>,
+        escrow_payment_bump: u8,
+        _free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+        partial_order_size: u64,
+        partial_order_price: u64,
+    ) -> ProgramResult {
+        execute_sale_logic(
+            ctx.accounts,
+            ctx.remaining_accounts,
+            escrow_payment_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+            partial_order_price,
+            partial_order_size,
+            None,
+        )
+    }
 
-        let (fee_payer, fee_payer_seeds) = get_fee_payer(
-            authority,
-            auction_house,
-            wallet_to_use.to_account_info(),
-            auction_house_fee_account.to_account_info(),
-            &seeds,
-        )?;
-        let fee_payer_clone = fee_payer.to_account_info();
+    /// Execute sale between a listing and a collection offer on the collection of `collection_mint`, transferring funds to seller wallet and token to buyer wallet.
+    ///
+    /// The metadata of the listed token must hold a verified collection equal to `collection_mint`.
+    #[inline(never)]
+    pub fn execute_collection_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteCollectionSale<'info>>,
+        escrow_payment_bump: u8,
+        _free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> ProgramResult {
+        let collection_mint = ctx.accounts.collection_mint.to_account_info();
+        execute_sale_logic(
+            &ctx.accounts.execute_sale,
+            ctx.remaining_accounts,
+            escrow_payment_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+            buyer_price,
+            token_size,
+            Some(&collection_mint),
+        )
+    }
 
-        assert_is_ata(
-            &token_account.to_account_info(),
-            &seller.key(),
-            &token_account_mint,
-        )?;
-        assert_derivation(
-            &mpl_token_metadata::id(),
-            &metadata.to_account_info(),
-            &[
-                mpl_token_metadata::state::PREFIX.as_bytes(),
-                mpl_token_metadata::id().as_ref(),
-                token_account_mint.as_ref(),
-            ],
-        )?;
+    /// Create a collection offer on any token of a verified collection by creating a `buyer_trade_state` account.
+    pub fn collection_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, CollectionBuy<'info>>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> ProgramResult {
+        collection_offer::collection_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
+            buyer_price,
+            token_size,
+        )
+    }
 
-        if metadata.data_is_empty() {
-            return Err(ErrorCode::MetadataDoesntExist.into());
-        }
+    /// Cancel a collection offer by closing its `buyer_trade_state` account.
+    pub fn cancel_collection_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelCollectionBuy<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> ProgramResult {
+        collection_offer::cancel_collection_bid(ctx, buyer_price, token_size)
+    }
 
-        let auction_house_key = auction_house.key();
-        let wallet_key = buyer.key();
-        let escrow_signer_seeds = [
-            PREFIX.as_bytes(),
-            auction_house_key.as_ref(),
-            wallet_key.as_ref(),
-            &[escrow_payment_bump],
-        ];
+    /// Create a sell bid by creating a `seller_trade_state` account and approving the program as the token delegate.
+    pub fn sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
+        trade_state_bump: u8,
+        _free_trade_state_bump: u8,
+        _program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> ProgramResult {
+        sell_logic(
+            ctx.program_id,
+            &ctx.accounts.wallet,
+            &ctx.accounts.token_account,
+            &ctx.accounts.metadata,
+            &ctx.accounts.authority,
+            &ctx.accounts.auction_house,
+            &ctx.accounts.auction_house_fee_account,
+            &ctx.accounts.seller_trade_state,
+            &ctx.accounts.free_seller_trade_state,
+            &ctx.accounts.token_program,
+            &ctx.accounts.system_program,
+            &ctx.accounts.program_as_signer,
+            &ctx.accounts.rent,
+            trade_state_bump,
+            buyer_price,
+            token_size,
+        )
+    }
 
-        let ah_seeds = [
-            PREFIX.as_bytes(),
-            auction_house.creator.as_ref(),
-            auction_house.treasury_mint.as_ref(),
-            &[auction_house.bump],
-        ];
+    /// Create a sell bid for each token of a batch, as `sell` does for one token.
+    ///
+    /// The token accounts, metadata, seller trade states and free seller trade states are passed as parallel arrays in the remaining accounts. The whole batch fails if any listing is invalid. It fails with `BatchItemFailed`, the index and error of the failing item are logged.
+    pub fn batch_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchSell<'info>>,
+        program_as_signer_bump: u8,
+        trade_state_bumps: Vec<u8>,
+        buyer_prices: Vec<u64>,
+        token_sizes: Vec<u64>,
+    ) -> ProgramResult {
+        batch::batch_sell(
+            ctx,
+            program_as_signer_bump,
+            trade_state_bumps,
+            buyer_prices,
+            token_sizes,
+        )
+    }
 
-        // with the native account, the escrow is its own owner,
-        // whereas with token, it is the auction house that is owner.
-        let signer_seeds_for_royalties = if is_native {
-            escrow_signer_seeds
-        } else {
-            ah_seeds
-        };
+    /// Cancel a batch of bids or asks, as `cancel` does for one.
+    ///
+    /// The token accounts, token mints and trade states are passed as parallel arrays in the remaining accounts. The whole batch fails if any item is invalid. It fails with `BatchItemFailed`, the index and error of the failing item are logged.
+    pub fn batch_cancel<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchCancel<'info>>,
+        buyer_prices: Vec<u64>,
+        token_sizes: Vec<u64>,
+    ) -> ProgramResult {
+        batch::batch_cancel(ctx, buyer_prices, token_sizes)
+    }
 
-        let buyer_leftover_after_royalties = pay_creator_fees(
-            &mut ctx.remaining_accounts.iter(),
-            &metadata_clone,
-            &escrow_clone,
-            &auction_house_clone,
-            &fee_payer_clone,
-            treasury_mint,
-            &ata_clone,
-            &token_clone,
-            &sys_clone,
-            &rent_clone,
-            &signer_seeds_for_royalties,
-            &fee_payer_seeds,
+    /// Create a private buy bid by creating a `buyer_trade_state` account and an `escrow_payment` account and funding the escrow with the necessary SOL or SPL token amount.
+    pub fn buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> ProgramResult {
+        private_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
             buyer_price,
-            is_native,
-        )?;
+            token_size,
+        )
+    }
 
-        let auction_house_fee_paid = pay_auction_house_fees(
-            &auction_house,
-            &treasury_clone,
-            &escrow_clone,
-            &token_clone,
-            &sys_clone,
-            &signer_seeds_for_royalties,
+    /// Create a public buy bid by creating a `public_buyer_trade_state` account and an `escrow_payment` account and funding the escrow with the necessary SOL or SPL token amount.
+    pub fn public_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, PublicBuy<'info>>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> ProgramResult {
+        public_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
             buyer_price,
-            is_native,
-        )?;
+            token_size,
+        )
+    }
 
-        let buyer_leftover_after_royalties_and_house_fee = buyer_leftover_after_royalties
-            .checked_sub(auction_house_fee_paid)
-            .ok_or(ErrorCode::NumericalOverflow)?;
+    /// Create a listing receipt by creating a `listing_receipt` account.
+    pub fn print_listing_receipt<'info>(
+        ctx: Context<'_, '_, '_, 'info, PrintListingReceipt<'info>>,
+        receipt_bump: u8,
+    ) -> ProgramResult {
+        receipt::print_listing_receipt(ctx, receipt_bump)
+    }
 
-        if !is_native {
-            if seller_payment_receipt_account.data_is_empty() {
-                make_ata(
-                    seller_payment_receipt_account.to_account_info(),
-                    seller.to_account_info(),
-                    treasury_mint.to_account_info(),
-                    fee_payer.to_account_info(),
-                    ata_program.to_account_info(),
-                    token_program.to_account_info(),
-                    system_program.to_account_info(),
-                    rent.to_account_info(),
-                    &fee_payer_seeds,
-                )?;
-            }
+    /// Create a listing receipt for each listing of the previous `batch_sell` instruction.
+    ///
+    /// The whole batch fails if any receipt is invalid. It fails with `BatchItemFailed`, the index and error of the failing item are logged.
+    pub fn batch_print_listing_receipt<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchPrintListingReceipt<'info>>,
+        receipt_bumps: Vec<u8>,
+    ) -> ProgramResult {
+        receipt::batch_print_listing_receipt(ctx, receipt_bumps)
+    }
 
-            let seller_rec_acct = assert_is_ata(
-                &seller_payment_receipt_account.to_account_info(),
-                &seller.key(),
-                &treasury_mint.key(),
-            )?;
+    /// Cancel an active listing receipt by setting the `canceled_at` field to the current time.
+    pub fn cancel_listing_receipt<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelListingReceipt<'info>>,
+    ) -> ProgramResult {
+        receipt::cancel_listing_receipt(ctx)
+    }
 
-            // make sure you cant get rugged
-            if seller_rec_acct.delegate.is_some() {
-                return Err(ErrorCode::SellerATACannotHaveDelegate.into());
-            }
+    /// Create a bid receipt by creating a `bid_receipt` account.
+    pub fn print_bid_receipt<'info>(
+        ctx: Context<'_, '_, '_, 'info, PrintBidReceipt<'info>>,
+        receipt_bump: u8,
+    ) -> ProgramResult {
+        receipt::print_bid_receipt(ctx, receipt_bump)
+    }
 
-            invoke_signed(
-                &spl_token::instruction::transfer(
-                    token_program.key,
-                    &escrow_payment_account.key(),
-                    &seller_payment_receipt_account.key(),
-                    &auction_house.key(),
-                    &[],
-                    buyer_leftover_after_royalties_and_house_fee,
-                )?,
-                &[
-                    escrow_payment_account.to_account_info(),
-                    seller_payment_receipt_account.to_account_info(),
-                    token_program.to_account_info(),
-                    auction_house.to_account_info(),
-                ],
-                &[&ah_seeds],
-            )?;
-        } else {
-            assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
-            invoke_signed(
-                &system_instruction::transfer(
-                    &escrow_payment_account.key,
-                    seller_payment_receipt_account.key,
-                    buyer_leftover_after_royalties_and_house_fee,
-                ),
-                &[
-                    escrow_payment_account.to_account_info(),
-                    seller_payment_receipt_account.to_account_info(),
-                    system_program.to_account_info(),
-                ],
-                &[&escrow_signer_seeds],
-            )?;
-        }
+    /// Cancel an active bid receipt by setting the `canceled_at` field to the current time.
+    pub fn cancel_bid_receipt<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelBidReceipt<'info>>,
+    ) -> ProgramResult {
+        receipt::cancel_bid_receipt(ctx)
+    }
 
-        if buyer_receipt_token_account.data_is_empty() {
-            make_ata(
-                buyer_receipt_token_account.to_account_info(),
-                buyer.to_account_info(),
-                token_mint.to_account_info(),
-                fee_payer.to_account_info(),
-                ata_program.to_account_info(),
-                token_program.to_account_info(),
-                system_program.to_account_info(),
-                rent.to_account_info(),
-                &fee_payer_seeds,
-            )?;
-        }
+    /// Create a purchase receipt by creating a `purchase_receipt` account.
+    pub fn print_purchase_receipt<'info>(
+        ctx: Context<'_, '_, '_, 'info, PrintPurchaseReceipt<'info>>,
+        purchase_receipt_bump: u8,
+    ) -> ProgramResult {
+        receipt::print_purchase_receipt(ctx, purchase_receipt_bump)
+    }
 
-        let buyer_rec_acct = assert_is_ata(&buyer_receipt_clone, &buyer.key(), &token_mint.key())?;
+    /// Delegate the listing and bid rules of the Auction House to an auctioneer by creating an `auctioneer` scope account.
+    pub fn delegate_auctioneer<'info>(
+        ctx: Context<'_, '_, '_, 'info, DelegateAuctioneer<'info>>,
+        auctioneer_bump: u8,
+    ) -> ProgramResult {
+        auctioneer::delegate_auctioneer(ctx, auctioneer_bump)
+    }
 
-        // make sure you cant get rugged
-        if buyer_rec_acct.delegate.is_some() {
-            return Err(ErrorCode::BuyerATACannotHaveDelegate.into());
+    /// Take the listing and bid rules of the Auction House back from its auctioneer by closing the `auctioneer` scope account.
+    pub fn undelegate_auctioneer<'info>(
+        ctx: Context<'_, '_, '_, 'info, UndelegateAuctioneer<'info>>,
+    ) -> ProgramResult {
+        auctioneer::undelegate_auctioneer(ctx)
+    }
+
+    /// Run a timed auction on a free listing by creating a `listing_config` account.
+    pub fn create_listing_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateListingConfig<'info>>,
+        listing_config_bump: u8,
+        token_size: u64,
+        start_time: i64,
+        end_time: i64,
+        reserve_price: u64,
+        min_bid_increment: u64,
+        time_ext_period: u32,
+        time_ext_delta: u32,
+    ) -> ProgramResult {
+        auctioneer::create_listing_config(
+            ctx,
+            listing_config_bump,
+            token_size,
+            start_time,
+            end_time,
+            reserve_price,
+            min_bid_increment,
+            time_ext_period,
+            time_ext_delta,
+        )
+    }
+
+    /// Close a `listing_config` account, returning its rent to the seller.
+    pub fn close_listing_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseListingConfig<'info>>,
+    ) -> ProgramResult {
+        auctioneer::close_listing_config(ctx)
+    }
+}
+
+/// Handles the sell logic for both single and batch listings.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn sell_logic<'info>(
+    program_id: &Pubkey,
+    wallet: &UncheckedAccount<'info>,
+    token_account: &Account<'info, TokenAccount>,
+    metadata: &UncheckedAccount<'info>,
+    authority: &UncheckedAccount<'info>,
+    auction_house: &Account<'info, AuctionHouse>,
+    auction_house_fee_account: &UncheckedAccount<'info>,
+    seller_trade_state: &UncheckedAccount<'info>,
+    free_seller_trade_state: &UncheckedAccount<'info>,
+    token_program: &Program<'info, Token>,
+    system_program: &Program<'info, System>,
+    program_as_signer: &UncheckedAccount<'info>,
+    rent: &Sysvar<'info, Rent>,
+    trade_state_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> ProgramResult {
+    // Wallet has to be a signer but there are different kinds of errors when it's not.
+    if !wallet.to_account_info().is_signer {
+        if buyer_price == 0 {
+            return Err(ErrorCode::SaleRequiresSigner.into());
+        } else {
+            if free_seller_trade_state.data_is_empty() {
+                return Err(ErrorCode::SaleRequiresSigner.into());
+            } else if !free_seller_trade_state.data_is_empty()
+                && (!authority.to_account_info().is_signer || !auction_house.can_change_sale_price)
+            {
+                return Err(ErrorCode::SaleRequiresSigner.into());
+            }
         }
+    }
 
-        let program_as_signer_seeds = [
-            PREFIX.as_bytes(),
-            SIGNER.as_bytes(),
-            &[program_as_signer_bump],
-        ];
+    let auction_house_key = auction_house.key();
+
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, fee_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+    assert_is_ata(
+        &token_account.to_account_info(),
+        &wallet.key(),
+        &token_account.mint,
+    )?;
+
+    assert_metadata_valid(metadata, token_account)?;
+
+    let ts_info = seller_trade_state.to_account_info();
+    // Sending `sell` again for an open listing keeps what is left of it, partial sales included.
+    let remaining_size = if ts_info.data_len() >= SELLER_TRADE_STATE_SIZE {
+        let data = ts_info.data.borrow();
+        u64::from_le_bytes(*array_ref![data, 1, 8])
+    } else {
+        token_size
+    };
+
+    if remaining_size > token_account.amount {
+        return Err(ErrorCode::InvalidTokenAmount.into());
+    }
 
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
+    if wallet.is_signer {
+        invoke(
+            &approve(
+                &token_program.key(),
                 &token_account.key(),
-                &buyer_receipt_token_account.key(),
                 &program_as_signer.key(),
+                &wallet.key(),
                 &[],
-                token_size,
-            )?,
+                remaining_size,
+            )
+            .unwrap(),
             &[
+                token_program.to_account_info(),
                 token_account.to_account_info(),
-                buyer_receipt_clone,
                 program_as_signer.to_account_info(),
-                token_clone,
+                wallet.to_account_info(),
             ],
-            &[&program_as_signer_seeds],
         )?;
+    }
 
-        let curr_seller_lamp = seller_trade_state.lamports();
-        **seller_trade_state.lamports.borrow_mut() = 0;
-        sol_memset(&mut *seller_ts_data, 0, TRADE_STATE_SIZE);
+    if ts_info.data_is_empty() {
+        let token_account_key = token_account.key();
+        let wallet_key = wallet.key();
+        let ts_seeds = [
+            PREFIX.as_bytes(),
+            wallet_key.as_ref(),
+            auction_house_key.as_ref(),
+            token_account_key.as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+            &[trade_state_bump],
+        ];
+        create_or_allocate_account_raw(
+            *program_id,
+            &ts_info,
+            &rent.to_account_info(),
+            &system_program,
+            &fee_payer,
+            SELLER_TRADE_STATE_SIZE,
+            fee_seeds,
+            &ts_seeds,
+        )?;
 
-        **fee_payer.lamports.borrow_mut() = fee_payer
-            .lamports()
-            .checked_add(curr_seller_lamp)
-            .ok_or(ErrorCode::NumericalOverflow)?;
+        let data = &mut ts_info.data.borrow_mut();
+        data[1..SELLER_TRADE_STATE_SIZE].copy_from_slice(&token_size.to_le_bytes());
+    }
 
-        let curr_buyer_lamp = buyer_trade_state.lamports();
-        **buyer_trade_state.lamports.borrow_mut() = 0;
-        sol_memset(&mut *buyer_ts_data, 0, TRADE_STATE_SIZE);
-        **fee_payer.lamports.borrow_mut() = fee_payer
-            .lamports()
-            .checked_add(curr_buyer_lamp)
-            .ok_or(ErrorCode::NumericalOverflow)?;
+    let data = &mut ts_info.data.borrow_mut();
+    data[0] = trade_state_bump;
 
-        if free_trade_state.lamports() > 0 {
-            let curr_buyer_lamp = free_trade_state.lamports();
-            **free_trade_state.lamports.borrow_mut() = 0;
-
-            **fee_payer.lamports.borrow_mut() = fee_payer
-                .lamports()
-                .checked_add(curr_buyer_lamp)
-                .ok_or(ErrorCode::NumericalOverflow)?;
-            sol_memset(
-                *free_trade_state.try_borrow_mut_data()?,
-                0,
-                TRADE_STATE_SIZE,
-            );
-        }
-        Ok(())
+    Ok(())
+}
+
+/// Handles the cancel logic for both single and batch cancellations.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn cancel_logic<'info>(
+    wallet: &UncheckedAccount<'info>,
+    token_account: &Account<'info, TokenAccount>,
+    token_mint: &Pubkey,
+    authority: &UncheckedAccount<'info>,
+    auction_house: &Account<'info, AuctionHouse>,
+    auction_house_fee_account: &UncheckedAccount<'info>,
+    trade_state: &UncheckedAccount<'info>,
+    token_program: &Program<'info, Token>,
+    auction_accounts: &[AccountInfo<'info>],
+    buyer_price: u64,
+    token_size: u64,
+) -> ProgramResult {
+    let ts_bump = trade_state.try_borrow_data()?[0];
+    assert_valid_trade_state(
+        &wallet.key(),
+        auction_house,
+        buyer_price,
+        token_size,
+        &trade_state.to_account_info(),
+        &token_account.mint.key(),
+        &token_account.key(),
+        ts_bump,
+    )?;
+    assert_keys_equal(*token_mint, token_account.mint)?;
+    if !wallet.to_account_info().is_signer && !authority.to_account_info().is_signer {
+        return Err(ErrorCode::NoValidSignerPresent.into());
+    }
+    if auction_house.has_auctioneer {
+        assert_auction_cancel_allowed(
+            auction_accounts,
+            &auction_house.key(),
+            &auction_house.treasury_mint,
+            &token_account.key(),
+            token_mint,
+            &wallet.key(),
+            &trade_state.key(),
+            buyer_price,
+            token_size,
+        )?;
     }
 
-    /// Create a sell bid by creating a `seller_trade_state` account and approving the program as the token delegate.
-    pub fn sell<'info>(
-        ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
-        trade_state_bump: u8,
-        _free_trade_state_bump: u8,
-        _program_as_signer_bump: u8,
-        buyer_price: u64,
-        token_size: u64,
-    ) -> ProgramResult {
-        let wallet = &ctx.accounts.wallet;
-        let token_account = &ctx.accounts.token_account;
-        let metadata = &ctx.accounts.metadata;
-        let authority = &ctx.accounts.authority;
-        let seller_trade_state = &ctx.accounts.seller_trade_state;
-        let free_seller_trade_state = &ctx.accounts.free_seller_trade_state;
-        let auction_house = &ctx.accounts.auction_house;
-        let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
-        let token_program = &ctx.accounts.token_program;
-        let system_program = &ctx.accounts.system_program;
-        let program_as_signer = &ctx.accounts.program_as_signer;
-        let rent = &ctx.accounts.rent;
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, _) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    if token_account.owner == wallet.key() && wallet.is_signer {
+        invoke(
+            &revoke(
+                &token_program.key(),
+                &token_account.key(),
+                &wallet.key(),
+                &[],
+            )
+            .unwrap(),
+            &[
+                token_program.to_account_info(),
+                token_account.to_account_info(),
+                wallet.to_account_info(),
+            ],
+        )?;
+    }
 
-        // Wallet has to be a signer but there are different kinds of errors when it's not.
-        if !wallet.to_account_info().is_signer {
-            if buyer_price == 0 {
-                return Err(ErrorCode::SaleRequiresSigner.into());
-            } else {
-                if free_seller_trade_state.data_is_empty() {
-                    return Err(ErrorCode::SaleRequiresSigner.into());
-                } else if !free_seller_trade_state.data_is_empty()
-                    && (!authority.to_account_info().is_signer
-                        || !auction_house.can_change_sale_price)
-                {
-                    return Err(ErrorCode::SaleRequiresSigner.into());
-                }
-            }
-        }
+    let curr_lamp = trade_state.lamports();
+    **trade_state.lamports.borrow_mut() = 0;
 
-        let auction_house_key = auction_house.key();
+    **fee_payer.lamports.borrow_mut() = fee_payer
+        .lamports()
+        .checked_add(curr_lamp)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    let trade_state_len = trade_state.data_len();
+    sol_memset(*trade_state.try_borrow_mut_data()?, 0, trade_state_len);
+    Ok(())
+}
 
-        let seeds = [
-            PREFIX.as_bytes(),
-            auction_house_key.as_ref(),
-            FEE_PAYER.as_bytes(),
-            &[auction_house.fee_payer_bump],
-        ];
+/// Handles the sale logic for both full and partial sales.
+///
+/// `buyer_price` and `token_size` describe the listing while `order_price` and `order_size` describe the matched bid. When `collection_mint` is set, the buyer trade state is a collection offer on that collection.
+#[inline(never)]
+#[allow(clippy::too_many_arguments)]
+fn execute_sale_logic<'info>(
+    accounts: &ExecuteSale<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    escrow_payment_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    order_price: u64,
+    order_size: u64,
+    collection_mint: Option<&AccountInfo<'info>>,
+) -> ProgramResult {
+    let buyer = &accounts.buyer;
+    let seller = &accounts.seller;
+    let token_account = &accounts.token_account;
+    let token_mint = &accounts.token_mint;
+    let metadata = &accounts.metadata;
+    let treasury_mint = &accounts.treasury_mint;
+    let seller_payment_receipt_account = &accounts.seller_payment_receipt_account;
+    let buyer_receipt_token_account = &accounts.buyer_receipt_token_account;
+    let escrow_payment_account = &accounts.escrow_payment_account;
+    let authority = &accounts.authority;
+    let auction_house = &accounts.auction_house;
+    let auction_house_fee_account = &accounts.auction_house_fee_account;
+    let auction_house_treasury = &accounts.auction_house_treasury;
+    let buyer_trade_state = &accounts.buyer_trade_state;
+    let seller_trade_state = &accounts.seller_trade_state;
+    let free_trade_state = &accounts.free_trade_state;
+    let token_program = &accounts.token_program;
+    let system_program = &accounts.system_program;
+    let ata_program = &accounts.ata_program;
+    let program_as_signer = &accounts.program_as_signer;
+    let rent = &accounts.rent;
+
+    let metadata_clone = metadata.to_account_info();
+    let escrow_clone = escrow_payment_account.to_account_info();
+    let auction_house_clone = auction_house.to_account_info();
+    let ata_clone = ata_program.to_account_info();
+    let token_clone = token_program.to_account_info();
+    let sys_clone = system_program.to_account_info();
+    let rent_clone = rent.to_account_info();
+    let treasury_clone = auction_house_treasury.to_account_info();
+    let authority_clone = authority.to_account_info();
+    let buyer_receipt_clone = buyer_receipt_token_account.to_account_info();
+    let token_account_clone = token_account.to_account_info();
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    if buyer_price == 0 && !authority_clone.is_signer && !seller.is_signer {
+        return Err(ErrorCode::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into());
+    }
 
-        let (fee_payer, fee_seeds) = get_fee_payer(
-            authority,
+    let token_account_mint = get_mint_from_token_account(&token_account_clone)?;
+
+    assert_keys_equal(token_mint.key(), token_account_mint)?;
+    let delegate = get_delegate_from_token_account(&token_account_clone)?;
+    if let Some(d) = delegate {
+        assert_keys_equal(program_as_signer.key(), d)?;
+    } else {
+        msg!("No delegate detected on token account.");
+        return Err(ErrorCode::BothPartiesNeedToAgreeToSale.into());
+    }
+    let buyer_ts_data = &mut buyer_trade_state.try_borrow_mut_data()?;
+    let seller_ts_data = &mut seller_trade_state.try_borrow_mut_data()?;
+    let ts_bump = buyer_ts_data[0];
+    if let Some(collection_mint) = collection_mint {
+        assert_valid_collection_trade_state(
+            &buyer.key(),
             auction_house,
-            wallet.to_account_info(),
-            auction_house_fee_account.to_account_info(),
-            &seeds,
+            order_price,
+            order_size,
+            buyer_trade_state,
+            &collection_mint.key(),
+            ts_bump,
         )?;
-        assert_is_ata(
-            &token_account.to_account_info(),
-            &wallet.key(),
-            &token_account.mint,
+    } else {
+        assert_valid_trade_state(
+            &buyer.key(),
+            auction_house,
+            order_price,
+            order_size,
+            buyer_trade_state,
+            &token_mint.key(),
+            &token_account.key(),
+            ts_bump,
         )?;
+    }
+    if ts_bump == 0 || buyer_ts_data.len() == 0 || seller_ts_data.len() == 0 {
+        return Err(ErrorCode::BothPartiesNeedToAgreeToSale.into());
+    }
 
-        assert_metadata_valid(metadata, token_account)?;
+    // Seller trade states created before partial sales only hold their bump and can only be
+    // filled whole.
+    let partially_fillable = seller_ts_data.len() >= SELLER_TRADE_STATE_SIZE;
+    let remaining_size = if partially_fillable {
+        u64::from_le_bytes(*array_ref![seller_ts_data, 1, 8])
+    } else {
+        token_size
+    };
+    if order_size == 0 || order_size > remaining_size {
+        return Err(ErrorCode::InvalidTokenAmount.into());
+    }
+    if order_size != token_size && !partially_fillable {
+        return Err(ErrorCode::TradeStateNotPartiallyFillable.into());
+    }
+    let listing_price_share = (buyer_price as u128)
+        .checked_mul(order_size as u128)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    // Rounding down would sell a fill below the listing price, down to a free fill of a single
+    // token, so the listing price has to split evenly across the order size.
+    if listing_price_share
+        .checked_rem(token_size as u128)
+        .ok_or(ErrorCode::NumericalOverflow)?
+        != 0
+    {
+        return Err(ErrorCode::PartialPriceNotExact.into());
+    }
+    let expected_order_price = listing_price_share
+        .checked_div(token_size as u128)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    if order_price as u128 != expected_order_price {
+        return Err(ErrorCode::PartialPriceMismatch.into());
+    }
+    let remaining_size = remaining_size
+        .checked_sub(order_size)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let wallet_to_use = if buyer.is_signer { buyer } else { seller };
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet_to_use.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+    let fee_payer_clone = fee_payer.to_account_info();
+
+    assert_is_ata(
+        &token_account.to_account_info(),
+        &seller.key(),
+        &token_account_mint,
+    )?;
+    assert_derivation(
+        &mpl_token_metadata::id(),
+        &metadata.to_account_info(),
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            token_account_mint.as_ref(),
+        ],
+    )?;
+
+    if metadata.data_is_empty() {
+        return Err(ErrorCode::MetadataDoesntExist.into());
+    }
+    if let Some(collection_mint) = collection_mint {
+        assert_verified_collection_member(&metadata_clone, &collection_mint.key())?;
+    }
 
-        if token_size > token_account.amount {
-            return Err(ErrorCode::InvalidTokenAmount.into());
+    let auction_house_key = auction_house.key();
+    let wallet_key = buyer.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        wallet_key.as_ref(),
+        &[escrow_payment_bump],
+    ];
+
+    let ah_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    // with the native account, the escrow is its own owner,
+    // whereas with token, it is the auction house that is owner.
+    let signer_seeds_for_royalties = if is_native {
+        escrow_signer_seeds
+    } else {
+        ah_seeds
+    };
+
+    let remaining_accounts = &mut remaining_accounts.iter();
+    let mut listing_config = None;
+    if auction_house.has_auctioneer {
+        let listing_config_info =
+            next_account_info(remaining_accounts).map_err(|_| ErrorCode::MissingListingConfig)?;
+        let escrow_commitment = next_account_info(remaining_accounts)
+            .map_err(|_| ErrorCode::MissingEscrowCommitment)?;
+        // listings without a listing config are sold at their fixed price
+        if is_auction_listing(
+            listing_config_info,
+            &auction_house_key,
+            &token_account.key(),
+        )? {
+            assert_auction_settled(
+                listing_config_info,
+                &auction_house_key,
+                &token_account.key(),
+                &buyer_trade_state.key(),
+                order_price,
+                order_size,
+            )?;
+            release_escrow(
+                escrow_commitment,
+                &auction_house_key,
+                &buyer.key(),
+                order_price,
+            )?;
+            listing_config = Some(listing_config_info);
         }
 
-        if wallet.is_signer {
-            invoke(
-                &approve(
-                    &token_program.key(),
-                    &token_account.key(),
-                    &program_as_signer.key(),
-                    &wallet.key(),
-                    &[],
-                    token_size,
-                )
-                .unwrap(),
-                &[
-                    token_program.to_account_info(),
-                    token_account.to_account_info(),
-                    program_as_signer.to_account_info(),
-                    wallet.to_account_info(),
-                ],
-            )?;
+        // the escrow has to keep paying for the other highest bids it backs
+        let committed = get_escrow_commitment(escrow_commitment, &auction_house_key, &wallet_key)?;
+        let escrow_balance = if is_native {
+            escrow_payment_account.lamports()
+        } else {
+            Account::<TokenAccount>::try_from(&escrow_clone)?.amount
+        };
+        if escrow_balance.saturating_sub(order_price) < committed {
+            return Err(ErrorCode::EscrowCommittedToHighestBid.into());
         }
+    }
 
-        let ts_info = seller_trade_state.to_account_info();
-        if ts_info.data_is_empty() {
-            let token_account_key = token_account.key();
-            let wallet_key = wallet.key();
-            let ts_seeds = [
-                PREFIX.as_bytes(),
-                wallet_key.as_ref(),
-                auction_house_key.as_ref(),
-                token_account_key.as_ref(),
-                auction_house.treasury_mint.as_ref(),
-                token_account.mint.as_ref(),
-                &buyer_price.to_le_bytes(),
-                &token_size.to_le_bytes(),
-                &[trade_state_bump],
-            ];
-            create_or_allocate_account_raw(
-                *ctx.program_id,
-                &ts_info,
-                &rent.to_account_info(),
-                &system_program,
-                &fee_payer,
-                TRADE_STATE_SIZE,
-                fee_seeds,
-                &ts_seeds,
+    // The free transfer royalty is charged on top of the zero price the buyer bid, so the buyer has to agree to it
+    if order_price == 0
+        && auction_house.pay_royalties_on_free_transfers
+        && auction_house.free_transfer_royalty > 0
+        && !buyer.is_signer
+    {
+        return Err(ErrorCode::FreeTransferNeedsBuyerSignature.into());
+    }
+
+    let buyer_leftover_after_royalties = pay_creator_fees(
+        remaining_accounts,
+        auction_house,
+        &metadata_clone,
+        &escrow_clone,
+        &auction_house_clone,
+        &fee_payer_clone,
+        treasury_mint,
+        &ata_clone,
+        &token_clone,
+        &sys_clone,
+        &rent_clone,
+        &signer_seeds_for_royalties,
+        &fee_payer_seeds,
+        order_price,
+        is_native,
+    )?;
+
+    let auction_house_fee_paid = pay_auction_house_fees(
+        &auction_house,
+        &treasury_clone,
+        &escrow_clone,
+        &token_clone,
+        &sys_clone,
+        &signer_seeds_for_royalties,
+        order_price,
+        is_native,
+    )?;
+
+    let buyer_leftover_after_royalties_and_house_fee = buyer_leftover_after_royalties
+        .checked_sub(auction_house_fee_paid)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    if !is_native {
+        if seller_payment_receipt_account.data_is_empty() {
+            make_ata(
+                seller_payment_receipt_account.to_account_info(),
+                seller.to_account_info(),
+                treasury_mint.to_account_info(),
+                fee_payer.to_account_info(),
+                ata_program.to_account_info(),
+                token_program.to_account_info(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                &fee_payer_seeds,
             )?;
         }
 
-        let data = &mut ts_info.data.borrow_mut();
-        data[0] = trade_state_bump;
+        let seller_rec_acct = assert_is_ata(
+            &seller_payment_receipt_account.to_account_info(),
+            &seller.key(),
+            &treasury_mint.key(),
+        )?;
 
-        Ok(())
-    }
+        // make sure you cant get rugged
+        if seller_rec_acct.delegate.is_some() {
+            return Err(ErrorCode::SellerATACannotHaveDelegate.into());
+        }
 
-    /// Create a private buy bid by creating a `buyer_trade_state` account and an `escrow_payment` account and funding the escrow with the necessary SOL or SPL token amount.
-    pub fn buy<'info>(
-        ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
-        trade_state_bump: u8,
-        escrow_payment_bump: u8,
-        buyer_price: u64,
-        token_size: u64,
-    ) -> ProgramResult {
-        private_bid(
-            ctx,
-            trade_state_bump,
-            escrow_payment_bump,
-            buyer_price,
-            token_size,
-        )
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                &escrow_payment_account.key(),
+                &seller_payment_receipt_account.key(),
+                &auction_house.key(),
+                &[],
+                buyer_leftover_after_royalties_and_house_fee,
+            )?,
+            &[
+                escrow_payment_account.to_account_info(),
+                seller_payment_receipt_account.to_account_info(),
+                token_program.to_account_info(),
+                auction_house.to_account_info(),
+            ],
+            &[&ah_seeds],
+        )?;
+    } else {
+        assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
+        invoke_signed(
+            &system_instruction::transfer(
+                &escrow_payment_account.key,
+                seller_payment_receipt_account.key,
+                buyer_leftover_after_royalties_and_house_fee,
+            ),
+            &[
+                escrow_payment_account.to_account_info(),
+                seller_payment_receipt_account.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            &[&escrow_signer_seeds],
+        )?;
     }
 
-    /// Create a public buy bid by creating a `public_buyer_trade_state` account and an `escrow_payment` account and funding the escrow with the necessary SOL or SPL token amount.
-    pub fn public_buy<'info>(
-        ctx: Context<'_, '_, '_, 'info, PublicBuy<'info>>,
-        trade_state_bump: u8,
-        escrow_payment_bump: u8,
-        buyer_price: u64,
-        token_size: u64,
-    ) -> ProgramResult {
-        public_bid(
-            ctx,
-            trade_state_bump,
-            escrow_payment_bump,
-            buyer_price,
-            token_size,
-        )
+    if buyer_receipt_token_account.data_is_empty() {
+        make_ata(
+            buyer_receipt_token_account.to_account_info(),
+            buyer.to_account_info(),
+            token_mint.to_account_info(),
+            fee_payer.to_account_info(),
+            ata_program.to_account_info(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+            &fee_payer_seeds,
+        )?;
     }
 
-    /// Create a listing receipt by creating a `listing_receipt` account.
-    pub fn print_listing_receipt<'info>(
-        ctx: Context<'_, '_, '_, 'info, PrintListingReceipt<'info>>,
-        receipt_bump: u8,
-    ) -> ProgramResult {
-        receipt::print_listing_receipt(ctx, receipt_bump)
-    }
+    let buyer_rec_acct = assert_is_ata(&buyer_receipt_clone, &buyer.key(), &token_mint.key())?;
 
-    /// Cancel an active listing receipt by setting the `canceled_at` field to the current time.
-    pub fn cancel_listing_receipt<'info>(
-        ctx: Context<'_, '_, '_, 'info, CancelListingReceipt<'info>>,
-    ) -> ProgramResult {
-        receipt::cancel_listing_receipt(ctx)
+    // make sure you cant get rugged
+    if buyer_rec_acct.delegate.is_some() {
+        return Err(ErrorCode::BuyerATACannotHaveDelegate.into());
     }
 
-    /// Create a bid receipt by creating a `bid_receipt` account.
-    pub fn print_bid_receipt<'info>(
-        ctx: Context<'_, '_, '_, 'info, PrintBidReceipt<'info>>,
-        receipt_bump: u8,
-    ) -> ProgramResult {
-        receipt::print_bid_receipt(ctx, receipt_bump)
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            &token_account.key(),
+            &buyer_receipt_token_account.key(),
+            &program_as_signer.key(),
+            &[],
+            order_size,
+        )?,
+        &[
+            token_account.to_account_info(),
+            buyer_receipt_clone,
+            program_as_signer.to_account_info(),
+            token_clone,
+        ],
+        &[&program_as_signer_seeds],
+    )?;
+
+    if remaining_size > 0 {
+        seller_ts_data[1..SELLER_TRADE_STATE_SIZE].copy_from_slice(&remaining_size.to_le_bytes());
+    } else {
+        let curr_seller_lamp = seller_trade_state.lamports();
+        **seller_trade_state.lamports.borrow_mut() = 0;
+        let seller_ts_len = seller_ts_data.len();
+        sol_memset(&mut *seller_ts_data, 0, seller_ts_len);
+
+        **fee_payer.lamports.borrow_mut() = fee_payer
+            .lamports()
+            .checked_add(curr_seller_lamp)
+            .ok_or(ErrorCode::NumericalOverflow)?;
     }
 
-    /// Cancel an active bid receipt by setting the `canceled_at` field to the current time.
-    pub fn cancel_bid_receipt<'info>(
-        ctx: Context<'_, '_, '_, 'info, CancelBidReceipt<'info>>,
-    ) -> ProgramResult {
-        receipt::cancel_bid_receipt(ctx)
+    let curr_buyer_lamp = buyer_trade_state.lamports();
+    **buyer_trade_state.lamports.borrow_mut() = 0;
+    sol_memset(&mut *buyer_ts_data, 0, TRADE_STATE_SIZE);
+    **fee_payer.lamports.borrow_mut() = fee_payer
+        .lamports()
+        .checked_add(curr_buyer_lamp)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    if let Some(listing_config) = listing_config {
+        close_settled_listing_config(listing_config, seller)?;
     }
 
-    /// Create a purchase receipt by creating a `purchase_receipt` account.
-    pub fn print_purchase_receipt<'info>(
-        ctx: Context<'_, '_, '_, 'info, PrintPurchaseReceipt<'info>>,
-        purchase_receipt_bump: u8,
-    ) -> ProgramResult {
-        receipt::print_purchase_receipt(ctx, purchase_receipt_bump)
+    if remaining_size == 0 && free_trade_state.lamports() > 0 {
+        let curr_buyer_lamp = free_trade_state.lamports();
+        **free_trade_state.lamports.borrow_mut() = 0;
+
+        **fee_payer.lamports.borrow_mut() = fee_payer
+            .lamports()
+            .checked_add(curr_buyer_lamp)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+        let free_trade_state_len = free_trade_state.data_len();
+        sol_memset(
+            *free_trade_state.try_borrow_mut_data()?,
+            0,
+            free_trade_state_len,
+        );
     }
+    Ok(())
 }
 
 /// Accounts for the [`sell` handler](auction_house/fn.sell.html).
@@ -1176,6 +1623,15 @@ pub struct ExecuteSale<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Accounts for the [`execute_collection_sale` handler](auction_house/fn.execute_collection_sale.html).
+#[derive(Accounts)]
+pub struct ExecuteCollectionSale<'info> {
+    /// Sale accounts, with a collection offer as the buyer trade state.
+    pub execute_sale: ExecuteSale<'info>,
+    /// Collection mint account the offer was made on.
+    pub collection_mint: UncheckedAccount<'info>,
+}
+
 /// Accounts for the [`deposit` handler](auction_house/fn.deposit.html).
 #[derive(Accounts)]
 #[instruction(escrow_payment_bump: u8)]
@@ -1320,6 +1776,16 @@ pub struct UpdateAuctionHouse<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Accounts for the [`update_royalty_settings` handler](auction_house/fn.update_royalty_settings.html).
+#[derive(Accounts)]
+pub struct UpdateRoyaltySettings<'info> {
+    /// Authority key for the Auction House.
+    pub authority: Signer<'info>,
+    /// Auction House instance PDA account.
+    #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], bump=auction_house.bump, has_one=authority)]
+    pub auction_house: Account<'info, AuctionHouse>,
+}
+
 /// Accounts for the [`withdraw_from_treasury` handler](auction_house/fn.withdraw_from_treasury.html).
 #[derive(Accounts)]
 pub struct WithdrawFromTreasury<'info> {
@@ -1371,7 +1837,16 @@ pub const AUCTION_HOUSE_SIZE: usize = 8 + // key
 2 + // seller fee basis points
 1 + // requires sign off
 1 + // can change sale price
-220; //padding
+1 + // has auctioneer
+32 + // auctioneer address
+1 + // enforce royalties
+2 + // min royalty basis points
+1 + // pay royalties on free transfers
+8 + // free transfer royalty
+4 + 32 * MAX_ALLOWED_CREATORS + // creator allow list
+11; //padding
+
+pub const MAX_ALLOWED_CREATORS: usize = 5;
 
 #[account]
 pub struct AuctionHouse {
@@ -1388,10 +1863,42 @@ pub struct AuctionHouse {
     pub seller_fee_basis_points: u16,
     pub requires_sign_off: bool,
     pub can_change_sale_price: bool,
+    pub has_auctioneer: bool,
+    pub auctioneer_address: Pubkey,
+    /// Reject sales paying creators less than `min_royalty_basis_points` of the price, which includes tokens with no creators to pay.
+    pub enforce_royalties: bool,
+    pub min_royalty_basis_points: u16,
+    /// Charge the buyer `free_transfer_royalty` for creators on zero-price sales, which the buyer has to sign.
+    pub pay_royalties_on_free_transfers: bool,
+    /// Royalty paid in the treasury mint on zero-price sales, split between creators by share.
+    pub free_transfer_royalty: u64,
+    /// When not empty, only tokens with a verified creator in this list can be sold.
+    pub creator_allow_list: Vec<Pubkey>,
+}
+
+/// Royalty paid to a single creator on a sale.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct CreatorPayout {
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when creator royalties are paid on a sale.
+#[event]
+pub struct RoyaltyPayoutEvent {
+    pub auction_house: Pubkey,
+    pub metadata: Pubkey,
+    pub treasury_mint: Pubkey,
+    /// Sum of all creator amounts.
+    pub total_royalty: u64,
+    pub creators: Vec<CreatorPayout>,
 }
 
 pub const TRADE_STATE_SIZE: usize = 1;
 
+pub const SELLER_TRADE_STATE_SIZE: usize = 1 + // bump
+8; // remaining token size
+
 #[error]
 pub enum ErrorCode {
     #[msg("PublicKeyMismatch")]
@@ -1450,4 +1957,58 @@ pub enum ErrorCode {
     ReceiptIsEmpty,
     #[msg("The instruction does not match")]
     InstructionMismatch,
+    #[msg("The Auction House already has an auctioneer")]
+    AuctioneerAlreadyDelegated,
+    #[msg("An auctioneer requires the Auction House to allow sale price changes")]
+    AuctioneerRequiresSalePriceChanges,
+    #[msg("The listing config account is missing")]
+    MissingListingConfig,
+    #[msg("The auction must end after it starts")]
+    InvalidAuctionWindow,
+    #[msg("The auction has not started")]
+    AuctionNotStarted,
+    #[msg("The auction has ended")]
+    AuctionEnded,
+    #[msg("The auction is still active")]
+    AuctionActive,
+    #[msg("The bid is below the reserve price")]
+    BidBelowReservePrice,
+    #[msg("The bid does not beat the highest bid by the minimum increment")]
+    BidBelowMinimumIncrement,
+    #[msg("Only the highest bid can be matched")]
+    NotHighestBid,
+    #[msg("The seller trade state can only be filled whole")]
+    TradeStateNotPartiallyFillable,
+    #[msg("The partial order price does not match the listing price")]
+    PartialPriceMismatch,
+    #[msg("The token is not part of the offered collection")]
+    CollectionMismatch,
+    #[msg("The collection of the token is not verified")]
+    CollectionNotVerified,
+    #[msg("The royalty of the token is below the Auction House minimum")]
+    RoyaltyBelowMinimum,
+    #[msg("None of the token's verified creators is on the Auction House allow list")]
+    CreatorNotAllowed,
+    #[msg("Too many creators in the allow list")]
+    TooManyAllowedCreators,
+    #[msg("The batch arguments and remaining accounts must have the same length")]
+    BatchLengthMismatch,
+    #[msg("The batch must have at least one item")]
+    EmptyBatch,
+    #[msg("The highest bid of an auction cannot be canceled while its listing is open")]
+    HighestBidCannotBeCanceled,
+    #[msg("The listing config has a bid, its auction has to be settled")]
+    ListingConfigHasBids,
+    #[msg("The escrow cannot go below the highest bids it backs")]
+    EscrowCommittedToHighestBid,
+    #[msg("The listing price does not split evenly across the partial order size")]
+    PartialPriceNotExact,
+    #[msg("The buyer has to sign a free transfer charged a royalty")]
+    FreeTransferNeedsBuyerSignature,
+    #[msg("The escrow commitment account is missing")]
+    MissingEscrowCommitment,
+    #[msg("The Auction House has no auctioneer")]
+    AuctioneerNotDelegated,
+    #[msg("A batch item failed, its index and error are in the program logs")]
+    BatchItemFailed,
 }
@@ -126,3 +126,66 @@ pub fn find_purchase_receipt_address(
         &id(),
     )
 }
+
+/// Return auctioneer scope `Pubkey` address and bump seed.
+pub fn find_auctioneer_address(
+    auction_house: &Pubkey,
+    auctioneer_authority: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            AUCTIONEER.as_bytes(),
+            auction_house.as_ref(),
+            auctioneer_authority.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return listing config `Pubkey` address and bump seed.
+pub fn find_listing_config_address(auction_house: &Pubkey, token_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            LISTING_CONFIG.as_bytes(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return escrow commitment `Pubkey` address and bump seed.
+pub fn find_escrow_commitment_address(auction_house: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ESCROW_COMMITMENT.as_bytes(),
+            auction_house.as_ref(),
+            wallet.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return collection offer trade state `Pubkey` address and bump seed.
+pub fn find_collection_offer_trade_state_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    treasury_mint: &Pubkey,
+    collection_mint: &Pubkey,
+    price: u64,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            COLLECTION_OFFER.as_bytes(),
+            treasury_mint.as_ref(),
+            collection_mint.as_ref(),
+            &price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
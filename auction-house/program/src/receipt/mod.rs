@@ -1,8 +1,9 @@
 //! Create PDAs to to track the status and results of various Auction House actions.
 use crate::{
+    batch::BATCH_SELL_ACCOUNTS,
     constants::*,
     id,
-    instruction::{Buy, ExecuteSale, Sell},
+    instruction::{BatchSell, Buy, ExecutePartialSale, ExecuteSale, Sell},
     utils::*,
     ErrorCode,
 };
@@ -54,7 +55,8 @@ pub const LISTING_RECEIPT_SIZE: usize = 8 + //key
 1 + // bump
 1 + // trade_state_bump
 8 + // created_at
-1 + 8; // canceled_at;
+1 + 8 + // canceled_at
+8; // remaining_size
 
 /// Receipt for a listing transaction.
 #[account]
@@ -71,6 +73,8 @@ pub struct ListingReceipt {
     pub trade_state_bump: u8,
     pub created_at: i64,
     pub canceled_at: Option<i64>,
+    /// Token size left on the listing after partial sales.
+    pub remaining_size: u64,
 }
 
 pub const PURCHASE_RECEIPT_SIZE: usize = 8 + //key
@@ -148,43 +152,175 @@ pub fn print_listing_receipt<'info>(
 
     assert_keys_equal(prev_instruction.program_id, id())?;
 
-    let receipt_info = receipt_account.to_account_info();
+    write_listing_receipt(
+        ctx.program_id,
+        &receipt_account.to_account_info(),
+        bookkeeper_account,
+        rent,
+        system_program,
+        &wallet.pubkey,
+        &auction_house.pubkey,
+        &seller_trade_state.pubkey,
+        &metadata.pubkey,
+        receipt_bump,
+        sell_data.trade_state_bump,
+        sell_data.buyer_price,
+        sell_data.token_size,
+        clock.unix_timestamp,
+    )
+}
+
+/// Accounts for the [`batch_print_listing_receipt` handler](fn.batch_print_listing_receipt.html).
+///
+/// The remaining accounts are the listing receipts, in the order of the listings of the previous `batch_sell` instruction.
+#[derive(Accounts)]
+pub struct BatchPrintListingReceipt<'info> {
+    #[account(mut)]
+    pub bookkeeper: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(address = sysvar::instructions::id())]
+    pub instruction: UncheckedAccount<'info>,
+}
+
+/// Create a Listing Receipt account for each listing of the previous `batch_sell` instruction, with the same seeds as [`print_listing_receipt`](fn.print_listing_receipt.html).
+pub fn batch_print_listing_receipt<'info>(
+    ctx: Context<'_, '_, '_, 'info, BatchPrintListingReceipt<'info>>,
+    receipt_bumps: Vec<u8>,
+) -> ProgramResult {
+    let instruction_account = &ctx.accounts.instruction;
+    let bookkeeper_account = &ctx.accounts.bookkeeper;
+
+    let rent = &ctx.accounts.rent;
+    let system_program = &ctx.accounts.system_program;
+    let clock = Clock::get()?;
+
+    let prev_instruction = get_instruction_relative(-1, instruction_account)?;
+
+    assert_program_instruction_equal(
+        &prev_instruction.data[..8],
+        [243, 223, 17, 41, 61, 65, 242, 118],
+    )?;
+
+    assert_keys_equal(prev_instruction.program_id, id())?;
+
+    let mut buffer = &prev_instruction.data[8..];
+    let sell_data = BatchSell::deserialize(&mut buffer)?;
+
+    let size = receipt_bumps.len();
+    if sell_data.trade_state_bumps.len() != size {
+        return Err(ErrorCode::BatchLengthMismatch.into());
+    }
+    let receipts = split_batch_accounts(ctx.remaining_accounts, size, 1)?[0];
+    let listings =
+        split_batch_accounts(&prev_instruction.accounts[BATCH_SELL_ACCOUNTS..], size, 4)?;
+
+    let wallet = &prev_instruction.accounts[0];
+    let auction_house = &prev_instruction.accounts[2];
+
+    for index in 0..size {
+        write_listing_receipt(
+            ctx.program_id,
+            &receipts[index],
+            bookkeeper_account,
+            rent,
+            system_program,
+            &wallet.pubkey,
+            &auction_house.pubkey,
+            &listings[2][index].pubkey,
+            &listings[1][index].pubkey,
+            receipt_bumps[index],
+            sell_data.trade_state_bumps[index],
+            sell_data.buyer_prices[index],
+            sell_data.token_sizes[index],
+            clock.unix_timestamp,
+        )
+        .map_err(|error| batch_item_error(index, error))?;
+    }
+
+    Ok(())
+}
+
+/// Create the listing receipt PDA if it is empty and write the listing into it.
+///
+/// The receipt must be the PDA of `seller_trade_state` at `receipt_bump`, so that an existing account of the program cannot be written over with a listing.
+#[allow(clippy::too_many_arguments)]
+fn write_listing_receipt<'info>(
+    program_id: &Pubkey,
+    receipt_info: &AccountInfo<'info>,
+    bookkeeper_account: &Signer<'info>,
+    rent: &Sysvar<'info, Rent>,
+    system_program: &Program<'info, System>,
+    seller: &Pubkey,
+    auction_house: &Pubkey,
+    seller_trade_state: &Pubkey,
+    metadata: &Pubkey,
+    receipt_bump: u8,
+    trade_state_bump: u8,
+    price: u64,
+    token_size: u64,
+    created_at: i64,
+) -> ProgramResult {
+    let bump = assert_derivation(
+        program_id,
+        receipt_info,
+        &[
+            LISTING_RECEIPT_PREFIX.as_bytes(),
+            seller_trade_state.as_ref(),
+        ],
+    )?;
+    if bump != receipt_bump {
+        return Err(ErrorCode::DerivedKeyInvalid.into());
+    }
 
+    let mut remaining_size = token_size;
+    let mut purchase_receipt = None;
     if receipt_info.data_is_empty() {
         let receipt_seeds = [
             LISTING_RECEIPT_PREFIX.as_bytes(),
-            seller_trade_state.pubkey.as_ref(),
+            seller_trade_state.as_ref(),
             &[receipt_bump],
         ];
 
         create_or_allocate_account_raw(
-            *ctx.program_id,
-            &receipt_info,
+            *program_id,
+            receipt_info,
             &rent.to_account_info(),
-            &system_program,
-            &bookkeeper_account,
+            system_program,
+            bookkeeper_account,
             LISTING_RECEIPT_SIZE,
             &[],
             &receipt_seeds,
         )?;
+    } else {
+        // A receipt printed again for a listing that is still open keeps its partial sales.
+        let listing_receipt =
+            ListingReceipt::try_deserialize(&mut &receipt_info.try_borrow_data()?[..])?;
+        if listing_receipt.canceled_at.is_none()
+            && (listing_receipt.purchase_receipt.is_none() || listing_receipt.remaining_size > 0)
+        {
+            remaining_size = listing_receipt.remaining_size;
+            purchase_receipt = listing_receipt.purchase_receipt;
+        }
     }
 
     let receipt = ListingReceipt {
-        trade_state: seller_trade_state.pubkey,
+        trade_state: *seller_trade_state,
         bookkeeper: bookkeeper_account.key(),
-        auction_house: auction_house.pubkey,
-        seller: wallet.pubkey,
-        metadata: metadata.pubkey,
-        purchase_receipt: None,
-        price: sell_data.buyer_price,
-        token_size: sell_data.token_size,
+        auction_house: *auction_house,
+        seller: *seller,
+        metadata: *metadata,
+        purchase_receipt,
+        price,
+        token_size,
         bump: receipt_bump,
-        trade_state_bump: sell_data.trade_state_bump,
-        created_at: clock.unix_timestamp,
+        trade_state_bump,
+        created_at,
         canceled_at: None,
+        remaining_size,
     };
 
-    receipt.try_serialize(&mut *receipt_account.try_borrow_mut_data()?)?;
+    receipt.try_serialize(&mut *receipt_info.try_borrow_mut_data()?)?;
 
     Ok(())
 }
@@ -410,7 +546,8 @@ pub struct PrintPurchaseReceipt<'info> {
 /// "listing_receipt", <SELLER_TRADE_STATE_PUBKEY>, <BUYER_TRADE_STATE_PUBKEY>.
 ///
 /// The previous instruction is checked to ensure that it is a "Purchase" type to
-/// match the receipt type being created. Passing in an empty account results in the PDA
+/// match the receipt type being created. A listing sold in several partial sales gets one
+/// Purchase Receipt per sale and its Listing Receipt tracks the size left. Passing in an empty account results in the PDA
 /// being created; an existing account will be written over.
 pub fn print_purchase_receipt<'info>(
     ctx: Context<'_, '_, '_, 'info, PrintPurchaseReceipt<'info>>,
@@ -429,12 +566,19 @@ pub fn print_purchase_receipt<'info>(
     let prev_instruction_accounts = prev_instruction.accounts;
 
     let mut buffer = &prev_instruction.data[8..];
-    let execute_sale_data = ExecuteSale::deserialize(&mut buffer)?;
-
-    assert_program_instruction_equal(
-        &prev_instruction.data[..8],
-        [37, 74, 217, 157, 79, 49, 35, 6],
-    )?;
+    let (price, token_size) = match assert_program_sale_instruction(&prev_instruction.data[..8])? {
+        SaleType::FullSale => {
+            let execute_sale_data = ExecuteSale::deserialize(&mut buffer)?;
+            (execute_sale_data.buyer_price, execute_sale_data.token_size)
+        }
+        SaleType::PartialSale => {
+            let execute_partial_sale_data = ExecutePartialSale::deserialize(&mut buffer)?;
+            (
+                execute_partial_sale_data.partial_order_price,
+                execute_partial_sale_data.partial_order_size,
+            )
+        }
+    };
 
     assert_keys_equal(prev_instruction.program_id, id())?;
 
@@ -499,8 +643,8 @@ pub fn print_purchase_receipt<'info>(
         metadata: metadata.pubkey,
         bookkeeper: bookkeeper.key(),
         bump: purchase_receipt_bump,
-        price: execute_sale_data.buyer_price,
-        token_size: execute_sale_data.token_size,
+        price,
+        token_size,
         created_at: timestamp,
     };
 
@@ -511,6 +655,14 @@ pub fn print_purchase_receipt<'info>(
 
     let mut listing_receipt = ListingReceipt::try_deserialize(&mut listing_receipt_data_slice)?;
 
+    // Receipts printed before partial sales read a zero remaining size until their first purchase.
+    if listing_receipt.purchase_receipt.is_none() && listing_receipt.remaining_size == 0 {
+        listing_receipt.remaining_size = listing_receipt.token_size;
+    }
+    listing_receipt.remaining_size = listing_receipt
+        .remaining_size
+        .checked_sub(token_size)
+        .ok_or(ErrorCode::NumericalOverflow)?;
     listing_receipt.purchase_receipt = Some(purchase_receipt_account.key());
 
     listing_receipt.try_serialize(&mut *listing_receipt_data)?;
@@ -1,4 +1,4 @@
-use crate::{AuctionHouse, ErrorCode, PREFIX};
+use crate::{AuctionHouse, CreatorPayout, ErrorCode, RoyaltyPayoutEvent, PREFIX};
 use anchor_lang::{
     prelude::*,
     solana_program::{
@@ -10,7 +10,7 @@ use anchor_lang::{
 };
 use anchor_spl::token::{Mint, Token, TokenAccount};
 use arrayref::array_ref;
-use metaplex_token_metadata::state::Metadata;
+use mpl_token_metadata::state::{Creator, Metadata};
 use spl_associated_token_account::get_associated_token_address;
 use spl_token::{instruction::initialize_account2, state::Account as SplAccount};
 use std::{convert::TryInto, slice::Iter};
@@ -181,6 +181,19 @@ pub fn assert_program_bid_instruction(sighash: &[u8]) -> Result<BidType, ErrorCo
     }
 }
 
+pub enum SaleType {
+    FullSale,
+    PartialSale,
+}
+
+pub fn assert_program_sale_instruction(sighash: &[u8]) -> Result<SaleType, ErrorCode> {
+    match sighash {
+        [37, 74, 217, 157, 79, 49, 35, 6] => Ok(SaleType::FullSale),
+        [163, 18, 35, 157, 49, 164, 203, 133] => Ok(SaleType::PartialSale),
+        _ => Err(ErrorCode::InstructionMismatch.into()),
+    }
+}
+
 pub fn assert_program_instruction_equal(
     sighash: &[u8],
     expected_sighash: [u8; 8],
@@ -310,9 +323,36 @@ pub fn create_program_token_account_if_not_present<'a>(
     Ok(())
 }
 
+/// Checks the royalty of a token against the royalty policy of the Auction House.
+pub fn assert_royalty_policy(
+    auction_house: &AuctionHouse,
+    seller_fee_basis_points: u16,
+    creators: &Option<Vec<Creator>>,
+) -> ProgramResult {
+    if auction_house.enforce_royalties
+        && seller_fee_basis_points < auction_house.min_royalty_basis_points
+    {
+        return Err(ErrorCode::RoyaltyBelowMinimum.into());
+    }
+    if !auction_house.creator_allow_list.is_empty() {
+        let allowed = creators.iter().flatten().any(|creator| {
+            creator.verified && auction_house.creator_allow_list.contains(&creator.address)
+        });
+        if !allowed {
+            return Err(ErrorCode::CreatorNotAllowed.into());
+        }
+    }
+    Ok(())
+}
+
+/// Pays the creators their royalty out of the escrow and returns what is left for the seller.
+///
+/// On zero-price sales of an Auction House paying royalties on free transfers, the buyer is charged the `free_transfer_royalty` on top of the price.
+/// When the Auction House enforces royalties, the creators must be paid at least `min_royalty_basis_points` of `size`.
 #[allow(clippy::too_many_arguments)]
 pub fn pay_creator_fees<'a>(
     remaining_accounts: &mut Iter<AccountInfo<'a>>,
+    auction_house: &anchor_lang::prelude::Account<'a, AuctionHouse>,
     metadata_info: &AccountInfo<'a>,
     escrow_payment_account: &AccountInfo<'a>,
     payment_account_owner: &AccountInfo<'a>,
@@ -329,15 +369,30 @@ pub fn pay_creator_fees<'a>(
 ) -> Result<u64, ProgramError> {
     let metadata = Metadata::from_account_info(metadata_info)?;
     let fees = metadata.data.seller_fee_basis_points;
-    let total_fee = (fees as u128)
-        .checked_mul(size as u128)
-        .ok_or(ErrorCode::NumericalOverflow)?
-        .checked_div(10000)
-        .ok_or(ErrorCode::NumericalOverflow)? as u64;
+    assert_royalty_policy(auction_house, fees, &metadata.data.creators)?;
+    let free_transfer = size == 0 && auction_house.pay_royalties_on_free_transfers;
+    let total_fee = if free_transfer {
+        auction_house.free_transfer_royalty
+    } else {
+        (fees as u128)
+            .checked_mul(size as u128)
+            .ok_or(ErrorCode::NumericalOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::NumericalOverflow)? as u64
+    };
     let mut remaining_fee = total_fee;
-    let remaining_size = size
-        .checked_sub(total_fee)
-        .ok_or(ErrorCode::NumericalOverflow)?;
+    let remaining_size = if free_transfer {
+        0
+    } else {
+        size.checked_sub(total_fee)
+            .ok_or(ErrorCode::NumericalOverflow)?
+    };
+    let mut payouts = Vec::new();
+    let has_creators = metadata
+        .data
+        .creators
+        .as_ref()
+        .map_or(false, |creators| !creators.is_empty());
     match metadata.data.creators {
         Some(creators) => {
             for creator in creators {
@@ -406,13 +461,42 @@ pub fn pay_creator_fees<'a>(
                         &[signer_seeds],
                     )?;
                 }
+                payouts.push(CreatorPayout {
+                    creator: creator.address,
+                    amount: creator_fee,
+                });
             }
         }
         None => {
             msg!("No creators found in metadata");
         }
     }
-    // Any dust is returned to the party posting the NFT
+    let total_royalty = total_fee
+        .checked_sub(remaining_fee)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    if auction_house.enforce_royalties {
+        let min_royalty = (auction_house.min_royalty_basis_points as u128)
+            .checked_mul(size as u128)
+            .ok_or(ErrorCode::NumericalOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+        // The dust of splitting the royalty between creators does not count against the minimum
+        let royalty_paid = if has_creators { total_fee } else { 0 };
+        if (royalty_paid as u128) < min_royalty {
+            return Err(ErrorCode::RoyaltyBelowMinimum.into());
+        }
+    }
+    emit!(RoyaltyPayoutEvent {
+        auction_house: auction_house.key(),
+        metadata: metadata_info.key(),
+        treasury_mint: treasury_mint.key(),
+        total_royalty,
+        creators: payouts,
+    });
+    // Any dust is returned to the party posting the NFT, except on free transfers where it is never charged
+    if free_transfer {
+        return Ok(remaining_size);
+    }
     Ok(remaining_size
         .checked_add(remaining_fee)
         .ok_or(ErrorCode::NumericalOverflow)?)
@@ -576,3 +660,24 @@ pub fn assert_valid_trade_state<'a>(
         _ => Err(ErrorCode::DerivedKeyInvalid.into()),
     }
 }
+
+/// Splits the accounts of a batch instruction into `arrays` parallel arrays of `size` accounts each.
+pub fn split_batch_accounts<T>(
+    accounts: &[T],
+    size: usize,
+    arrays: usize,
+) -> Result<Vec<&[T]>, ProgramError> {
+    if size == 0 {
+        return Err(ErrorCode::EmptyBatch.into());
+    }
+    if accounts.len() != size * arrays {
+        return Err(ErrorCode::BatchLengthMismatch.into());
+    }
+    Ok(accounts.chunks(size).collect())
+}
+
+/// Logs the index and error of the batch item that failed and fails with `BatchItemFailed`.
+pub fn batch_item_error(index: usize, error: ProgramError) -> ProgramError {
+    msg!("Batch item {} failed: {}", index, error);
+    ErrorCode::BatchItemFailed.into()
+}
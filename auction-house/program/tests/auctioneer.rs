@@ -0,0 +1,966 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use mpl_auction_house::{
+    auctioneer::{EscrowCommitment, HighestBid, ListingConfig},
+    pda::{
+        find_auctioneer_address, find_escrow_commitment_address, find_escrow_payment_address,
+        find_listing_config_address, find_program_as_signer_address, find_trade_state_address,
+    },
+    AuctionHouse, ErrorCode,
+};
+use mpl_testing_utils::{assert_error, solana::airdrop, utils::Metadata};
+use solana_program::program_pack::Pack;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    sysvar::{self, clock::Clock},
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use spl_associated_token_account::get_associated_token_address;
+use std::assert_eq;
+use utils::setup_functions::*;
+
+async fn get_listing_config(context: &mut ProgramTestContext, key: &Pubkey) -> ListingConfig {
+    let account = context
+        .banks_client
+        .get_account(*key)
+        .await
+        .expect("Error Getting Listing Config")
+        .expect("Listing Config Empty");
+    ListingConfig::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+async fn funded_bidder(context: &mut ProgramTestContext) -> Keypair {
+    let bidder = Keypair::new();
+    airdrop(context, &bidder.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    bidder
+}
+
+/// Cancel the trade state of `wallet` on the auctioned token account.
+fn cancel(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    trade_state: &Pubkey,
+    wallet: &Keypair,
+    price: u64,
+    auction_accounts: Vec<AccountMeta>,
+) -> Transaction {
+    let token_account =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let mut accounts = mpl_auction_house::accounts::Cancel {
+        wallet: wallet.pubkey(),
+        token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        trade_state: *trade_state,
+        token_program: spl_token::id(),
+    }
+    .to_account_metas(None);
+    accounts.extend(auction_accounts);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Cancel {
+            buyer_price: price,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&wallet.pubkey()),
+        &[wallet],
+        context.last_blockhash,
+    )
+}
+
+fn withdraw(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    wallet: &Keypair,
+    amount: u64,
+) -> Transaction {
+    let (escrow_payment_account, escrow_payment_bump) =
+        find_escrow_payment_address(ahkey, &wallet.pubkey());
+    let (escrow_commitment, _) = find_escrow_commitment_address(ahkey, &wallet.pubkey());
+    let mut accounts = mpl_auction_house::accounts::Withdraw {
+        wallet: wallet.pubkey(),
+        receipt_account: wallet.pubkey(),
+        escrow_payment_account,
+        treasury_mint: ah.treasury_mint,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(escrow_commitment, false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Withdraw {
+            escrow_payment_bump,
+            amount,
+        }
+        .data(),
+        accounts,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&wallet.pubkey()),
+        &[wallet],
+        context.last_blockhash,
+    )
+}
+
+async fn get_escrow_commitment(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    wallet: &Pubkey,
+) -> u64 {
+    let (escrow_commitment, _) = find_escrow_commitment_address(ahkey, wallet);
+    let account = context
+        .banks_client
+        .get_account(escrow_commitment)
+        .await
+        .expect("Error Getting Escrow Commitment")
+        .expect("Escrow Commitment Empty");
+    EscrowCommitment::try_deserialize(&mut account.data.as_ref())
+        .unwrap()
+        .amount
+}
+
+fn close_listing_config(
+    context: &mut ProgramTestContext,
+    test_metadata: &Metadata,
+    listing_config: &Pubkey,
+) -> Transaction {
+    let accounts = mpl_auction_house::accounts::CloseListingConfig {
+        seller: test_metadata.token.pubkey(),
+        listing_config: *listing_config,
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CloseListingConfig {}.data(),
+        accounts,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    )
+}
+
+/// Execute a fixed price sale on an Auction House with an auctioneer, passing the missing listing config of the token account and the escrow commitment of the buyer.
+fn fixed_price_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    sell_acc: &mpl_auction_house::accounts::Sell,
+    buyer: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    price: u64,
+) -> Transaction {
+    let seller = test_metadata.token.pubkey();
+    let ((accounts, _), _) = execute_sale(
+        context,
+        ahkey,
+        ah,
+        authority,
+        test_metadata,
+        buyer,
+        &seller,
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        buyer_trade_state,
+        1,
+        price,
+    );
+    let (_, escrow_payment_bump) = find_escrow_payment_address(ahkey, buyer);
+    let (_, free_trade_state_bump) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, program_as_signer_bump) = find_program_as_signer_address();
+    let (listing_config, _) = find_listing_config_address(ahkey, &sell_acc.token_account);
+    let (escrow_commitment, _) = find_escrow_commitment_address(ahkey, buyer);
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.push(AccountMeta::new(listing_config, false));
+    account_metas.push(AccountMeta::new(escrow_commitment, false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteSale {
+            escrow_payment_bump,
+            _free_trade_state_bump: free_trade_state_bump,
+            program_as_signer_bump,
+            token_size: 1,
+            buyer_price: price,
+        }
+        .data(),
+        accounts: account_metas,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    )
+}
+
+/// Warp until the clock is past `timestamp`.
+async fn warp_past(context: &mut ProgramTestContext, timestamp: i64) {
+    loop {
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        if clock.unix_timestamp > timestamp {
+            break;
+        }
+        context.warp_to_slot(clock.slot + 1_000).unwrap();
+    }
+    context.last_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+}
+
+#[tokio::test]
+async fn auction_bid_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata, listing_config, end_time) =
+        auction_listing_context(&mut context, 0, 0).await;
+
+    let first_bidder = funded_bidder(&mut context).await;
+    let (first_acc, first_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &first_bidder,
+        100_000_000,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(first_tx)
+        .await
+        .unwrap();
+
+    let second_bidder = funded_bidder(&mut context).await;
+    let (second_acc, second_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &second_bidder,
+        110_000_000,
+        Some(&first_bidder.pubkey()),
+    );
+    context
+        .banks_client
+        .process_transaction(second_tx)
+        .await
+        .unwrap();
+
+    let config = get_listing_config(&mut context, &listing_config).await;
+    assert_ne!(first_acc.buyer_trade_state, second_acc.buyer_trade_state);
+    assert_eq!(
+        config.highest_bid,
+        Some(HighestBid {
+            buyer: second_bidder.pubkey(),
+            buyer_trade_state: second_acc.buyer_trade_state,
+            price: 110_000_000,
+        })
+    );
+    assert_eq!(config.end_time, end_time);
+}
+
+#[tokio::test]
+async fn auction_bid_below_reserve_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata, _, _) = auction_listing_context(&mut context, 0, 0).await;
+
+    let bidder = funded_bidder(&mut context).await;
+    let (_, buy_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &bidder,
+        99_999_999,
+        None,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::BidBelowReservePrice as u32 + 6000);
+}
+
+#[tokio::test]
+async fn auction_bid_below_increment_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata, _, _) = auction_listing_context(&mut context, 0, 0).await;
+
+    let first_bidder = funded_bidder(&mut context).await;
+    let (_, first_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &first_bidder,
+        100_000_000,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(first_tx)
+        .await
+        .unwrap();
+
+    let second_bidder = funded_bidder(&mut context).await;
+    let (_, second_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &second_bidder,
+        109_999_999,
+        Some(&first_bidder.pubkey()),
+    );
+    let err = context
+        .banks_client
+        .process_transaction(second_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::BidBelowMinimumIncrement as u32 + 6000);
+}
+
+#[tokio::test]
+async fn auction_late_bid_extends_end_time() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata, listing_config, end_time) =
+        auction_listing_context(&mut context, 7200, 600).await;
+
+    let bidder = funded_bidder(&mut context).await;
+    let (_, buy_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &bidder,
+        100_000_000,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let config = get_listing_config(&mut context, &listing_config).await;
+    assert_eq!(config.end_time, end_time + 600);
+}
+
+#[tokio::test]
+async fn auction_cancel_highest_bid_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata, listing_config, _) =
+        auction_listing_context(&mut context, 0, 0).await;
+
+    let first_bidder = funded_bidder(&mut context).await;
+    let (first_acc, first_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &first_bidder,
+        100_000_000,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(first_tx)
+        .await
+        .unwrap();
+
+    let second_bidder = funded_bidder(&mut context).await;
+    let (second_acc, second_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &second_bidder,
+        110_000_000,
+        Some(&first_bidder.pubkey()),
+    );
+    context
+        .banks_client
+        .process_transaction(second_tx)
+        .await
+        .unwrap();
+
+    // An outbid bid can be canceled.
+    let cancel_tx = cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &first_acc.buyer_trade_state,
+        &first_bidder,
+        100_000_000,
+        vec![AccountMeta::new(listing_config, false)],
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    let cancel_tx = cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &second_acc.buyer_trade_state,
+        &second_bidder,
+        110_000_000,
+        vec![AccountMeta::new(listing_config, false)],
+    );
+    let err = context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::HighestBidCannotBeCanceled as u32 + 6000);
+
+    let config = get_listing_config(&mut context, &listing_config).await;
+    assert_eq!(
+        config.highest_bid,
+        Some(HighestBid {
+            buyer: second_bidder.pubkey(),
+            buyer_trade_state: second_acc.buyer_trade_state,
+            price: 110_000_000,
+        })
+    );
+}
+
+#[tokio::test]
+async fn close_listing_config_with_bid_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata, listing_config, _) =
+        auction_listing_context(&mut context, 0, 0).await;
+
+    let bidder = funded_bidder(&mut context).await;
+    let (_, buy_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &bidder,
+        100_000_000,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let tx = close_listing_config(&mut context, &test_metadata, &listing_config);
+    let err = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::ListingConfigHasBids as u32 + 6000);
+}
+
+#[tokio::test]
+async fn abandoned_auction_releases_the_highest_bid() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata, listing_config, end_time) =
+        auction_listing_context(&mut context, 0, 0).await;
+    let seller = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let (seller_trade_state, _) = find_trade_state_address(
+        &seller,
+        &ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+
+    let bidder = funded_bidder(&mut context).await;
+    let (bid_acc, buy_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &bidder,
+        100_000_000,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    let (escrow_commitment, _) = find_escrow_commitment_address(&ahkey, &bidder.pubkey());
+    let highest_bid_accounts = vec![
+        AccountMeta::new(listing_config, false),
+        AccountMeta::new_readonly(seller_trade_state, false),
+        AccountMeta::new(escrow_commitment, false),
+    ];
+
+    // The listing delegates the token the auction is settled with.
+    let cancel_tx = cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &seller_trade_state,
+        &test_metadata.token,
+        0,
+        vec![AccountMeta::new(listing_config, false)],
+    );
+    let err = context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::AuctionActive as u32 + 6000);
+
+    let cancel_tx = cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &bid_acc.buyer_trade_state,
+        &bidder,
+        100_000_000,
+        highest_bid_accounts.clone(),
+    );
+    let err = context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::HighestBidCannotBeCanceled as u32 + 6000);
+
+    // Once the auction ends the seller can walk away, and so can the bidder.
+    warp_past(&mut context, end_time).await;
+    let cancel_tx = cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &seller_trade_state,
+        &test_metadata.token,
+        0,
+        vec![AccountMeta::new(listing_config, false)],
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    let cancel_tx = cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &bid_acc.buyer_trade_state,
+        &bidder,
+        100_000_000,
+        highest_bid_accounts,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    let config = get_listing_config(&mut context, &listing_config).await;
+    assert_eq!(config.highest_bid, None);
+    assert_eq!(
+        get_escrow_commitment(&mut context, &ahkey, &bidder.pubkey()).await,
+        0
+    );
+
+    let tx = close_listing_config(&mut context, &test_metadata, &listing_config);
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+#[tokio::test]
+async fn auction_withdraw_keeps_highest_bid_funded() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata, _, _) = auction_listing_context(&mut context, 0, 0).await;
+
+    let first_bidder = funded_bidder(&mut context).await;
+    let (_, first_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &first_bidder,
+        100_000_000,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(first_tx)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_escrow_commitment(&mut context, &ahkey, &first_bidder.pubkey()).await,
+        100_000_000
+    );
+
+    let withdraw_tx = withdraw(&mut context, &ahkey, &ah, &first_bidder, 1);
+    let err = context
+        .banks_client
+        .process_transaction(withdraw_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::EscrowCommittedToHighestBid as u32 + 6000);
+
+    // Being outbid releases the escrow.
+    let second_bidder = funded_bidder(&mut context).await;
+    let (_, second_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &second_bidder,
+        110_000_000,
+        Some(&first_bidder.pubkey()),
+    );
+    context
+        .banks_client
+        .process_transaction(second_tx)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_escrow_commitment(&mut context, &ahkey, &first_bidder.pubkey()).await,
+        0
+    );
+    assert_eq!(
+        get_escrow_commitment(&mut context, &ahkey, &second_bidder.pubkey()).await,
+        110_000_000
+    );
+
+    let withdraw_tx = withdraw(&mut context, &ahkey, &ah, &first_bidder, 100_000_000);
+    context
+        .banks_client
+        .process_transaction(withdraw_tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn simultaneous_highest_bids_are_both_funded() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _, auctioneer_authority) = existing_auctioneer_test_context(&mut context)
+        .await
+        .unwrap();
+    let end_time = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+        + 3600;
+
+    let bidder = funded_bidder(&mut context).await;
+    for _ in 0..2 {
+        let (test_metadata, _, _, _) =
+            listed_metadata(&mut context, &ahkey, &ah, 0, 1, 10, None).await;
+        create_listing_config(
+            &mut context,
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &auctioneer_authority,
+            0,
+            end_time,
+            100_000_000,
+            10_000_000,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+        let (_, buy_tx) = auction_buy(
+            &mut context,
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &bidder,
+            100_000_000,
+            None,
+        );
+        context
+            .banks_client
+            .process_transaction(buy_tx)
+            .await
+            .unwrap();
+    }
+
+    // Each highest bid is funded on top of the one the escrow already backs.
+    assert_eq!(
+        get_escrow_commitment(&mut context, &ahkey, &bidder.pubkey()).await,
+        200_000_000
+    );
+    let (escrow_payment_account, _) = find_escrow_payment_address(&ahkey, &bidder.pubkey());
+    let escrow = context
+        .banks_client
+        .get_account(escrow_payment_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(escrow.lamports, 200_000_000);
+}
+
+#[tokio::test]
+async fn fixed_price_sale_with_auctioneer_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority, _) = existing_auctioneer_test_context(&mut context)
+        .await
+        .unwrap();
+    let (test_metadata, _, sell_acc, _) =
+        listed_metadata(&mut context, &ahkey, &ah, 100_000_000, 1, 10, None).await;
+
+    // Without a listing config the bid follows the usual rules and commits no escrow.
+    let buyer = funded_bidder(&mut context).await;
+    let (bid_acc, buy_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer,
+        100_000_000,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    let (escrow_commitment, _) = find_escrow_commitment_address(&ahkey, &buyer.pubkey());
+    assert!(context
+        .banks_client
+        .get_account(escrow_commitment)
+        .await
+        .unwrap()
+        .is_none());
+
+    let sale_tx = fixed_price_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &sell_acc,
+        &buyer.pubkey(),
+        &bid_acc.buyer_trade_state,
+        100_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    let buyer_token_account =
+        get_associated_token_address(&buyer.pubkey(), &test_metadata.mint.pubkey());
+    let buyer_token = context
+        .banks_client
+        .get_account(buyer_token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let buyer_token = spl_token::state::Account::unpack(&buyer_token.data).unwrap();
+    assert_eq!(buyer_token.amount, 1);
+}
+
+#[tokio::test]
+async fn undelegate_auctioneer_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority, auctioneer_authority) =
+        existing_auctioneer_test_context(&mut context)
+            .await
+            .unwrap();
+
+    undelegate_auctioneer(
+        &mut context,
+        &ahkey,
+        &authority,
+        &auctioneer_authority.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    let auction_house_acc = context
+        .banks_client
+        .get_account(ahkey)
+        .await
+        .unwrap()
+        .expect("account empty");
+    let auction_house =
+        AuctionHouse::try_deserialize(&mut auction_house_acc.data.as_ref()).unwrap();
+    assert_eq!(auction_house.has_auctioneer, false);
+    assert_eq!(auction_house.auctioneer_address, Pubkey::default());
+    let (ah_auctioneer_pda, _) = find_auctioneer_address(&ahkey, &auctioneer_authority.pubkey());
+    assert!(context
+        .banks_client
+        .get_account(ah_auctioneer_pda)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Bids no longer expect auction accounts.
+    let (test_metadata, _, _, _) =
+        listed_metadata(&mut context, &ahkey, &ah, 100_000_000, 1, 10, None).await;
+    let buyer = funded_bidder(&mut context).await;
+    let owner = test_metadata.token.pubkey();
+    let (_, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &owner,
+        &buyer,
+        100_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let err = undelegate_auctioneer(
+        &mut context,
+        &ahkey,
+        &authority,
+        &auctioneer_authority.pubkey(),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        TransportError::TransactionError(TransactionError::InstructionError(0, _))
+    ));
+}
+
+#[tokio::test]
+async fn fixed_price_sale_cannot_spend_committed_escrow() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority, auctioneer_authority) =
+        existing_auctioneer_test_context(&mut context)
+            .await
+            .unwrap();
+    let (test_metadata, _, sell_acc, _) =
+        listed_metadata(&mut context, &ahkey, &ah, 100_000_000, 1, 10, None).await;
+    let (auctioned_metadata, _, _, _) =
+        listed_metadata(&mut context, &ahkey, &ah, 0, 1, 10, None).await;
+    let end_time = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+        + 3600;
+    create_listing_config(
+        &mut context,
+        &ahkey,
+        &ah,
+        &auctioned_metadata,
+        &auctioneer_authority,
+        0,
+        end_time,
+        100_000_000,
+        10_000_000,
+        0,
+        0,
+    )
+    .await
+    .unwrap();
+
+    // The fixed price bid funds the escrow before the highest bid commits it.
+    let buyer = funded_bidder(&mut context).await;
+    let (bid_acc, buy_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer,
+        100_000_000,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    let (_, auction_tx) = auction_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &auctioned_metadata,
+        &buyer,
+        100_000_000,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(auction_tx)
+        .await
+        .unwrap();
+
+    let sale_tx = fixed_price_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &sell_acc,
+        &buyer.pubkey(),
+        &bid_acc.buyer_trade_state,
+        100_000_000,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::EscrowCommittedToHighestBid as u32 + 6000);
+}
@@ -0,0 +1,218 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+use anchor_lang::AccountDeserialize;
+
+use mpl_auction_house::{pda::find_listing_receipt_address, receipt::ListingReceipt, ErrorCode};
+use mpl_testing_utils::assert_error;
+use solana_program::program_pack::Pack;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError, signer::Signer, transaction::TransactionError,
+    transport::TransportError,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account;
+use std::assert_eq;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn batch_sell_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, wallet, test_metadata) = batch_listing_context(&mut context, 2).await;
+
+    let (seller_trade_states, sell_tx) =
+        batch_sell(&mut context, &ahkey, &ah, &wallet, &test_metadata, 100);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    for (metadata, seller_trade_state) in test_metadata.iter().zip(seller_trade_states.iter()) {
+        let sts = context
+            .banks_client
+            .get_account(*seller_trade_state)
+            .await
+            .expect("Error Getting Trade State")
+            .expect("Trade State Empty");
+        assert_eq!(sts.data.len(), 9);
+        assert_eq!(sts.data[1..9], 1u64.to_le_bytes());
+
+        let token = get_associated_token_address(&wallet.pubkey(), &metadata.mint.pubkey());
+        let token_account = Account::unpack_from_slice(
+            context
+                .banks_client
+                .get_account(token)
+                .await
+                .unwrap()
+                .unwrap()
+                .data
+                .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(token_account.delegated_amount, 1);
+
+        let (listing_receipt, _) = find_listing_receipt_address(seller_trade_state);
+        let listing_receipt_account = context
+            .banks_client
+            .get_account(listing_receipt)
+            .await
+            .expect("getting listing receipt")
+            .expect("empty listing receipt data");
+        let listing_receipt =
+            ListingReceipt::try_deserialize(&mut listing_receipt_account.data.as_ref()).unwrap();
+        assert_eq!(listing_receipt.trade_state, *seller_trade_state);
+        assert_eq!(listing_receipt.auction_house, ahkey);
+        assert_eq!(listing_receipt.metadata, metadata.pubkey);
+        assert_eq!(listing_receipt.seller, wallet.pubkey());
+        assert_eq!(listing_receipt.bookkeeper, wallet.pubkey());
+        assert_eq!(listing_receipt.price, 100);
+        assert_eq!(listing_receipt.token_size, 1);
+    }
+}
+
+#[tokio::test]
+async fn batch_cancel_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, wallet, test_metadata) = batch_listing_context(&mut context, 2).await;
+    let (seller_trade_states, sell_tx) =
+        batch_sell(&mut context, &ahkey, &ah, &wallet, &test_metadata, 100);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let cancel_tx = batch_cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &wallet,
+        &test_metadata,
+        &seller_trade_states,
+        100,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    for seller_trade_state in seller_trade_states.iter() {
+        let sts = context
+            .banks_client
+            .get_account(*seller_trade_state)
+            .await
+            .unwrap();
+        assert_eq!(sts.is_none(), true);
+    }
+}
+
+#[tokio::test]
+async fn batch_cancel_invalid_item_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, wallet, test_metadata) = batch_listing_context(&mut context, 2).await;
+    let (seller_trade_states, sell_tx) =
+        batch_sell(&mut context, &ahkey, &ah, &wallet, &test_metadata, 100);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // The second trade state does not belong to the second token.
+    let cancel_tx = batch_cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &wallet,
+        &test_metadata,
+        &[seller_trade_states[0], seller_trade_states[0]],
+        100,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::BatchItemFailed as u32 + 6000);
+
+    // The batch is atomic so the first listing is still open.
+    let sts = context
+        .banks_client
+        .get_account(seller_trade_states[0])
+        .await
+        .unwrap();
+    assert_eq!(sts.is_some(), true);
+}
+
+#[tokio::test]
+async fn batch_cancel_length_mismatch_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, wallet, test_metadata) = batch_listing_context(&mut context, 2).await;
+    let (seller_trade_states, sell_tx) =
+        batch_sell(&mut context, &ahkey, &ah, &wallet, &test_metadata, 100);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let cancel_tx = batch_cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &wallet,
+        &test_metadata,
+        &seller_trade_states[..1],
+        100,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::BatchLengthMismatch as u32 + 6000);
+}
+
+#[tokio::test]
+async fn batch_print_foreign_listing_receipt_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, wallet, test_metadata) = batch_listing_context(&mut context, 2).await;
+    let (first_trade_states, first_sell_tx) =
+        batch_sell(&mut context, &ahkey, &ah, &wallet, &test_metadata[..1], 100);
+    context
+        .banks_client
+        .process_transaction(first_sell_tx)
+        .await
+        .unwrap();
+
+    // The receipt of the first listing is passed for the second one.
+    let (first_receipt, _) = find_listing_receipt_address(&first_trade_states[0]);
+    let (_, second_sell_tx) = batch_sell_with_receipts(
+        &mut context,
+        &ahkey,
+        &ah,
+        &wallet,
+        &test_metadata[1..],
+        100,
+        Some(&[first_receipt]),
+    );
+    let err = context
+        .banks_client
+        .process_transaction(second_sell_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::BatchItemFailed as u32 + 6000);
+
+    let listing_receipt_account = context
+        .banks_client
+        .get_account(first_receipt)
+        .await
+        .expect("getting listing receipt")
+        .expect("empty listing receipt data");
+    let listing_receipt =
+        ListingReceipt::try_deserialize(&mut listing_receipt_account.data.as_ref()).unwrap();
+    assert_eq!(listing_receipt.trade_state, first_trade_states[0]);
+    assert_eq!(listing_receipt.metadata, test_metadata[0].pubkey);
+}
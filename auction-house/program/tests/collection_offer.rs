@@ -0,0 +1,136 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_auction_house::ErrorCode;
+use mpl_testing_utils::{assert_error, solana::airdrop};
+use solana_program::program_pack::Pack;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError, signature::Keypair, signer::Signer,
+    transaction::TransactionError, transport::TransportError,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account;
+use std::assert_eq;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn execute_collection_sale_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (test_metadata, collection_mint, sell_acc, _) =
+        listed_metadata(&mut context, &ahkey, &ah, 100_000_000, 1, 10, Some(true)).await;
+    let collection_mint = collection_mint.unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let (bid_acc, buy_tx) = collection_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &collection_mint,
+        &buyer,
+        100_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (_, sale_tx) = execute_collection_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        &collection_mint,
+        100_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    let buyer_token_account =
+        get_associated_token_address(&buyer.pubkey(), &test_metadata.mint.pubkey());
+    let buyer_token_after = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(buyer_token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token_after.amount, 1);
+
+    let sts = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .unwrap();
+    assert_eq!(sts.is_none(), true);
+}
+
+#[tokio::test]
+async fn execute_collection_sale_unverified_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (test_metadata, collection_mint, sell_acc, _) =
+        listed_metadata(&mut context, &ahkey, &ah, 100_000_000, 1, 10, Some(false)).await;
+    let collection_mint = collection_mint.unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let (bid_acc, buy_tx) = collection_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &collection_mint,
+        &buyer,
+        100_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (_, sale_tx) = execute_collection_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        &collection_mint,
+        100_000_000,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::CollectionNotVerified as u32 + 6000);
+}
@@ -0,0 +1,345 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use anchor_lang::AccountDeserialize;
+use mpl_auction_house::{receipt::ListingReceipt, ErrorCode};
+use mpl_testing_utils::{assert_error, solana::airdrop, utils::Metadata};
+use solana_program::program_pack::Pack;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError, pubkey::Pubkey, signature::Keypair, signer::Signer,
+    transaction::TransactionError, transport::TransportError,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account;
+use std::assert_eq;
+use utils::setup_functions::*;
+
+async fn partial_buyer(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &mpl_auction_house::AuctionHouse,
+    test_metadata: &Metadata,
+    price: u64,
+    token_size: u64,
+) -> (Keypair, mpl_auction_house::accounts::Buy) {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) = buy_tokens(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        price,
+        token_size,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    (buyer, bid_acc)
+}
+
+#[tokio::test]
+async fn execute_partial_sale_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (test_metadata, _, sell_acc, listing_receipt_acc) =
+        listed_metadata(&mut context, &ahkey, &ah, 1_000_000_000, 10, 10, None).await;
+
+    let (first_buyer, first_bid) =
+        partial_buyer(&mut context, &ahkey, &ah, &test_metadata, 400_000_000, 4).await;
+    let (_, first_sale_tx) = execute_partial_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &first_buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &first_bid.buyer_trade_state,
+        10,
+        1_000_000_000,
+        4,
+        400_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(first_sale_tx)
+        .await
+        .unwrap();
+
+    let sts = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .expect("Error Getting Trade State")
+        .expect("Trade State Empty");
+    assert_eq!(sts.data[1..9], 6u64.to_le_bytes());
+
+    let listing_receipt_account = context
+        .banks_client
+        .get_account(listing_receipt_acc.receipt)
+        .await
+        .expect("getting listing receipt")
+        .expect("empty listing receipt data");
+    let listing_receipt =
+        ListingReceipt::try_deserialize(&mut listing_receipt_account.data.as_ref()).unwrap();
+    assert_eq!(listing_receipt.token_size, 10);
+    assert_eq!(listing_receipt.remaining_size, 6);
+
+    let (second_buyer, second_bid) =
+        partial_buyer(&mut context, &ahkey, &ah, &test_metadata, 600_000_000, 6).await;
+    let ((_, second_receipt_acc), second_sale_tx) = execute_partial_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &second_buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &second_bid.buyer_trade_state,
+        10,
+        1_000_000_000,
+        6,
+        600_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(second_sale_tx)
+        .await
+        .unwrap();
+
+    let sts = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .unwrap();
+    assert_eq!(sts.is_none(), true);
+
+    let second_buyer_token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(get_associated_token_address(
+                &second_buyer.pubkey(),
+                &test_metadata.mint.pubkey(),
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(second_buyer_token.amount, 6);
+
+    let listing_receipt_account = context
+        .banks_client
+        .get_account(listing_receipt_acc.receipt)
+        .await
+        .expect("getting listing receipt")
+        .expect("empty listing receipt data");
+    let listing_receipt =
+        ListingReceipt::try_deserialize(&mut listing_receipt_account.data.as_ref()).unwrap();
+    assert_eq!(listing_receipt.remaining_size, 0);
+    assert_eq!(
+        listing_receipt.purchase_receipt,
+        Some(second_receipt_acc.purchase_receipt)
+    );
+}
+
+#[tokio::test]
+async fn execute_partial_sale_price_mismatch_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (test_metadata, _, sell_acc, _) =
+        listed_metadata(&mut context, &ahkey, &ah, 1_000_000_000, 10, 10, None).await;
+
+    let (buyer, bid_acc) =
+        partial_buyer(&mut context, &ahkey, &ah, &test_metadata, 300_000_000, 4).await;
+    let (_, sale_tx) = execute_partial_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        10,
+        1_000_000_000,
+        4,
+        300_000_000,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::PartialPriceMismatch as u32 + 6000);
+}
+
+#[tokio::test]
+async fn execute_partial_sale_rounded_price_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (test_metadata, _, sell_acc, _) =
+        listed_metadata(&mut context, &ahkey, &ah, 99, 100, 10, None).await;
+
+    // One token of 100 listed at 99 would round down to a free token.
+    let (buyer, bid_acc) = partial_buyer(&mut context, &ahkey, &ah, &test_metadata, 0, 1).await;
+    let (_, sale_tx) = execute_partial_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        100,
+        99,
+        1,
+        0,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::PartialPriceNotExact as u32 + 6000);
+}
+
+#[tokio::test]
+async fn execute_partial_sale_after_sell_is_sent_again() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (test_metadata, _, sell_acc, listing_receipt_acc) =
+        listed_metadata(&mut context, &ahkey, &ah, 1_000_000_000, 10, 10, None).await;
+
+    let (first_buyer, first_bid) =
+        partial_buyer(&mut context, &ahkey, &ah, &test_metadata, 400_000_000, 4).await;
+    let (_, first_sale_tx) = execute_partial_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &first_buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &first_bid.buyer_trade_state,
+        10,
+        1_000_000_000,
+        4,
+        400_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(first_sale_tx)
+        .await
+        .unwrap();
+
+    // The listing is sent again with a fresh blockhash, along with its receipt.
+    context.last_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let (_, sell_tx) = sell_tokens(&mut context, &ahkey, &ah, &test_metadata, 1_000_000_000, 10);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let sts = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .expect("Error Getting Trade State")
+        .expect("Trade State Empty");
+    assert_eq!(sts.data[1..9], 6u64.to_le_bytes());
+
+    let seller_token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(sell_acc.token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(seller_token.delegated_amount, 6);
+
+    let listing_receipt_account = context
+        .banks_client
+        .get_account(listing_receipt_acc.receipt)
+        .await
+        .expect("getting listing receipt")
+        .expect("empty listing receipt data");
+    let listing_receipt =
+        ListingReceipt::try_deserialize(&mut listing_receipt_account.data.as_ref()).unwrap();
+    assert_eq!(listing_receipt.remaining_size, 6);
+
+    let (second_buyer, second_bid) =
+        partial_buyer(&mut context, &ahkey, &ah, &test_metadata, 600_000_000, 6).await;
+    let (_, second_sale_tx) = execute_partial_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &second_buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &second_bid.buyer_trade_state,
+        10,
+        1_000_000_000,
+        6,
+        600_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(second_sale_tx)
+        .await
+        .unwrap();
+
+    let sts = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .unwrap();
+    assert!(sts.is_none());
+
+    let listing_receipt_account = context
+        .banks_client
+        .get_account(listing_receipt_acc.receipt)
+        .await
+        .expect("getting listing receipt")
+        .expect("empty listing receipt data");
+    let listing_receipt =
+        ListingReceipt::try_deserialize(&mut listing_receipt_account.data.as_ref()).unwrap();
+    assert_eq!(listing_receipt.remaining_size, 0);
+}
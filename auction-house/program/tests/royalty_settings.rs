@@ -0,0 +1,158 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_auction_house::ErrorCode;
+use mpl_testing_utils::assert_error;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError, pubkey::Pubkey, transaction::TransactionError,
+    transport::TransportError,
+};
+use std::assert_eq;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn update_royalty_settings_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    assert_eq!(ah.enforce_royalties, false);
+    assert_eq!(ah.creator_allow_list.is_empty(), true);
+
+    let allowed_creator = Pubkey::new_unique();
+    let ah = update_royalty_settings(
+        &mut context,
+        &ahkey,
+        &authority,
+        Some(true),
+        Some(500),
+        Some(true),
+        Some(1_000_000),
+        Some(vec![allowed_creator]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(ah.enforce_royalties, true);
+    assert_eq!(ah.min_royalty_basis_points, 500);
+    assert_eq!(ah.pay_royalties_on_free_transfers, true);
+    assert_eq!(ah.free_transfer_royalty, 1_000_000);
+    assert_eq!(ah.creator_allow_list, vec![allowed_creator]);
+}
+
+#[tokio::test]
+async fn execute_sale_royalty_below_minimum_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let ah = update_royalty_settings(
+        &mut context,
+        &ahkey,
+        &authority,
+        Some(true),
+        Some(500),
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let sale_tx = sale_with_settings(&mut context, &ah, &ahkey, &authority, 10, 100_000_000).await;
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::RoyaltyBelowMinimum as u32 + 6000);
+}
+
+#[tokio::test]
+async fn execute_sale_creator_not_allowed_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let ah = update_royalty_settings(
+        &mut context,
+        &ahkey,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        Some(vec![Pubkey::new_unique()]),
+    )
+    .await
+    .unwrap();
+
+    let sale_tx = sale_with_settings(&mut context, &ah, &ahkey, &authority, 10, 100_000_000).await;
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::CreatorNotAllowed as u32 + 6000);
+}
+
+#[tokio::test]
+async fn execute_sale_no_creators_to_pay_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let ah = update_royalty_settings(
+        &mut context,
+        &ahkey,
+        &authority,
+        Some(true),
+        Some(500),
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // A royalty above the minimum does not count when the token has no creators to pay.
+    let sale_tx =
+        sale_with_settings(&mut context, &ah, &ahkey, &authority, 1000, 100_000_000).await;
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, ErrorCode::RoyaltyBelowMinimum as u32 + 6000);
+}
+
+#[tokio::test]
+async fn execute_free_transfer_without_buyer_signature_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let ah = update_royalty_settings(
+        &mut context,
+        &ahkey,
+        &authority,
+        None,
+        None,
+        Some(true),
+        Some(1_000_000),
+        None,
+    )
+    .await
+    .unwrap();
+
+    let sale_tx = sale_with_settings(&mut context, &ah, &ahkey, &authority, 10, 0).await;
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(
+        err,
+        ErrorCode::FreeTransferNeedsBuyerSignature as u32 + 6000
+    );
+}
@@ -46,7 +46,8 @@ async fn sell_success() {
         .await
         .expect("Error Getting Trade State")
         .expect("Trade State Empty");
-    assert_eq!(sts.data.len(), 1);
+    assert_eq!(sts.data.len(), 9);
+    assert_eq!(sts.data[1..9], 1u64.to_le_bytes());
 
     let timestamp = context
         .banks_client
@@ -75,6 +76,7 @@ async fn sell_success() {
     assert_eq!(listing_receipt.seller, *owner_pubkey);
     assert_eq!(listing_receipt.price, 1);
     assert_eq!(listing_receipt.token_size, 1);
+    assert_eq!(listing_receipt.remaining_size, 1);
 
     ()
 }
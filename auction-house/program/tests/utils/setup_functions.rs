@@ -9,18 +9,29 @@ use anchor_lang::*;
 use mpl_auction_house::{
     pda::{
         find_auction_house_address, find_auction_house_fee_account_address,
-        find_auction_house_treasury_address, find_bid_receipt_address, find_escrow_payment_address,
-        find_listing_receipt_address, find_program_as_signer_address,
-        find_public_bid_trade_state_address, find_purchase_receipt_address,
-        find_trade_state_address,
+        find_auction_house_treasury_address, find_auctioneer_address, find_bid_receipt_address,
+        find_collection_offer_trade_state_address, find_escrow_commitment_address,
+        find_escrow_payment_address, find_listing_config_address, find_listing_receipt_address,
+        find_program_as_signer_address, find_public_bid_trade_state_address,
+        find_purchase_receipt_address, find_trade_state_address,
     },
     AuctionHouse,
 };
-use mpl_testing_utils::{solana::airdrop, utils::Metadata};
+use mpl_testing_utils::{
+    solana::{airdrop, mint_tokens},
+    utils::Metadata,
+};
 
-use mpl_token_metadata::pda::find_metadata_account;
+use mpl_token_metadata::{
+    pda::{find_master_edition_account, find_metadata_account},
+    state::{Collection, DataV2},
+};
 use solana_program_test::*;
-use solana_sdk::{instruction::Instruction, transaction::Transaction, transport::TransportError};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    transaction::Transaction,
+    transport::TransportError,
+};
 use spl_associated_token_account::get_associated_token_address;
 
 pub fn auction_house_program_test<'a>() -> ProgramTest {
@@ -165,6 +176,34 @@ pub fn buy(
         mpl_auction_house::accounts::PrintBidReceipt,
     ),
     Transaction,
+) {
+    buy_tokens(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        owner,
+        buyer,
+        sale_price,
+        1,
+    )
+}
+
+pub fn buy_tokens(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    owner: &Pubkey,
+    buyer: &Keypair,
+    sale_price: u64,
+    token_size: u64,
+) -> (
+    (
+        mpl_auction_house::accounts::Buy,
+        mpl_auction_house::accounts::PrintBidReceipt,
+    ),
+    Transaction,
 ) {
     let seller_token_account = get_associated_token_address(&owner, &test_metadata.mint.pubkey());
     let trade_state = find_trade_state_address(
@@ -174,7 +213,7 @@ pub fn buy(
         &ah.treasury_mint,
         &test_metadata.mint.pubkey(),
         sale_price,
-        1,
+        token_size,
     );
     let (escrow, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
     let (bts, bts_bump) = trade_state;
@@ -200,7 +239,7 @@ pub fn buy(
     let buy_ix = mpl_auction_house::instruction::Buy {
         trade_state_bump: bts_bump,
         escrow_payment_bump: escrow_bump,
-        token_size: 1,
+        token_size,
         buyer_price: sale_price,
     };
     let data = buy_ix.data();
@@ -443,6 +482,117 @@ pub fn execute_sale(
     ((execute_sale_accounts, print_purchase_receipt_accounts), tx)
 }
 
+pub fn execute_partial_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    token_account: &Pubkey,
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    token_size: u64,
+    buyer_price: u64,
+    partial_order_size: u64,
+    partial_order_price: u64,
+) -> (
+    (
+        mpl_auction_house::accounts::ExecuteSale,
+        mpl_auction_house::accounts::PrintPurchaseReceipt,
+    ),
+    Transaction,
+) {
+    let program_id = mpl_auction_house::id();
+    let buyer_token_account = get_associated_token_address(&buyer, &test_metadata.mint.pubkey());
+
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        &seller,
+        &ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        token_size,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer);
+    let (purchase_receipt, purchase_receipt_bump) =
+        find_purchase_receipt_address(seller_trade_state, buyer_trade_state);
+    let (listing_receipt, _listing_receipt_bump) = find_listing_receipt_address(seller_trade_state);
+    let (bid_receipt, _public_bid_receipt_bump) = find_bid_receipt_address(buyer_trade_state);
+    let execute_sale_accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: *buyer,
+        seller: *seller,
+        auction_house: *ahkey,
+        token_account: *token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        seller_trade_state: *seller_trade_state,
+        buyer_trade_state: *buyer_trade_state,
+        free_trade_state: free_trade_state,
+        seller_payment_receipt_account: *seller,
+        buyer_receipt_token_account: buyer_token_account,
+        escrow_payment_account: escrow_payment_account,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        program_as_signer: program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let execute_sale_account_metas = execute_sale_accounts.to_account_metas(None);
+
+    let execute_sale_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::ExecutePartialSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price,
+            token_size,
+            partial_order_size,
+            partial_order_price,
+        }
+        .data(),
+        accounts: execute_sale_account_metas,
+    };
+
+    let print_purchase_receipt_accounts = mpl_auction_house::accounts::PrintPurchaseReceipt {
+        purchase_receipt,
+        listing_receipt,
+        bid_receipt,
+        bookkeeper: authority.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let print_purchase_receipt_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::PrintPurchaseReceipt {
+            purchase_receipt_bump,
+        }
+        .data(),
+        accounts: print_purchase_receipt_accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[execute_sale_instruction, print_purchase_receipt_instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    ((execute_sale_accounts, print_purchase_receipt_accounts), tx)
+}
+
 pub fn sell_mint(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
@@ -548,6 +698,114 @@ pub fn sell(
         mpl_auction_house::accounts::PrintListingReceipt,
     ),
     Transaction,
+) {
+    sell_tokens(context, ahkey, ah, test_metadata, sale_price, 1)
+}
+
+/// Create a token of `owner`, or of a new funded wallet, in a new collection when `collection` is set, verified when it is true.
+pub async fn create_test_metadata(
+    context: &mut ProgramTestContext,
+    owner: Option<&Keypair>,
+    seller_fee_basis_points: u16,
+    collection: Option<bool>,
+) -> (Metadata, Option<Pubkey>) {
+    let mut test_metadata = Metadata::new();
+    match owner {
+        Some(owner) => test_metadata.token = Keypair::from_bytes(&owner.to_bytes()).unwrap(),
+        None => airdrop(context, &test_metadata.token.pubkey(), 10_000_000_000)
+            .await
+            .unwrap(),
+    }
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            seller_fee_basis_points,
+            collection.is_some(),
+        )
+        .await
+        .unwrap();
+    let collection_mint = match collection {
+        Some(verify) => Some(
+            add_to_collection(context, &test_metadata, verify)
+                .await
+                .unwrap(),
+        ),
+        None => None,
+    };
+
+    (test_metadata, collection_mint)
+}
+
+/// List `token_size` tokens of a new token at `price` and fund the Auction House fee account to sell them.
+pub async fn listed_metadata(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    price: u64,
+    token_size: u64,
+    seller_fee_basis_points: u16,
+    collection: Option<bool>,
+) -> (
+    Metadata,
+    Option<Pubkey>,
+    mpl_auction_house::accounts::Sell,
+    mpl_auction_house::accounts::PrintListingReceipt,
+) {
+    let (test_metadata, collection_mint) =
+        create_test_metadata(context, None, seller_fee_basis_points, collection).await;
+    if token_size > 1 {
+        let mint_authority = context.payer.pubkey();
+        let seller_token_account = get_associated_token_address(
+            &test_metadata.token.pubkey(),
+            &test_metadata.mint.pubkey(),
+        );
+        mint_tokens(
+            context,
+            &test_metadata.mint.pubkey(),
+            &seller_token_account,
+            token_size - 1,
+            &mint_authority,
+            None,
+        )
+        .await
+        .unwrap();
+    }
+    let ((sell_acc, listing_receipt_acc), sell_tx) =
+        sell_tokens(context, ahkey, ah, &test_metadata, price, token_size);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    airdrop(context, &ah.auction_house_fee_account, 10_000_000_000)
+        .await
+        .unwrap();
+
+    (
+        test_metadata,
+        collection_mint,
+        sell_acc,
+        listing_receipt_acc,
+    )
+}
+
+pub fn sell_tokens(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    sale_price: u64,
+    token_size: u64,
+) -> (
+    (
+        mpl_auction_house::accounts::Sell,
+        mpl_auction_house::accounts::PrintListingReceipt,
+    ),
+    Transaction,
 ) {
     let program_id = mpl_auction_house::id();
     let token =
@@ -559,7 +817,7 @@ pub fn sell(
         &ah.treasury_mint,
         &test_metadata.mint.pubkey(),
         sale_price,
-        1,
+        token_size,
     );
     let (listing_receipt, receipt_bump) = find_listing_receipt_address(&seller_trade_state);
 
@@ -570,7 +828,7 @@ pub fn sell(
         &ah.treasury_mint,
         &test_metadata.mint.pubkey(),
         0,
-        1,
+        token_size,
     );
     let (pas, pas_bump) = find_program_as_signer_address();
 
@@ -594,7 +852,7 @@ pub fn sell(
         trade_state_bump: sts_bump,
         _free_trade_state_bump: free_sts_bump,
         _program_as_signer_bump: pas_bump,
-        token_size: 1,
+        token_size,
         buyer_price: sale_price,
     }
     .data();
@@ -630,51 +888,814 @@ pub fn sell(
     )
 }
 
-pub async fn existing_auction_house_test_context(
+pub async fn batch_listing_context(
     context: &mut ProgramTestContext,
-) -> Result<(AuctionHouse, Pubkey, Keypair), TransportError> {
-    let twd_key = context.payer.pubkey().clone();
-    let fwd_key = context.payer.pubkey().clone();
-    let t_mint_key = spl_token::native_mint::id();
-    let tdw_ata = twd_key;
-    let seller_fee_basis_points: u16 = 100;
-    let authority = Keypair::new();
-    airdrop(context, &authority.pubkey(), 10_000_000_000).await?;
-    // Derive Auction House Key
-    let (auction_house_address, bump) =
-        find_auction_house_address(&authority.pubkey(), &t_mint_key);
-    let (auction_fee_account_key, fee_payer_bump) =
-        find_auction_house_fee_account_address(&auction_house_address);
-    // Derive Auction House Treasury Key
-    let (auction_house_treasury_key, treasury_bump) =
-        find_auction_house_treasury_address(&auction_house_address);
-    let auction_house = create_auction_house(
-        context,
-        &authority,
-        &twd_key,
-        &fwd_key,
-        &t_mint_key,
-        &tdw_ata,
-        &auction_house_address,
-        bump,
-        &auction_fee_account_key,
-        fee_payer_bump,
-        &auction_house_treasury_key,
-        treasury_bump,
-        seller_fee_basis_points,
-        false,
-        false,
-    );
+    size: usize,
+) -> (AuctionHouse, Pubkey, Keypair, Vec<Metadata>) {
+    let (ah, ahkey, _) = existing_auction_house_test_context(context).await.unwrap();
+    let wallet = Keypair::new();
+    airdrop(context, &wallet.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let mut test_metadata = Vec::new();
+    for _ in 0..size {
+        let (metadata, _) = create_test_metadata(context, Some(&wallet), 10, None).await;
+        test_metadata.push(metadata);
+    }
 
-    let auction_house_account = auction_house.await.unwrap();
+    (ah, ahkey, wallet, test_metadata)
+}
 
-    let auction_house_acc = context
-        .banks_client
-        .get_account(auction_house_account)
-        .await?
-        .expect("account empty");
+pub fn batch_sell(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    wallet: &Keypair,
+    test_metadata: &[Metadata],
+    sale_price: u64,
+) -> (Vec<Pubkey>, Transaction) {
+    batch_sell_with_receipts(context, ahkey, ah, wallet, test_metadata, sale_price, None)
+}
 
-    let auction_house_data = AuctionHouse::try_deserialize(&mut auction_house_acc.data.as_ref())
-        .map_err(|e| TransportError::IoError(io::Error::new(io::ErrorKind::InvalidData, e)))?;
-    return Ok((auction_house_data, auction_house_address, authority));
+/// Same as `batch_sell`, printing the listing receipts into `receipts` when set.
+pub fn batch_sell_with_receipts(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    wallet: &Keypair,
+    test_metadata: &[Metadata],
+    sale_price: u64,
+    receipts: Option<&[Pubkey]>,
+) -> (Vec<Pubkey>, Transaction) {
+    let program_id = mpl_auction_house::id();
+    let tokens: Vec<Pubkey> = test_metadata
+        .iter()
+        .map(|metadata| get_associated_token_address(&wallet.pubkey(), &metadata.mint.pubkey()))
+        .collect();
+    let mut seller_trade_states = Vec::new();
+    let mut free_seller_trade_states = Vec::new();
+    let mut trade_state_bumps = Vec::new();
+    let mut listing_receipts = Vec::new();
+    let mut receipt_bumps = Vec::new();
+    for (metadata, token) in test_metadata.iter().zip(tokens.iter()) {
+        let (seller_trade_state, sts_bump) = find_trade_state_address(
+            &wallet.pubkey(),
+            &ahkey,
+            token,
+            &ah.treasury_mint,
+            &metadata.mint.pubkey(),
+            sale_price,
+            1,
+        );
+        let (free_seller_trade_state, _) = find_trade_state_address(
+            &wallet.pubkey(),
+            &ahkey,
+            token,
+            &ah.treasury_mint,
+            &metadata.mint.pubkey(),
+            0,
+            1,
+        );
+        let (listing_receipt, receipt_bump) = find_listing_receipt_address(&seller_trade_state);
+        seller_trade_states.push(seller_trade_state);
+        free_seller_trade_states.push(free_seller_trade_state);
+        trade_state_bumps.push(sts_bump);
+        listing_receipts.push(listing_receipt);
+        receipt_bumps.push(receipt_bump);
+    }
+    let (pas, pas_bump) = find_program_as_signer_address();
+
+    let mut account_metas = mpl_auction_house::accounts::BatchSell {
+        wallet: wallet.pubkey(),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        program_as_signer: pas,
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    account_metas.extend(tokens.iter().map(|token| AccountMeta::new(*token, false)));
+    account_metas.extend(
+        test_metadata
+            .iter()
+            .map(|metadata| AccountMeta::new_readonly(metadata.pubkey, false)),
+    );
+    account_metas.extend(
+        seller_trade_states
+            .iter()
+            .map(|trade_state| AccountMeta::new(*trade_state, false)),
+    );
+    account_metas.extend(
+        free_seller_trade_states
+            .iter()
+            .map(|trade_state| AccountMeta::new_readonly(*trade_state, false)),
+    );
+
+    let instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::BatchSell {
+            program_as_signer_bump: pas_bump,
+            trade_state_bumps,
+            buyer_prices: vec![sale_price; test_metadata.len()],
+            token_sizes: vec![1; test_metadata.len()],
+        }
+        .data(),
+        accounts: account_metas,
+    };
+
+    let mut receipt_metas = mpl_auction_house::accounts::BatchPrintListingReceipt {
+        bookkeeper: wallet.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        instruction: sysvar::instructions::id(),
+    }
+    .to_account_metas(None);
+    receipt_metas.extend(
+        receipts
+            .unwrap_or(&listing_receipts)
+            .iter()
+            .map(|receipt| AccountMeta::new(*receipt, false)),
+    );
+
+    let print_receipt_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::BatchPrintListingReceipt { receipt_bumps }.data(),
+        accounts: receipt_metas,
+    };
+
+    (
+        seller_trade_states,
+        Transaction::new_signed_with_payer(
+            &[instruction, print_receipt_instruction],
+            Some(&wallet.pubkey()),
+            &[wallet],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn batch_cancel(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    wallet: &Keypair,
+    test_metadata: &[Metadata],
+    trade_states: &[Pubkey],
+    sale_price: u64,
+) -> Transaction {
+    let mut account_metas = mpl_auction_house::accounts::BatchCancel {
+        wallet: wallet.pubkey(),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: spl_token::id(),
+    }
+    .to_account_metas(None);
+    account_metas.extend(test_metadata.iter().map(|metadata| {
+        AccountMeta::new(
+            get_associated_token_address(&wallet.pubkey(), &metadata.mint.pubkey()),
+            false,
+        )
+    }));
+    account_metas.extend(
+        test_metadata
+            .iter()
+            .map(|metadata| AccountMeta::new_readonly(metadata.mint.pubkey(), false)),
+    );
+    account_metas.extend(
+        trade_states
+            .iter()
+            .map(|trade_state| AccountMeta::new(*trade_state, false)),
+    );
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::BatchCancel {
+            buyer_prices: vec![sale_price; test_metadata.len()],
+            token_sizes: vec![1; test_metadata.len()],
+        }
+        .data(),
+        accounts: account_metas,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&wallet.pubkey()),
+        &[wallet],
+        context.last_blockhash,
+    )
+}
+
+pub async fn existing_auction_house_test_context(
+    context: &mut ProgramTestContext,
+) -> Result<(AuctionHouse, Pubkey, Keypair), TransportError> {
+    auction_house_test_context(context, false).await
+}
+
+pub async fn auction_house_test_context(
+    context: &mut ProgramTestContext,
+    can_change_sale_price: bool,
+) -> Result<(AuctionHouse, Pubkey, Keypair), TransportError> {
+    let twd_key = context.payer.pubkey().clone();
+    let fwd_key = context.payer.pubkey().clone();
+    let t_mint_key = spl_token::native_mint::id();
+    let tdw_ata = twd_key;
+    let seller_fee_basis_points: u16 = 100;
+    let authority = Keypair::new();
+    airdrop(context, &authority.pubkey(), 10_000_000_000).await?;
+    // Derive Auction House Key
+    let (auction_house_address, bump) =
+        find_auction_house_address(&authority.pubkey(), &t_mint_key);
+    let (auction_fee_account_key, fee_payer_bump) =
+        find_auction_house_fee_account_address(&auction_house_address);
+    // Derive Auction House Treasury Key
+    let (auction_house_treasury_key, treasury_bump) =
+        find_auction_house_treasury_address(&auction_house_address);
+    let auction_house = create_auction_house(
+        context,
+        &authority,
+        &twd_key,
+        &fwd_key,
+        &t_mint_key,
+        &tdw_ata,
+        &auction_house_address,
+        bump,
+        &auction_fee_account_key,
+        fee_payer_bump,
+        &auction_house_treasury_key,
+        treasury_bump,
+        seller_fee_basis_points,
+        false,
+        can_change_sale_price,
+    );
+
+    let auction_house_account = auction_house.await.unwrap();
+
+    let auction_house_acc = context
+        .banks_client
+        .get_account(auction_house_account)
+        .await?
+        .expect("account empty");
+
+    let auction_house_data = AuctionHouse::try_deserialize(&mut auction_house_acc.data.as_ref())
+        .map_err(|e| TransportError::IoError(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+    return Ok((auction_house_data, auction_house_address, authority));
+}
+
+pub async fn delegate_auctioneer(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    auctioneer_authority: &Pubkey,
+) -> Result<Pubkey, TransportError> {
+    let (ah_auctioneer_pda, auctioneer_bump) = find_auctioneer_address(ahkey, auctioneer_authority);
+    let accounts = mpl_auction_house::accounts::DelegateAuctioneer {
+        auction_house: *ahkey,
+        authority: authority.pubkey(),
+        auctioneer_authority: *auctioneer_authority,
+        ah_auctioneer_pda,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::DelegateAuctioneer { auctioneer_bump }.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map(|_| ah_auctioneer_pda)
+}
+
+pub async fn undelegate_auctioneer(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    auctioneer_authority: &Pubkey,
+) -> Result<(), TransportError> {
+    let (ah_auctioneer_pda, _) = find_auctioneer_address(ahkey, auctioneer_authority);
+    let accounts = mpl_auction_house::accounts::UndelegateAuctioneer {
+        auction_house: *ahkey,
+        authority: authority.pubkey(),
+        auctioneer_authority: *auctioneer_authority,
+        ah_auctioneer_pda,
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::UndelegateAuctioneer {}.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn sale_with_settings(
+    context: &mut ProgramTestContext,
+    ah: &AuctionHouse,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    seller_fee_basis_points: u16,
+    sale_price: u64,
+) -> Transaction {
+    let (test_metadata, _, sell_acc, _) = listed_metadata(
+        context,
+        ahkey,
+        ah,
+        sale_price,
+        1,
+        seller_fee_basis_points,
+        None,
+    )
+    .await;
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        context,
+        ahkey,
+        ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        sale_price,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (_, sale_tx) = execute_sale(
+        context,
+        ahkey,
+        ah,
+        authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        sale_price,
+    );
+    sale_tx
+}
+
+pub async fn update_royalty_settings(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    enforce_royalties: Option<bool>,
+    min_royalty_basis_points: Option<u16>,
+    pay_royalties_on_free_transfers: Option<bool>,
+    free_transfer_royalty: Option<u64>,
+    creator_allow_list: Option<Vec<Pubkey>>,
+) -> Result<AuctionHouse, TransportError> {
+    let accounts = mpl_auction_house::accounts::UpdateRoyaltySettings {
+        authority: authority.pubkey(),
+        auction_house: *ahkey,
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::UpdateRoyaltySettings {
+            enforce_royalties,
+            min_royalty_basis_points,
+            pay_royalties_on_free_transfers,
+            free_transfer_royalty,
+            creator_allow_list,
+        }
+        .data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+
+    let auction_house_acc = context
+        .banks_client
+        .get_account(*ahkey)
+        .await?
+        .expect("account empty");
+
+    AuctionHouse::try_deserialize(&mut auction_house_acc.data.as_ref())
+        .map_err(|e| TransportError::IoError(io::Error::new(io::ErrorKind::InvalidData, e)))
+}
+
+pub async fn existing_auctioneer_test_context(
+    context: &mut ProgramTestContext,
+) -> Result<(AuctionHouse, Pubkey, Keypair, Keypair), TransportError> {
+    let (_, ahkey, authority) = auction_house_test_context(context, true).await?;
+    let auctioneer_authority = Keypair::new();
+    delegate_auctioneer(context, &ahkey, &authority, &auctioneer_authority.pubkey()).await?;
+
+    let auction_house_acc = context
+        .banks_client
+        .get_account(ahkey)
+        .await?
+        .expect("account empty");
+
+    let auction_house_data = AuctionHouse::try_deserialize(&mut auction_house_acc.data.as_ref())
+        .map_err(|e| TransportError::IoError(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+    Ok((auction_house_data, ahkey, authority, auctioneer_authority))
+}
+
+pub async fn create_listing_config(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    auctioneer_authority: &Keypair,
+    start_time: i64,
+    end_time: i64,
+    reserve_price: u64,
+    min_bid_increment: u64,
+    time_ext_period: u32,
+    time_ext_delta: u32,
+) -> Result<Pubkey, TransportError> {
+    let seller = test_metadata.token.pubkey();
+    let token = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let (free_seller_trade_state, _) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &token,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (ah_auctioneer_pda, _) = find_auctioneer_address(ahkey, &auctioneer_authority.pubkey());
+    let (listing_config, listing_config_bump) = find_listing_config_address(ahkey, &token);
+    let accounts = mpl_auction_house::accounts::CreateListingConfig {
+        wallet: seller,
+        token_account: token,
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        auction_house: *ahkey,
+        ah_auctioneer_pda,
+        free_seller_trade_state,
+        listing_config,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CreateListingConfig {
+            listing_config_bump,
+            token_size: 1,
+            start_time,
+            end_time,
+            reserve_price,
+            min_bid_increment,
+            time_ext_period,
+            time_ext_delta,
+        }
+        .data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&seller),
+        &[&test_metadata.token, auctioneer_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map(|_| listing_config)
+}
+
+pub async fn auction_listing_context(
+    context: &mut ProgramTestContext,
+    time_ext_period: u32,
+    time_ext_delta: u32,
+) -> (AuctionHouse, Pubkey, Metadata, Pubkey, i64) {
+    let (ah, ahkey, _, auctioneer_authority) =
+        existing_auctioneer_test_context(context).await.unwrap();
+    assert_eq!(ah.has_auctioneer, true);
+    let (test_metadata, _, _, _) = listed_metadata(context, &ahkey, &ah, 0, 1, 10, None).await;
+    let end_time = context
+        .banks_client
+        .get_sysvar::<sysvar::clock::Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+        + 3600;
+    let listing_config = create_listing_config(
+        context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &auctioneer_authority,
+        0,
+        end_time,
+        100_000_000,
+        10_000_000,
+        time_ext_period,
+        time_ext_delta,
+    )
+    .await
+    .unwrap();
+
+    (ah, ahkey, test_metadata, listing_config, end_time)
+}
+
+/// Bid on an auction listing, outbidding the highest bid of `outbid` if there is one.
+pub fn auction_buy(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Keypair,
+    sale_price: u64,
+    outbid: Option<&Pubkey>,
+) -> (mpl_auction_house::accounts::Buy, Transaction) {
+    let owner = test_metadata.token.pubkey();
+    let ((accounts, _), _) = buy(context, ahkey, ah, test_metadata, &owner, buyer, sale_price);
+    let (_, trade_state_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &accounts.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (_, escrow_payment_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let (listing_config, _) = find_listing_config_address(ahkey, &accounts.token_account);
+
+    let (escrow_commitment, _) = find_escrow_commitment_address(ahkey, &buyer.pubkey());
+
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.push(AccountMeta::new(listing_config, false));
+    account_metas.push(AccountMeta::new(escrow_commitment, false));
+    if let Some(outbid) = outbid {
+        let (outbid_escrow_commitment, _) = find_escrow_commitment_address(ahkey, outbid);
+        account_metas.push(AccountMeta::new(outbid_escrow_commitment, false));
+    }
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Buy {
+            trade_state_bump,
+            escrow_payment_bump,
+            token_size: 1,
+            buyer_price: sale_price,
+        }
+        .data(),
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub async fn add_to_collection(
+    context: &mut ProgramTestContext,
+    test_metadata: &Metadata,
+    verify: bool,
+) -> Result<Pubkey, TransportError> {
+    let collection = Metadata::new();
+    collection
+        .create(
+            context,
+            "Collection".to_string(),
+            "COL".to_string(),
+            "uri".to_string(),
+            None,
+            0,
+            false,
+        )
+        .await?;
+    let (collection_edition, _) = find_master_edition_account(&collection.mint.pubkey());
+    let mut instructions = vec![
+        mpl_token_metadata::instruction::create_master_edition_v3(
+            mpl_token_metadata::id(),
+            collection_edition,
+            collection.mint.pubkey(),
+            context.payer.pubkey(),
+            context.payer.pubkey(),
+            collection.pubkey,
+            context.payer.pubkey(),
+            Some(0),
+        ),
+        mpl_token_metadata::instruction::update_metadata_accounts_v2(
+            mpl_token_metadata::id(),
+            test_metadata.pubkey,
+            context.payer.pubkey(),
+            None,
+            Some(DataV2 {
+                name: "Test".to_string(),
+                symbol: "TST".to_string(),
+                uri: "uri".to_string(),
+                seller_fee_basis_points: 10,
+                creators: None,
+                collection: Some(Collection {
+                    verified: false,
+                    key: collection.mint.pubkey(),
+                }),
+                uses: None,
+            }),
+            None,
+            None,
+        ),
+    ];
+    if verify {
+        instructions.push(mpl_token_metadata::instruction::verify_collection(
+            mpl_token_metadata::id(),
+            test_metadata.pubkey,
+            context.payer.pubkey(),
+            context.payer.pubkey(),
+            collection.mint.pubkey(),
+            collection.pubkey,
+            collection_edition,
+            None,
+        ));
+    }
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+
+    Ok(collection.mint.pubkey())
+}
+
+pub fn collection_buy(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    collection_mint: &Pubkey,
+    buyer: &Keypair,
+    sale_price: u64,
+) -> (mpl_auction_house::accounts::CollectionBuy, Transaction) {
+    let (bts, bts_bump) = find_collection_offer_trade_state_address(
+        &buyer.pubkey(),
+        &ahkey,
+        &ah.treasury_mint,
+        &collection_mint,
+        sale_price,
+        1,
+    );
+    let (escrow, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let accounts = mpl_auction_house::accounts::CollectionBuy {
+        wallet: buyer.pubkey(),
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        collection_mint: *collection_mint,
+        escrow_payment_account: escrow,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        buyer_trade_state: bts,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CollectionBuy {
+            trade_state_bump: bts_bump,
+            escrow_payment_bump: escrow_bump,
+            buyer_price: sale_price,
+            token_size: 1,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn execute_collection_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    token_account: &Pubkey,
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    collection_mint: &Pubkey,
+    buyer_price: u64,
+) -> (
+    mpl_auction_house::accounts::ExecuteCollectionSale,
+    Transaction,
+) {
+    let buyer_token_account = get_associated_token_address(&buyer, &test_metadata.mint.pubkey());
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        &seller,
+        &ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer);
+    let accounts = mpl_auction_house::accounts::ExecuteCollectionSale {
+        execute_sale: mpl_auction_house::accounts::ExecuteSale {
+            buyer: *buyer,
+            seller: *seller,
+            auction_house: *ahkey,
+            token_account: *token_account,
+            token_mint: test_metadata.mint.pubkey(),
+            treasury_mint: ah.treasury_mint,
+            metadata: test_metadata.pubkey,
+            authority: ah.authority,
+            seller_trade_state: *seller_trade_state,
+            buyer_trade_state: *buyer_trade_state,
+            free_trade_state,
+            seller_payment_receipt_account: *seller,
+            buyer_receipt_token_account: buyer_token_account,
+            escrow_payment_account,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            auction_house_treasury: ah.auction_house_treasury,
+            program_as_signer,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            ata_program: spl_associated_token_account::id(),
+            rent: sysvar::rent::id(),
+        },
+        collection_mint: *collection_mint,
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteCollectionSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price,
+            token_size: 1,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&authority.pubkey()),
+            &[authority],
+            context.last_blockhash,
+        ),
+    )
 }
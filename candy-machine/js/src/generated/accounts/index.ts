@@ -1,2 +1,8 @@
+export * from './CandyGuard';
 export * from './CandyMachine';
 export * from './CollectionPDA';
+export * from './ConfigLock';
+export * from './MintCounter';
+export * from './MintIndex';
+export * from './MintSettings';
+export * from './PendingMint';
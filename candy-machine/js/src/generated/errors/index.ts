@@ -643,6 +643,867 @@ createErrorFromNameLookup.set(
   () => new MismatchedCollectionMintError(),
 );
 
+/**
+ * MissingCandyGuard: 'Candy guard account is missing or does not belong to this candy machine'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MissingCandyGuardError extends Error {
+  readonly code: number = 0x178e;
+  readonly name: string = 'MissingCandyGuard';
+  constructor() {
+    super('Candy guard account is missing or does not belong to this candy machine');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MissingCandyGuardError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x178e, () => new MissingCandyGuardError());
+createErrorFromNameLookup.set('MissingCandyGuard', () => new MissingCandyGuardError());
+
+/**
+ * MissingGuardAccount: 'Missing an account required by a guard'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MissingGuardAccountError extends Error {
+  readonly code: number = 0x178f;
+  readonly name: string = 'MissingGuardAccount';
+  constructor() {
+    super('Missing an account required by a guard');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MissingGuardAccountError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x178f, () => new MissingGuardAccountError());
+createErrorFromNameLookup.set('MissingGuardAccount', () => new MissingGuardAccountError());
+
+/**
+ * TooManyGuards: 'Too many guards in the guard set'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TooManyGuardsError extends Error {
+  readonly code: number = 0x1790;
+  readonly name: string = 'TooManyGuards';
+  constructor() {
+    super('Too many guards in the guard set');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, TooManyGuardsError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1790, () => new TooManyGuardsError());
+createErrorFromNameLookup.set('TooManyGuards', () => new TooManyGuardsError());
+
+/**
+ * DuplicateGuard: 'A guard can only appear once in a guard set'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class DuplicateGuardError extends Error {
+  readonly code: number = 0x1791;
+  readonly name: string = 'DuplicateGuard';
+  constructor() {
+    super('A guard can only appear once in a guard set');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, DuplicateGuardError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1791, () => new DuplicateGuardError());
+createErrorFromNameLookup.set('DuplicateGuard', () => new DuplicateGuardError());
+
+/**
+ * InvalidGuardSettings: 'Invalid guard settings'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidGuardSettingsError extends Error {
+  readonly code: number = 0x1792;
+  readonly name: string = 'InvalidGuardSettings';
+  constructor() {
+    super('Invalid guard settings');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, InvalidGuardSettingsError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1792, () => new InvalidGuardSettingsError());
+createErrorFromNameLookup.set('InvalidGuardSettings', () => new InvalidGuardSettingsError());
+
+/**
+ * NotEnoughGateTokens: 'Not enough tokens to pass the token gate'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class NotEnoughGateTokensError extends Error {
+  readonly code: number = 0x1793;
+  readonly name: string = 'NotEnoughGateTokens';
+  constructor() {
+    super('Not enough tokens to pass the token gate');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, NotEnoughGateTokensError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1793, () => new NotEnoughGateTokensError());
+createErrorFromNameLookup.set('NotEnoughGateTokens', () => new NotEnoughGateTokensError());
+
+/**
+ * TooManyGuardGroups: 'Too many guard groups in the guard set'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TooManyGuardGroupsError extends Error {
+  readonly code: number = 0x1794;
+  readonly name: string = 'TooManyGuardGroups';
+  constructor() {
+    super('Too many guard groups in the guard set');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, TooManyGuardGroupsError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1794, () => new TooManyGuardGroupsError());
+createErrorFromNameLookup.set('TooManyGuardGroups', () => new TooManyGuardGroupsError());
+
+/**
+ * InvalidGuardGroupLabel: 'Guard group labels must be between 1 and 6 characters'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidGuardGroupLabelError extends Error {
+  readonly code: number = 0x1795;
+  readonly name: string = 'InvalidGuardGroupLabel';
+  constructor() {
+    super('Guard group labels must be between 1 and 6 characters');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, InvalidGuardGroupLabelError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1795, () => new InvalidGuardGroupLabelError());
+createErrorFromNameLookup.set('InvalidGuardGroupLabel', () => new InvalidGuardGroupLabelError());
+
+/**
+ * DuplicateGuardGroup: 'Guard group labels must be unique'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class DuplicateGuardGroupError extends Error {
+  readonly code: number = 0x1796;
+  readonly name: string = 'DuplicateGuardGroup';
+  constructor() {
+    super('Guard group labels must be unique');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, DuplicateGuardGroupError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1796, () => new DuplicateGuardGroupError());
+createErrorFromNameLookup.set('DuplicateGuardGroup', () => new DuplicateGuardGroupError());
+
+/**
+ * GuardGroupNotFound: 'No guard group with this label'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class GuardGroupNotFoundError extends Error {
+  readonly code: number = 0x1797;
+  readonly name: string = 'GuardGroupNotFound';
+  constructor() {
+    super('No guard group with this label');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, GuardGroupNotFoundError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1797, () => new GuardGroupNotFoundError());
+createErrorFromNameLookup.set('GuardGroupNotFound', () => new GuardGroupNotFoundError());
+
+/**
+ * GuardGroupRequired: 'This candy machine mints in groups, a group label is required'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class GuardGroupRequiredError extends Error {
+  readonly code: number = 0x1798;
+  readonly name: string = 'GuardGroupRequired';
+  constructor() {
+    super('This candy machine mints in groups, a group label is required');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, GuardGroupRequiredError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1798, () => new GuardGroupRequiredError());
+createErrorFromNameLookup.set('GuardGroupRequired', () => new GuardGroupRequiredError());
+
+/**
+ * MintLimitReached: 'This wallet reached its mint limit'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MintLimitReachedError extends Error {
+  readonly code: number = 0x1799;
+  readonly name: string = 'MintLimitReached';
+  constructor() {
+    super('This wallet reached its mint limit');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MintLimitReachedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x1799, () => new MintLimitReachedError());
+createErrorFromNameLookup.set('MintLimitReached', () => new MintLimitReachedError());
+
+/**
+ * MissingAllowListProof: 'The allow list guard needs a proof, mint with mint_nft_v2'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MissingAllowListProofError extends Error {
+  readonly code: number = 0x179a;
+  readonly name: string = 'MissingAllowListProof';
+  constructor() {
+    super('The allow list guard needs a proof, mint with mint_nft_v2');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MissingAllowListProofError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x179a, () => new MissingAllowListProofError());
+createErrorFromNameLookup.set('MissingAllowListProof', () => new MissingAllowListProofError());
+
+/**
+ * NotOnAllowList: 'This wallet is not on the allow list'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class NotOnAllowListError extends Error {
+  readonly code: number = 0x179b;
+  readonly name: string = 'NotOnAllowList';
+  constructor() {
+    super('This wallet is not on the allow list');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, NotOnAllowListError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x179b, () => new NotOnAllowListError());
+createErrorFromNameLookup.set('NotOnAllowList', () => new NotOnAllowListError());
+
+/**
+ * AllowListAllocationReached: 'This wallet minted its allow list allocation'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class AllowListAllocationReachedError extends Error {
+  readonly code: number = 0x179c;
+  readonly name: string = 'AllowListAllocationReached';
+  constructor() {
+    super('This wallet minted its allow list allocation');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, AllowListAllocationReachedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x179c, () => new AllowListAllocationReachedError());
+createErrorFromNameLookup.set(
+  'AllowListAllocationReached',
+  () => new AllowListAllocationReachedError(),
+);
+
+/**
+ * RevealNeedsHiddenSettings: 'Reveal needs hidden settings'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class RevealNeedsHiddenSettingsError extends Error {
+  readonly code: number = 0x179d;
+  readonly name: string = 'RevealNeedsHiddenSettings';
+  constructor() {
+    super('Reveal needs hidden settings');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, RevealNeedsHiddenSettingsError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x179d, () => new RevealNeedsHiddenSettingsError());
+createErrorFromNameLookup.set(
+  'RevealNeedsHiddenSettings',
+  () => new RevealNeedsHiddenSettingsError(),
+);
+
+/**
+ * RevealEnabledAfterMint: 'Reveal has to be enabled before the first mint'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class RevealEnabledAfterMintError extends Error {
+  readonly code: number = 0x179e;
+  readonly name: string = 'RevealEnabledAfterMint';
+  constructor() {
+    super('Reveal has to be enabled before the first mint');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, RevealEnabledAfterMintError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x179e, () => new RevealEnabledAfterMintError());
+createErrorFromNameLookup.set('RevealEnabledAfterMint', () => new RevealEnabledAfterMintError());
+
+/**
+ * MissingMintIndex: 'Candy machine has the reveal enabled, the mint index account is missing'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MissingMintIndexError extends Error {
+  readonly code: number = 0x179f;
+  readonly name: string = 'MissingMintIndex';
+  constructor() {
+    super('Candy machine has the reveal enabled, the mint index account is missing');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MissingMintIndexError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x179f, () => new MissingMintIndexError());
+createErrorFromNameLookup.set('MissingMintIndex', () => new MissingMintIndexError());
+
+/**
+ * RevealSettingsHashMismatch: 'Reveal settings do not match the hidden settings hash'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class RevealSettingsHashMismatchError extends Error {
+  readonly code: number = 0x17a0;
+  readonly name: string = 'RevealSettingsHashMismatch';
+  constructor() {
+    super('Reveal settings do not match the hidden settings hash');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, RevealSettingsHashMismatchError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17a0, () => new RevealSettingsHashMismatchError());
+createErrorFromNameLookup.set(
+  'RevealSettingsHashMismatch',
+  () => new RevealSettingsHashMismatchError(),
+);
+
+/**
+ * CandyMachineNotEmpty: 'Candy machine still has items to mint, it can only be closed with force'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class CandyMachineNotEmptyError extends Error {
+  readonly code: number = 0x17a1;
+  readonly name: string = 'CandyMachineNotEmpty';
+  constructor() {
+    super('Candy machine still has items to mint, it can only be closed with force');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, CandyMachineNotEmptyError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17a1, () => new CandyMachineNotEmptyError());
+createErrorFromNameLookup.set('CandyMachineNotEmpty', () => new CandyMachineNotEmptyError());
+
+/**
+ * CandyGuardNotClosed: 'Close the candy guard before closing the candy machine'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class CandyGuardNotClosedError extends Error {
+  readonly code: number = 0x17a2;
+  readonly name: string = 'CandyGuardNotClosed';
+  constructor() {
+    super('Close the candy guard before closing the candy machine');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, CandyGuardNotClosedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17a2, () => new CandyGuardNotClosedError());
+createErrorFromNameLookup.set('CandyGuardNotClosed', () => new CandyGuardNotClosedError());
+
+/**
+ * CommitRevealNeedsConfigLines: 'Commit reveal mints need config lines, not hidden settings'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class CommitRevealNeedsConfigLinesError extends Error {
+  readonly code: number = 0x17a3;
+  readonly name: string = 'CommitRevealNeedsConfigLines';
+  constructor() {
+    super('Commit reveal mints need config lines, not hidden settings');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, CommitRevealNeedsConfigLinesError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17a3, () => new CommitRevealNeedsConfigLinesError());
+createErrorFromNameLookup.set(
+  'CommitRevealNeedsConfigLines',
+  () => new CommitRevealNeedsConfigLinesError(),
+);
+
+/**
+ * MissingPendingMint: 'Candy machine has commit reveal mints enabled, the pending mint account is missing'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MissingPendingMintError extends Error {
+  readonly code: number = 0x17a4;
+  readonly name: string = 'MissingPendingMint';
+  constructor() {
+    super('Candy machine has commit reveal mints enabled, the pending mint account is missing');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MissingPendingMintError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17a4, () => new MissingPendingMintError());
+createErrorFromNameLookup.set('MissingPendingMint', () => new MissingPendingMintError());
+
+/**
+ * PendingMintNotReady: 'The target slot of the pending mint has not passed yet'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class PendingMintNotReadyError extends Error {
+  readonly code: number = 0x17a5;
+  readonly name: string = 'PendingMintNotReady';
+  constructor() {
+    super('The target slot of the pending mint has not passed yet');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, PendingMintNotReadyError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17a5, () => new PendingMintNotReadyError());
+createErrorFromNameLookup.set('PendingMintNotReady', () => new PendingMintNotReadyError());
+
+/**
+ * PendingMintExpired: 'The target slot hash of the pending mint is no longer available'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class PendingMintExpiredError extends Error {
+  readonly code: number = 0x17a6;
+  readonly name: string = 'PendingMintExpired';
+  constructor() {
+    super('The target slot hash of the pending mint is no longer available');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, PendingMintExpiredError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17a6, () => new PendingMintExpiredError());
+createErrorFromNameLookup.set('PendingMintExpired', () => new PendingMintExpiredError());
+
+/**
+ * ConfigLocked: 'Candy machine config is locked'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ConfigLockedError extends Error {
+  readonly code: number = 0x17a7;
+  readonly name: string = 'ConfigLocked';
+  constructor() {
+    super('Candy machine config is locked');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, ConfigLockedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17a7, () => new ConfigLockedError());
+createErrorFromNameLookup.set('ConfigLocked', () => new ConfigLockedError());
+
+/**
+ * ConfigNotLocked: 'Candy machine config has to be locked before minting'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ConfigNotLockedError extends Error {
+  readonly code: number = 0x17a8;
+  readonly name: string = 'ConfigNotLocked';
+  constructor() {
+    super('Candy machine config has to be locked before minting');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, ConfigNotLockedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17a8, () => new ConfigNotLockedError());
+createErrorFromNameLookup.set('ConfigNotLocked', () => new ConfigNotLockedError());
+
+/**
+ * ConfigLinesMissing: 'Every config line has to be added before locking the config'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ConfigLinesMissingError extends Error {
+  readonly code: number = 0x17a9;
+  readonly name: string = 'ConfigLinesMissing';
+  constructor() {
+    super('Every config line has to be added before locking the config');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, ConfigLinesMissingError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17a9, () => new ConfigLinesMissingError());
+createErrorFromNameLookup.set('ConfigLinesMissing', () => new ConfigLinesMissingError());
+
+/**
+ * MissingConfigLock: 'Candy machine has its config locked, the config lock account is missing'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MissingConfigLockError extends Error {
+  readonly code: number = 0x17aa;
+  readonly name: string = 'MissingConfigLock';
+  constructor() {
+    super('Candy machine has its config locked, the config lock account is missing');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MissingConfigLockError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17aa, () => new MissingConfigLockError());
+createErrorFromNameLookup.set('MissingConfigLock', () => new MissingConfigLockError());
+
+/**
+ * MissingMintSettings: 'Candy machine has mint settings, the mint settings account is missing'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MissingMintSettingsError extends Error {
+  readonly code: number = 0x17ab;
+  readonly name: string = 'MissingMintSettings';
+  constructor() {
+    super('Candy machine has mint settings, the mint settings account is missing');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MissingMintSettingsError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17ab, () => new MissingMintSettingsError());
+createErrorFromNameLookup.set('MissingMintSettings', () => new MissingMintSettingsError());
+
+/**
+ * DiscountPriceCurrencyMismatch: 'The whitelist discount price cannot apply to a guard payment in another currency'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class DiscountPriceCurrencyMismatchError extends Error {
+  readonly code: number = 0x17ac;
+  readonly name: string = 'DiscountPriceCurrencyMismatch';
+  constructor() {
+    super('The whitelist discount price cannot apply to a guard payment in another currency');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, DiscountPriceCurrencyMismatchError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17ac, () => new DiscountPriceCurrencyMismatchError());
+createErrorFromNameLookup.set(
+  'DiscountPriceCurrencyMismatch',
+  () => new DiscountPriceCurrencyMismatchError(),
+);
+
+/**
+ * MintNotLastInstruction: 'With a bot tax only set_collection_during_mint can follow the mint in its transaction'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MintNotLastInstructionError extends Error {
+  readonly code: number = 0x17ad;
+  readonly name: string = 'MintNotLastInstruction';
+  constructor() {
+    super('With a bot tax only set_collection_during_mint can follow the mint in its transaction');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MintNotLastInstructionError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17ad, () => new MintNotLastInstructionError());
+createErrorFromNameLookup.set('MintNotLastInstruction', () => new MintNotLastInstructionError());
+
+/**
+ * RevealNeedsConfigLock: 'Reveal needs the config to be locked'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class RevealNeedsConfigLockError extends Error {
+  readonly code: number = 0x17ae;
+  readonly name: string = 'RevealNeedsConfigLock';
+  constructor() {
+    super('Reveal needs the config to be locked');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, RevealNeedsConfigLockError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17ae, () => new RevealNeedsConfigLockError());
+createErrorFromNameLookup.set('RevealNeedsConfigLock', () => new RevealNeedsConfigLockError());
+
+/**
+ * MissingCollectionAccounts: 'Candy machine has a collection, the collection accounts are missing'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MissingCollectionAccountsError extends Error {
+  readonly code: number = 0x17af;
+  readonly name: string = 'MissingCollectionAccounts';
+  constructor() {
+    super('Candy machine has a collection, the collection accounts are missing');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MissingCollectionAccountsError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17af, () => new MissingCollectionAccountsError());
+createErrorFromNameLookup.set(
+  'MissingCollectionAccounts',
+  () => new MissingCollectionAccountsError(),
+);
+
+/**
+ * CommitRevealWithCollection: 'Commit reveal mints cannot be added to a collection'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class CommitRevealWithCollectionError extends Error {
+  readonly code: number = 0x17b0;
+  readonly name: string = 'CommitRevealWithCollection';
+  constructor() {
+    super('Commit reveal mints cannot be added to a collection');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, CommitRevealWithCollectionError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17b0, () => new CommitRevealWithCollectionError());
+createErrorFromNameLookup.set(
+  'CommitRevealWithCollection',
+  () => new CommitRevealWithCollectionError(),
+);
+
+/**
+ * PendingMintNotExpired: 'The target slot hash of the pending mint is still available'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class PendingMintNotExpiredError extends Error {
+  readonly code: number = 0x17b1;
+  readonly name: string = 'PendingMintNotExpired';
+  constructor() {
+    super('The target slot hash of the pending mint is still available');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, PendingMintNotExpiredError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17b1, () => new PendingMintNotExpiredError());
+createErrorFromNameLookup.set('PendingMintNotExpired', () => new PendingMintNotExpiredError());
+
+/**
+ * InvalidCreatorShares: 'Creator shares of the mint settings must add up to 100'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidCreatorSharesError extends Error {
+  readonly code: number = 0x17b2;
+  readonly name: string = 'InvalidCreatorShares';
+  constructor() {
+    super('Creator shares of the mint settings must add up to 100');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, InvalidCreatorSharesError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17b2, () => new InvalidCreatorSharesError());
+createErrorFromNameLookup.set('InvalidCreatorShares', () => new InvalidCreatorSharesError());
+
+/**
+ * InvalidUses: 'Remaining uses of the mint settings cannot exceed the total'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidUsesError extends Error {
+  readonly code: number = 0x17b3;
+  readonly name: string = 'InvalidUses';
+  constructor() {
+    super('Remaining uses of the mint settings cannot exceed the total');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, InvalidUsesError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17b3, () => new InvalidUsesError());
+createErrorFromNameLookup.set('InvalidUses', () => new InvalidUsesError());
+
+/**
+ * UnsettledMints: 'Candy machine has mints waiting to be settled or revealed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class UnsettledMintsError extends Error {
+  readonly code: number = 0x17b4;
+  readonly name: string = 'UnsettledMints';
+  constructor() {
+    super('Candy machine has mints waiting to be settled or revealed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, UnsettledMintsError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17b4, () => new UnsettledMintsError());
+createErrorFromNameLookup.set('UnsettledMints', () => new UnsettledMintsError());
+
+/**
+ * RevealNeedsMutableMetadata: 'Reveal needs mutable metadata with the update authority retained'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class RevealNeedsMutableMetadataError extends Error {
+  readonly code: number = 0x17b5;
+  readonly name: string = 'RevealNeedsMutableMetadata';
+  constructor() {
+    super('Reveal needs mutable metadata with the update authority retained');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, RevealNeedsMutableMetadataError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17b5, () => new RevealNeedsMutableMetadataError());
+createErrorFromNameLookup.set('RevealNeedsMutableMetadata', () => new RevealNeedsMutableMetadataError());
+
+/**
+ * InvalidRevealProof: 'Revealed content is not in the content root of the reveal settings'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class InvalidRevealProofError extends Error {
+  readonly code: number = 0x17b6;
+  readonly name: string = 'InvalidRevealProof';
+  constructor() {
+    super('Revealed content is not in the content root of the reveal settings');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, InvalidRevealProofError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17b6, () => new InvalidRevealProofError());
+createErrorFromNameLookup.set('InvalidRevealProof', () => new InvalidRevealProofError());
+
+/**
+ * PendingMintOutOfOrder: 'Pending mints settle in mint order, an earlier pending mint is not settled'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class PendingMintOutOfOrderError extends Error {
+  readonly code: number = 0x17b7;
+  readonly name: string = 'PendingMintOutOfOrder';
+  constructor() {
+    super('Pending mints settle in mint order, an earlier pending mint is not settled');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, PendingMintOutOfOrderError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x17b7, () => new PendingMintOutOfOrderError());
+createErrorFromNameLookup.set('PendingMintOutOfOrder', () => new PendingMintOutOfOrderError());
+
 /**
  * Attempts to resolve a custom program error from the provided error code.
  * @category Errors
@@ -1,9 +1,21 @@
 export * from './addConfigLines';
+export * from './closeCandyGuard';
+export * from './closeCandyMachine';
+export * from './enableCommitReveal';
+export * from './enableReveal';
+export * from './initializeCandyGuard';
 export * from './initializeCandyMachine';
+export * from './lockConfig';
 export * from './mintNft';
+export * from './mintNftV2';
 export * from './removeCollection';
+export * from './renewPendingMint';
+export * from './reveal';
 export * from './setCollection';
 export * from './setCollectionDuringMint';
+export * from './setMintSettings';
+export * from './settlePendingMint';
 export * from './updateAuthority';
+export * from './updateCandyGuard';
 export * from './updateCandyMachine';
 export * from './withdrawFunds';
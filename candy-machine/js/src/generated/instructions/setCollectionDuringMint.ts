@@ -108,7 +108,7 @@ export function createSetCollectionDuringMintInstruction(
     },
     {
       pubkey: collectionMetadata,
-      isWritable: false,
+      isWritable: true,
       isSigner: false,
     },
     {
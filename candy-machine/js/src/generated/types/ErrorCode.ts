@@ -41,6 +41,48 @@ export enum ErrorCode {
   IncorrectCollectionAuthority,
   MismatchedCollectionPDA,
   MismatchedCollectionMint,
+  MissingCandyGuard,
+  MissingGuardAccount,
+  TooManyGuards,
+  DuplicateGuard,
+  InvalidGuardSettings,
+  NotEnoughGateTokens,
+  TooManyGuardGroups,
+  InvalidGuardGroupLabel,
+  DuplicateGuardGroup,
+  GuardGroupNotFound,
+  GuardGroupRequired,
+  MintLimitReached,
+  MissingAllowListProof,
+  NotOnAllowList,
+  AllowListAllocationReached,
+  RevealNeedsHiddenSettings,
+  RevealEnabledAfterMint,
+  MissingMintIndex,
+  RevealSettingsHashMismatch,
+  CandyMachineNotEmpty,
+  CandyGuardNotClosed,
+  CommitRevealNeedsConfigLines,
+  MissingPendingMint,
+  PendingMintNotReady,
+  PendingMintExpired,
+  ConfigLocked,
+  ConfigNotLocked,
+  ConfigLinesMissing,
+  MissingConfigLock,
+  MissingMintSettings,
+  DiscountPriceCurrencyMismatch,
+  MintNotLastInstruction,
+  RevealNeedsConfigLock,
+  MissingCollectionAccounts,
+  CommitRevealWithCollection,
+  PendingMintNotExpired,
+  InvalidCreatorShares,
+  InvalidUses,
+  UnsettledMints,
+  RevealNeedsMutableMetadata,
+  InvalidRevealProof,
+  PendingMintOutOfOrder,
 }
 
 /**
@@ -0,0 +1,112 @@
+/**
+ * This file is written by hand since solita cannot render data enums with beet ^0.1.0.
+ * Keep it in sync with the Guard enum of the program and restore it after running `yarn api:gen`.
+ */
+
+import * as beet from '@metaplex-foundation/beet';
+import { StartDate, startDateBeet } from './StartDate';
+import { EndDate, endDateBeet } from './EndDate';
+import { TokenGate, tokenGateBeet } from './TokenGate';
+import { Payment, paymentBeet } from './Payment';
+import { MintLimit, mintLimitBeet } from './MintLimit';
+import { AllowList, allowListBeet } from './AllowList';
+
+/**
+ * Union type respresenting the Guard data enum defined in Rust.
+ *
+ * NOTE: that it includes a `__kind` property which allows to narrow types in
+ * switch/if statements.
+ * Additionally `isGuard*` type guards are exposed below to narrow to a specific variant.
+ *
+ * @category userTypes
+ * @category enums
+ */
+export type Guard =
+  | { __kind: 'StartDate'; fields: [StartDate] }
+  | { __kind: 'EndDate'; fields: [EndDate] }
+  | { __kind: 'TokenGate'; fields: [TokenGate] }
+  | { __kind: 'Payment'; fields: [Payment] }
+  | { __kind: 'MintLimit'; fields: [MintLimit] }
+  | { __kind: 'AllowList'; fields: [AllowList] };
+
+export const isGuardStartDate = (x: Guard): x is Guard & { __kind: 'StartDate' } =>
+  x.__kind === 'StartDate';
+export const isGuardEndDate = (x: Guard): x is Guard & { __kind: 'EndDate' } =>
+  x.__kind === 'EndDate';
+export const isGuardTokenGate = (x: Guard): x is Guard & { __kind: 'TokenGate' } =>
+  x.__kind === 'TokenGate';
+export const isGuardPayment = (x: Guard): x is Guard & { __kind: 'Payment' } =>
+  x.__kind === 'Payment';
+export const isGuardMintLimit = (x: Guard): x is Guard & { __kind: 'MintLimit' } =>
+  x.__kind === 'MintLimit';
+export const isGuardAllowList = (x: Guard): x is Guard & { __kind: 'AllowList' } =>
+  x.__kind === 'AllowList';
+
+type GuardFields = Guard['fields'][0];
+type FieldsBeet = beet.FixedSizeBeet<GuardFields> | beet.FixableBeet<GuardFields>;
+
+// In the order of the variants of the Rust enum, the index is the borsh discriminant.
+const variants: [Guard['__kind'], FieldsBeet][] = [
+  ['StartDate', startDateBeet as FieldsBeet],
+  ['EndDate', endDateBeet as FieldsBeet],
+  ['TokenGate', tokenGateBeet as FieldsBeet],
+  ['Payment', paymentBeet as FieldsBeet],
+  ['MintLimit', mintLimitBeet as FieldsBeet],
+  ['AllowList', allowListBeet as FieldsBeet],
+];
+
+function isFixable(inner: FieldsBeet): inner is beet.FixableBeet<GuardFields> {
+  return typeof (inner as beet.FixableBeet<GuardFields>).toFixedFromData === 'function';
+}
+
+function fixedGuardBeet(
+  discriminant: number,
+  inner: beet.FixedSizeBeet<GuardFields>,
+): beet.FixedSizeBeet<Guard> {
+  const [kind] = variants[discriminant];
+  return {
+    write(buf: Buffer, offset: number, value: Guard) {
+      buf.writeUInt8(discriminant, offset);
+      inner.write(buf, offset + 1, value.fields[0]);
+    },
+    read(buf: Buffer, offset: number): Guard {
+      return { __kind: kind, fields: [inner.read(buf, offset + 1)] } as Guard;
+    },
+    byteSize: 1 + inner.byteSize,
+    description: `Guard.${kind}`,
+  };
+}
+
+function variantIndex(kind: Guard['__kind']): number {
+  const discriminant = variants.findIndex(([variant]) => variant === kind);
+  if (discriminant < 0) {
+    throw new Error(`Unknown Guard variant ${kind}`);
+  }
+  return discriminant;
+}
+
+/**
+ * @category userTypes
+ */
+export const guardBeet: beet.FixableBeet<Guard> = {
+  toFixedFromData(buf: Buffer, offset: number): beet.FixedSizeBeet<Guard> {
+    const discriminant = buf.readUInt8(offset);
+    if (discriminant >= variants.length) {
+      throw new Error(`Unknown Guard discriminant ${discriminant}`);
+    }
+    const inner = variants[discriminant][1];
+    return fixedGuardBeet(
+      discriminant,
+      isFixable(inner) ? inner.toFixedFromData(buf, offset + 1) : inner,
+    );
+  },
+  toFixedFromValue(value: Guard): beet.FixedSizeBeet<Guard> {
+    const discriminant = variantIndex(value.__kind);
+    const inner = variants[discriminant][1];
+    return fixedGuardBeet(
+      discriminant,
+      isFixable(inner) ? inner.toFixedFromValue(value.fields[0]) : inner,
+    );
+  },
+  description: 'Guard',
+};
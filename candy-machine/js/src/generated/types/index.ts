@@ -1,10 +1,26 @@
+export * from './AllowList';
+export * from './AllowListProof';
+export * from './BotTax';
+export * from './CandyGuardData';
 export * from './CandyMachineData';
 export * from './ConfigLine';
 export * from './Creator';
+export * from './EndDate';
 export * from './EndSettingType';
 export * from './EndSettings';
 export * from './ErrorCode';
 export * from './GatekeeperConfig';
+export * from './Guard';
+export * from './GuardGroup';
 export * from './HiddenSettings';
+export * from './MintArgs';
+export * from './MintLimit';
+export * from './MintSettingsData';
+export * from './Payment';
+export * from './RevealSettings';
+export * from './StartDate';
+export * from './TokenGate';
+export * from './UseMethod';
+export * from './Uses';
 export * from './WhitelistMintMode';
 export * from './WhitelistMintSettings';
@@ -0,0 +1,81 @@
+import { AccountInfo, Keypair, PublicKey } from '@solana/web3.js';
+import {
+  CandyGuard,
+  CandyGuardArgs,
+  createMintNftV2Instruction,
+  isGuardAllowList,
+  PROGRAM_ID,
+} from 'src/generated';
+import { errorFromName } from 'src/generated/errors';
+import test from 'tape';
+
+function randomKey(): PublicKey {
+  return Keypair.generate().publicKey;
+}
+
+test('account candy-guard: round trip serialization of an allow list', async (t) => {
+  const merkleRoot = Array.from(randomKey().toBytes());
+
+  const args: CandyGuardArgs = {
+    candyMachine: randomKey(),
+    bump: 251,
+    data: {
+      guards: [{ __kind: 'AllowList', fields: [{ merkleRoot }] }],
+      groups: [],
+      botTax: null,
+    },
+  };
+
+  const [data] = CandyGuard.fromArgs(args).serialize();
+  const info: AccountInfo<Buffer> = {
+    executable: false,
+    data,
+    owner: PROGRAM_ID,
+    lamports: 1000,
+  };
+
+  const [allowList] = CandyGuard.fromAccountInfo(info)[0].data.guards;
+  t.ok(isGuardAllowList(allowList), 'allow list guard');
+  if (isGuardAllowList(allowList)) {
+    t.deepEqual(allowList.fields[0].merkleRoot, merkleRoot, 'merkle root');
+  }
+});
+
+test('ix mint-nft-v2: passes the allow list proof', async (t) => {
+  const proof = [Array.from(randomKey().toBytes()), Array.from(randomKey().toBytes())];
+
+  const ix = createMintNftV2Instruction(
+    {
+      candyMachine: randomKey(),
+      candyMachineCreator: randomKey(),
+      payer: randomKey(),
+      wallet: randomKey(),
+      metadata: randomKey(),
+      mint: randomKey(),
+      mintAuthority: randomKey(),
+      updateAuthority: randomKey(),
+      masterEdition: randomKey(),
+      tokenMetadataProgram: randomKey(),
+      clock: randomKey(),
+      recentBlockhashes: randomKey(),
+      instructionSysvarAccount: randomKey(),
+    },
+    { creatorBump: 254, args: { group: null, allowListProof: { allocation: 3, proof } } },
+  );
+
+  // discriminator, creator bump, group option, proof option, allocation option and proof
+  t.equal(ix.data.length, 8 + 1 + 1 + 1 + 3 + 4 + 2 * 32, 'data length');
+  t.equal(ix.data.readUInt16LE(12), 3, 'allocation');
+  t.equal(ix.data.readUInt32LE(14), 2, 'proof length');
+  t.deepEqual(Array.from(ix.data.slice(18, 50)), proof[0], 'first proof node');
+  t.deepEqual(Array.from(ix.data.slice(50)), proof[1], 'second proof node');
+});
+
+test('errors allow-list: resolves the allow list errors by name', async (t) => {
+  t.equal(errorFromName('NotOnAllowList')?.code, 0x179b, 'not on allow list');
+  t.equal(
+    errorFromName('AllowListAllocationReached')?.code,
+    0x179c,
+    'allow list allocation reached',
+  );
+});
@@ -0,0 +1,51 @@
+import { AccountInfo, Keypair } from '@solana/web3.js';
+import {
+  CandyGuard,
+  CandyGuardArgs,
+  createUpdateCandyGuardInstruction,
+  PROGRAM_ID,
+} from 'src/generated';
+import { errorFromName } from 'src/generated/errors';
+import test from 'tape';
+import spok from 'spok';
+
+test('account candy-guard: round trip serialization of a bot tax', async (t) => {
+  const args: CandyGuardArgs = {
+    candyMachine: Keypair.generate().publicKey,
+    bump: 252,
+    data: { guards: [], groups: [], botTax: { lamports: 10000000 } },
+  };
+
+  const [data] = CandyGuard.fromArgs(args).serialize();
+  const info: AccountInfo<Buffer> = {
+    executable: false,
+    data,
+    owner: PROGRAM_ID,
+    lamports: 1000,
+  };
+
+  const { botTax } = CandyGuard.fromAccountInfo(info)[0].data;
+  t.equal(botTax?.lamports.toString(), '10000000', 'bot tax lamports');
+});
+
+test('ix update-candy-guard: sets the bot tax of the guard set', async (t) => {
+  const candyMachine = Keypair.generate().publicKey;
+  const candyGuard = Keypair.generate().publicKey;
+  const authority = Keypair.generate().publicKey;
+
+  const ix = createUpdateCandyGuardInstruction(
+    { candyMachine, candyGuard, authority },
+    { data: { guards: [], groups: [], botTax: { lamports: 10000000 } } },
+  );
+
+  spok(t, ix.keys[0], { $topic: 'candy machine', pubkey: candyMachine, isWritable: false });
+  spok(t, ix.keys[1], { $topic: 'candy guard', pubkey: candyGuard, isWritable: true });
+  spok(t, ix.keys[2], { $topic: 'authority', pubkey: authority, isSigner: true });
+  // discriminator, empty guards and groups, bot tax option and lamports
+  t.equal(ix.data.length, 8 + 4 + 4 + 1 + 8, 'data length');
+  t.equal(ix.data.readUInt32LE(17), 10000000, 'bot tax lamports');
+});
+
+test('errors bot-tax: resolves the bot tax error by name', async (t) => {
+  t.equal(errorFromName('MintNotLastInstruction')?.code, 0x17ad, 'mint not last instruction');
+});
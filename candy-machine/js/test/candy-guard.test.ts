@@ -0,0 +1,76 @@
+import { AccountInfo, Keypair } from '@solana/web3.js';
+import {
+  CandyGuard,
+  CandyGuardArgs,
+  createInitializeCandyGuardInstruction,
+  isGuardStartDate,
+  isGuardTokenGate,
+  PROGRAM_ID,
+} from 'src/generated';
+import { errorFromName } from 'src/generated/errors';
+import test from 'tape';
+import spok from 'spok';
+
+test('account candy-guard: round trip serialization', async (t) => {
+  const candyMachine = Keypair.generate().publicKey;
+  const gateMint = Keypair.generate().publicKey;
+
+  const args: CandyGuardArgs = {
+    candyMachine,
+    bump: 254,
+    data: {
+      guards: [
+        { __kind: 'StartDate', fields: [{ date: 1650000000 }] },
+        { __kind: 'TokenGate', fields: [{ mint: gateMint, amount: 2 }] },
+      ],
+      groups: [],
+      botTax: null,
+    },
+  };
+
+  const [data] = CandyGuard.fromArgs(args).serialize();
+  const info: AccountInfo<Buffer> = {
+    executable: false,
+    data,
+    owner: PROGRAM_ID,
+    lamports: 1000,
+  };
+
+  const actual = CandyGuard.fromAccountInfo(info)[0];
+  spok(t, actual, { $topic: 'candy guard', candyMachine, bump: 254 });
+  t.equal(actual.data.guards.length, 2, 'keeps both guards');
+
+  const [startDate, tokenGate] = actual.data.guards;
+  t.ok(isGuardStartDate(startDate), 'first guard is the start date');
+  if (isGuardStartDate(startDate)) {
+    t.equal(startDate.fields[0].date.toString(), '1650000000', 'start date');
+  }
+  t.ok(isGuardTokenGate(tokenGate), 'second guard is the token gate');
+  if (isGuardTokenGate(tokenGate)) {
+    spok(t, tokenGate.fields[0], { $topic: 'token gate', mint: gateMint });
+    t.equal(tokenGate.fields[0].amount.toString(), '2', 'token gate amount');
+  }
+});
+
+test('ix initialize-candy-guard: the payer funds the new candy guard', async (t) => {
+  const candyMachine = Keypair.generate().publicKey;
+  const candyGuard = Keypair.generate().publicKey;
+  const authority = Keypair.generate().publicKey;
+  const payer = Keypair.generate().publicKey;
+
+  const ix = createInitializeCandyGuardInstruction(
+    { candyMachine, candyGuard, authority, payer },
+    { data: { guards: [], groups: [], botTax: null } },
+  );
+
+  t.ok(ix.programId.equals(PROGRAM_ID), 'candy machine program');
+  spok(t, ix.keys[0], { $topic: 'candy machine', pubkey: candyMachine, isWritable: true });
+  spok(t, ix.keys[1], { $topic: 'candy guard', pubkey: candyGuard, isWritable: true });
+  spok(t, ix.keys[2], { $topic: 'authority', pubkey: authority, isSigner: true });
+  spok(t, ix.keys[3], { $topic: 'payer', pubkey: payer, isWritable: true, isSigner: true });
+});
+
+test('errors candy-guard: resolves the guard errors by name', async (t) => {
+  t.equal(errorFromName('MissingCandyGuard')?.code, 0x178e, 'missing candy guard');
+  t.equal(errorFromName('NotEnoughGateTokens')?.code, 0x1793, 'not enough gate tokens');
+});
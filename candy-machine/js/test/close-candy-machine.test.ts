@@ -0,0 +1,42 @@
+import { Keypair } from '@solana/web3.js';
+import { createCloseCandyMachineInstruction } from 'src/generated';
+import { errorFromName } from 'src/generated/errors';
+import test from 'tape';
+import spok from 'spok';
+
+test('ix close-candy-machine: the authority receives the rent', async (t) => {
+  const candyMachine = Keypair.generate().publicKey;
+  const authority = Keypair.generate().publicKey;
+  const collectionPda = Keypair.generate().publicKey;
+
+  const ix = createCloseCandyMachineInstruction(
+    { candyMachine, authority, collectionPda },
+    { force: true },
+  );
+
+  spok(t, ix.keys[0], { $topic: 'candy machine', pubkey: candyMachine, isWritable: true });
+  spok(t, ix.keys[1], { $topic: 'authority', pubkey: authority, isWritable: true, isSigner: true });
+  spok(t, ix.keys[2], { $topic: 'collection pda', pubkey: collectionPda, isWritable: true });
+  t.equal(ix.data.length, 8 + 1, 'data length');
+  t.equal(ix.data[8], 1, 'force');
+});
+
+test('ix close-candy-machine: closes only an empty candy machine by default', async (t) => {
+  const ix = createCloseCandyMachineInstruction(
+    {
+      candyMachine: Keypair.generate().publicKey,
+      authority: Keypair.generate().publicKey,
+      collectionPda: Keypair.generate().publicKey,
+    },
+    { force: false },
+  );
+
+  t.equal(ix.data[8], 0, 'no force');
+});
+
+test('errors close-candy-machine: resolves the close errors by name', async (t) => {
+  t.equal(errorFromName('CandyMachineNotEmpty')?.code, 0x17a1, 'candy machine not empty');
+  t.equal(errorFromName('CandyGuardNotClosed')?.code, 0x17a2, 'candy guard not closed');
+  t.equal(errorFromName('MissingCollectionAccounts')?.code, 0x17af, 'missing collection accounts');
+  t.equal(errorFromName('UnsettledMints')?.code, 0x17b4, 'unsettled mints');
+});
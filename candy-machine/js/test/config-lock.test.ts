@@ -0,0 +1,44 @@
+import { AccountInfo, Keypair } from '@solana/web3.js';
+import { ConfigLock, ConfigLockArgs, createLockConfigInstruction, PROGRAM_ID } from 'src/generated';
+import { errorFromName } from 'src/generated/errors';
+import test from 'tape';
+import spok from 'spok';
+
+test('account config-lock: round trip serialization', async (t) => {
+  const args: ConfigLockArgs = {
+    candyMachine: Keypair.generate().publicKey,
+    hash: Array.from({ length: 32 }, (_, i) => i),
+  };
+
+  const expected = ConfigLock.fromArgs(args);
+  const [data] = expected.serialize();
+  t.equal(data.length, 8 + 32 + 32, 'discriminator, candy machine and hash');
+
+  const info: AccountInfo<Buffer> = {
+    executable: false,
+    data,
+    owner: PROGRAM_ID,
+    lamports: 1000,
+  };
+
+  const actual = ConfigLock.fromAccountInfo(info)[0];
+  spok(t, actual, expected);
+});
+
+test('ix lock-config: the authority pays for the config lock', async (t) => {
+  const candyMachine = Keypair.generate().publicKey;
+  const configLock = Keypair.generate().publicKey;
+  const authority = Keypair.generate().publicKey;
+
+  const ix = createLockConfigInstruction({ candyMachine, configLock, authority });
+
+  spok(t, ix.keys[0], { $topic: 'candy machine', pubkey: candyMachine, isWritable: true });
+  spok(t, ix.keys[1], { $topic: 'config lock', pubkey: configLock, isWritable: true });
+  spok(t, ix.keys[2], { $topic: 'authority', pubkey: authority, isWritable: true, isSigner: true });
+});
+
+test('errors config-lock: resolves the config lock errors by name', async (t) => {
+  t.equal(errorFromName('ConfigLocked')?.code, 0x17a7, 'config locked');
+  t.equal(errorFromName('ConfigNotLocked')?.code, 0x17a8, 'config not locked');
+  t.equal(errorFromName('ConfigLinesMissing')?.code, 0x17a9, 'config lines missing');
+});
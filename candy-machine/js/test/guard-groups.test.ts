@@ -0,0 +1,106 @@
+import { AccountInfo, Keypair, PublicKey } from '@solana/web3.js';
+import {
+  CandyGuard,
+  CandyGuardArgs,
+  createMintNftInstruction,
+  createMintNftV2Instruction,
+  isGuardPayment,
+  MintNftInstructionAccounts,
+  PROGRAM_ID,
+} from 'src/generated';
+import { errorFromName } from 'src/generated/errors';
+import test from 'tape';
+import spok from 'spok';
+
+function randomKey(): PublicKey {
+  return Keypair.generate().publicKey;
+}
+
+function mintAccounts(): MintNftInstructionAccounts {
+  return {
+    candyMachine: randomKey(),
+    candyMachineCreator: randomKey(),
+    payer: randomKey(),
+    wallet: randomKey(),
+    metadata: randomKey(),
+    mint: randomKey(),
+    mintAuthority: randomKey(),
+    updateAuthority: randomKey(),
+    masterEdition: randomKey(),
+    tokenMetadataProgram: randomKey(),
+    clock: randomKey(),
+    recentBlockhashes: randomKey(),
+    instructionSysvarAccount: randomKey(),
+  };
+}
+
+test('account candy-guard: round trip serialization of guard groups', async (t) => {
+  const destination = randomKey();
+  const paymentMint = randomKey();
+
+  const args: CandyGuardArgs = {
+    candyMachine: randomKey(),
+    bump: 255,
+    data: {
+      guards: [],
+      groups: [
+        {
+          label: 'early',
+          guards: [{ __kind: 'Payment', fields: [{ price: 1000, mint: null, destination }] }],
+        },
+        {
+          label: 'public',
+          guards: [{ __kind: 'Payment', fields: [{ price: 5, mint: paymentMint, destination }] }],
+        },
+      ],
+      botTax: null,
+    },
+  };
+
+  const [data] = CandyGuard.fromArgs(args).serialize();
+  const info: AccountInfo<Buffer> = {
+    executable: false,
+    data,
+    owner: PROGRAM_ID,
+    lamports: 1000,
+  };
+
+  const [early, pub] = CandyGuard.fromAccountInfo(info)[0].data.groups;
+  t.equal(early.label, 'early', 'early group label');
+  t.equal(pub.label, 'public', 'public group label');
+
+  const [earlyPayment] = early.guards;
+  const [publicPayment] = pub.guards;
+  t.ok(isGuardPayment(earlyPayment) && isGuardPayment(publicPayment), 'groups pay with a guard');
+  if (isGuardPayment(earlyPayment) && isGuardPayment(publicPayment)) {
+    spok(t, earlyPayment.fields[0], { $topic: 'early payment', mint: null, destination });
+    t.equal(earlyPayment.fields[0].price.toString(), '1000', 'early payment price');
+    spok(t, publicPayment.fields[0], { $topic: 'public payment', mint: paymentMint, destination });
+    t.equal(publicPayment.fields[0].price.toString(), '5', 'public payment price');
+  }
+});
+
+test('ix mint-nft-v2: takes the accounts of mint-nft and the group label', async (t) => {
+  const accounts = mintAccounts();
+
+  const v1 = createMintNftInstruction(accounts, { creatorBump: 254 });
+  const v2 = createMintNftV2Instruction(accounts, {
+    creatorBump: 254,
+    args: { group: 'early', allowListProof: null },
+  });
+
+  t.deepEqual(v2.keys, v1.keys, 'same accounts as mint-nft');
+  // discriminator, creator bump, group option, group label and allow list proof option
+  t.equal(v2.data.length, 8 + 1 + 1 + 4 + 5 + 1, 'data length');
+  t.equal(v2.data[8], 254, 'creator bump');
+  t.equal(v2.data.slice(14, 19).toString(), 'early', 'group label');
+});
+
+test('errors guard-groups: resolves the guard group errors by name', async (t) => {
+  t.equal(errorFromName('GuardGroupRequired')?.code, 0x1798, 'guard group required');
+  t.equal(
+    errorFromName('DiscountPriceCurrencyMismatch')?.code,
+    0x17ac,
+    'discount price currency mismatch',
+  );
+});
@@ -0,0 +1,61 @@
+import { AccountInfo, Keypair } from '@solana/web3.js';
+import {
+  CandyGuard,
+  CandyGuardArgs,
+  isGuardMintLimit,
+  MintCounter,
+  MintCounterArgs,
+  PROGRAM_ID,
+} from 'src/generated';
+import { errorFromName } from 'src/generated/errors';
+import test from 'tape';
+import spok from 'spok';
+
+test('account mint-counter: round trip serialization', async (t) => {
+  const args: MintCounterArgs = { count: 3 };
+
+  const expected = MintCounter.fromArgs(args);
+  const [data] = expected.serialize();
+  t.equal(data.length, 8 + 2, 'discriminator and count');
+  t.ok(MintCounter.hasCorrectByteSize(data), 'fixed size account');
+
+  const info: AccountInfo<Buffer> = {
+    executable: false,
+    data,
+    owner: PROGRAM_ID,
+    lamports: 1000,
+  };
+
+  const actual = MintCounter.fromAccountInfo(info)[0];
+  spok(t, actual, expected);
+});
+
+test('account candy-guard: round trip serialization of a mint limit', async (t) => {
+  const args: CandyGuardArgs = {
+    candyMachine: Keypair.generate().publicKey,
+    bump: 253,
+    data: {
+      guards: [{ __kind: 'MintLimit', fields: [{ id: 1, limit: 2 }] }],
+      groups: [],
+      botTax: null,
+    },
+  };
+
+  const [data] = CandyGuard.fromArgs(args).serialize();
+  const info: AccountInfo<Buffer> = {
+    executable: false,
+    data,
+    owner: PROGRAM_ID,
+    lamports: 1000,
+  };
+
+  const [mintLimit] = CandyGuard.fromAccountInfo(info)[0].data.guards;
+  t.ok(isGuardMintLimit(mintLimit), 'mint limit guard');
+  if (isGuardMintLimit(mintLimit)) {
+    spok(t, mintLimit.fields[0], { $topic: 'mint limit', id: 1, limit: 2 });
+  }
+});
+
+test('errors mint-limit: resolves the mint limit error by name', async (t) => {
+  t.equal(errorFromName('MintLimitReached')?.code, 0x1799, 'mint limit reached');
+});
@@ -0,0 +1,89 @@
+import { AccountInfo, Keypair, PublicKey } from '@solana/web3.js';
+import {
+  createSetCollectionDuringMintInstruction,
+  createSetMintSettingsInstruction,
+  MintSettings,
+  MintSettingsArgs,
+  PROGRAM_ID,
+  UseMethod,
+} from 'src/generated';
+import { errorFromName } from 'src/generated/errors';
+import test from 'tape';
+import spok from 'spok';
+
+function randomKey(): PublicKey {
+  return Keypair.generate().publicKey;
+}
+
+test('account mint-settings: round trip serialization', async (t) => {
+  const [first, second] = [randomKey(), randomKey()];
+  const args: MintSettingsArgs = {
+    candyMachine: randomKey(),
+    data: {
+      creators: [
+        { address: first, verified: false, share: 60 },
+        { address: second, verified: false, share: 40 },
+      ],
+      uses: { useMethod: UseMethod.Multiple, remaining: 5, total: 10 },
+    },
+  };
+
+  const [data] = MintSettings.fromArgs(args).serialize();
+  const info: AccountInfo<Buffer> = {
+    executable: false,
+    data,
+    owner: PROGRAM_ID,
+    lamports: 1000,
+  };
+
+  const actual = MintSettings.fromAccountInfo(info)[0];
+  spok(t, actual, { $topic: 'mint settings', candyMachine: args.candyMachine });
+  t.deepEqual(actual.data.creators, args.data.creators, 'royalty split');
+  t.equal(actual.data.uses?.useMethod, UseMethod.Multiple, 'use method');
+  t.equal(actual.data.uses?.remaining.toString(), '5', 'remaining uses');
+  t.equal(actual.data.uses?.total.toString(), '10', 'total uses');
+});
+
+test('ix set-mint-settings: the authority pays for the mint settings', async (t) => {
+  const mintSettings = randomKey();
+  const authority = randomKey();
+
+  const ix = createSetMintSettingsInstruction(
+    { candyMachine: randomKey(), mintSettings, authority },
+    { data: { creators: null, uses: null } },
+  );
+
+  spok(t, ix.keys[1], { $topic: 'mint settings', pubkey: mintSettings, isWritable: true });
+  spok(t, ix.keys[2], { $topic: 'authority', pubkey: authority, isWritable: true, isSigner: true });
+  t.equal(ix.data.length, 8 + 1 + 1, 'data length');
+});
+
+test('ix set-collection-during-mint: the collection metadata is writable', async (t) => {
+  const collectionMetadata = randomKey();
+
+  const ix = createSetCollectionDuringMintInstruction({
+    candyMachine: randomKey(),
+    metadata: randomKey(),
+    payer: randomKey(),
+    collectionPda: randomKey(),
+    tokenMetadataProgram: randomKey(),
+    instructions: randomKey(),
+    collectionMint: randomKey(),
+    collectionMetadata,
+    collectionMasterEdition: randomKey(),
+    authority: randomKey(),
+    collectionAuthorityRecord: randomKey(),
+  });
+
+  spok(t, ix.keys[7], {
+    $topic: 'collection metadata',
+    pubkey: collectionMetadata,
+    isWritable: true,
+  });
+});
+
+test('errors mint-settings: resolves the mint settings errors by name', async (t) => {
+  t.equal(errorFromName('MissingMintSettings')?.code, 0x17ab, 'missing mint settings');
+  t.equal(errorFromName('InvalidCreatorShares')?.code, 0x17b2, 'invalid creator shares');
+  t.equal(errorFromName('InvalidUses')?.code, 0x17b3, 'invalid uses');
+});
@@ -0,0 +1,109 @@
+import { AccountInfo, Keypair, PublicKey } from '@solana/web3.js';
+import {
+  createEnableCommitRevealInstruction,
+  createRenewPendingMintInstruction,
+  createSettlePendingMintInstruction,
+  PendingMint,
+  PendingMintArgs,
+  PROGRAM_ID,
+} from 'src/generated';
+import { errorFromName } from 'src/generated/errors';
+import test from 'tape';
+import spok from 'spok';
+
+function randomKey(): PublicKey {
+  return Keypair.generate().publicKey;
+}
+
+test('account pending-mint: round trip serialization', async (t) => {
+  const payer = randomKey();
+  const newUpdateAuthority = randomKey();
+  const args: PendingMintArgs = {
+    payer,
+    mintNumber: 7,
+    targetSlot: 123456,
+    newUpdateAuthority,
+    primarySaleHappened: true,
+  };
+
+  const [data] = PendingMint.fromArgs(args).serialize();
+  t.ok(PendingMint.hasCorrectByteSize(data), 'fixed size account');
+
+  const info: AccountInfo<Buffer> = {
+    executable: false,
+    data,
+    owner: PROGRAM_ID,
+    lamports: 1000,
+  };
+
+  const actual = PendingMint.fromAccountInfo(info)[0];
+  spok(t, actual, {
+    $topic: 'pending mint',
+    payer,
+    newUpdateAuthority,
+    primarySaleHappened: true,
+  });
+  t.equal(actual.mintNumber.toString(), '7', 'mint number');
+  t.equal(actual.targetSlot.toString(), '123456', 'target slot');
+});
+
+test('ix enable-commit-reveal: checks the candy machine has no collection', async (t) => {
+  const collectionPda = randomKey();
+
+  const ix = createEnableCommitRevealInstruction({
+    candyMachine: randomKey(),
+    authority: randomKey(),
+    collectionPda,
+  });
+
+  spok(t, ix.keys[2], { $topic: 'collection pda', pubkey: collectionPda, isWritable: false });
+});
+
+test('ix settle-pending-mint: anyone can settle and the payer gets the rent', async (t) => {
+  const pendingMint = randomKey();
+  const payer = randomKey();
+
+  const ix = createSettlePendingMintInstruction({
+    candyMachine: randomKey(),
+    candyMachineCreator: randomKey(),
+    pendingMint,
+    mint: randomKey(),
+    metadata: randomKey(),
+    payer,
+    tokenMetadataProgram: randomKey(),
+    recentSlothashes: randomKey(),
+    clock: randomKey(),
+  });
+
+  t.notOk(ix.keys.some((key) => key.isSigner), 'no signer');
+  spok(t, ix.keys[2], { $topic: 'pending mint', pubkey: pendingMint, isWritable: true });
+  spok(t, ix.keys[5], { $topic: 'payer', pubkey: payer, isWritable: true });
+});
+
+test('ix renew-pending-mint: only the authority renews', async (t) => {
+  const authority = randomKey();
+  const pendingMint = randomKey();
+
+  const ix = createRenewPendingMintInstruction({
+    candyMachine: randomKey(),
+    authority,
+    pendingMint,
+    mint: randomKey(),
+    recentSlothashes: randomKey(),
+    clock: randomKey(),
+  });
+
+  spok(t, ix.keys[1], { $topic: 'authority', pubkey: authority, isSigner: true });
+  spok(t, ix.keys[2], { $topic: 'pending mint', pubkey: pendingMint, isWritable: true });
+});
+
+test('errors pending-mint: resolves the commit reveal errors by name', async (t) => {
+  t.equal(errorFromName('PendingMintExpired')?.code, 0x17a6, 'pending mint expired');
+  t.equal(errorFromName('PendingMintNotExpired')?.code, 0x17b1, 'pending mint not expired');
+  t.equal(
+    errorFromName('CommitRevealWithCollection')?.code,
+    0x17b0,
+    'commit reveal with collection',
+  );
+  t.equal(errorFromName('PendingMintOutOfOrder')?.code, 0x17b7, 'pending mint out of order');
+});
@@ -0,0 +1,82 @@
+import { AccountInfo, Keypair, PublicKey } from '@solana/web3.js';
+import {
+  createEnableRevealInstruction,
+  createRevealInstruction,
+  MintIndex,
+  PROGRAM_ID,
+  RevealSettings,
+  revealSettingsBeet,
+} from 'src/generated';
+import { errorFromName } from 'src/generated/errors';
+import test from 'tape';
+import spok from 'spok';
+
+function randomKey(): PublicKey {
+  return Keypair.generate().publicKey;
+}
+
+test('account mint-index: round trip serialization', async (t) => {
+  const [data] = MintIndex.fromArgs({
+    index: 42,
+    revealed: false,
+    contentHash: new Array(32).fill(0),
+  }).serialize();
+  t.ok(MintIndex.hasCorrectByteSize(data), 'fixed size account');
+
+  const info: AccountInfo<Buffer> = {
+    executable: false,
+    data,
+    owner: PROGRAM_ID,
+    lamports: 1000,
+  };
+
+  const actual = MintIndex.fromAccountInfo(info)[0];
+  t.equal(actual.index.toString(), '42', 'mint index');
+  t.equal(actual.revealed, false, 'not revealed');
+});
+
+test('ix enable-reveal: flags the candy machine', async (t) => {
+  const candyMachine = randomKey();
+  const authority = randomKey();
+
+  const ix = createEnableRevealInstruction({ candyMachine, authority });
+
+  spok(t, ix.keys[0], { $topic: 'candy machine', pubkey: candyMachine, isWritable: true });
+  spok(t, ix.keys[1], { $topic: 'authority', pubkey: authority, isSigner: true });
+});
+
+test('ix reveal: updates the metadata with the revealed settings', async (t) => {
+  const mintIndex = randomKey();
+  const metadata = randomKey();
+  const revealSettings: RevealSettings = {
+    uriPrefix: 'https://arweave.net/reveal/',
+    uriSuffix: '.json',
+    contentRoot: new Array(32).fill(1),
+  };
+  const contentHash = new Array(32).fill(2);
+  const proof = [new Array(32).fill(3)];
+
+  const ix = createRevealInstruction(
+    {
+      candyMachine: randomKey(),
+      authority: randomKey(),
+      mintIndex,
+      mint: randomKey(),
+      metadata,
+      tokenMetadataProgram: randomKey(),
+    },
+    { revealSettings, contentHash, proof },
+  );
+
+  spok(t, ix.keys[2], { $topic: 'mint index', pubkey: mintIndex, isWritable: true });
+  spok(t, ix.keys[4], { $topic: 'metadata', pubkey: metadata, isWritable: true });
+  // the hidden settings hash is the sha256 of these bytes
+  const [settings] = revealSettingsBeet.serialize(revealSettings);
+  t.ok(ix.data.slice(8, 8 + settings.length).equals(settings), 'serialized reveal settings');
+});
+
+test('errors reveal: resolves the reveal errors by name', async (t) => {
+  t.equal(errorFromName('RevealSettingsHashMismatch')?.code, 0x17a0, 'hash mismatch');
+  t.equal(errorFromName('RevealNeedsConfigLock')?.code, 0x17ae, 'reveal needs config lock');
+  t.equal(errorFromName('InvalidRevealProof')?.code, 0x17b6, 'invalid reveal proof');
+});
@@ -0,0 +1,542 @@
+use {
+    crate::{
+        utils::{assert_is_ata, assert_keys_equal, assert_owned_by},
+        CandyMachine, ErrorCode,
+    },
+    anchor_lang::{
+        prelude::*,
+        solana_program::{
+            keccak, program::invoke, system_instruction,
+            sysvar::instructions::get_instruction_relative,
+        },
+    },
+    mpl_token_metadata::utils::{assert_derivation, create_or_allocate_account_raw},
+    mpl_utils::merkle_proof,
+    std::mem::discriminant,
+};
+
+pub const CANDY_GUARD: &str = "candy_guard";
+pub const MINT_LIMIT: &str = "mint_limit";
+pub const ALLOW_LIST: &str = "allow_list";
+pub const MINT_COUNTER_SIZE: usize = 8 + 2;
+pub const MAX_GUARDS: usize = 8;
+pub const MAX_GROUPS: usize = 5;
+pub const MAX_GROUP_LABEL_LENGTH: usize = 6;
+// Largest guard settings plus the enum tag.
+pub const MAX_GUARD_LEN: usize = 1 + 8 + 33 + 32;
+pub const MAX_GUARD_GROUP_LEN: usize = 4 + MAX_GROUP_LABEL_LENGTH + // label
+    4 + MAX_GUARDS * MAX_GUARD_LEN; // u32 len + guards
+pub const CANDY_GUARD_SIZE: usize = 8 + // key
+    32 + // candy machine
+    1 + // bump
+    4 + MAX_GUARDS * MAX_GUARD_LEN + // u32 len + default guards
+    4 + MAX_GROUPS * MAX_GUARD_GROUP_LEN + // u32 len + groups
+    1 + 8; // bot tax
+
+/// Guard set of a candy machine. Every guard in it has to pass for a mint to go through,
+/// on top of the settings stored in [`crate::CandyMachineData`].
+#[account]
+#[derive(Default)]
+pub struct CandyGuard {
+    pub candy_machine: Pubkey,
+    pub bump: u8,
+    pub data: CandyGuardData,
+}
+
+/// Guard set settings data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct CandyGuardData {
+    /// Guards every mint has to pass.
+    pub guards: Vec<Guard>,
+    /// Mint phases. When there are groups every mint has to pick one of them by label, its
+    /// guards run together with the default guards and replace a default guard of the same kind.
+    pub groups: Vec<GuardGroup>,
+    /// When set, a mint that fails its checks is charged the tax and succeeds without minting
+    /// instead of failing.
+    pub bot_tax: Option<BotTax>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GuardGroup {
+    pub label: String,
+    pub guards: Vec<Guard>,
+}
+
+/// Penalty charged to the payer of a mint that fails its checks. Failed mints revert for free,
+/// so without it bots can retry until the candy machine goes live. With a bot tax the mint has to
+/// be the last instruction of its transaction. A mint sent along with, or called by, another
+/// program still fails without a tax, since that program could revert the tax.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BotTax {
+    pub lamports: u64,
+}
+
+impl BotTax {
+    /// Makes sure nothing after the mint can revert a charged tax. Only `set_collection_during_mint`
+    /// is allowed to follow the mint.
+    pub fn assert_last_instruction(&self, instruction_sysvar: &AccountInfo) -> ProgramResult {
+        let mut index = 1;
+        while let Ok(instruction) = get_instruction_relative(index, instruction_sysvar) {
+            // set_collection_during_mint
+            if instruction.program_id != crate::id()
+                || instruction.data.get(0..8) != Some(&[103, 17, 200, 25, 118, 95, 125, 61])
+            {
+                msg!(
+                    "Transaction had ix with program id {} after the mint",
+                    instruction.program_id
+                );
+                return Err(ErrorCode::MintNotLastInstruction.into());
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+
+    /// Logs why the mint failed and moves the tax from the payer to the candy machine, where it
+    /// is collected by `withdraw_funds`.
+    pub fn charge<'info>(
+        &self,
+        error: &crate::Error,
+        payer: &AccountInfo<'info>,
+        candy_machine: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        let lamports = self.lamports;
+        if payer.lamports() < lamports {
+            return Err(ErrorCode::NotEnoughSOL.into());
+        }
+        msg!(
+            "Mint failed: {}. Charged a bot tax of {} lamports",
+            error,
+            lamports
+        );
+        invoke(
+            &system_instruction::transfer(payer.key, candy_machine.key, lamports),
+            &[payer.clone(), candy_machine.clone(), system_program.clone()],
+        )?;
+        Ok(())
+    }
+}
+
+/// Number of mints of a wallet for one [`MintLimit`] id or [`AllowList`] root.
+#[account]
+#[derive(Default)]
+pub struct MintCounter {
+    pub count: u16,
+}
+
+/// Payment a guard puts in place of the candy machine price.
+pub struct PaymentOverride<'a, 'info> {
+    pub price: u64,
+    pub mint: Option<Pubkey>,
+    pub destination: &'a AccountInfo<'info>,
+}
+
+/// State a guard can look at while validating a mint.
+pub struct GuardContext<'a, 'info> {
+    pub candy_machine: &'a CandyMachine,
+    pub candy_machine_key: Pubkey,
+    pub payer: &'a AccountInfo<'info>,
+    pub mint_authority: &'a AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+    pub clock: &'a Clock,
+    pub remaining_accounts: &'a [AccountInfo<'info>],
+    pub remaining_accounts_counter: usize,
+    pub payment: Option<PaymentOverride<'a, 'info>>,
+    pub allow_list_proof: Option<&'a AllowListProof>,
+}
+
+impl<'a, 'info> GuardContext<'a, 'info> {
+    /// Takes the next remaining account, guards read their accounts in the order they are listed.
+    pub fn next_account(&mut self) -> core::result::Result<&'a AccountInfo<'info>, ProgramError> {
+        let account = self
+            .remaining_accounts
+            .get(self.remaining_accounts_counter)
+            .ok_or(ErrorCode::MissingGuardAccount)?;
+        self.remaining_accounts_counter += 1;
+        Ok(account)
+    }
+
+    /// Whether the candy machine authority mints for itself, signing as the minter. A mint the
+    /// authority only pays for, as gumdrop does, is not exempt from the guards.
+    pub fn is_authority(&self) -> bool {
+        let minter = self.minter();
+        minter.is_signer && *minter.key == self.candy_machine.authority
+    }
+
+    /// Wallet a mint is counted against. A mint paid by the candy machine authority is made on
+    /// someone else's behalf, as gumdrop does from its distributor wallet, so it counts against
+    /// the mint authority instead. Gumdrop only claims from candy machines whose authority is its
+    /// distributor wallet, so every claim counts against its claimant.
+    pub fn minter(&self) -> &'a AccountInfo<'info> {
+        if *self.payer.key == self.candy_machine.authority {
+            self.mint_authority
+        } else {
+            self.payer
+        }
+    }
+}
+
+pub trait MintGuard {
+    /// Checks the settings when the guard set is written.
+    fn assert_valid_settings(&self) -> ProgramResult {
+        Ok(())
+    }
+
+    /// Checks a mint against the guard, without changing any state.
+    fn validate(&self, ctx: &mut GuardContext) -> ProgramResult;
+
+    /// Updates the guard state once the whole mint passed validation. The guard is handed the
+    /// same accounts as in [`MintGuard::validate`].
+    fn record_mint(&self, _ctx: &mut GuardContext) -> ProgramResult {
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum Guard {
+    StartDate(StartDate),
+    EndDate(EndDate),
+    TokenGate(TokenGate),
+    Payment(Payment),
+    MintLimit(MintLimit),
+    AllowList(AllowList),
+}
+
+impl Guard {
+    fn inner(&self) -> &dyn MintGuard {
+        match self {
+            Guard::StartDate(guard) => guard,
+            Guard::EndDate(guard) => guard,
+            Guard::TokenGate(guard) => guard,
+            Guard::Payment(guard) => guard,
+            Guard::MintLimit(guard) => guard,
+            Guard::AllowList(guard) => guard,
+        }
+    }
+}
+
+/// Minting opens at `date`, the candy machine authority can mint before it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct StartDate {
+    pub date: i64,
+}
+
+impl MintGuard for StartDate {
+    fn validate(&self, ctx: &mut GuardContext) -> ProgramResult {
+        if ctx.clock.unix_timestamp < self.date && !ctx.is_authority() {
+            return Err(ErrorCode::CandyMachineNotLive.into());
+        }
+        Ok(())
+    }
+}
+
+/// Minting closes after `date`, the candy machine authority can mint after it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EndDate {
+    pub date: i64,
+}
+
+impl MintGuard for EndDate {
+    fn validate(&self, ctx: &mut GuardContext) -> ProgramResult {
+        if ctx.clock.unix_timestamp > self.date && !ctx.is_authority() {
+            return Err(ErrorCode::CandyMachineNotLive.into());
+        }
+        Ok(())
+    }
+}
+
+/// The minter must hold at least `amount` tokens of `mint` in their associated token account.
+/// Nothing is burned or transferred.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenGate {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+impl MintGuard for TokenGate {
+    fn assert_valid_settings(&self) -> ProgramResult {
+        if self.amount == 0 {
+            return Err(ErrorCode::InvalidGuardSettings.into());
+        }
+        Ok(())
+    }
+
+    fn validate(&self, ctx: &mut GuardContext) -> ProgramResult {
+        let token_account_info = ctx.next_account()?;
+        let token_account = assert_is_ata(token_account_info, ctx.minter().key, &self.mint)?;
+        if token_account.amount < self.amount {
+            return Err(ErrorCode::NotEnoughGateTokens.into());
+        }
+        Ok(())
+    }
+}
+
+/// Charges `price` instead of the candy machine price, in SOL or in tokens of `mint`. It is paid
+/// to `destination`, a wallet for SOL and a token account of `mint` otherwise.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Payment {
+    pub price: u64,
+    pub mint: Option<Pubkey>,
+    pub destination: Pubkey,
+}
+
+impl MintGuard for Payment {
+    fn validate(&self, ctx: &mut GuardContext) -> ProgramResult {
+        let destination = ctx.next_account()?;
+        assert_keys_equal(*destination.key, self.destination)?;
+        ctx.payment = Some(PaymentOverride {
+            price: self.price,
+            mint: self.mint,
+            destination,
+        });
+        Ok(())
+    }
+}
+
+/// Caps the number of mints of a wallet at `limit`. Mints are counted in a [`MintCounter`] PDA
+/// seeded by `id`, the wallet and the candy machine, so groups can share a counter or keep
+/// their own. The wallet pays for its counter.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MintLimit {
+    pub id: u8,
+    pub limit: u16,
+}
+
+impl MintLimit {
+    fn counter_seeds<'s>(&'s self, minter: &'s Pubkey, candy_machine: &'s Pubkey) -> [&'s [u8]; 4] {
+        [
+            MINT_LIMIT.as_bytes(),
+            std::slice::from_ref(&self.id),
+            minter.as_ref(),
+            candy_machine.as_ref(),
+        ]
+    }
+}
+
+impl MintGuard for MintLimit {
+    fn assert_valid_settings(&self) -> ProgramResult {
+        if self.limit == 0 {
+            return Err(ErrorCode::InvalidGuardSettings.into());
+        }
+        Ok(())
+    }
+
+    fn validate(&self, ctx: &mut GuardContext) -> ProgramResult {
+        let (minter, candy_machine) = (*ctx.minter().key, ctx.candy_machine_key);
+        let (_, _, count) = load_counter(ctx, &self.counter_seeds(&minter, &candy_machine))?;
+        if count >= self.limit {
+            return Err(ErrorCode::MintLimitReached.into());
+        }
+        Ok(())
+    }
+
+    fn record_mint(&self, ctx: &mut GuardContext) -> ProgramResult {
+        let (minter, candy_machine) = (*ctx.minter().key, ctx.candy_machine_key);
+        increment_counter(ctx, &self.counter_seeds(&minter, &candy_machine))
+    }
+}
+
+/// Only wallets in the merkle tree of `merkle_root` can mint, each up to its allocation. A leaf
+/// is `keccak(0x00 || wallet)` for one mint, or `keccak(0x00 || wallet || allocation as u16 le)`
+/// for `allocation` mints. The mint passes the proof of its leaf as [`AllowListProof`] and
+/// claims are counted in a [`MintCounter`] PDA seeded by the root, the wallet and the candy
+/// machine. The wallet pays for its counter.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AllowList {
+    pub merkle_root: [u8; 32],
+}
+
+/// Proof that the minter is a leaf of an [`AllowList`] tree.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AllowListProof {
+    pub allocation: Option<u16>,
+    pub proof: Vec<[u8; 32]>,
+}
+
+impl AllowList {
+    fn counter_seeds<'s>(&'s self, minter: &'s Pubkey, candy_machine: &'s Pubkey) -> [&'s [u8]; 4] {
+        [
+            ALLOW_LIST.as_bytes(),
+            &self.merkle_root,
+            minter.as_ref(),
+            candy_machine.as_ref(),
+        ]
+    }
+}
+
+impl MintGuard for AllowList {
+    fn validate(&self, ctx: &mut GuardContext) -> ProgramResult {
+        let allow_list_proof = ctx
+            .allow_list_proof
+            .ok_or(ErrorCode::MissingAllowListProof)?;
+        let minter = *ctx.minter().key;
+        let leaf = match allow_list_proof.allocation {
+            Some(allocation) => {
+                keccak::hashv(&[&[0x00], minter.as_ref(), &allocation.to_le_bytes()])
+            }
+            None => keccak::hashv(&[&[0x00], minter.as_ref()]),
+        };
+        if !merkle_proof::verify(allow_list_proof.proof.clone(), self.merkle_root, leaf.0) {
+            return Err(ErrorCode::NotOnAllowList.into());
+        }
+
+        let candy_machine = ctx.candy_machine_key;
+        let (_, _, count) = load_counter(ctx, &self.counter_seeds(&minter, &candy_machine))?;
+        if count >= allow_list_proof.allocation.unwrap_or(1) {
+            return Err(ErrorCode::AllowListAllocationReached.into());
+        }
+        Ok(())
+    }
+
+    fn record_mint(&self, ctx: &mut GuardContext) -> ProgramResult {
+        let (minter, candy_machine) = (*ctx.minter().key, ctx.candy_machine_key);
+        increment_counter(ctx, &self.counter_seeds(&minter, &candy_machine))
+    }
+}
+
+/// Takes the [`MintCounter`] account derived from `seeds` and returns it with its bump and the
+/// current count.
+fn load_counter<'a, 'info>(
+    ctx: &mut GuardContext<'a, 'info>,
+    seeds: &[&[u8]],
+) -> core::result::Result<(&'a AccountInfo<'info>, u8, u16), ProgramError> {
+    let counter_info = ctx.next_account()?;
+    let bump = assert_derivation(&crate::id(), counter_info, seeds)?;
+    if counter_info.data_is_empty() {
+        return Ok((counter_info, bump, 0));
+    }
+    assert_owned_by(counter_info, &crate::id())?;
+    let counter = MintCounter::try_deserialize(&mut &counter_info.data.borrow()[..])?;
+    Ok((counter_info, bump, counter.count))
+}
+
+/// Adds a mint to the [`MintCounter`] account derived from `seeds`, creating it on the first
+/// mint of the wallet.
+fn increment_counter(ctx: &mut GuardContext, seeds: &[&[u8]]) -> ProgramResult {
+    let (counter_info, bump, count) = load_counter(ctx, seeds)?;
+    if counter_info.data_is_empty() {
+        let bump = [bump];
+        let mut signer_seeds = seeds.to_vec();
+        signer_seeds.push(&bump);
+        create_or_allocate_account_raw(
+            crate::id(),
+            counter_info,
+            &ctx.rent,
+            &ctx.system_program,
+            ctx.minter(),
+            MINT_COUNTER_SIZE,
+            &signer_seeds,
+        )?;
+    }
+
+    let counter = MintCounter {
+        count: count
+            .checked_add(1)
+            .ok_or(ErrorCode::NumericalOverflowError)?,
+    };
+    let mut data: &mut [u8] = &mut counter_info.try_borrow_mut_data()?;
+    counter.try_serialize(&mut data)?;
+    Ok(())
+}
+
+fn assert_valid_guards(guards: &[Guard]) -> ProgramResult {
+    if guards.len() > MAX_GUARDS {
+        return Err(ErrorCode::TooManyGuards.into());
+    }
+    for (i, guard) in guards.iter().enumerate() {
+        if guards[..i]
+            .iter()
+            .any(|other| discriminant(other) == discriminant(guard))
+        {
+            return Err(ErrorCode::DuplicateGuard.into());
+        }
+        guard.inner().assert_valid_settings()?;
+    }
+    Ok(())
+}
+
+pub fn assert_valid_guard_data(data: &CandyGuardData) -> ProgramResult {
+    assert_valid_guards(&data.guards)?;
+    if data.groups.len() > MAX_GROUPS {
+        return Err(ErrorCode::TooManyGuardGroups.into());
+    }
+    for (i, group) in data.groups.iter().enumerate() {
+        if group.label.is_empty() || group.label.len() > MAX_GROUP_LABEL_LENGTH {
+            return Err(ErrorCode::InvalidGuardGroupLabel.into());
+        }
+        if data.groups[..i]
+            .iter()
+            .any(|other| other.label == group.label)
+        {
+            return Err(ErrorCode::DuplicateGuardGroup.into());
+        }
+        assert_valid_guards(&group.guards)?;
+    }
+    if let Some(bot_tax) = &data.bot_tax {
+        if bot_tax.lamports == 0 {
+            return Err(ErrorCode::InvalidGuardSettings.into());
+        }
+    }
+    Ok(())
+}
+
+/// Guards that passed validation, each with the position of its first account.
+pub struct ActiveGuards(Vec<(usize, Guard)>);
+
+impl ActiveGuards {
+    /// Lets every guard update its state, once nothing else in the mint can fail.
+    pub fn record_mint(&self, ctx: &mut GuardContext) -> ProgramResult {
+        for (first_account, guard) in &self.0 {
+            ctx.remaining_accounts_counter = *first_account;
+            guard.inner().record_mint(ctx)?;
+        }
+        Ok(())
+    }
+}
+
+/// Loads the guard set passed for the candy machine.
+pub fn load_candy_guard<'info>(
+    candy_guard_info: &AccountInfo<'info>,
+    candy_machine_key: &Pubkey,
+) -> core::result::Result<Account<'info, CandyGuard>, ProgramError> {
+    let candy_guard: Account<CandyGuard> = Account::try_from(candy_guard_info)?;
+    if candy_guard.candy_machine != *candy_machine_key {
+        return Err(ErrorCode::MissingCandyGuard.into());
+    }
+    Ok(candy_guard)
+}
+
+/// Validates the default guards and the guards of the selected group.
+pub fn validate_guards(
+    candy_guard: &CandyGuard,
+    group: Option<&str>,
+    ctx: &mut GuardContext,
+) -> core::result::Result<ActiveGuards, ProgramError> {
+    let group_guards: &[Guard] = match group {
+        Some(label) => {
+            &candy_guard
+                .data
+                .groups
+                .iter()
+                .find(|group| group.label == label)
+                .ok_or(ErrorCode::GuardGroupNotFound)?
+                .guards
+        }
+        None if candy_guard.data.groups.is_empty() => &[],
+        None => return Err(ErrorCode::GuardGroupRequired.into()),
+    };
+
+    let default_guards = candy_guard.data.guards.iter().filter(|guard| {
+        !group_guards
+            .iter()
+            .any(|other| discriminant(other) == discriminant(*guard))
+    });
+    let mut active_guards = vec![];
+    for guard in default_guards.chain(group_guards) {
+        active_guards.push((ctx.remaining_accounts_counter, guard.clone()));
+        guard.inner().validate(ctx)?;
+    }
+    Ok(ActiveGuards(active_guards))
+}
@@ -1,16 +1,28 @@
+pub mod guards;
 pub mod utils;
 
 use solana_program::sysvar::{instructions::get_instruction_relative, SysvarId};
 use {
-    crate::utils::{
-        assert_initialized, assert_is_ata, assert_keys_equal, assert_owned_by,
-        assert_valid_go_live, spl_token_burn, spl_token_transfer, TokenBurnParams,
-        TokenTransferParams,
+    crate::{
+        guards::{
+            assert_valid_guard_data, load_candy_guard, validate_guards, ActiveGuards,
+            AllowListProof, CandyGuard, CandyGuardData, GuardContext, CANDY_GUARD,
+            CANDY_GUARD_SIZE,
+        },
+        utils::{
+            assert_initialized, assert_is_ata, assert_keys_equal, assert_owned_by,
+            assert_valid_go_live, close_program_account, get_slot_hash, spl_token_burn,
+            spl_token_transfer, TokenBurnParams, TokenTransferParams,
+        },
     },
     anchor_lang::{
         prelude::*,
         solana_program::{
+            hash::hash,
+            instruction::{AccountMeta, Instruction},
+            keccak,
             program::{invoke, invoke_signed},
+            program_memory::sol_memset,
             serialize_utils::{read_pubkey, read_u16},
             system_instruction, sysvar,
         },
@@ -19,18 +31,19 @@ use {
     anchor_spl::token::Token,
     arrayref::array_ref,
     mpl_token_metadata::{
-        assertions::collection::assert_master_edition,
+        assertions::{collection::assert_master_edition, uses::assert_valid_use},
         error::MetadataError,
         instruction::{
             approve_collection_authority, create_master_edition_v3, create_metadata_accounts_v2,
-            revoke_collection_authority, set_and_verify_collection, update_metadata_accounts_v2,
+            revoke_collection_authority, update_metadata_accounts_v2, MetadataInstruction,
         },
         state::{
-            Metadata, MAX_CREATOR_LEN, MAX_CREATOR_LIMIT, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH,
-            MAX_URI_LENGTH,
+            DataV2, Metadata, MAX_CREATOR_LEN, MAX_CREATOR_LIMIT, MAX_NAME_LENGTH,
+            MAX_SYMBOL_LENGTH, MAX_URI_LENGTH,
         },
         utils::{assert_derivation, create_or_allocate_account_raw},
     },
+    mpl_utils::merkle_proof,
     spl_token::state::Mint,
     std::{cell::RefMut, ops::Deref, str::FromStr},
 };
@@ -39,6 +52,8 @@ const EXPIRE_OFFSET: i64 = 10 * 60;
 const PREFIX: &str = "candy_machine";
 // here just in case solana removes the var
 const BLOCK_HASHES: &str = "SysvarRecentB1ockHashes11111111111111111111";
+// gumdrop mints through claim_candy_v2
+const GUMDROP: &str = "gdrpGjVffourzkdDRrQmySw4aTHr8a3xmQzzxSwFD1a";
 #[program]
 pub mod candy_machine {
     use super::*;
@@ -48,16 +63,25 @@ pub mod candy_machine {
         ctx: Context<'_, '_, '_, 'info, MintNFT<'info>>,
         creator_bump: u8,
     ) -> ProgramResult {
-        let candy_machine = &mut ctx.accounts.candy_machine;
+        mint_nft_v2(ctx, creator_bump, MintArgs::default())
+    }
+
+    /// Mints with the arguments the candy guard needs, see [`MintArgs`].
+    #[inline(never)]
+    pub fn mint_nft_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintNFT<'info>>,
+        creator_bump: u8,
+        args: MintArgs,
+    ) -> ProgramResult {
+        let MintArgs {
+            group,
+            allow_list_proof,
+        } = args;
         let candy_machine_creator = &ctx.accounts.candy_machine_creator;
-        let clock = &ctx.accounts.clock;
-        // Note this is the wallet of the Candy machine
-        let wallet = &ctx.accounts.wallet;
         let payer = &ctx.accounts.payer;
         let token_program = &ctx.accounts.token_program;
         //Account name the same for IDL compatability
         let recent_slothashes = &ctx.accounts.recent_blockhashes;
-        let instruction_sysvar_account = &ctx.accounts.instruction_sysvar_account;
         if recent_slothashes.key().to_string() == BLOCK_HASHES {
             msg!("recent_blockhashes is deprecated and will break soon");
         }
@@ -66,183 +90,87 @@ pub mod candy_machine {
         {
             return Err(ErrorCode::IncorrectSlotHashesPubkey.into());
         }
-        let mut price = candy_machine.data.price;
-        if let Some(es) = &candy_machine.data.end_settings {
-            match es.end_setting_type {
-                EndSettingType::Date => {
-                    if clock.unix_timestamp > es.number as i64 {
-                        if ctx.accounts.payer.key() != candy_machine.authority {
-                            return Err(ErrorCode::CandyMachineNotLive.into());
-                        }
-                    }
-                }
-                EndSettingType::Amount => {
-                    if candy_machine.items_redeemed >= es.number {
-                        return Err(ErrorCode::CandyMachineNotLive.into());
-                    }
-                }
-            }
+        let candy_machine_info = ctx.accounts.candy_machine.to_account_info();
+        if is_feature_active(&candy_machine_info.data.borrow(), CONFIG_LOCK_REQUIRED_FLAG)
+            && !is_feature_active(&candy_machine_info.data.borrow(), CONFIG_LOCKED_FLAG)
+        {
+            return Err(ErrorCode::ConfigNotLocked.into());
         }
+        let candy_guard = if is_feature_active(&candy_machine_info.data.borrow(), CANDY_GUARD_FLAG)
+        {
+            let candy_guard_info = ctx
+                .remaining_accounts
+                .first()
+                .ok_or(ErrorCode::MissingCandyGuard)?;
+            Some(load_candy_guard(
+                candy_guard_info,
+                &ctx.accounts.candy_machine.key(),
+            )?)
+        } else if group.is_some() {
+            return Err(ErrorCode::MissingCandyGuard.into());
+        } else {
+            None
+        };
 
-        let mut remaining_accounts_counter: usize = 0;
-        if let Some(gatekeeper) = &candy_machine.data.gatekeeper {
-            if ctx.remaining_accounts.len() <= remaining_accounts_counter {
-                return Err(ErrorCode::GatewayTokenMissing.into());
-            }
-            let gateway_token_info = &ctx.remaining_accounts[remaining_accounts_counter];
-            let gateway_token = ::solana_gateway::borsh::try_from_slice_incomplete::<
-                ::solana_gateway::state::GatewayToken,
-            >(*gateway_token_info.data.borrow())?;
-            // stores the expire_time before the verification, since the verification
-            // will update the expire_time of the token and we won't be able to
-            // calculate the creation time
-            let expire_time = gateway_token
-                .expire_time
-                .ok_or(ErrorCode::GatewayTokenExpireTimeInvalid)?
-                as i64;
-            remaining_accounts_counter += 1;
-            if gatekeeper.expire_on_use {
-                if ctx.remaining_accounts.len() <= remaining_accounts_counter {
-                    return Err(ErrorCode::GatewayAppMissing.into());
-                }
-                let gateway_app = &ctx.remaining_accounts[remaining_accounts_counter];
-                remaining_accounts_counter += 1;
-                if ctx.remaining_accounts.len() <= remaining_accounts_counter {
-                    return Err(ErrorCode::NetworkExpireFeatureMissing.into());
-                }
-                let network_expire_feature = &ctx.remaining_accounts[remaining_accounts_counter];
-                remaining_accounts_counter += 1;
-                ::solana_gateway::Gateway::verify_and_expire_token(
-                    gateway_app.clone(),
-                    gateway_token_info.clone(),
-                    payer.deref().clone(),
-                    &gatekeeper.gatekeeper_network,
-                    network_expire_feature.clone(),
-                )?;
-            } else {
-                ::solana_gateway::Gateway::verify_gateway_token_account_info(
-                    gateway_token_info,
-                    &payer.key(),
-                    &gatekeeper.gatekeeper_network,
-                    None,
-                )?;
-            }
-            // verifies that the gatway token was not created before the candy
-            // machine go_live_date (avoids pre-solving the captcha)
-            match candy_machine.data.go_live_date {
-                Some(val) => {
-                    if (expire_time - EXPIRE_OFFSET) < val {
-                        if let Some(ws) = &candy_machine.data.whitelist_mint_settings {
-                            // when dealing with whitelist, the expire_time can be
-                            // before the go_live_date only if presale enabled
-                            if !ws.presale {
-                                msg!(
-                                    "Invalid gateway token: calculated creation time {} and go_live_date {}",
-                                    expire_time - EXPIRE_OFFSET,
-                                    val);
-                                return Err(ErrorCode::GatewayTokenExpireTimeInvalid.into());
-                            }
-                        } else {
-                            msg!(
-                                "Invalid gateway token: calculated creation time {} and go_live_date {}",
-                                expire_time - EXPIRE_OFFSET,
-                                val);
-                            return Err(ErrorCode::GatewayTokenExpireTimeInvalid.into());
-                        }
-                    }
-                }
-                None => {}
-            }
+        assert_mint_transaction(&ctx.accounts.instruction_sysvar_account)?;
+        if let Some(bot_tax) = candy_guard
+            .as_ref()
+            .and_then(|candy_guard| candy_guard.data.bot_tax.as_ref())
+        {
+            bot_tax.assert_last_instruction(&ctx.accounts.instruction_sysvar_account)?;
         }
 
-        if let Some(ws) = &candy_machine.data.whitelist_mint_settings {
-            let whitelist_token_account = &ctx.remaining_accounts[remaining_accounts_counter];
-            remaining_accounts_counter += 1;
-            // If the user has not actually made this account,
-            // this explodes and we just check normal dates.
-            // If they have, we check amount, if it's > 0 we let them use the logic
-            // if 0, check normal dates.
-            match assert_is_ata(whitelist_token_account, &payer.key(), &ws.mint) {
-                Ok(wta) => {
-                    if wta.amount > 0 {
-                        if ws.mode == WhitelistMintMode::BurnEveryTime {
-                            let whitelist_token_mint =
-                                &ctx.remaining_accounts[remaining_accounts_counter];
-                            remaining_accounts_counter += 1;
-
-                            let whitelist_burn_authority =
-                                &ctx.remaining_accounts[remaining_accounts_counter];
-                            remaining_accounts_counter += 1;
-
-                            assert_keys_equal(whitelist_token_mint.key(), ws.mint)?;
-
-                            spl_token_burn(TokenBurnParams {
-                                mint: whitelist_token_mint.clone(),
-                                source: whitelist_token_account.clone(),
-                                amount: 1,
-                                authority: whitelist_burn_authority.clone(),
-                                authority_signer_seeds: None,
-                                token_program: token_program.to_account_info(),
-                            })?;
-                        }
-
-                        match candy_machine.data.go_live_date {
-                            None => {
-                                if ctx.accounts.payer.key() != candy_machine.authority
-                                    && !ws.presale
-                                {
-                                    return Err(ErrorCode::CandyMachineNotLive.into());
-                                }
-                            }
-                            Some(val) => {
-                                if clock.unix_timestamp < val
-                                    && ctx.accounts.payer.key() != candy_machine.authority
-                                    && !ws.presale
-                                {
-                                    return Err(ErrorCode::CandyMachineNotLive.into());
-                                }
-                            }
-                        }
-
-                        if let Some(dp) = ws.discount_price {
-                            price = dp;
-                        }
-                    } else {
-                        if wta.amount == 0 && ws.discount_price.is_none() && !ws.presale {
-                            // A non-presale whitelist with no discount price is a forced whitelist
-                            // If a pre-sale has no discount, its no issue, because the "discount"
-                            // is minting first - a presale whitelist always has an open post sale.
-                            return Err(ErrorCode::NoWhitelistToken.into());
-                        }
-                        assert_valid_go_live(payer, clock, candy_machine)?;
-                        if ws.mode == WhitelistMintMode::BurnEveryTime {
-                            remaining_accounts_counter += 2;
-                        }
-                    }
-                }
-                Err(_) => {
-                    if ws.discount_price.is_none() && !ws.presale {
-                        // A non-presale whitelist with no discount price is a forced whitelist
-                        // If a pre-sale has no discount, its no issue, because the "discount"
-                        // is minting first - a presale whitelist always has an open post sale.
-                        return Err(ErrorCode::NoWhitelistToken.into());
-                    }
-                    if ws.mode == WhitelistMintMode::BurnEveryTime {
-                        remaining_accounts_counter += 2;
-                    }
-                    assert_valid_go_live(payer, clock, candy_machine)?
-                }
+        // Nothing is changed until every check passed, a failed check either fails the mint or,
+        // with a bot tax, only charges the tax.
+        let checks = match check_mint(
+            &ctx,
+            candy_guard.as_deref(),
+            group.as_deref(),
+            allow_list_proof.as_ref(),
+        ) {
+            Ok(checks) => checks,
+            Err(error) => {
+                return match candy_guard
+                    .as_ref()
+                    .and_then(|candy_guard| candy_guard.data.bot_tax.as_ref())
+                {
+                    Some(bot_tax) => bot_tax.charge(
+                        &error,
+                        payer,
+                        &ctx.accounts.candy_machine.to_account_info(),
+                        &ctx.accounts.system_program.to_account_info(),
+                    ),
+                    None => Err(error.into()),
+                };
             }
-        } else {
-            // no whitelist means normal datecheck
-            assert_valid_go_live(payer, clock, candy_machine)?;
+        };
+        let MintChecks {
+            price,
+            payment_mint,
+            payment_destination,
+            mut remaining_accounts_counter,
+            guards,
+            gateway_expire,
+            whitelist_burn,
+        } = checks;
+
+        if let Some((gateway_app, gateway_token_info, network_expire_feature, gatekeeper_network)) =
+            gateway_expire
+        {
+            ::solana_gateway::Gateway::verify_and_expire_token(
+                gateway_app.clone(),
+                gateway_token_info.clone(),
+                payer.deref().clone(),
+                &gatekeeper_network,
+                network_expire_feature.clone(),
+            )?;
         }
 
-        if candy_machine.items_redeemed >= candy_machine.data.items_available {
-            return Err(ErrorCode::CandyMachineEmpty.into());
+        if let Some(burn_params) = whitelist_burn {
+            spl_token_burn(burn_params)?;
         }
 
-        if let Some(mint) = candy_machine.token_mint {
+        if let Some(mint) = payment_mint {
             let token_account_info = &ctx.remaining_accounts[remaining_accounts_counter];
             remaining_accounts_counter += 1;
             let transfer_authority_info = &ctx.remaining_accounts[remaining_accounts_counter];
@@ -255,7 +183,7 @@ pub mod candy_machine {
 
             spl_token_transfer(TokenTransferParams {
                 source: token_account_info.clone(),
-                destination: wallet.to_account_info(),
+                destination: payment_destination,
                 authority: transfer_authority_info.clone(),
                 authority_signer_seeds: &[],
                 token_program: token_program.to_account_info(),
@@ -267,24 +195,47 @@ pub mod candy_machine {
             }
 
             invoke(
-                &system_instruction::transfer(&ctx.accounts.payer.key(), &wallet.key(), price),
+                &system_instruction::transfer(
+                    &ctx.accounts.payer.key(),
+                    &payment_destination.key(),
+                    price,
+                ),
                 &[
                     ctx.accounts.payer.to_account_info(),
-                    wallet.to_account_info(),
+                    payment_destination,
                     ctx.accounts.system_program.to_account_info(),
                 ],
             )?;
         }
 
-        let data = recent_slothashes.data.borrow();
-        let most_recent = array_ref![data, 12, 8];
+        if let Some((active_guards, mut guard_ctx)) = guards {
+            active_guards.record_mint(&mut guard_ctx)?;
+        }
 
-        let index = u64::from_le_bytes(*most_recent);
-        let modded: usize = index
-            .checked_rem(candy_machine.data.items_available)
-            .ok_or(ErrorCode::NumericalOverflowError)? as usize;
+        let candy_machine = &mut ctx.accounts.candy_machine;
 
-        let config_line = get_config_line(&candy_machine, modded, candy_machine.items_redeemed)?;
+        let mint_number = candy_machine.items_redeemed;
+        let commit_reveal = is_feature_active(
+            &candy_machine.to_account_info().data.borrow(),
+            COMMIT_REVEAL_FLAG,
+        );
+        let config_line = if commit_reveal {
+            // the config line is picked by settle_pending_mint, from a slot hash nobody knows yet
+            ConfigLine {
+                name: format!("#{}", mint_number + 1),
+                uri: String::new(),
+            }
+        } else {
+            let data = recent_slothashes.data.borrow();
+            let most_recent = array_ref![data, 12, 8];
+
+            let index = u64::from_le_bytes(*most_recent);
+            let modded: usize = index
+                .checked_rem(candy_machine.data.items_available)
+                .ok_or(ErrorCode::NumericalOverflowError)? as usize;
+
+            get_config_line(&candy_machine, modded, candy_machine.items_redeemed)?
+        };
 
         candy_machine.items_redeemed = candy_machine
             .items_redeemed
@@ -294,6 +245,62 @@ pub mod candy_machine {
         let cm_key = candy_machine.key();
         let authority_seeds = [PREFIX.as_bytes(), cm_key.as_ref(), &[creator_bump]];
 
+        if is_feature_active(&candy_machine.to_account_info().data.borrow(), REVEAL_FLAG) {
+            let mint_index_info = ctx
+                .remaining_accounts
+                .get(remaining_accounts_counter)
+                .ok_or(ErrorCode::MissingMintIndex)?;
+            let mint_key = ctx.accounts.mint.key();
+            let bump = assert_derivation(
+                &crate::id(),
+                mint_index_info,
+                &[MINT_INDEX.as_bytes(), cm_key.as_ref(), mint_key.as_ref()],
+            )?;
+            create_or_allocate_account_raw(
+                crate::id(),
+                mint_index_info,
+                &ctx.accounts.rent.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.payer.to_account_info(),
+                MINT_INDEX_SIZE,
+                &[
+                    MINT_INDEX.as_bytes(),
+                    cm_key.as_ref(),
+                    mint_key.as_ref(),
+                    &[bump],
+                ],
+            )?;
+            let mut data: &mut [u8] = &mut mint_index_info.try_borrow_mut_data()?;
+            MintIndex {
+                index: mint_number,
+                revealed: false,
+                content_hash: [0; 32],
+            }
+            .try_serialize(&mut data)?;
+            remaining_accounts_counter += 1;
+            add_unsettled_mint(&mut candy_machine.to_account_info().data.borrow_mut())?;
+        }
+
+        let mut mint_settings = MintSettingsData::default();
+        if is_feature_active(
+            &candy_machine.to_account_info().data.borrow(),
+            MINT_SETTINGS_FLAG,
+        ) {
+            let mint_settings_info = ctx
+                .remaining_accounts
+                .get(remaining_accounts_counter)
+                .ok_or(ErrorCode::MissingMintSettings)?;
+            remaining_accounts_counter += 1;
+            assert_derivation(
+                &crate::id(),
+                mint_settings_info,
+                &[MINT_SETTINGS.as_bytes(), cm_key.as_ref()],
+            )?;
+            assert_owned_by(mint_settings_info, &crate::id())?;
+            mint_settings =
+                MintSettings::try_deserialize(&mut &mint_settings_info.data.borrow()[..])?.data;
+        }
+
         let mut creators: Vec<mpl_token_metadata::state::Creator> =
             vec![mpl_token_metadata::state::Creator {
                 address: candy_machine_creator.key(),
@@ -301,7 +308,11 @@ pub mod candy_machine {
                 share: 0,
             }];
 
-        for c in &candy_machine.data.creators {
+        for c in mint_settings
+            .creators
+            .as_ref()
+            .unwrap_or(&candy_machine.data.creators)
+        {
             creators.push(mpl_token_metadata::state::Creator {
                 address: c.address,
                 verified: false,
@@ -347,9 +358,9 @@ pub mod candy_machine {
                 Some(creators),
                 candy_machine.data.seller_fee_basis_points,
                 true,
-                candy_machine.data.is_mutable,
-                None,
+                candy_machine.data.is_mutable || commit_reveal,
                 None,
+                mint_settings.uses.map(Into::into),
             ),
             metadata_infos.as_slice(),
             &[&authority_seeds],
@@ -374,6 +385,54 @@ pub mod candy_machine {
         if !candy_machine.data.retain_authority {
             new_update_authority = Some(ctx.accounts.update_authority.key());
         }
+        // a mint is a primary sale only when it charged something, in SOL or in tokens, a free one
+        // leaves the primary sale to the first paid transfer of the NFT
+        let primary_sale_happened = price > 0;
+
+        if commit_reveal {
+            let pending_mint_info = ctx
+                .remaining_accounts
+                .get(remaining_accounts_counter)
+                .ok_or(ErrorCode::MissingPendingMint)?;
+            let mint_key = ctx.accounts.mint.key();
+            let bump = assert_derivation(
+                &crate::id(),
+                pending_mint_info,
+                &[PENDING_MINT.as_bytes(), cm_key.as_ref(), mint_key.as_ref()],
+            )?;
+            create_or_allocate_account_raw(
+                crate::id(),
+                pending_mint_info,
+                &ctx.accounts.rent.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.payer.to_account_info(),
+                PENDING_MINT_SIZE,
+                &[
+                    PENDING_MINT.as_bytes(),
+                    cm_key.as_ref(),
+                    mint_key.as_ref(),
+                    &[bump],
+                ],
+            )?;
+            let pending_mint = PendingMint {
+                payer: ctx.accounts.payer.key(),
+                mint_number,
+                target_slot: ctx
+                    .accounts
+                    .clock
+                    .slot
+                    .checked_add(COMMIT_REVEAL_DELAY)
+                    .ok_or(ErrorCode::NumericalOverflowError)?,
+                new_update_authority: new_update_authority.unwrap(),
+                primary_sale_happened,
+            };
+            let mut data: &mut [u8] = &mut pending_mint_info.try_borrow_mut_data()?;
+            pending_mint.try_serialize(&mut data)?;
+            add_unsettled_mint(&mut candy_machine.to_account_info().data.borrow_mut())?;
+            // the creator keeps the update authority until settle_pending_mint sets the config line
+            return Ok(());
+        }
+
         invoke_signed(
             &update_metadata_accounts_v2(
                 ctx.accounts.token_metadata_program.key(),
@@ -381,7 +440,11 @@ pub mod candy_machine {
                 candy_machine_creator.key(),
                 new_update_authority,
                 None,
-                Some(true),
+                if primary_sale_happened {
+                    Some(true)
+                } else {
+                    None
+                },
                 if !candy_machine.data.is_mutable {
                     Some(false)
                 } else {
@@ -395,39 +458,16 @@ pub mod candy_machine {
             ],
             &[&authority_seeds],
         )?;
-        let instruction_sysvar_account_info = instruction_sysvar_account.to_account_info();
-
-        let instruction_sysvar = instruction_sysvar_account_info.data.borrow();
-
-        let mut idx = 0;
-        let num_instructions = read_u16(&mut idx, &instruction_sysvar)
-            .map_err(|_| ProgramError::InvalidAccountData)?;
-
-        let associated_token =
-            Pubkey::from_str("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL").unwrap();
-
-        for index in 0..num_instructions {
-            let mut current = 2 + (index * 2) as usize;
-            let start = read_u16(&mut current, &instruction_sysvar).unwrap();
-
-            current = start as usize;
-            let num_accounts = read_u16(&mut current, &instruction_sysvar).unwrap();
-            current += (num_accounts as usize) * (1 + 32);
-            let program_id = read_pubkey(&mut current, &instruction_sysvar).unwrap();
-
-            if program_id != candy_machine::id()
-                && program_id != spl_token::id()
-                && program_id != anchor_lang::solana_program::system_program::ID
-                && program_id != associated_token
-            {
-                msg!("Transaction had ix with program id {}", program_id);
-                return Err(ErrorCode::SuspiciousTransaction.into());
-            }
-        }
         Ok(())
     }
 
     pub fn set_collection_during_mint(ctx: Context<SetCollectionDuringMint>) -> ProgramResult {
+        if is_feature_active(
+            &ctx.accounts.candy_machine.to_account_info().data.borrow(),
+            COMMIT_REVEAL_FLAG,
+        ) {
+            return Err(ErrorCode::CommitRevealWithCollection.into());
+        }
         let ixs = &ctx.accounts.instructions;
         let previous_instruction = get_instruction_relative(-1, ixs)?;
         if &previous_instruction.program_id != &candy_machine::id() {
@@ -439,7 +479,10 @@ pub mod candy_machine {
         }
 
         let discriminator = &previous_instruction.data[0..8];
-        if discriminator != [211, 57, 6, 167, 15, 219, 35, 251] {
+        // mint_nft and mint_nft_v2
+        if discriminator != [211, 57, 6, 167, 15, 219, 35, 251]
+            && discriminator != [5, 15, 164, 174, 195, 156, 107, 244]
+        {
             msg!(
                 "Transaction had ix with data {:?}",
                 discriminator
@@ -471,6 +514,11 @@ pub mod candy_machine {
             );
             return Err(ErrorCode::SuspiciousTransaction.into());
         }
+        // a mint charged the bot tax succeeds without creating its metadata
+        if ctx.accounts.metadata.data_is_empty() {
+            msg!("Mint was charged the bot tax, no collection to set");
+            return Ok(());
+        }
 
         let collection_pda = &ctx.accounts.collection_pda;
         let collection_mint = ctx.accounts.collection_mint.to_account_info();
@@ -490,18 +538,24 @@ pub mod candy_machine {
             ctx.accounts.collection_master_edition.to_account_info(),
             ctx.accounts.collection_authority_record.to_account_info(),
         ];
+        // the builder of mpl-token-metadata 1.2.5 passes the collection metadata read only, but
+        // the size of a sized collection is kept there
+        let set_collection_ix = Instruction {
+            program_id: ctx.accounts.token_metadata_program.key(),
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.metadata.key(), false),
+                AccountMeta::new(collection_pda.key(), true),
+                AccountMeta::new(ctx.accounts.payer.key(), true),
+                AccountMeta::new_readonly(ctx.accounts.authority.key(), false),
+                AccountMeta::new_readonly(collection_mint.key(), false),
+                AccountMeta::new(ctx.accounts.collection_metadata.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.collection_master_edition.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.collection_authority_record.key(), false),
+            ],
+            data: MetadataInstruction::SetAndVerifyCollection.try_to_vec()?,
+        };
         invoke_signed(
-            &set_and_verify_collection(
-                ctx.accounts.token_metadata_program.key(),
-                ctx.accounts.metadata.key(),
-                collection_pda.key(),
-                ctx.accounts.payer.key(),
-                ctx.accounts.authority.key(),
-                collection_mint.key(),
-                ctx.accounts.collection_metadata.key(),
-                ctx.accounts.collection_master_edition.key(),
-                Some(ctx.accounts.collection_authority_record.key()),
-            ),
+            &set_collection_ix,
             set_collection_infos.as_slice(),
             &[&signer_seeds],
         )?;
@@ -527,6 +581,14 @@ pub mod candy_machine {
             return Err(ErrorCode::CannotSwitchToHiddenSettings.into());
         }
 
+        if is_feature_active(
+            &candy_machine.to_account_info().data.borrow(),
+            CONFIG_LOCKED_FLAG,
+        ) && locked_fields(&data)? != locked_fields(&candy_machine.data)?
+        {
+            return Err(ErrorCode::ConfigLocked.into());
+        }
+
         candy_machine.wallet = ctx.accounts.wallet.key();
         candy_machine.data = data;
 
@@ -556,6 +618,9 @@ pub mod candy_machine {
         if candy_machine.data.hidden_settings.is_some() {
             return Err(ErrorCode::HiddenSettingsConfigsDoNotHaveConfigLines.into());
         }
+        if is_feature_active(&data, CONFIG_LOCKED_FLAG) {
+            return Err(ErrorCode::ConfigLocked.into());
+        }
         for line in &config_lines {
             let mut array_of_zeroes = vec![];
             while array_of_zeroes.len() < MAX_NAME_LENGTH - line.name.len() {
@@ -629,6 +694,8 @@ pub mod candy_machine {
         Ok(())
     }
 
+    /// Creates a candy machine. It cannot mint until its config is locked with `lock_config`,
+    /// candy machines created before the lock existed mint as they did.
     pub fn initialize_candy_machine(
         ctx: Context<InitializeCandyMachine>,
         data: CandyMachineData,
@@ -696,6 +763,7 @@ pub mod candy_machine {
         for i in 0..4 {
             data[vec_start + i] = as_bytes[i]
         }
+        set_feature_flag(&mut data, CONFIG_LOCK_REQUIRED_FLAG, true);
 
         Ok(())
     }
@@ -713,6 +781,12 @@ pub mod candy_machine {
         let edition = ctx.accounts.edition.to_account_info();
         let authority_record = ctx.accounts.collection_authority_record.to_account_info();
         let candy_machine = &ctx.accounts.candy_machine;
+        if is_feature_active(
+            &candy_machine.to_account_info().data.borrow(),
+            COMMIT_REVEAL_FLAG,
+        ) {
+            return Err(ErrorCode::CommitRevealWithCollection.into());
+        }
         if authority_record.data_is_empty() {
             assert_master_edition(&metadata, &edition)?;
             let approve_collection_infos = vec![
@@ -851,136 +925,1104 @@ pub mod candy_machine {
 
         Ok(())
     }
-}
 
-fn get_space_for_candy(data: CandyMachineData) -> core::result::Result<usize, ProgramError> {
-    let num = if data.hidden_settings.is_some() {
-        CONFIG_ARRAY_START
-    } else {
-        CONFIG_ARRAY_START
-            + 4
-            + (data.items_available as usize) * CONFIG_LINE_SIZE
-            + 8
-            + 2 * ((data
-                .items_available
-                .checked_div(8)
-                .ok_or(ErrorCode::NumericalOverflowError)?
-                + 1) as usize)
-    };
+    pub fn initialize_candy_guard(
+        ctx: Context<InitializeCandyGuard>,
+        data: CandyGuardData,
+    ) -> ProgramResult {
+        assert_valid_guard_data(&data)?;
+
+        let candy_guard = &mut ctx.accounts.candy_guard;
+        candy_guard.candy_machine = ctx.accounts.candy_machine.key();
+        candy_guard.bump = *ctx.bumps.get("candy_guard").unwrap();
+        candy_guard.data = data;
+
+        let candy_machine_info = ctx.accounts.candy_machine.to_account_info();
+        set_feature_flag(
+            &mut candy_machine_info.data.borrow_mut(),
+            CANDY_GUARD_FLAG,
+            true,
+        );
 
-    Ok(num)
-}
+        Ok(())
+    }
 
-/// Create a new candy machine.
-#[derive(Accounts)]
-#[instruction(data: CandyMachineData)]
-pub struct InitializeCandyMachine<'info> {
-    /// CHECK: account constraints checked in account trait
-    #[account(zero, rent_exempt = skip, constraint = candy_machine.to_account_info().owner == program_id && candy_machine.to_account_info().data_len() >= get_space_for_candy(data)?)]
-    candy_machine: UncheckedAccount<'info>,
-    /// CHECK: wallet can be any account and is not written to or read
-    wallet: UncheckedAccount<'info>,
-    /// CHECK: authority can be any account and is not written to or read
-    authority: UncheckedAccount<'info>,
-    payer: Signer<'info>,
-    system_program: Program<'info, System>,
-    rent: Sysvar<'info, Rent>,
-}
+    pub fn update_candy_guard(
+        ctx: Context<UpdateCandyGuard>,
+        data: CandyGuardData,
+    ) -> ProgramResult {
+        assert_valid_guard_data(&data)?;
+        ctx.accounts.candy_guard.data = data;
 
-/// Sets and verifies the collection during a candy machine mint
-#[derive(Accounts)]
-pub struct SetCollectionDuringMint<'info> {
-    #[account(has_one = authority)]
-    candy_machine: Account<'info, CandyMachine>,
-    /// CHECK: account checked in CPI/instruction sysvar
-    metadata: UncheckedAccount<'info>,
-    payer: Signer<'info>,
-    #[account(mut, seeds = [b"collection".as_ref(), candy_machine.to_account_info().key.as_ref()], bump)]
-    collection_pda: Account<'info, CollectionPDA>,
-    /// CHECK: account constraints checked in account trait
-    #[account(address = mpl_token_metadata::id())]
-    token_metadata_program: UncheckedAccount<'info>,
-    /// CHECK: account constraints checked in account trait
-    #[account(address = sysvar::instructions::id())]
-    instructions: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    collection_mint: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    collection_metadata: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    collection_master_edition: UncheckedAccount<'info>,
-    /// CHECK: authority can be any account and is checked in CPI
-    authority: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    collection_authority_record: UncheckedAccount<'info>,
-}
+        Ok(())
+    }
 
-/// Set the collection PDA for the candy machine
-#[derive(Accounts)]
-pub struct SetCollection<'info> {
-    #[account(has_one = authority)]
-    candy_machine: Account<'info, CandyMachine>,
-    authority: Signer<'info>,
-    /// CHECK: account constraints checked in account trait
-    #[account(mut, seeds = [b"collection".as_ref(), candy_machine.to_account_info().key.as_ref()], bump)]
-    collection_pda: UncheckedAccount<'info>,
-    payer: Signer<'info>,
-    system_program: Program<'info, System>,
-    rent: Sysvar<'info, Rent>,
+    pub fn close_candy_guard(ctx: Context<CloseCandyGuard>) -> ProgramResult {
+        let candy_machine_info = ctx.accounts.candy_machine.to_account_info();
+        set_feature_flag(
+            &mut candy_machine_info.data.borrow_mut(),
+            CANDY_GUARD_FLAG,
+            false,
+        );
 
-    /// CHECK: account checked in CPI
-    metadata: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    mint: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    edition: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    #[account(mut)]
-    collection_authority_record: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    #[account(address = mpl_token_metadata::id())]
-    token_metadata_program: UncheckedAccount<'info>,
-}
+        Ok(())
+    }
 
-/// Set the collection PDA for the candy machine
-#[derive(Accounts)]
-pub struct RemoveCollection<'info> {
-    #[account(has_one = authority)]
-    candy_machine: Account<'info, CandyMachine>,
-    authority: Signer<'info>,
-    #[account(mut, seeds = [b"collection".as_ref(), candy_machine.to_account_info().key.as_ref()], bump, close=authority)]
-    collection_pda: Account<'info, CollectionPDA>,
-    /// CHECK: account checked in CPI
-    metadata: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    mint: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    #[account(mut)]
-    collection_authority_record: UncheckedAccount<'info>,
-    /// CHECK: account checked in CPI
-    #[account(address = mpl_token_metadata::id())]
-    token_metadata_program: UncheckedAccount<'info>,
-}
+    /// Records the index of every mint so the hidden settings can be revealed on chain. The config
+    /// has to be locked first, so the hidden settings hash cannot change after the mint starts.
+    pub fn enable_reveal(ctx: Context<EnableReveal>) -> ProgramResult {
+        let candy_machine = &ctx.accounts.candy_machine;
+        if candy_machine.data.hidden_settings.is_none() {
+            return Err(ErrorCode::RevealNeedsHiddenSettings.into());
+        }
+        if candy_machine.items_redeemed > 0 {
+            return Err(ErrorCode::RevealEnabledAfterMint.into());
+        }
+        // the reveal updates the metadata signed by the candy machine authority
+        if !candy_machine.data.is_mutable || !candy_machine.data.retain_authority {
+            return Err(ErrorCode::RevealNeedsMutableMetadata.into());
+        }
 
-/// Add multiple config lines to the candy machine.
-#[derive(Accounts)]
-pub struct AddConfigLines<'info> {
-    #[account(mut, has_one = authority)]
-    candy_machine: Account<'info, CandyMachine>,
-    authority: Signer<'info>,
-}
+        let candy_machine_info = candy_machine.to_account_info();
+        if !is_feature_active(&candy_machine_info.data.borrow(), CONFIG_LOCKED_FLAG) {
+            return Err(ErrorCode::RevealNeedsConfigLock.into());
+        }
+        set_feature_flag(&mut candy_machine_info.data.borrow_mut(), REVEAL_FLAG, true);
 
-/// Withdraw SOL from candy machine account.
-#[derive(Accounts)]
-pub struct WithdrawFunds<'info> {
-    #[account(mut, has_one = authority)]
-    candy_machine: Account<'info, CandyMachine>,
-    #[account(address = candy_machine.authority)]
-    authority: Signer<'info>,
+        Ok(())
+    }
+
+    /// Replaces the hidden settings URI of a minted NFT with its revealed URI. `content_hash` is
+    /// the hash of the revealed content at the mint index, proved to be in the content root of the
+    /// reveal settings by `proof`.
+    pub fn reveal(
+        ctx: Context<Reveal>,
+        reveal_settings: RevealSettings,
+        content_hash: [u8; 32],
+        proof: Vec<[u8; 32]>,
+    ) -> ProgramResult {
+        let candy_machine = &ctx.accounts.candy_machine;
+        let hidden_settings = candy_machine
+            .data
+            .hidden_settings
+            .as_ref()
+            .ok_or(ErrorCode::RevealNeedsHiddenSettings)?;
+        if hash(&reveal_settings.try_to_vec()?).to_bytes() != hidden_settings.hash {
+            return Err(ErrorCode::RevealSettingsHashMismatch.into());
+        }
+
+        let metadata: Metadata =
+            Metadata::from_account_info(&ctx.accounts.metadata.to_account_info())?;
+        if metadata.mint != ctx.accounts.mint.key() {
+            return Err(MetadataError::MintMismatch.into());
+        }
+
+        let mint_index = &mut ctx.accounts.mint_index;
+        let leaf = keccak::hashv(&[&[0x00], &mint_index.index.to_le_bytes(), &content_hash]);
+        if !merkle_proof::verify(proof, reveal_settings.content_root, leaf.0) {
+            return Err(ErrorCode::InvalidRevealProof.into());
+        }
+
+        let uri = format!(
+            "{}{}{}",
+            reveal_settings.uri_prefix, mint_index.index, reveal_settings.uri_suffix
+        );
+        mint_index.content_hash = content_hash;
+        // revealing again only fixes the URI, the mint was settled the first time
+        if !mint_index.revealed {
+            mint_index.revealed = true;
+            settle_unsettled_mint(&mut candy_machine.to_account_info().data.borrow_mut())?;
+        }
+        msg!("Revealing {} at {}", ctx.accounts.mint.key(), uri);
+        invoke(
+            &update_metadata_accounts_v2(
+                ctx.accounts.token_metadata_program.key(),
+                ctx.accounts.metadata.key(),
+                ctx.accounts.authority.key(),
+                None,
+                Some(DataV2 {
+                    name: metadata.data.name.trim_matches(char::from(0)).to_string(),
+                    symbol: metadata.data.symbol.trim_matches(char::from(0)).to_string(),
+                    uri,
+                    seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+                    creators: metadata.data.creators,
+                    collection: metadata.collection,
+                    uses: metadata.uses,
+                }),
+                None,
+                None,
+            ),
+            &[
+                ctx.accounts.metadata.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Makes every mint commit to a pending mint, settled later from a future slot hash. Until it
+    /// is settled the NFT has no config line and the candy machine creator is its update authority.
+    pub fn enable_commit_reveal(ctx: Context<EnableCommitReveal>) -> ProgramResult {
+        let candy_machine = &ctx.accounts.candy_machine;
+        if candy_machine.data.hidden_settings.is_some() {
+            return Err(ErrorCode::CommitRevealNeedsConfigLines.into());
+        }
+        // the collection is verified during the mint, before the pending mint is settled
+        if !ctx.accounts.collection_pda.data_is_empty() {
+            return Err(ErrorCode::CommitRevealWithCollection.into());
+        }
+
+        let candy_machine_info = candy_machine.to_account_info();
+        set_feature_flag(
+            &mut candy_machine_info.data.borrow_mut(),
+            COMMIT_REVEAL_FLAG,
+            true,
+        );
+
+        Ok(())
+    }
+
+    /// Picks the config line of a pending mint from the hash of its target slot and finishes
+    /// its metadata. Anyone can settle a pending mint, the payer gets the rent back.
+    ///
+    /// Pending mints settle in mint order. The config line is the first unused one from the slot
+    /// hash index, so settling in any other order would let the settler steer which mint gets
+    /// which line.
+    pub fn settle_pending_mint(ctx: Context<SettlePendingMint>) -> ProgramResult {
+        let candy_machine = &ctx.accounts.candy_machine;
+        let candy_machine_creator = &ctx.accounts.candy_machine_creator;
+        let pending_mint = &ctx.accounts.pending_mint;
+        // every mint after the unsettled ones is pending, the oldest of them settles first
+        let next_mint_number = candy_machine
+            .items_redeemed
+            .checked_sub(get_unsettled_mints(&candy_machine.to_account_info().data.borrow()) as u64)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+        if pending_mint.mint_number != next_mint_number {
+            return Err(ErrorCode::PendingMintOutOfOrder.into());
+        }
+        if ctx.accounts.clock.slot <= pending_mint.target_slot {
+            return Err(ErrorCode::PendingMintNotReady.into());
+        }
+        let slot_hash = get_slot_hash(
+            &ctx.accounts.recent_slothashes.data.borrow(),
+            pending_mint.target_slot,
+        )?;
+
+        let metadata: Metadata =
+            Metadata::from_account_info(&ctx.accounts.metadata.to_account_info())?;
+        if metadata.mint != ctx.accounts.mint.key() {
+            return Err(MetadataError::MintMismatch.into());
+        }
+
+        let index = u64::from_le_bytes(*array_ref![slot_hash, 0, 8]);
+        let modded: usize = index
+            .checked_rem(candy_machine.data.items_available)
+            .ok_or(ErrorCode::NumericalOverflowError)? as usize;
+        let config_line = get_config_line(candy_machine, modded, pending_mint.mint_number)?;
+        settle_unsettled_mint(&mut candy_machine.to_account_info().data.borrow_mut())?;
+
+        let cm_key = candy_machine.key();
+        let authority_seeds = [
+            PREFIX.as_bytes(),
+            cm_key.as_ref(),
+            &[*ctx.bumps.get("candy_machine_creator").unwrap()],
+        ];
+        invoke_signed(
+            &update_metadata_accounts_v2(
+                ctx.accounts.token_metadata_program.key(),
+                ctx.accounts.metadata.key(),
+                candy_machine_creator.key(),
+                Some(pending_mint.new_update_authority),
+                Some(DataV2 {
+                    name: config_line.name,
+                    symbol: candy_machine.data.symbol.clone(),
+                    uri: config_line.uri,
+                    seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+                    creators: metadata.data.creators,
+                    collection: metadata.collection,
+                    uses: metadata.uses,
+                }),
+                if pending_mint.primary_sale_happened {
+                    Some(true)
+                } else {
+                    None
+                },
+                if !candy_machine.data.is_mutable {
+                    Some(false)
+                } else {
+                    None
+                },
+            ),
+            &[
+                ctx.accounts.token_metadata_program.to_account_info(),
+                ctx.accounts.metadata.to_account_info(),
+                candy_machine_creator.to_account_info(),
+            ],
+            &[&authority_seeds],
+        )?;
+
+        Ok(())
+    }
+
+    /// Moves the target slot of a pending mint whose slot hash was evicted from the SlotHashes
+    /// sysvar before anyone settled it. Only the authority can renew, so the minter cannot draw
+    /// again by letting an unwanted outcome expire.
+    pub fn renew_pending_mint(ctx: Context<RenewPendingMint>) -> ProgramResult {
+        let pending_mint = &mut ctx.accounts.pending_mint;
+        match get_slot_hash(
+            &ctx.accounts.recent_slothashes.data.borrow(),
+            pending_mint.target_slot,
+        ) {
+            Err(error) if error == ErrorCode::PendingMintExpired.into() => {}
+            _ => return Err(ErrorCode::PendingMintNotExpired.into()),
+        }
+
+        pending_mint.target_slot = ctx
+            .accounts
+            .clock
+            .slot
+            .checked_add(COMMIT_REVEAL_DELAY)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+
+        Ok(())
+    }
+
+    /// Freezes the config lines and the fields describing the NFTs, and records the hash of the
+    /// config lines. Candy machines created with the lock in place cannot mint before it.
+    ///
+    /// The hash is not checked by any instruction, the lock flag is what freezes the config. It
+    /// is there for off-chain readers to verify the config lines the candy machine minted from.
+    pub fn lock_config(ctx: Context<LockConfig>) -> ProgramResult {
+        let candy_machine = &ctx.accounts.candy_machine;
+        let candy_machine_info = candy_machine.to_account_info();
+        let mut data = candy_machine_info.data.borrow_mut();
+        if is_feature_active(&data, CONFIG_LOCKED_FLAG) {
+            return Err(ErrorCode::ConfigLocked.into());
+        }
+
+        let config_lines_len = if candy_machine.data.hidden_settings.is_some() {
+            0
+        } else {
+            if get_config_count(&data)? < candy_machine.data.items_available as usize {
+                return Err(ErrorCode::ConfigLinesMissing.into());
+            }
+            (candy_machine.data.items_available as usize) * CONFIG_LINE_SIZE
+        };
+        let config_lines_start = CONFIG_ARRAY_START + 4;
+        let config_lock = &mut ctx.accounts.config_lock;
+        config_lock.candy_machine = candy_machine.key();
+        config_lock.hash =
+            hash(&data[config_lines_start..config_lines_start + config_lines_len]).to_bytes();
+
+        set_feature_flag(&mut data, CONFIG_LOCKED_FLAG, true);
+
+        Ok(())
+    }
+
+    /// Sets the royalty split and the uses of the NFTs the candy machine mints.
+    pub fn set_mint_settings(
+        ctx: Context<SetMintSettings>,
+        data: MintSettingsData,
+    ) -> ProgramResult {
+        let candy_machine = &ctx.accounts.candy_machine;
+        let candy_machine_info = candy_machine.to_account_info();
+        if is_feature_active(&candy_machine_info.data.borrow(), CONFIG_LOCKED_FLAG) {
+            return Err(ErrorCode::ConfigLocked.into());
+        }
+        if let Some(creators) = &data.creators {
+            // - 1 because the candy machine is going to be a creator
+            if creators.len() > MAX_CREATOR_LIMIT - 1 {
+                return Err(ErrorCode::TooManyCreators.into());
+            }
+            let total_share: u16 = creators.iter().map(|c| c.share as u16).sum();
+            if total_share != 100 {
+                return Err(ErrorCode::InvalidCreatorShares.into());
+            }
+        }
+        if let Some(uses) = data.uses {
+            if uses.remaining > uses.total {
+                return Err(ErrorCode::InvalidUses.into());
+            }
+            assert_valid_use(&Some(uses.into()), &None)?;
+        }
+
+        let mint_settings_info = ctx.accounts.mint_settings.to_account_info();
+        if mint_settings_info.data_is_empty() {
+            create_or_allocate_account_raw(
+                crate::id(),
+                &mint_settings_info,
+                &ctx.accounts.rent.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                MINT_SETTINGS_SIZE,
+                &[
+                    MINT_SETTINGS.as_bytes(),
+                    candy_machine.key().as_ref(),
+                    &[*ctx.bumps.get("mint_settings").unwrap()],
+                ],
+            )?;
+        } else {
+            assert_owned_by(&mint_settings_info, &crate::id())?;
+        }
+        let mint_settings = MintSettings {
+            candy_machine: candy_machine.key(),
+            data,
+        };
+        let mut data_ref: &mut [u8] = &mut mint_settings_info.try_borrow_mut_data()?;
+        mint_settings.try_serialize(&mut data_ref)?;
+
+        set_feature_flag(
+            &mut candy_machine_info.data.borrow_mut(),
+            MINT_SETTINGS_FLAG,
+            true,
+        );
+
+        Ok(())
+    }
+
+    /// Closes a candy machine once every item is minted, or before that when `force` is set,
+    /// along with its config lock, mint settings, collection PDA and the collection authority it
+    /// was approved. Pending mints have to be settled and reveal mints revealed first.
+    pub fn close_candy_machine<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseCandyMachine<'info>>,
+        force: bool,
+    ) -> ProgramResult {
+        let candy_machine = &ctx.accounts.candy_machine;
+        let authority = &ctx.accounts.authority;
+        if !force && candy_machine.items_redeemed < candy_machine.data.items_available {
+            return Err(ErrorCode::CandyMachineNotEmpty.into());
+        }
+        let candy_machine_info = candy_machine.to_account_info();
+        if is_feature_active(&candy_machine_info.data.borrow(), CANDY_GUARD_FLAG) {
+            return Err(ErrorCode::CandyGuardNotClosed.into());
+        }
+        // settle_pending_mint and reveal need the candy machine, even with force
+        if get_unsettled_mints(&candy_machine_info.data.borrow()) > 0 {
+            return Err(ErrorCode::UnsettledMints.into());
+        }
+
+        let candy_key = candy_machine.key();
+        let mut remaining_accounts = ctx.remaining_accounts;
+        if is_feature_active(&candy_machine_info.data.borrow(), CONFIG_LOCKED_FLAG) {
+            let config_lock = remaining_accounts
+                .first()
+                .ok_or(ErrorCode::MissingConfigLock)?;
+            assert_derivation(
+                &candy_machine::id(),
+                config_lock,
+                &[CONFIG_LOCK.as_bytes(), candy_key.as_ref()],
+            )?;
+            assert_owned_by(config_lock, &candy_machine::id())?;
+            close_program_account(config_lock, authority)?;
+            remaining_accounts = &remaining_accounts[1..];
+        }
+        if is_feature_active(&candy_machine_info.data.borrow(), MINT_SETTINGS_FLAG) {
+            let mint_settings = remaining_accounts
+                .first()
+                .ok_or(ErrorCode::MissingMintSettings)?;
+            assert_derivation(
+                &candy_machine::id(),
+                mint_settings,
+                &[MINT_SETTINGS.as_bytes(), candy_key.as_ref()],
+            )?;
+            assert_owned_by(mint_settings, &candy_machine::id())?;
+            close_program_account(mint_settings, authority)?;
+            remaining_accounts = &remaining_accounts[1..];
+        }
+
+        let collection_pda = ctx.accounts.collection_pda.to_account_info();
+        if !collection_pda.data_is_empty() {
+            let collection_account = |index: usize| {
+                remaining_accounts
+                    .get(index)
+                    .ok_or(ErrorCode::MissingCollectionAccounts)
+            };
+            assert_owned_by(&collection_pda, &candy_machine::id())?;
+            let collection_authority_record = collection_account(0)?;
+            let collection_metadata = collection_account(1)?;
+            let collection_mint = collection_account(2)?;
+            let token_metadata_program = collection_account(3)?;
+            assert_keys_equal(token_metadata_program.key(), mpl_token_metadata::id())?;
+            let collection: CollectionPDA =
+                CollectionPDA::try_deserialize(&mut &collection_pda.data.borrow()[..])?;
+            if collection.mint != collection_mint.key() {
+                return Err(ErrorCode::MismatchedCollectionMint.into());
+            }
+
+            if !collection_authority_record.data_is_empty() {
+                msg!(
+                    "About to revoke collection authority for {}.",
+                    collection_metadata.key()
+                );
+                invoke(
+                    &revoke_collection_authority(
+                        token_metadata_program.key(),
+                        collection_authority_record.key(),
+                        collection_pda.key(),
+                        authority.key(),
+                        collection_metadata.key(),
+                        collection_mint.key(),
+                    ),
+                    &[
+                        collection_authority_record.clone(),
+                        collection_pda.clone(),
+                        authority.to_account_info(),
+                        collection_metadata.clone(),
+                        collection_mint.clone(),
+                    ],
+                )?;
+            }
+
+            close_program_account(&collection_pda, authority)?;
+        }
+
+        // the account is closed on exit, which only overwrites the discriminator
+        let mut data = candy_machine_info.try_borrow_mut_data()?;
+        let len = data.len();
+        sol_memset(&mut data, 0, len);
+
+        Ok(())
+    }
+}
+
+fn get_space_for_candy(data: CandyMachineData) -> core::result::Result<usize, ProgramError> {
+    let num = if data.hidden_settings.is_some() {
+        CONFIG_ARRAY_START
+    } else {
+        CONFIG_ARRAY_START
+            + 4
+            + (data.items_available as usize) * CONFIG_LINE_SIZE
+            + 8
+            + 2 * ((data
+                .items_available
+                .checked_div(8)
+                .ok_or(ErrorCode::NumericalOverflowError)?
+                + 1) as usize)
+    };
+
+    Ok(num)
+}
+
+/// What a mint still has to act on once [`check_mint`] passed.
+struct MintChecks<'a, 'info> {
+    price: u64,
+    payment_mint: Option<Pubkey>,
+    payment_destination: AccountInfo<'info>,
+    remaining_accounts_counter: usize,
+    guards: Option<(ActiveGuards, GuardContext<'a, 'info>)>,
+    /// Gateway app, gateway token, network expire feature and gatekeeper network of a gateway
+    /// token to expire.
+    gateway_expire: Option<(
+        &'a AccountInfo<'info>,
+        &'a AccountInfo<'info>,
+        &'a AccountInfo<'info>,
+        Pubkey,
+    )>,
+    whitelist_burn: Option<TokenBurnParams<'info, 'a>>,
+}
+
+/// Fails a mint sent along with instructions of other programs, or called by a program other than
+/// gumdrop. This is never charged a bot tax: a calling program could see nothing was minted and
+/// fail the transaction, reverting the tax with it.
+fn assert_mint_transaction(instruction_sysvar_account: &AccountInfo) -> ProgramResult {
+    let instruction_sysvar = instruction_sysvar_account.data.borrow();
+
+    let mut idx = 0;
+    let num_instructions =
+        read_u16(&mut idx, &instruction_sysvar).map_err(|_| ProgramError::InvalidAccountData)?;
+
+    let associated_token =
+        Pubkey::from_str("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL").unwrap();
+    // gumdrop claim_candy_v2 mints through a CPI. It only calls the candy machine from its
+    // distributor wallet, which has to be the candy machine authority, and it does not fail after
+    // a taxed mint, it only leaves the claim unused.
+    let gumdrop = Pubkey::from_str(GUMDROP).unwrap();
+
+    for index in 0..num_instructions {
+        let mut current = 2 + (index * 2) as usize;
+        let start = read_u16(&mut current, &instruction_sysvar).unwrap();
+
+        current = start as usize;
+        let num_accounts = read_u16(&mut current, &instruction_sysvar).unwrap();
+        current += (num_accounts as usize) * (1 + 32);
+        let program_id = read_pubkey(&mut current, &instruction_sysvar).unwrap();
+
+        if program_id != crate::id()
+            && program_id != spl_token::id()
+            && program_id != anchor_lang::solana_program::system_program::ID
+            && program_id != associated_token
+            && program_id != gumdrop
+        {
+            msg!("Transaction had ix with program id {}", program_id);
+            return Err(ErrorCode::SuspiciousTransaction.into());
+        }
+    }
+    drop(instruction_sysvar);
+
+    // the top-level instruction is the one running the mint, directly or through gumdrop
+    let current = get_instruction_relative(0, instruction_sysvar_account)?;
+    if current.program_id != crate::id() && current.program_id != gumdrop {
+        msg!("Mint called by program id {}", current.program_id);
+        return Err(ErrorCode::SuspiciousTransaction.into());
+    }
+
+    Ok(())
+}
+
+/// Runs every check of a mint without changing any state, so a failed check can be charged a
+/// bot tax instead of failing the mint.
+fn check_mint<'a, 'info>(
+    ctx: &'a Context<'_, '_, '_, 'info, MintNFT<'info>>,
+    candy_guard: Option<&CandyGuard>,
+    group: Option<&str>,
+    allow_list_proof: Option<&'a AllowListProof>,
+) -> Result<MintChecks<'a, 'info>> {
+    let candy_machine = &ctx.accounts.candy_machine;
+    let clock = &ctx.accounts.clock;
+    // Note this is the wallet of the Candy machine
+    let wallet = &ctx.accounts.wallet;
+    let payer = &ctx.accounts.payer;
+    let token_program = &ctx.accounts.token_program;
+
+    let mut price = candy_machine.data.price;
+    let mut payment_mint = candy_machine.token_mint;
+    let mut payment_destination = wallet.to_account_info();
+    let mut remaining_accounts_counter: usize = 0;
+    let mut guards = None;
+    if let Some(candy_guard) = candy_guard {
+        remaining_accounts_counter += 1;
+        let mut guard_ctx = GuardContext {
+            candy_machine,
+            candy_machine_key: candy_machine.key(),
+            payer: payer.deref(),
+            mint_authority: ctx.accounts.mint_authority.deref(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            clock,
+            remaining_accounts: ctx.remaining_accounts,
+            remaining_accounts_counter,
+            payment: None,
+            allow_list_proof,
+        };
+        let active_guards = validate_guards(candy_guard, group, &mut guard_ctx)?;
+        remaining_accounts_counter = guard_ctx.remaining_accounts_counter;
+        if let Some(payment) = guard_ctx.payment.take() {
+            price = payment.price;
+            payment_mint = payment.mint;
+            payment_destination = payment.destination.clone();
+        }
+        guards = Some((active_guards, guard_ctx));
+    }
+
+    if let Some(es) = &candy_machine.data.end_settings {
+        match es.end_setting_type {
+            EndSettingType::Date => {
+                if clock.unix_timestamp > es.number as i64 {
+                    if ctx.accounts.payer.key() != candy_machine.authority {
+                        return Err(ErrorCode::CandyMachineNotLive.into());
+                    }
+                }
+            }
+            EndSettingType::Amount => {
+                if candy_machine.items_redeemed >= es.number {
+                    return Err(ErrorCode::CandyMachineNotLive.into());
+                }
+            }
+        }
+    }
+
+    let mut gateway_expire = None;
+    if let Some(gatekeeper) = &candy_machine.data.gatekeeper {
+        if ctx.remaining_accounts.len() <= remaining_accounts_counter {
+            return Err(ErrorCode::GatewayTokenMissing.into());
+        }
+        let gateway_token_info = &ctx.remaining_accounts[remaining_accounts_counter];
+        let gateway_token = ::solana_gateway::borsh::try_from_slice_incomplete::<
+            ::solana_gateway::state::GatewayToken,
+        >(*gateway_token_info.data.borrow())
+        .map_err(ProgramError::from)?;
+        // stores the expire_time before the verification, since the verification
+        // will update the expire_time of the token and we won't be able to
+        // calculate the creation time
+        let expire_time = gateway_token
+            .expire_time
+            .ok_or(ErrorCode::GatewayTokenExpireTimeInvalid)? as i64;
+        remaining_accounts_counter += 1;
+        ::solana_gateway::Gateway::verify_gateway_token_account_info(
+            gateway_token_info,
+            &payer.key(),
+            &gatekeeper.gatekeeper_network,
+            None,
+        )
+        .map_err(ProgramError::from)?;
+        if gatekeeper.expire_on_use {
+            if ctx.remaining_accounts.len() <= remaining_accounts_counter {
+                return Err(ErrorCode::GatewayAppMissing.into());
+            }
+            let gateway_app = &ctx.remaining_accounts[remaining_accounts_counter];
+            remaining_accounts_counter += 1;
+            if ctx.remaining_accounts.len() <= remaining_accounts_counter {
+                return Err(ErrorCode::NetworkExpireFeatureMissing.into());
+            }
+            let network_expire_feature = &ctx.remaining_accounts[remaining_accounts_counter];
+            remaining_accounts_counter += 1;
+            // the token is expired once every check passed
+            gateway_expire = Some((
+                gateway_app,
+                gateway_token_info,
+                network_expire_feature,
+                gatekeeper.gatekeeper_network,
+            ));
+        }
+        // verifies that the gatway token was not created before the candy
+        // machine go_live_date (avoids pre-solving the captcha)
+        match candy_machine.data.go_live_date {
+            Some(val) => {
+                if (expire_time - EXPIRE_OFFSET) < val {
+                    if let Some(ws) = &candy_machine.data.whitelist_mint_settings {
+                        // when dealing with whitelist, the expire_time can be
+                        // before the go_live_date only if presale enabled
+                        if !ws.presale {
+                            msg!(
+                                "Invalid gateway token: calculated creation time {} and go_live_date {}",
+                                expire_time - EXPIRE_OFFSET,
+                                val);
+                            return Err(ErrorCode::GatewayTokenExpireTimeInvalid.into());
+                        }
+                    } else {
+                        msg!(
+                            "Invalid gateway token: calculated creation time {} and go_live_date {}",
+                            expire_time - EXPIRE_OFFSET,
+                            val);
+                        return Err(ErrorCode::GatewayTokenExpireTimeInvalid.into());
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+
+    let mut whitelist_burn = None;
+    if let Some(ws) = &candy_machine.data.whitelist_mint_settings {
+        let whitelist_token_account = &ctx.remaining_accounts[remaining_accounts_counter];
+        remaining_accounts_counter += 1;
+        // If the user has not actually made this account,
+        // this explodes and we just check normal dates.
+        // If they have, we check amount, if it's > 0 we let them use the logic
+        // if 0, check normal dates.
+        match assert_is_ata(whitelist_token_account, &payer.key(), &ws.mint) {
+            Ok(wta) => {
+                if wta.amount > 0 {
+                    if ws.mode == WhitelistMintMode::BurnEveryTime {
+                        let whitelist_token_mint =
+                            &ctx.remaining_accounts[remaining_accounts_counter];
+                        remaining_accounts_counter += 1;
+
+                        let whitelist_burn_authority =
+                            &ctx.remaining_accounts[remaining_accounts_counter];
+                        remaining_accounts_counter += 1;
+
+                        assert_keys_equal(whitelist_token_mint.key(), ws.mint)?;
+
+                        // the token is burned once every check passed
+                        whitelist_burn = Some(TokenBurnParams {
+                            mint: whitelist_token_mint.clone(),
+                            source: whitelist_token_account.clone(),
+                            amount: 1,
+                            authority: whitelist_burn_authority.clone(),
+                            authority_signer_seeds: None,
+                            token_program: token_program.to_account_info(),
+                        });
+                    }
+
+                    match candy_machine.data.go_live_date {
+                        None => {
+                            if ctx.accounts.payer.key() != candy_machine.authority && !ws.presale {
+                                return Err(ErrorCode::CandyMachineNotLive.into());
+                            }
+                        }
+                        Some(val) => {
+                            if clock.unix_timestamp < val
+                                && ctx.accounts.payer.key() != candy_machine.authority
+                                && !ws.presale
+                            {
+                                return Err(ErrorCode::CandyMachineNotLive.into());
+                            }
+                        }
+                    }
+
+                    if let Some(dp) = ws.discount_price {
+                        // the discount is in the currency of the candy machine
+                        if payment_mint != candy_machine.token_mint {
+                            return Err(ErrorCode::DiscountPriceCurrencyMismatch.into());
+                        }
+                        price = dp;
+                    }
+                } else {
+                    if wta.amount == 0 && ws.discount_price.is_none() && !ws.presale {
+                        // A non-presale whitelist with no discount price is a forced whitelist
+                        // If a pre-sale has no discount, its no issue, because the "discount"
+                        // is minting first - a presale whitelist always has an open post sale.
+                        return Err(ErrorCode::NoWhitelistToken.into());
+                    }
+                    assert_valid_go_live(payer, clock, candy_machine)?;
+                    if ws.mode == WhitelistMintMode::BurnEveryTime {
+                        remaining_accounts_counter += 2;
+                    }
+                }
+            }
+            Err(_) => {
+                if ws.discount_price.is_none() && !ws.presale {
+                    // A non-presale whitelist with no discount price is a forced whitelist
+                    // If a pre-sale has no discount, its no issue, because the "discount"
+                    // is minting first - a presale whitelist always has an open post sale.
+                    return Err(ErrorCode::NoWhitelistToken.into());
+                }
+                if ws.mode == WhitelistMintMode::BurnEveryTime {
+                    remaining_accounts_counter += 2;
+                }
+                assert_valid_go_live(payer, clock, candy_machine)?
+            }
+        }
+    } else {
+        // no whitelist means normal datecheck
+        assert_valid_go_live(payer, clock, candy_machine)?;
+    }
+
+    if candy_machine.items_redeemed >= candy_machine.data.items_available {
+        return Err(ErrorCode::CandyMachineEmpty.into());
+    }
+
+    Ok(MintChecks {
+        price,
+        payment_mint,
+        payment_destination,
+        remaining_accounts_counter,
+        guards,
+        gateway_expire,
+        whitelist_burn,
+    })
+}
+
+/// Create a new candy machine.
+#[derive(Accounts)]
+#[instruction(data: CandyMachineData)]
+pub struct InitializeCandyMachine<'info> {
+    /// CHECK: account constraints checked in account trait
+    #[account(zero, rent_exempt = skip, constraint = candy_machine.to_account_info().owner == program_id && candy_machine.to_account_info().data_len() >= get_space_for_candy(data)?)]
+    candy_machine: UncheckedAccount<'info>,
+    /// CHECK: wallet can be any account and is not written to or read
+    wallet: UncheckedAccount<'info>,
+    /// CHECK: authority can be any account and is not written to or read
+    authority: UncheckedAccount<'info>,
+    payer: Signer<'info>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+/// Sets and verifies the collection during a candy machine mint
+#[derive(Accounts)]
+pub struct SetCollectionDuringMint<'info> {
+    #[account(has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    /// CHECK: account checked in CPI/instruction sysvar
+    metadata: UncheckedAccount<'info>,
+    payer: Signer<'info>,
+    #[account(mut, seeds = [b"collection".as_ref(), candy_machine.to_account_info().key.as_ref()], bump)]
+    collection_pda: Account<'info, CollectionPDA>,
+    /// CHECK: account constraints checked in account trait
+    #[account(address = mpl_token_metadata::id())]
+    token_metadata_program: UncheckedAccount<'info>,
+    /// CHECK: account constraints checked in account trait
+    #[account(address = sysvar::instructions::id())]
+    instructions: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    collection_mint: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    #[account(mut)]
+    collection_metadata: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    collection_master_edition: UncheckedAccount<'info>,
+    /// CHECK: authority can be any account and is checked in CPI
+    authority: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    collection_authority_record: UncheckedAccount<'info>,
+}
+
+/// Set the collection PDA for the candy machine
+#[derive(Accounts)]
+pub struct SetCollection<'info> {
+    #[account(has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    authority: Signer<'info>,
+    /// CHECK: account constraints checked in account trait
+    #[account(mut, seeds = [b"collection".as_ref(), candy_machine.to_account_info().key.as_ref()], bump)]
+    collection_pda: UncheckedAccount<'info>,
+    payer: Signer<'info>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+
+    /// CHECK: account checked in CPI
+    metadata: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    mint: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    edition: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    #[account(mut)]
+    collection_authority_record: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    #[account(address = mpl_token_metadata::id())]
+    token_metadata_program: UncheckedAccount<'info>,
+}
+
+/// Set the collection PDA for the candy machine
+#[derive(Accounts)]
+pub struct RemoveCollection<'info> {
+    #[account(has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    authority: Signer<'info>,
+    #[account(mut, seeds = [b"collection".as_ref(), candy_machine.to_account_info().key.as_ref()], bump, close=authority)]
+    collection_pda: Account<'info, CollectionPDA>,
+    /// CHECK: account checked in CPI
+    metadata: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    mint: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    #[account(mut)]
+    collection_authority_record: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    #[account(address = mpl_token_metadata::id())]
+    token_metadata_program: UncheckedAccount<'info>,
+}
+
+/// Add multiple config lines to the candy machine.
+#[derive(Accounts)]
+pub struct AddConfigLines<'info> {
+    #[account(mut, has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    authority: Signer<'info>,
+}
+
+/// Withdraw SOL from candy machine account.
+#[derive(Accounts)]
+pub struct WithdrawFunds<'info> {
+    #[account(mut, has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(address = candy_machine.authority)]
+    authority: Signer<'info>,
     // > Only if collection
     // CollectionPDA account
 }
 
+/// Create the guard set of a candy machine.
+#[derive(Accounts)]
+pub struct InitializeCandyGuard<'info> {
+    #[account(mut, has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(
+    init,
+    seeds = [CANDY_GUARD.as_bytes(), candy_machine.key().as_ref()],
+    bump,
+    payer = payer,
+    space = CANDY_GUARD_SIZE
+    )]
+    candy_guard: Account<'info, CandyGuard>,
+    authority: Signer<'info>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+/// Replace the guards of a candy machine.
+#[derive(Accounts)]
+pub struct UpdateCandyGuard<'info> {
+    #[account(has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(
+    mut,
+    seeds = [CANDY_GUARD.as_bytes(), candy_machine.key().as_ref()],
+    bump = candy_guard.bump,
+    has_one = candy_machine
+    )]
+    candy_guard: Account<'info, CandyGuard>,
+    authority: Signer<'info>,
+}
+
+/// Remove the guard set of a candy machine and reclaim its rent.
+#[derive(Accounts)]
+pub struct CloseCandyGuard<'info> {
+    #[account(mut, has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(
+    mut,
+    seeds = [CANDY_GUARD.as_bytes(), candy_machine.key().as_ref()],
+    bump = candy_guard.bump,
+    has_one = candy_machine,
+    close = authority
+    )]
+    candy_guard: Account<'info, CandyGuard>,
+    #[account(mut)]
+    authority: Signer<'info>,
+}
+
+/// Set the mint settings of a candy machine.
+#[derive(Accounts)]
+pub struct SetMintSettings<'info> {
+    #[account(mut, has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    /// CHECK: account created and checked in the instruction
+    #[account(
+    mut,
+    seeds = [MINT_SETTINGS.as_bytes(), candy_machine.key().as_ref()],
+    bump
+    )]
+    mint_settings: UncheckedAccount<'info>,
+    #[account(mut)]
+    authority: Signer<'info>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+/// Lock the config of a candy machine.
+#[derive(Accounts)]
+pub struct LockConfig<'info> {
+    #[account(mut, has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(
+    init,
+    seeds = [CONFIG_LOCK.as_bytes(), candy_machine.key().as_ref()],
+    bump,
+    payer = authority,
+    space = CONFIG_LOCK_SIZE
+    )]
+    config_lock: Account<'info, ConfigLock>,
+    #[account(mut)]
+    authority: Signer<'info>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+/// Close a candy machine and reclaim its rent.
+#[derive(Accounts)]
+pub struct CloseCandyMachine<'info> {
+    #[account(
+    mut,
+    has_one = authority,
+    close = authority
+    )]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(mut)]
+    authority: Signer<'info>,
+    /// CHECK: account constraints checked in account trait
+    #[account(mut, seeds = [b"collection".as_ref(), candy_machine.to_account_info().key.as_ref()], bump)]
+    collection_pda: UncheckedAccount<'info>,
+    // > Only needed if candy machine has its config locked
+    // config_lock
+    // > Only needed if candy machine has mint settings
+    // mint_settings
+    // > Only needed if candy machine has a collection
+    // collection_authority_record
+    // collection_metadata
+    // collection_mint
+    // token_metadata_program
+}
+
+/// Turn on the commit reveal mints of a candy machine.
+#[derive(Accounts)]
+pub struct EnableCommitReveal<'info> {
+    #[account(mut, has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    authority: Signer<'info>,
+    /// CHECK: account constraints checked in account trait
+    #[account(seeds = [b"collection".as_ref(), candy_machine.to_account_info().key.as_ref()], bump)]
+    collection_pda: UncheckedAccount<'info>,
+}
+
+/// Settle a pending mint of a candy machine with commit reveal mints.
+#[derive(Accounts)]
+pub struct SettlePendingMint<'info> {
+    #[account(mut)]
+    candy_machine: Account<'info, CandyMachine>,
+    /// CHECK: account constraints checked in account trait
+    #[account(seeds=[PREFIX.as_bytes(), candy_machine.key().as_ref()], bump)]
+    candy_machine_creator: UncheckedAccount<'info>,
+    #[account(
+    mut,
+    seeds = [PENDING_MINT.as_bytes(), candy_machine.key().as_ref(), mint.key().as_ref()],
+    bump,
+    close = payer
+    )]
+    pending_mint: Account<'info, PendingMint>,
+    /// CHECK: account checked by the pending mint seeds
+    mint: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    #[account(mut)]
+    metadata: UncheckedAccount<'info>,
+    /// CHECK: account checked against the pending mint
+    #[account(mut, address = pending_mint.payer)]
+    payer: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    #[account(address = mpl_token_metadata::id())]
+    token_metadata_program: UncheckedAccount<'info>,
+    /// CHECK: account constraints checked in account trait
+    #[account(address = sysvar::slot_hashes::id())]
+    recent_slothashes: UncheckedAccount<'info>,
+    clock: Sysvar<'info, Clock>,
+}
+
+/// Renew the target slot of an expired pending mint.
+#[derive(Accounts)]
+pub struct RenewPendingMint<'info> {
+    #[account(has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    authority: Signer<'info>,
+    #[account(
+    mut,
+    seeds = [PENDING_MINT.as_bytes(), candy_machine.key().as_ref(), mint.key().as_ref()],
+    bump
+    )]
+    pending_mint: Account<'info, PendingMint>,
+    /// CHECK: account checked by the pending mint seeds
+    mint: UncheckedAccount<'info>,
+    /// CHECK: account constraints checked in account trait
+    #[account(address = sysvar::slot_hashes::id())]
+    recent_slothashes: UncheckedAccount<'info>,
+    clock: Sysvar<'info, Clock>,
+}
+
+/// Turn on the on chain reveal of a candy machine with hidden settings.
+#[derive(Accounts)]
+pub struct EnableReveal<'info> {
+    #[account(mut, has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    authority: Signer<'info>,
+}
+
+/// Reveal the metadata of an NFT minted with hidden settings.
+#[derive(Accounts)]
+pub struct Reveal<'info> {
+    #[account(mut, has_one = authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    authority: Signer<'info>,
+    #[account(
+    mut,
+    seeds = [MINT_INDEX.as_bytes(), candy_machine.key().as_ref(), mint.key().as_ref()],
+    bump
+    )]
+    mint_index: Account<'info, MintIndex>,
+    /// CHECK: account checked by the mint index seeds
+    mint: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    #[account(mut)]
+    metadata: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    #[account(address = mpl_token_metadata::id())]
+    token_metadata_program: UncheckedAccount<'info>,
+}
+
 /// Mint a new NFT pseudo-randomly from the config array.
 #[derive(Accounts)]
 #[instruction(creator_bump: u8)]
@@ -1023,6 +2065,14 @@ pub struct MintNFT<'info> {
     /// CHECK: account constraints checked in account trait
     #[account(address = sysvar::instructions::id())]
     instruction_sysvar_account: UncheckedAccount<'info>,
+    // > Only needed if candy machine has a candy guard
+    // candy_guard
+    // followed by the accounts each guard needs, default guards first and then the guards of the
+    // selected group, in order:
+    // token_gate: payer token account of the gate mint
+    // payment: payment destination
+    // mint_limit: mint counter PDA of the minting wallet
+    // allow_list: allow list counter PDA of the minting wallet
     // > Only needed if candy machine has a gatekeeper
     // gateway_token
     // > Only needed if candy machine has a gatekeeper and it has expire_on_use set to true:
@@ -1033,9 +2083,15 @@ pub struct MintNFT<'info> {
     // > Only needed if candy machine has whitelist_mint_settings and mode is BurnEveryTime
     // whitelist_token_mint
     // whitelist_burn_authority
-    // > Only needed if candy machine has token mint
+    // > Only needed if candy machine has token mint or the guards set a payment in tokens
     // token_account_info
     // transfer_authority_info
+    // > Only needed if candy machine has the reveal enabled
+    // mint_index
+    // > Only needed if candy machine has mint settings
+    // mint_settings
+    // > Only needed if candy machine has commit reveal mints enabled
+    // pending_mint
 }
 
 /// Update the candy machine state.
@@ -1074,6 +2130,69 @@ pub struct CollectionPDA {
     pub candy_machine: Pubkey,
 }
 
+pub const MINT_INDEX: &str = "mint_index";
+const MINT_INDEX_SIZE: usize = 8 + 8 + 1 + 32;
+pub const MINT_SETTINGS: &str = "mint_settings";
+const MINT_SETTINGS_SIZE: usize = 8 + // key
+    32 + // candy machine
+    1 + 4 + (MAX_CREATOR_LIMIT - 1) * MAX_CREATOR_LEN + // optional u32 len + creators
+    1 + 17; // optional uses
+/// Settings of the NFTs a candy machine mints that do not fit in [`CandyMachineData`].
+#[account]
+#[derive(Default)]
+pub struct MintSettings {
+    pub candy_machine: Pubkey,
+    pub data: MintSettingsData,
+}
+
+/// Settings applied to every NFT minted by a candy machine. There is no token standard setting,
+/// token-metadata derives it from the master edition the mint creates, so every candy machine NFT
+/// is `NonFungible`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct MintSettingsData {
+    /// Royalty split of the minted NFTs, in place of the candy machine creators.
+    pub creators: Option<Vec<Creator>>,
+    /// Uses of the minted NFTs.
+    pub uses: Option<Uses>,
+}
+
+pub const CONFIG_LOCK: &str = "config_lock";
+const CONFIG_LOCK_SIZE: usize = 8 + 32 + 32;
+/// Hash of the config lines of a candy machine, recorded when its config was locked.
+#[account]
+#[derive(Default)]
+pub struct ConfigLock {
+    pub candy_machine: Pubkey,
+    pub hash: [u8; 32],
+}
+
+pub const PENDING_MINT: &str = "pending_mint";
+const PENDING_MINT_SIZE: usize = 8 + 32 + 8 + 8 + 32 + 1;
+// Slots between a commit and the slot whose hash picks its config line. A leader produces four
+// slots in a row, so the target slot has to fall outside the window of the committing leader.
+const COMMIT_REVEAL_DELAY: u64 = 8;
+/// Mint of a candy machine with commit reveal mints, waiting for the hash of `target_slot` to
+/// pick its config line.
+#[account]
+#[derive(Default)]
+pub struct PendingMint {
+    pub payer: Pubkey,
+    pub mint_number: u64,
+    pub target_slot: u64,
+    pub new_update_authority: Pubkey,
+    pub primary_sale_happened: bool,
+}
+
+/// Position of an NFT in the mint order of a candy machine with the reveal enabled, and the hash
+/// of its content once revealed.
+#[account]
+#[derive(Default)]
+pub struct MintIndex {
+    pub index: u64,
+    pub revealed: bool,
+    pub content_hash: [u8; 32],
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct WhitelistMintSettings {
     pub mode: WhitelistMintMode,
@@ -1172,10 +2291,113 @@ pub struct HiddenSettings {
     pub hash: [u8; 32],
 }
 
+/// Revealed content of a candy machine with hidden settings, an NFT gets `uri_prefix`, its mint
+/// index and `uri_suffix`. `content_root` is the root of the merkle tree of the content in mint
+/// order, a leaf is `keccak(0x00 || index as u64 le || content hash)`. The hidden settings hash is
+/// the sha256 of these settings serialized.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RevealSettings {
+    pub uri_prefix: String,
+    pub uri_suffix: String,
+    pub content_root: [u8; 32],
+}
+
+/// Serialized fields of the candy machine data that cannot change once the config is locked.
+fn locked_fields(data: &CandyMachineData) -> core::result::Result<Vec<u8>, ProgramError> {
+    Ok((
+        data.symbol.trim_matches(char::from(0)),
+        data.seller_fee_basis_points,
+        data.max_supply,
+        data.is_mutable,
+        data.retain_authority,
+        &data.creators,
+        &data.hidden_settings,
+        data.items_available,
+    )
+        .try_to_vec()?)
+}
+
 pub fn get_config_count(data: &RefMut<&mut [u8]>) -> core::result::Result<usize, ProgramError> {
     return Ok(u32::from_le_bytes(*array_ref![data, CONFIG_ARRAY_START, 4]) as usize);
 }
 
+// The four bytes budgeted for the max number of lines in CONFIG_ARRAY_START are never
+// written by the serialized candy machine, the first one holds feature flags.
+pub const FEATURE_FLAGS_OFFSET: usize = CONFIG_ARRAY_START - 4;
+
+/// Largest serialized candy machine, in field order, which has to end before the feature flags.
+const MAX_CANDY_MACHINE_LEN: usize = 8 + // key
+    32 + // authority
+    32 + // wallet
+    33 + // token mint
+    8 + // items redeemed
+    4 + 6 + // uuid
+    8 + // price
+    4 + MAX_SYMBOL_LENGTH + // u32 len + symbol
+    2 + // seller fee basis points
+    8 + // max supply
+    1 + // is mutable
+    1 + // retain authority
+    9 + // go live
+    10 + // end settings
+    4 + MAX_CREATOR_LIMIT*MAX_CREATOR_LEN + // u32 len + creators
+    1 + 4 + MAX_NAME_LENGTH + 4 + MAX_URI_LENGTH + 32 + // hidden settings
+    1 + 1 + 32 + 1 + 9 + // whitelist mint settings
+    8 + // items available
+    1 + 32 + 1 // gatekeeper
+;
+const _: () = assert!(MAX_CANDY_MACHINE_LEN <= FEATURE_FLAGS_OFFSET);
+pub const CANDY_GUARD_FLAG: u8 = 1;
+pub const REVEAL_FLAG: u8 = 2;
+pub const COMMIT_REVEAL_FLAG: u8 = 4;
+pub const CONFIG_LOCKED_FLAG: u8 = 8;
+pub const CONFIG_LOCK_REQUIRED_FLAG: u8 = 16;
+pub const MINT_SETTINGS_FLAG: u8 = 32;
+
+// The three bytes after the feature flags count the mints waiting for settle_pending_mint or
+// reveal, which both need the candy machine to stay open.
+pub const UNSETTLED_MINTS_OFFSET: usize = FEATURE_FLAGS_OFFSET + 1;
+const MAX_UNSETTLED_MINTS: u32 = 0xff_ffff;
+
+pub fn is_feature_active(data: &[u8], flag: u8) -> bool {
+    data[FEATURE_FLAGS_OFFSET] & flag != 0
+}
+
+pub fn set_feature_flag(data: &mut [u8], flag: u8, active: bool) {
+    if active {
+        data[FEATURE_FLAGS_OFFSET] |= flag;
+    } else {
+        data[FEATURE_FLAGS_OFFSET] &= !flag;
+    }
+}
+
+pub fn get_unsettled_mints(data: &[u8]) -> u32 {
+    let count = array_ref![data, UNSETTLED_MINTS_OFFSET, 3];
+    u32::from_le_bytes([count[0], count[1], count[2], 0])
+}
+
+fn set_unsettled_mints(data: &mut [u8], count: u32) {
+    data[UNSETTLED_MINTS_OFFSET..UNSETTLED_MINTS_OFFSET + 3]
+        .copy_from_slice(&count.to_le_bytes()[..3]);
+}
+
+pub fn add_unsettled_mint(data: &mut [u8]) -> ProgramResult {
+    let count = get_unsettled_mints(data)
+        .checked_add(1)
+        .filter(|count| *count <= MAX_UNSETTLED_MINTS)
+        .ok_or(ErrorCode::NumericalOverflowError)?;
+    set_unsettled_mints(data, count);
+    Ok(())
+}
+
+pub fn settle_unsettled_mint(data: &mut [u8]) -> ProgramResult {
+    let count = get_unsettled_mints(data)
+        .checked_sub(1)
+        .ok_or(ErrorCode::NumericalOverflowError)?;
+    set_unsettled_mints(data, count);
+    Ok(())
+}
+
 pub fn get_good_index(
     arr: &mut RefMut<&mut [u8]>,
     items_available: usize,
@@ -1321,6 +2543,15 @@ pub fn get_config_line<'info>(
     Ok(config_line)
 }
 
+/// Arguments of [`candy_machine::mint_nft_v2`], only read when the candy machine has a candy guard.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct MintArgs {
+    /// Label of the guard group to mint with.
+    pub group: Option<String>,
+    /// Proof the allow list guard checks the minter against.
+    pub allow_list_proof: Option<AllowListProof>,
+}
+
 /// Individual config line for storing NFT data pre-mint.
 pub const CONFIG_LINE_SIZE: usize = 4 + MAX_NAME_LENGTH + 4 + MAX_URI_LENGTH;
 #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
@@ -1340,6 +2571,34 @@ pub struct Creator {
     pub share: u8,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum UseMethod {
+    Burn,
+    Multiple,
+    Single,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Uses {
+    pub use_method: UseMethod,
+    pub remaining: u64,
+    pub total: u64,
+}
+
+impl From<Uses> for mpl_token_metadata::state::Uses {
+    fn from(uses: Uses) -> Self {
+        mpl_token_metadata::state::Uses {
+            use_method: match uses.use_method {
+                UseMethod::Burn => mpl_token_metadata::state::UseMethod::Burn,
+                UseMethod::Multiple => mpl_token_metadata::state::UseMethod::Multiple,
+                UseMethod::Single => mpl_token_metadata::state::UseMethod::Single,
+            },
+            remaining: uses.remaining,
+            total: uses.total,
+        }
+    }
+}
+
 #[error]
 pub enum ErrorCode {
     #[msg("Account does not have correct owner!")]
@@ -1402,4 +2661,88 @@ pub enum ErrorCode {
     MismatchedCollectionPDA,
     #[msg("Provided mint account doesn't match collection PDA mint")]
     MismatchedCollectionMint,
+    #[msg("Candy guard account is missing or does not belong to this candy machine")]
+    MissingCandyGuard,
+    #[msg("Missing an account required by a guard")]
+    MissingGuardAccount,
+    #[msg("Too many guards in the guard set")]
+    TooManyGuards,
+    #[msg("A guard can only appear once in a guard set")]
+    DuplicateGuard,
+    #[msg("Invalid guard settings")]
+    InvalidGuardSettings,
+    #[msg("Not enough tokens to pass the token gate")]
+    NotEnoughGateTokens,
+    #[msg("Too many guard groups in the guard set")]
+    TooManyGuardGroups,
+    #[msg("Guard group labels must be between 1 and 6 characters")]
+    InvalidGuardGroupLabel,
+    #[msg("Guard group labels must be unique")]
+    DuplicateGuardGroup,
+    #[msg("No guard group with this label")]
+    GuardGroupNotFound,
+    #[msg("This candy machine mints in groups, a group label is required")]
+    GuardGroupRequired,
+    #[msg("This wallet reached its mint limit")]
+    MintLimitReached,
+    #[msg("The allow list guard needs a proof, mint with mint_nft_v2")]
+    MissingAllowListProof,
+    #[msg("This wallet is not on the allow list")]
+    NotOnAllowList,
+    #[msg("This wallet minted its allow list allocation")]
+    AllowListAllocationReached,
+    #[msg("Reveal needs hidden settings")]
+    RevealNeedsHiddenSettings,
+    #[msg("Reveal has to be enabled before the first mint")]
+    RevealEnabledAfterMint,
+    #[msg("Candy machine has the reveal enabled, the mint index account is missing")]
+    MissingMintIndex,
+    #[msg("Reveal settings do not match the hidden settings hash")]
+    RevealSettingsHashMismatch,
+    #[msg("Candy machine still has items to mint, it can only be closed with force")]
+    CandyMachineNotEmpty,
+    #[msg("Close the candy guard before closing the candy machine")]
+    CandyGuardNotClosed,
+    #[msg("Commit reveal mints need config lines, not hidden settings")]
+    CommitRevealNeedsConfigLines,
+    #[msg("Candy machine has commit reveal mints enabled, the pending mint account is missing")]
+    MissingPendingMint,
+    #[msg("The target slot of the pending mint has not passed yet")]
+    PendingMintNotReady,
+    #[msg("The target slot hash of the pending mint is no longer available")]
+    PendingMintExpired,
+    #[msg("Candy machine config is locked")]
+    ConfigLocked,
+    #[msg("Candy machine config has to be locked before minting")]
+    ConfigNotLocked,
+    #[msg("Every config line has to be added before locking the config")]
+    ConfigLinesMissing,
+    #[msg("Candy machine has its config locked, the config lock account is missing")]
+    MissingConfigLock,
+    #[msg("Candy machine has mint settings, the mint settings account is missing")]
+    MissingMintSettings,
+    #[msg("The whitelist discount price cannot apply to a guard payment in another currency")]
+    DiscountPriceCurrencyMismatch,
+    #[msg("With a bot tax only set_collection_during_mint can follow the mint in its transaction")]
+    MintNotLastInstruction,
+    #[msg("Reveal needs the config to be locked")]
+    RevealNeedsConfigLock,
+    #[msg("Candy machine has a collection, the collection accounts are missing")]
+    MissingCollectionAccounts,
+    #[msg("Commit reveal mints cannot be added to a collection")]
+    CommitRevealWithCollection,
+    #[msg("The target slot hash of the pending mint is still available")]
+    PendingMintNotExpired,
+    #[msg("Creator shares of the mint settings must add up to 100")]
+    InvalidCreatorShares,
+    #[msg("Remaining uses of the mint settings cannot exceed the total")]
+    InvalidUses,
+    #[msg("Candy machine has mints waiting to be settled or revealed")]
+    UnsettledMints,
+    #[msg("Reveal needs mutable metadata with the update authority retained")]
+    RevealNeedsMutableMetadata,
+    #[msg("Revealed content is not in the content root of the reveal settings")]
+    InvalidRevealProof,
+    #[msg("Pending mints settle in mint order, an earlier pending mint is not settled")]
+    PendingMintOutOfOrder,
 }
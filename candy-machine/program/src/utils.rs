@@ -6,9 +6,11 @@ use {
         prelude::{Account, AccountInfo, Clock, ProgramError, ProgramResult, Pubkey},
         solana_program::{
             program::invoke_signed,
+            program_memory::sol_memset,
             program_pack::{IsInitialized, Pack},
         },
     },
+    arrayref::array_ref,
     spl_associated_token_account::get_associated_token_address,
 };
 
@@ -170,3 +172,41 @@ pub fn spl_token_burn(params: TokenBurnParams<'_, '_>) -> ProgramResult {
     );
     result.map_err(|_| ErrorCode::TokenBurnFailed.into())
 }
+
+/// Finds the hash of the first slot at or after `slot` in the data of the SlotHashes sysvar,
+/// which keeps the most recent slots first. A skipped slot has no entry, the next produced slot
+/// stands in for it.
+pub fn get_slot_hash(data: &[u8], slot: u64) -> Result<[u8; 32], ProgramError> {
+    let len = u64::from_le_bytes(*array_ref![data, 0, 8]) as usize;
+    let mut hash = None;
+    for i in 0..len {
+        let entry = array_ref![data, 8 + i * 40, 40];
+        let entry_slot = u64::from_le_bytes(*array_ref![entry, 0, 8]);
+        if entry_slot < slot {
+            return hash.ok_or_else(|| ErrorCode::PendingMintNotReady.into());
+        }
+        hash = Some(*array_ref![entry, 8, 32]);
+        if entry_slot == slot {
+            return Ok(*array_ref![entry, 8, 32]);
+        }
+    }
+    match hash {
+        // every entry is newer than `slot`, the ones around it have been evicted
+        Some(_) => Err(ErrorCode::PendingMintExpired.into()),
+        None => Err(ErrorCode::PendingMintNotReady.into()),
+    }
+}
+
+/// Moves all lamports of a program owned account to `destination` and zeroes its data.
+pub fn close_program_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let snapshot: u64 = account.lamports();
+    **account.lamports.borrow_mut() = 0;
+    **destination.lamports.borrow_mut() = destination
+        .lamports()
+        .checked_add(snapshot)
+        .ok_or(ErrorCode::NumericalOverflowError)?;
+    let mut data = account.try_borrow_mut_data()?;
+    let len = data.len();
+    sol_memset(&mut data, 0, len);
+    Ok(())
+}
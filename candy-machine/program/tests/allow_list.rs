@@ -0,0 +1,162 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use anchor_lang::solana_program::keccak::hashv;
+use mpl_candy_machine::{
+    guards::{AllowList, AllowListProof, CandyGuardData, Guard},
+    ErrorCode, MintArgs,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use utils::setup_functions::*;
+
+fn leaf(wallet: &Pubkey, allocation: Option<u16>) -> [u8; 32] {
+    match allocation {
+        Some(allocation) => hashv(&[&[0x00], wallet.as_ref(), &allocation.to_le_bytes()]).0,
+        None => hashv(&[&[0x00], wallet.as_ref()]).0,
+    }
+}
+
+/// Root of a tree with two leaves, each is the proof of the other.
+fn root(first: [u8; 32], second: [u8; 32]) -> [u8; 32] {
+    if first <= second {
+        hashv(&[&[0x01], &first, &second]).0
+    } else {
+        hashv(&[&[0x01], &second, &first]).0
+    }
+}
+
+fn proof_args(allocation: Option<u16>, sibling: [u8; 32]) -> MintArgs {
+    MintArgs {
+        group: None,
+        allow_list_proof: Some(AllowListProof {
+            allocation,
+            proof: vec![sibling],
+        }),
+    }
+}
+
+#[tokio::test]
+async fn allow_list_guard() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let single = funded_keypair(&mut context).await;
+    let double = funded_keypair(&mut context).await;
+    let outsider = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 5),
+    )
+    .await;
+    let single_leaf = leaf(&single.pubkey(), None);
+    let double_leaf = leaf(&double.pubkey(), Some(2));
+    let merkle_root = root(single_leaf, double_leaf);
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        CandyGuardData {
+            guards: vec![Guard::AllowList(AllowList { merkle_root })],
+            groups: vec![],
+            bot_tax: None,
+        },
+    )
+    .await
+    .unwrap();
+    let remaining_accounts = |minter: &Keypair| {
+        let (counter, _) =
+            find_allow_list_counter_address(&merkle_root, &minter.pubkey(), &candy_machine);
+        vec![
+            AccountMeta::new_readonly(candy_guard, false),
+            AccountMeta::new(counter, false),
+        ]
+    };
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &single,
+        MintArgs::default(),
+        remaining_accounts(&single),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MissingAllowListProof);
+
+    mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &single,
+        proof_args(None, double_leaf),
+        remaining_accounts(&single),
+    )
+    .await
+    .unwrap();
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &single,
+        proof_args(None, double_leaf),
+        remaining_accounts(&single),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::AllowListAllocationReached);
+
+    // the allocation is part of the leaf
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &double,
+        proof_args(Some(3), single_leaf),
+        remaining_accounts(&double),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::NotOnAllowList);
+
+    for _ in 0..2 {
+        mint_nft(
+            &mut context,
+            &candy_machine,
+            &authority.pubkey(),
+            &double,
+            proof_args(Some(2), single_leaf),
+            remaining_accounts(&double),
+        )
+        .await
+        .unwrap();
+    }
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &double,
+        proof_args(Some(2), single_leaf),
+        remaining_accounts(&double),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::AllowListAllocationReached);
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &outsider,
+        proof_args(None, double_leaf),
+        remaining_accounts(&outsider),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::NotOnAllowList);
+}
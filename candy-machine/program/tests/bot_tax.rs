@@ -0,0 +1,244 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_candy_machine::{
+    guards::{BotTax, CandyGuardData, Guard, StartDate},
+    CandyMachine, ErrorCode, MintArgs,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    signature::Signer,
+    system_instruction,
+};
+use utils::setup_functions::*;
+
+const BOT_TAX: u64 = 10_000_000;
+// 2100-01-01
+const FUTURE: i64 = 4_102_444_800;
+
+fn taxed_guard_data(start_date: i64) -> CandyGuardData {
+    CandyGuardData {
+        guards: vec![Guard::StartDate(StartDate { date: start_date })],
+        groups: vec![],
+        bot_tax: Some(BotTax { lamports: BOT_TAX }),
+    }
+}
+
+#[tokio::test]
+async fn failed_mint_is_charged_the_bot_tax() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        taxed_guard_data(FUTURE),
+    )
+    .await
+    .unwrap();
+    let candy_machine_lamports = get_lamports(&mut context, &candy_machine).await;
+
+    // the mint succeeds without minting
+    let nft = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![AccountMeta::new_readonly(candy_guard, false)],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_lamports(&mut context, &candy_machine).await,
+        candy_machine_lamports + BOT_TAX
+    );
+    assert!(!account_exists(&mut context, &nft.metadata).await);
+    let candy_machine_data: CandyMachine = get_account_data(&mut context, &candy_machine).await;
+    assert_eq!(candy_machine_data.items_redeemed, 0);
+}
+
+#[tokio::test]
+async fn valid_mint_is_not_taxed() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        taxed_guard_data(0),
+    )
+    .await
+    .unwrap();
+    let candy_machine_lamports = get_lamports(&mut context, &candy_machine).await;
+
+    let nft = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![AccountMeta::new_readonly(candy_guard, false)],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_lamports(&mut context, &candy_machine).await,
+        candy_machine_lamports
+    );
+    assert!(account_exists(&mut context, &nft.metadata).await);
+}
+
+#[tokio::test]
+async fn taxed_mint_has_to_be_last() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        taxed_guard_data(FUTURE),
+    )
+    .await
+    .unwrap();
+    let nft = create_nft_mint(&mut context, &minter).await.unwrap();
+
+    // an instruction after the mint could fail the transaction and revert the tax
+    let instructions = [
+        mint_nft_instruction(
+            &candy_machine,
+            &authority.pubkey(),
+            &minter.pubkey(),
+            &nft,
+            MintArgs::default(),
+            vec![AccountMeta::new_readonly(candy_guard, false)],
+        ),
+        system_instruction::transfer(&minter.pubkey(), &authority.pubkey(), 1),
+    ];
+    let error = process_instructions(&mut context, &instructions, &minter, &[])
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::MintNotLastInstruction);
+}
+
+#[tokio::test]
+async fn mint_with_other_programs_fails_without_tax() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        taxed_guard_data(FUTURE),
+    )
+    .await
+    .unwrap();
+    let nft = create_nft_mint(&mut context, &minter).await.unwrap();
+
+    // a program sent along with the mint could fail the transaction once the tax is charged
+    let instructions = [
+        mint_nft_instruction(
+            &candy_machine,
+            &authority.pubkey(),
+            &minter.pubkey(),
+            &nft,
+            MintArgs::default(),
+            vec![AccountMeta::new_readonly(candy_guard, false)],
+        ),
+        Instruction {
+            program_id: mpl_token_metadata::id(),
+            accounts: vec![],
+            data: vec![],
+        },
+    ];
+    let error = process_instructions(&mut context, &instructions, &minter, &[])
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::SuspiciousTransaction);
+}
+
+#[tokio::test]
+async fn taxed_mint_with_set_collection_during_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let collection = create_collection(&mut context, &authority).await.unwrap();
+    set_collection(&mut context, &candy_machine, &authority, &collection)
+        .await
+        .unwrap();
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        taxed_guard_data(FUTURE),
+    )
+    .await
+    .unwrap();
+    let candy_machine_lamports = get_lamports(&mut context, &candy_machine).await;
+    let nft = create_nft_mint(&mut context, &minter).await.unwrap();
+
+    // the tax is kept even though there is no metadata to set the collection of
+    let instructions = [
+        mint_nft_instruction(
+            &candy_machine,
+            &authority.pubkey(),
+            &minter.pubkey(),
+            &nft,
+            MintArgs::default(),
+            vec![AccountMeta::new_readonly(candy_guard, false)],
+        ),
+        set_collection_during_mint_instruction(
+            &candy_machine,
+            &authority.pubkey(),
+            &minter.pubkey(),
+            &nft,
+            &collection,
+        ),
+    ];
+    process_instructions(&mut context, &instructions, &minter, &[])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_lamports(&mut context, &candy_machine).await,
+        candy_machine_lamports + BOT_TAX
+    );
+    assert!(!account_exists(&mut context, &nft.metadata).await);
+}
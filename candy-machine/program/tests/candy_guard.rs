@@ -0,0 +1,491 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_candy_machine::{
+    guards::{
+        CandyGuardData, EndDate, Guard, GuardGroup, MintLimit, Payment, StartDate, TokenGate,
+    },
+    CandyMachine, ErrorCode, MintArgs,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::AccountMeta,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+use utils::setup_functions::*;
+
+// 2100-01-01
+const FUTURE: i64 = 4_102_444_800;
+
+fn guard_data(guards: Vec<Guard>) -> CandyGuardData {
+    CandyGuardData {
+        guards,
+        groups: vec![],
+        bot_tax: None,
+    }
+}
+
+fn group(label: &str, guards: Vec<Guard>) -> GuardGroup {
+    GuardGroup {
+        label: label.to_string(),
+        guards,
+    }
+}
+
+fn group_args(label: &str) -> MintArgs {
+    MintArgs {
+        group: Some(label.to_string()),
+        allow_list_proof: None,
+    }
+}
+
+#[tokio::test]
+async fn start_date_guard() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        guard_data(vec![Guard::StartDate(StartDate { date: FUTURE })]),
+    )
+    .await
+    .unwrap();
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![AccountMeta::new_readonly(candy_guard, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::CandyMachineNotLive);
+
+    // the authority can mint before the start date
+    mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &authority,
+        MintArgs::default(),
+        vec![AccountMeta::new_readonly(candy_guard, false)],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn end_date_guard() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        guard_data(vec![Guard::EndDate(EndDate { date: 1 })]),
+    )
+    .await
+    .unwrap();
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![AccountMeta::new_readonly(candy_guard, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::CandyMachineNotLive);
+}
+
+#[tokio::test]
+async fn mint_needs_the_candy_guard_account() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+
+    // a group needs a candy guard
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        group_args("public"),
+        vec![],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MissingCandyGuard);
+
+    initialize_candy_guard(&mut context, &candy_machine, &authority, guard_data(vec![]))
+        .await
+        .unwrap();
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MissingCandyGuard);
+}
+
+#[tokio::test]
+async fn token_gate_guard() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let gate_mint = create_token(&mut context, &minter.pubkey(), 1)
+        .await
+        .unwrap();
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        guard_data(vec![Guard::TokenGate(TokenGate {
+            mint: gate_mint,
+            amount: 2,
+        })]),
+    )
+    .await
+    .unwrap();
+    let gate_token = get_associated_token_address(&minter.pubkey(), &gate_mint);
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![
+            AccountMeta::new_readonly(candy_guard, false),
+            AccountMeta::new_readonly(gate_token, false),
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::NotEnoughGateTokens);
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![AccountMeta::new_readonly(candy_guard, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MissingGuardAccount);
+
+    // tops the minter up to the gate amount
+    let gate_authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    process_instructions(
+        &mut context,
+        &[spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &gate_mint,
+            &gate_token,
+            &gate_authority.pubkey(),
+            &[],
+            1,
+        )
+        .unwrap()],
+        &gate_authority,
+        &[],
+    )
+    .await
+    .unwrap();
+    mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![
+            AccountMeta::new_readonly(candy_guard, false),
+            AccountMeta::new_readonly(gate_token, false),
+        ],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn payment_guard() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let treasury = Keypair::new();
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        guard_data(vec![Guard::Payment(Payment {
+            price: 2 * PRICE,
+            mint: None,
+            destination: treasury.pubkey(),
+        })]),
+    )
+    .await
+    .unwrap();
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![
+            AccountMeta::new_readonly(candy_guard, false),
+            AccountMeta::new(authority.pubkey(), false),
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::PublicKeyMismatch);
+
+    let wallet_lamports = get_lamports(&mut context, &authority.pubkey()).await;
+    mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![
+            AccountMeta::new_readonly(candy_guard, false),
+            AccountMeta::new(treasury.pubkey(), false),
+        ],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_lamports(&mut context, &treasury.pubkey()).await,
+        2 * PRICE
+    );
+    assert_eq!(
+        get_lamports(&mut context, &authority.pubkey()).await,
+        wallet_lamports
+    );
+}
+
+#[tokio::test]
+async fn guard_groups() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        CandyGuardData {
+            guards: vec![Guard::StartDate(StartDate { date: FUTURE })],
+            groups: vec![
+                // replaces the default start date
+                group("early", vec![Guard::StartDate(StartDate { date: 0 })]),
+                group("late", vec![]),
+            ],
+            bot_tax: None,
+        },
+    )
+    .await
+    .unwrap();
+    let remaining_accounts = vec![AccountMeta::new_readonly(candy_guard, false)];
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        remaining_accounts.clone(),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::GuardGroupRequired);
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        group_args("other"),
+        remaining_accounts.clone(),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::GuardGroupNotFound);
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        group_args("late"),
+        remaining_accounts.clone(),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::CandyMachineNotLive);
+
+    mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        group_args("early"),
+        remaining_accounts,
+    )
+    .await
+    .unwrap();
+    let candy_machine_data: CandyMachine = get_account_data(&mut context, &candy_machine).await;
+    assert_eq!(candy_machine_data.items_redeemed, 1);
+}
+
+#[tokio::test]
+async fn invalid_guard_data() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+
+    let error = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        guard_data(vec![
+            Guard::StartDate(StartDate { date: 0 }),
+            Guard::StartDate(StartDate { date: 1 }),
+        ]),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::DuplicateGuard);
+
+    let error = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        guard_data(vec![Guard::MintLimit(MintLimit { id: 0, limit: 0 })]),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::InvalidGuardSettings);
+
+    let error = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        CandyGuardData {
+            guards: vec![],
+            groups: vec![group("toolong", vec![])],
+            bot_tax: None,
+        },
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::InvalidGuardGroupLabel);
+
+    let error = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        CandyGuardData {
+            guards: vec![],
+            groups: vec![group("public", vec![]), group("public", vec![])],
+            bot_tax: None,
+        },
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::DuplicateGuardGroup);
+}
+
+#[tokio::test]
+async fn close_candy_guard_lifts_the_guards() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        guard_data(vec![Guard::StartDate(StartDate { date: FUTURE })]),
+    )
+    .await
+    .unwrap();
+
+    close_candy_guard(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+    assert!(!account_exists(&mut context, &candy_guard).await);
+
+    mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![],
+    )
+    .await
+    .unwrap();
+}
@@ -0,0 +1,369 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_candy_machine::{
+    guards::{CandyGuardData, Guard, StartDate},
+    ErrorCode, MintArgs, MintIndex, MintSettingsData, PendingMint,
+};
+use mpl_token_metadata::pda::find_collection_authority_account;
+use solana_program_test::*;
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey, signature::Signer};
+use utils::setup_functions::*;
+
+/// Accounts revoking the collection authority of the collection PDA of `candy_machine`.
+fn collection_accounts(candy_machine: &Pubkey, collection: &Nft) -> Vec<AccountMeta> {
+    let (collection_pda, _) = find_collection_pda_address(candy_machine);
+    let (collection_authority_record, _) =
+        find_collection_authority_account(&collection.mint.pubkey(), &collection_pda);
+    vec![
+        AccountMeta::new(collection_authority_record, false),
+        AccountMeta::new(collection.metadata, false),
+        AccountMeta::new_readonly(collection.mint.pubkey(), false),
+        AccountMeta::new_readonly(mpl_token_metadata::id(), false),
+    ]
+}
+
+#[tokio::test]
+async fn close_needs_force_before_sold_out() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let (config_lock, _) = find_config_lock_address(&candy_machine);
+
+    let error = close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        false,
+        vec![AccountMeta::new(config_lock, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::CandyMachineNotEmpty);
+
+    let error = close_candy_machine(&mut context, &candy_machine, &authority, true, vec![])
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::MissingConfigLock);
+
+    let authority_lamports = get_lamports(&mut context, &authority.pubkey()).await;
+    let candy_machine_lamports = get_lamports(&mut context, &candy_machine).await;
+    close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        true,
+        vec![AccountMeta::new(config_lock, false)],
+    )
+    .await
+    .unwrap();
+
+    // the config lock rent covers the fee
+    assert!(
+        get_lamports(&mut context, &authority.pubkey()).await
+            > authority_lamports + candy_machine_lamports
+    );
+    assert!(!account_exists(&mut context, &candy_machine).await);
+    assert!(!account_exists(&mut context, &config_lock).await);
+}
+
+#[tokio::test]
+async fn close_needs_the_candy_guard_closed() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let (config_lock, _) = find_config_lock_address(&candy_machine);
+    initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        CandyGuardData {
+            guards: vec![Guard::StartDate(StartDate { date: 0 })],
+            groups: vec![],
+            bot_tax: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let error = close_candy_machine(&mut context, &candy_machine, &authority, true, vec![])
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::CandyGuardNotClosed);
+
+    close_candy_guard(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+    close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        true,
+        vec![AccountMeta::new(config_lock, false)],
+    )
+    .await
+    .unwrap();
+    assert!(!account_exists(&mut context, &candy_machine).await);
+}
+
+#[tokio::test]
+async fn close_with_collection() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let (config_lock, _) = find_config_lock_address(&candy_machine);
+    let collection = create_collection(&mut context, &authority).await.unwrap();
+    set_collection(&mut context, &candy_machine, &authority, &collection)
+        .await
+        .unwrap();
+    let (collection_pda, _) = find_collection_pda_address(&candy_machine);
+    assert!(account_exists(&mut context, &collection_pda).await);
+
+    // the collection authority would stay approved otherwise
+    let error = close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        true,
+        vec![AccountMeta::new(config_lock, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MissingCollectionAccounts);
+
+    let mut remaining_accounts = vec![AccountMeta::new(config_lock, false)];
+    remaining_accounts.extend(collection_accounts(&candy_machine, &collection));
+    close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        true,
+        remaining_accounts,
+    )
+    .await
+    .unwrap();
+
+    assert!(!account_exists(&mut context, &candy_machine).await);
+    assert!(!account_exists(&mut context, &collection_pda).await);
+}
+
+#[tokio::test]
+async fn close_sold_out_candy_machine() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 2),
+    )
+    .await;
+    let (config_lock, _) = find_config_lock_address(&candy_machine);
+    for _ in 0..2 {
+        mint_nft(
+            &mut context,
+            &candy_machine,
+            &authority.pubkey(),
+            &minter,
+            MintArgs::default(),
+            vec![],
+        )
+        .await
+        .unwrap();
+    }
+
+    close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        false,
+        vec![AccountMeta::new(config_lock, false)],
+    )
+    .await
+    .unwrap();
+    assert!(!account_exists(&mut context, &candy_machine).await);
+}
+
+#[tokio::test]
+async fn close_after_settling_pending_mints() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 1),
+    )
+    .await;
+    let (config_lock, _) = find_config_lock_address(&candy_machine);
+    enable_commit_reveal(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+    let nft = create_nft_mint(&mut context, &minter).await.unwrap();
+    let (pending_mint, _) = find_pending_mint_address(&candy_machine, &nft.mint.pubkey());
+    let instruction = mint_nft_instruction(
+        &candy_machine,
+        &authority.pubkey(),
+        &minter.pubkey(),
+        &nft,
+        MintArgs::default(),
+        vec![AccountMeta::new(pending_mint, false)],
+    );
+    process_instructions(&mut context, &[instruction], &minter, &[])
+        .await
+        .unwrap();
+
+    // sold out, but settle_pending_mint still needs the candy machine
+    let error = close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        false,
+        vec![AccountMeta::new(config_lock, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::UnsettledMints);
+
+    let pending_mint_data: PendingMint = get_account_data(&mut context, &pending_mint).await;
+    warp_past_slot(&mut context, pending_mint_data.target_slot).await;
+    settle_pending_mint(&mut context, &candy_machine, &minter, &nft)
+        .await
+        .unwrap();
+    close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        false,
+        vec![AccountMeta::new(config_lock, false)],
+    )
+    .await
+    .unwrap();
+    assert!(!account_exists(&mut context, &candy_machine).await);
+}
+
+#[tokio::test]
+async fn close_after_revealing_every_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let mut data = candy_machine_data(&authority.pubkey(), 3);
+    data.hidden_settings = Some(hidden_settings(&reveal_settings()));
+    let candy_machine = create_candy_machine(&mut context, &authority, data).await;
+    let (config_lock, _) = find_config_lock_address(&candy_machine);
+    enable_reveal(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+    let nft = create_nft_mint(&mut context, &minter).await.unwrap();
+    let (mint_index, _) = find_mint_index_address(&candy_machine, &nft.mint.pubkey());
+    let instruction = mint_nft_instruction(
+        &candy_machine,
+        &authority.pubkey(),
+        &minter.pubkey(),
+        &nft,
+        MintArgs::default(),
+        vec![AccountMeta::new(mint_index, false)],
+    );
+    process_instructions(&mut context, &[instruction], &minter, &[])
+        .await
+        .unwrap();
+
+    let error = close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        true,
+        vec![AccountMeta::new(config_lock, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::UnsettledMints);
+
+    reveal(
+        &mut context,
+        &candy_machine,
+        &authority,
+        &nft,
+        reveal_settings(),
+        content_proof(0),
+    )
+    .await
+    .unwrap();
+    let mint_index_data: MintIndex = get_account_data(&mut context, &mint_index).await;
+    assert!(mint_index_data.revealed);
+    close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        true,
+        vec![AccountMeta::new(config_lock, false)],
+    )
+    .await
+    .unwrap();
+    assert!(!account_exists(&mut context, &candy_machine).await);
+}
+
+#[tokio::test]
+async fn close_with_mint_settings() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let candy_machine = create_unlocked_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let mint_settings = set_mint_settings(
+        &mut context,
+        &candy_machine,
+        &authority,
+        MintSettingsData::default(),
+    )
+    .await
+    .unwrap();
+    let config_lock = lock_config(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+
+    let error = close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        true,
+        vec![AccountMeta::new(config_lock, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MissingMintSettings);
+
+    close_candy_machine(
+        &mut context,
+        &candy_machine,
+        &authority,
+        true,
+        vec![
+            AccountMeta::new(config_lock, false),
+            AccountMeta::new(mint_settings, false),
+        ],
+    )
+    .await
+    .unwrap();
+    assert!(!account_exists(&mut context, &candy_machine).await);
+    assert!(!account_exists(&mut context, &config_lock).await);
+    assert!(!account_exists(&mut context, &mint_settings).await);
+}
@@ -0,0 +1,158 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_candy_machine::{ErrorCode, MintArgs, PendingMint};
+use solana_program_test::*;
+use solana_sdk::{instruction::AccountMeta, signature::Signer};
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn commit_reveal_needs_config_lines() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let mut data = candy_machine_data(&authority.pubkey(), 3);
+    data.hidden_settings = Some(hidden_settings(&reveal_settings()));
+    let candy_machine = create_candy_machine(&mut context, &authority, data).await;
+
+    let error = enable_commit_reveal(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::CommitRevealNeedsConfigLines);
+}
+
+#[tokio::test]
+async fn settle_pending_mint_after_the_target_slot() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    enable_commit_reveal(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MissingPendingMint);
+
+    let nft = create_nft_mint(&mut context, &minter).await.unwrap();
+    let (pending_mint, _) = find_pending_mint_address(&candy_machine, &nft.mint.pubkey());
+    let instruction = mint_nft_instruction(
+        &candy_machine,
+        &authority.pubkey(),
+        &minter.pubkey(),
+        &nft,
+        MintArgs::default(),
+        vec![AccountMeta::new(pending_mint, false)],
+    );
+    process_instructions(&mut context, &[instruction], &minter, &[])
+        .await
+        .unwrap();
+    let pending_mint_data: PendingMint = get_account_data(&mut context, &pending_mint).await;
+    assert_eq!(pending_mint_data.payer, minter.pubkey());
+    assert_eq!(pending_mint_data.mint_number, 0);
+    assert_eq!(pending_mint_data.new_update_authority, authority.pubkey());
+    // the candy machine creator keeps the update authority until the pending mint is settled
+    let (candy_machine_creator, _) = find_candy_machine_creator_address(&candy_machine);
+    let metadata = get_metadata(&mut context, &nft.metadata).await;
+    assert_eq!(metadata.update_authority, candy_machine_creator);
+
+    let error = settle_pending_mint(&mut context, &candy_machine, &minter, &nft)
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::PendingMintNotReady);
+
+    warp_past_slot(&mut context, pending_mint_data.target_slot).await;
+    settle_pending_mint(&mut context, &candy_machine, &minter, &nft)
+        .await
+        .unwrap();
+
+    let metadata = get_metadata(&mut context, &nft.metadata).await;
+    assert!(metadata.data.name.starts_with("Item #"));
+    assert_eq!(metadata.update_authority, authority.pubkey());
+    assert!(!account_exists(&mut context, &pending_mint).await);
+}
+
+#[tokio::test]
+async fn settle_pending_mints_in_mint_order() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    enable_commit_reveal(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+
+    let mut nfts = vec![];
+    for _ in 0..2 {
+        let nft = create_nft_mint(&mut context, &minter).await.unwrap();
+        let (pending_mint, _) = find_pending_mint_address(&candy_machine, &nft.mint.pubkey());
+        let instruction = mint_nft_instruction(
+            &candy_machine,
+            &authority.pubkey(),
+            &minter.pubkey(),
+            &nft,
+            MintArgs::default(),
+            vec![AccountMeta::new(pending_mint, false)],
+        );
+        process_instructions(&mut context, &[instruction], &minter, &[])
+            .await
+            .unwrap();
+        let pending_mint_data: PendingMint = get_account_data(&mut context, &pending_mint).await;
+        nfts.push((nft, pending_mint_data.target_slot));
+    }
+
+    // the second config line would depend on which mint took its line first
+    let error = settle_pending_mint(&mut context, &candy_machine, &minter, &nfts[1].0)
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::PendingMintOutOfOrder);
+
+    warp_past_slot(&mut context, nfts[1].1).await;
+
+    settle_pending_mint(&mut context, &candy_machine, &minter, &nfts[0].0)
+        .await
+        .unwrap();
+    settle_pending_mint(&mut context, &candy_machine, &minter, &nfts[1].0)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn commit_reveal_without_collection() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    enable_commit_reveal(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+    let collection = create_collection(&mut context, &authority).await.unwrap();
+
+    // the collection would be verified before the pending mint is settled
+    let error = set_collection(&mut context, &candy_machine, &authority, &collection)
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::CommitRevealWithCollection);
+}
@@ -0,0 +1,136 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use anchor_lang::solana_program::hash::hash;
+use mpl_candy_machine::{
+    CandyMachine, ConfigLock, ErrorCode, MintArgs, CONFIG_ARRAY_START, CONFIG_LINE_SIZE,
+};
+use solana_program_test::*;
+use solana_sdk::signature::Signer;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn mint_requires_locked_config() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_unlocked_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::ConfigNotLocked);
+}
+
+#[tokio::test]
+async fn lock_config_requires_every_config_line() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let candy_machine = initialize_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await
+    .unwrap();
+    add_config_lines(&mut context, &candy_machine, &authority, 0, 2)
+        .await
+        .unwrap();
+
+    let error = lock_config(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::ConfigLinesMissing);
+}
+
+#[tokio::test]
+async fn lock_config_freezes_config() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let data = candy_machine_data(&authority.pubkey(), 3);
+    let candy_machine = create_unlocked_candy_machine(&mut context, &authority, data.clone()).await;
+    let config_lock = lock_config(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(candy_machine)
+        .await
+        .unwrap()
+        .unwrap();
+    let config_lines_start = CONFIG_ARRAY_START + 4;
+    let config_lines = &account.data[config_lines_start..config_lines_start + 3 * CONFIG_LINE_SIZE];
+    let config_lock_data: ConfigLock = get_account_data(&mut context, &config_lock).await;
+    assert_eq!(config_lock_data.candy_machine, candy_machine);
+    assert_eq!(config_lock_data.hash, hash(config_lines).to_bytes());
+
+    let error = add_config_lines(&mut context, &candy_machine, &authority, 0, 1)
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::ConfigLocked);
+
+    let mut locked_change = data.clone();
+    locked_change.seller_fee_basis_points = 1000;
+    let error = update_candy_machine(&mut context, &candy_machine, &authority, locked_change)
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::ConfigLocked);
+
+    // the price is not part of what the lock freezes
+    let mut price_change = data;
+    price_change.price = 2 * PRICE;
+    update_candy_machine(&mut context, &candy_machine, &authority, price_change)
+        .await
+        .unwrap();
+    let candy_machine_data: CandyMachine = get_account_data(&mut context, &candy_machine).await;
+    assert_eq!(candy_machine_data.data.price, 2 * PRICE);
+}
+
+#[tokio::test]
+async fn mint_with_locked_config() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let wallet_lamports = get_lamports(&mut context, &authority.pubkey()).await;
+
+    let nft = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    let candy_machine_data: CandyMachine = get_account_data(&mut context, &candy_machine).await;
+    assert_eq!(candy_machine_data.items_redeemed, 1);
+    assert_eq!(
+        get_lamports(&mut context, &authority.pubkey()).await,
+        wallet_lamports + PRICE
+    );
+    let metadata = get_metadata(&mut context, &nft.metadata).await;
+    assert_eq!(metadata.update_authority, authority.pubkey());
+    assert!(metadata.primary_sale_happened);
+    assert!(metadata.data.name.starts_with("Item #"));
+}
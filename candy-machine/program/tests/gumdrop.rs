@@ -0,0 +1,404 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use std::{convert::TryInto, str::FromStr};
+
+use anchor_lang::{AnchorSerialize, InstructionData, ToAccountMetas};
+use mpl_candy_machine::{
+    guards::{BotTax, CandyGuardData, Guard, GuardGroup, StartDate},
+    CandyMachine, ErrorCode, MintArgs,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    keccak,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::TransactionError,
+    transport::TransportError,
+};
+use utils::setup_functions::*;
+
+const BOT_TAX: u64 = 10_000_000;
+// 2100-01-01
+const FUTURE: i64 = 4_102_444_800;
+// gumdrop DistributorNotCandyMachineAuthority, offset by anchor
+const DISTRIBUTOR_NOT_CANDY_MACHINE_AUTHORITY: u32 = 6007;
+
+fn gumdrop_id() -> Pubkey {
+    Pubkey::from_str("gdrpGjVffourzkdDRrQmySw4aTHr8a3xmQzzxSwFD1a").unwrap()
+}
+
+fn gumdrop_program_test() -> ProgramTest {
+    let mut program = candy_machine_program_test();
+    program.add_program("mpl_gumdrop", gumdrop_id(), None);
+    program
+}
+
+fn assert_gumdrop_error(error: TransportError, expected: u32) {
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        )) => assert_eq!(code, expected),
+        error => panic!("expected gumdrop error {}, got {:?}", expected, error),
+    }
+}
+
+/// Anchor discriminator of a gumdrop instruction.
+fn sighash(name: &str) -> Vec<u8> {
+    hash(format!("global:{}", name).as_bytes()).to_bytes()[..8].to_vec()
+}
+
+/// Merkle leaf allowing `claimant` to mint `amount` NFTs from `candy_machine`, which is also the
+/// root of a distributor with a single claim.
+fn claim_leaf(claimant: &Pubkey, candy_machine: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        &[0x00],
+        &0u64.to_le_bytes(),
+        claimant.as_ref(),
+        candy_machine.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .0
+}
+
+fn find_claim_count_address(distributor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"ClaimCount", &0u64.to_le_bytes(), distributor.as_ref()],
+        &gumdrop_id(),
+    )
+}
+
+/// Creates a distributor without a temporal signer.
+async fn create_distributor(context: &mut ProgramTestContext, root: [u8; 32]) -> Pubkey {
+    let base = Keypair::new();
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    let (distributor, bump) = Pubkey::find_program_address(
+        &[b"MerkleDistributor", base.pubkey().as_ref()],
+        &gumdrop_id(),
+    );
+    let mut data = sighash("new_distributor");
+    data.push(bump);
+    data.extend_from_slice(&root);
+    data.extend_from_slice(Pubkey::default().as_ref());
+    let instruction = Instruction {
+        program_id: gumdrop_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(base.pubkey(), true),
+            AccountMeta::new(distributor, false),
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    };
+
+    process_instructions(context, &[instruction], &payer, &[&base])
+        .await
+        .unwrap();
+    distributor
+}
+
+/// The claim of `nft` from `candy_machine` by `claimant`, who is its own temporal signer and
+/// may mint a single NFT.
+fn claim_candy_v2_instruction(
+    distributor: &Pubkey,
+    candy_machine: &Pubkey,
+    wallet: &Pubkey,
+    claimant: &Pubkey,
+    nft: &Nft,
+    args: MintArgs,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let (distributor_wallet, wallet_bump) =
+        Pubkey::find_program_address(&[b"Wallet", distributor.as_ref()], &gumdrop_id());
+    let (claim_count, claim_bump) = find_claim_count_address(distributor);
+    let (candy_machine_creator, creator_bump) = find_candy_machine_creator_address(candy_machine);
+    let mut data = sighash("claim_candy_v2");
+    data.push(wallet_bump);
+    data.push(claim_bump);
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&1u64.to_le_bytes());
+    data.extend_from_slice(claimant.as_ref());
+    // empty proof
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.push(creator_bump);
+    data.extend(args.try_to_vec().unwrap());
+    let mut accounts = vec![
+        AccountMeta::new(*distributor, false),
+        AccountMeta::new(distributor_wallet, false),
+        AccountMeta::new(claim_count, false),
+        AccountMeta::new_readonly(*claimant, true),
+        AccountMeta::new(*claimant, true),
+        AccountMeta::new(*candy_machine, false),
+        AccountMeta::new_readonly(candy_machine_creator, false),
+        AccountMeta::new(*wallet, false),
+        AccountMeta::new(nft.mint.pubkey(), false),
+        AccountMeta::new(nft.metadata, false),
+        AccountMeta::new(nft.master_edition, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(mpl_token_metadata::id(), false),
+        AccountMeta::new_readonly(mpl_candy_machine::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+    ];
+    accounts.extend(remaining_accounts);
+
+    Instruction {
+        program_id: gumdrop_id(),
+        accounts,
+        data,
+    }
+}
+
+/// Claims a new NFT from `candy_machine` with a single-claim distributor.
+async fn claim_candy_v2(
+    context: &mut ProgramTestContext,
+    distributor: &Pubkey,
+    candy_machine: &Pubkey,
+    wallet: &Pubkey,
+    claimant: &Keypair,
+    args: MintArgs,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Result<Nft, TransportError> {
+    let nft = create_nft_mint(context, claimant).await?;
+    let instruction = claim_candy_v2_instruction(
+        distributor,
+        candy_machine,
+        wallet,
+        &claimant.pubkey(),
+        &nft,
+        args,
+        remaining_accounts,
+    );
+
+    process_instructions(context, &[instruction], claimant, &[]).await?;
+    Ok(nft)
+}
+
+/// Makes the distributor wallet the candy machine authority, which pays for the claims.
+async fn hand_over_to_distributor(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+    distributor: &Pubkey,
+) {
+    let (distributor_wallet, _) =
+        Pubkey::find_program_address(&[b"Wallet", distributor.as_ref()], &gumdrop_id());
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::UpdateAuthority {
+            new_authority: Some(distributor_wallet),
+        }
+        .data(),
+        accounts: mpl_candy_machine::accounts::UpdateCandyMachine {
+            candy_machine: *candy_machine,
+            authority: authority.pubkey(),
+            wallet: authority.pubkey(),
+        }
+        .to_account_metas(None),
+    };
+    process_instructions(context, &[instruction], authority, &[])
+        .await
+        .unwrap();
+}
+
+async fn get_claim_count(context: &mut ProgramTestContext, distributor: &Pubkey) -> u64 {
+    let (claim_count, _) = find_claim_count_address(distributor);
+    let account = context
+        .banks_client
+        .get_account(claim_count)
+        .await
+        .expect("getting claim count")
+        .expect("empty claim count");
+    u64::from_le_bytes(account.data[8..16].try_into().unwrap())
+}
+
+#[tokio::test]
+async fn claim_with_guard_group() {
+    let mut context = gumdrop_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let claimant = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        CandyGuardData {
+            guards: vec![],
+            groups: vec![GuardGroup {
+                label: "gumdrop".to_string(),
+                guards: vec![Guard::StartDate(StartDate { date: 0 })],
+            }],
+            bot_tax: None,
+        },
+    )
+    .await
+    .unwrap();
+    let distributor = create_distributor(
+        &mut context,
+        claim_leaf(&claimant.pubkey(), &candy_machine, 1),
+    )
+    .await;
+
+    // a claim paid by another wallet than the candy machine authority would count the mint
+    // against that wallet instead of the claimant
+    let error = claim_candy_v2(
+        &mut context,
+        &distributor,
+        &candy_machine,
+        &authority.pubkey(),
+        &claimant,
+        MintArgs {
+            group: Some("gumdrop".to_string()),
+            allow_list_proof: None,
+        },
+        vec![AccountMeta::new_readonly(candy_guard, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_gumdrop_error(error, DISTRIBUTOR_NOT_CANDY_MACHINE_AUTHORITY);
+
+    hand_over_to_distributor(&mut context, &candy_machine, &authority, &distributor).await;
+    let error = claim_candy_v2(
+        &mut context,
+        &distributor,
+        &candy_machine,
+        &authority.pubkey(),
+        &claimant,
+        MintArgs::default(),
+        vec![AccountMeta::new_readonly(candy_guard, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::GuardGroupRequired);
+
+    let nft = claim_candy_v2(
+        &mut context,
+        &distributor,
+        &candy_machine,
+        &authority.pubkey(),
+        &claimant,
+        MintArgs {
+            group: Some("gumdrop".to_string()),
+            allow_list_proof: None,
+        },
+        vec![AccountMeta::new_readonly(candy_guard, false)],
+    )
+    .await
+    .unwrap();
+
+    assert!(account_exists(&mut context, &nft.metadata).await);
+    assert_eq!(get_claim_count(&mut context, &distributor).await, 1);
+    let candy_machine_data: CandyMachine = get_account_data(&mut context, &candy_machine).await;
+    assert_eq!(candy_machine_data.items_redeemed, 1);
+}
+
+#[tokio::test]
+async fn taxed_claim_is_not_counted() {
+    let mut context = gumdrop_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let claimant = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        CandyGuardData {
+            guards: vec![Guard::StartDate(StartDate { date: FUTURE })],
+            groups: vec![],
+            bot_tax: Some(BotTax { lamports: BOT_TAX }),
+        },
+    )
+    .await
+    .unwrap();
+    let distributor = create_distributor(
+        &mut context,
+        claim_leaf(&claimant.pubkey(), &candy_machine, 1),
+    )
+    .await;
+    hand_over_to_distributor(&mut context, &candy_machine, &authority, &distributor).await;
+    let candy_machine_lamports = get_lamports(&mut context, &candy_machine).await;
+
+    // the claim succeeds without minting, twice since the first one did not use up the claim
+    for taxed_mints in 1..=2 {
+        let nft = claim_candy_v2(
+            &mut context,
+            &distributor,
+            &candy_machine,
+            &authority.pubkey(),
+            &claimant,
+            MintArgs::default(),
+            vec![AccountMeta::new_readonly(candy_guard, false)],
+        )
+        .await
+        .unwrap();
+
+        assert!(!account_exists(&mut context, &nft.metadata).await);
+        assert_eq!(
+            get_lamports(&mut context, &candy_machine).await,
+            candy_machine_lamports + taxed_mints * BOT_TAX
+        );
+        assert_eq!(get_claim_count(&mut context, &distributor).await, 0);
+    }
+}
+
+#[tokio::test]
+async fn claim_from_distributor_authority_is_guarded() {
+    let mut context = gumdrop_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let claimant = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        CandyGuardData {
+            guards: vec![Guard::StartDate(StartDate { date: FUTURE })],
+            groups: vec![],
+            bot_tax: None,
+        },
+    )
+    .await
+    .unwrap();
+    let distributor = create_distributor(
+        &mut context,
+        claim_leaf(&claimant.pubkey(), &candy_machine, 1),
+    )
+    .await;
+
+    hand_over_to_distributor(&mut context, &candy_machine, &authority, &distributor).await;
+
+    let error = claim_candy_v2(
+        &mut context,
+        &distributor,
+        &candy_machine,
+        &authority.pubkey(),
+        &claimant,
+        MintArgs::default(),
+        vec![AccountMeta::new_readonly(candy_guard, false)],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::CandyMachineNotLive);
+}
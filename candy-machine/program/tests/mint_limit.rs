@@ -0,0 +1,190 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_candy_machine::{
+    guards::{CandyGuardData, Guard, GuardGroup, MintCounter, MintLimit},
+    ErrorCode, MintArgs,
+};
+use mpl_token_metadata::error::MetadataError;
+use solana_program_test::*;
+use solana_sdk::{instruction::AccountMeta, signature::Signer};
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn mint_limit_guard() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let other_minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 5),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        CandyGuardData {
+            guards: vec![Guard::MintLimit(MintLimit { id: 1, limit: 2 })],
+            groups: vec![],
+            bot_tax: None,
+        },
+    )
+    .await
+    .unwrap();
+    let (counter, _) = find_mint_limit_counter_address(1, &minter.pubkey(), &candy_machine);
+    let remaining_accounts = vec![
+        AccountMeta::new_readonly(candy_guard, false),
+        AccountMeta::new(counter, false),
+    ];
+
+    for _ in 0..2 {
+        mint_nft(
+            &mut context,
+            &candy_machine,
+            &authority.pubkey(),
+            &minter,
+            MintArgs::default(),
+            remaining_accounts.clone(),
+        )
+        .await
+        .unwrap();
+    }
+    let counter_data: MintCounter = get_account_data(&mut context, &counter).await;
+    assert_eq!(counter_data.count, 2);
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        remaining_accounts,
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MintLimitReached);
+
+    // the limit is per wallet
+    let (other_counter, _) =
+        find_mint_limit_counter_address(1, &other_minter.pubkey(), &candy_machine);
+    mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &other_minter,
+        MintArgs::default(),
+        vec![
+            AccountMeta::new_readonly(candy_guard, false),
+            AccountMeta::new(other_counter, false),
+        ],
+    )
+    .await
+    .unwrap();
+
+    // the counter of another wallet does not count
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![
+            AccountMeta::new_readonly(candy_guard, false),
+            AccountMeta::new(other_counter, false),
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert_metadata_error(error, MetadataError::DerivedKeyInvalid);
+}
+
+#[tokio::test]
+async fn groups_share_a_mint_limit_id() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 5),
+    )
+    .await;
+    let candy_guard = initialize_candy_guard(
+        &mut context,
+        &candy_machine,
+        &authority,
+        CandyGuardData {
+            guards: vec![],
+            groups: vec![
+                GuardGroup {
+                    label: "early".to_string(),
+                    guards: vec![Guard::MintLimit(MintLimit { id: 1, limit: 1 })],
+                },
+                GuardGroup {
+                    label: "public".to_string(),
+                    guards: vec![Guard::MintLimit(MintLimit { id: 1, limit: 2 })],
+                },
+            ],
+            bot_tax: None,
+        },
+    )
+    .await
+    .unwrap();
+    let (counter, _) = find_mint_limit_counter_address(1, &minter.pubkey(), &candy_machine);
+    let remaining_accounts = vec![
+        AccountMeta::new_readonly(candy_guard, false),
+        AccountMeta::new(counter, false),
+    ];
+    let args = |label: &str| MintArgs {
+        group: Some(label.to_string()),
+        allow_list_proof: None,
+    };
+
+    mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        args("early"),
+        remaining_accounts.clone(),
+    )
+    .await
+    .unwrap();
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        args("early"),
+        remaining_accounts.clone(),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MintLimitReached);
+
+    // the early mint counts against the public limit
+    mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        args("public"),
+        remaining_accounts.clone(),
+    )
+    .await
+    .unwrap();
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        args("public"),
+        remaining_accounts,
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MintLimitReached);
+}
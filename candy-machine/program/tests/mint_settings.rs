@@ -0,0 +1,172 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_candy_machine::{Creator, ErrorCode, MintArgs, MintSettingsData, UseMethod, Uses};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::AccountMeta,
+    signature::{Keypair, Signer},
+};
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn invalid_mint_settings() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let candy_machine = create_unlocked_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+
+    let error = set_mint_settings(
+        &mut context,
+        &candy_machine,
+        &authority,
+        MintSettingsData {
+            creators: Some(vec![Creator {
+                address: authority.pubkey(),
+                verified: false,
+                share: 50,
+            }]),
+            uses: None,
+        },
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::InvalidCreatorShares);
+
+    let error = set_mint_settings(
+        &mut context,
+        &candy_machine,
+        &authority,
+        MintSettingsData {
+            creators: None,
+            uses: Some(Uses {
+                use_method: UseMethod::Multiple,
+                remaining: 5,
+                total: 3,
+            }),
+        },
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::InvalidUses);
+
+    lock_config(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+    let error = set_mint_settings(
+        &mut context,
+        &candy_machine,
+        &authority,
+        MintSettingsData::default(),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::ConfigLocked);
+}
+
+#[tokio::test]
+async fn mint_with_mint_settings() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let artist = Keypair::new();
+    let candy_machine = create_unlocked_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+    let mint_settings = set_mint_settings(
+        &mut context,
+        &candy_machine,
+        &authority,
+        MintSettingsData {
+            creators: Some(vec![
+                Creator {
+                    address: authority.pubkey(),
+                    verified: false,
+                    share: 60,
+                },
+                Creator {
+                    address: artist.pubkey(),
+                    verified: false,
+                    share: 40,
+                },
+            ]),
+            uses: Some(Uses {
+                use_method: UseMethod::Multiple,
+                remaining: 3,
+                total: 3,
+            }),
+        },
+    )
+    .await
+    .unwrap();
+    lock_config(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MissingMintSettings);
+
+    let nft = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![AccountMeta::new_readonly(mint_settings, false)],
+    )
+    .await
+    .unwrap();
+
+    let metadata = get_metadata(&mut context, &nft.metadata).await;
+    let creators = metadata.data.creators.unwrap();
+    // the candy machine creator comes first, with no share
+    assert_eq!(creators.len(), 3);
+    assert_eq!(creators[0].share, 0);
+    assert_eq!(creators[1].address, authority.pubkey());
+    assert_eq!(creators[1].share, 60);
+    assert_eq!(creators[2].address, artist.pubkey());
+    assert_eq!(creators[2].share, 40);
+    let uses = metadata.uses.unwrap();
+    assert_eq!(uses.remaining, 3);
+    assert_eq!(uses.total, 3);
+}
+
+#[tokio::test]
+async fn free_mint_is_not_a_primary_sale() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let mut data = candy_machine_data(&authority.pubkey(), 3);
+    data.price = 0;
+    let candy_machine = create_candy_machine(&mut context, &authority, data).await;
+
+    let nft = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    let metadata = get_metadata(&mut context, &nft.metadata).await;
+    assert!(!metadata.primary_sale_happened);
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_candy_machine::{ErrorCode, MintArgs, MintIndex, RevealSettings};
+use solana_program_test::*;
+use solana_sdk::{instruction::AccountMeta, signature::Signer};
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn enable_reveal_needs_locked_hidden_settings() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let candy_machine = create_candy_machine(
+        &mut context,
+        &authority,
+        candy_machine_data(&authority.pubkey(), 3),
+    )
+    .await;
+
+    let error = enable_reveal(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::RevealNeedsHiddenSettings);
+
+    let mut data = candy_machine_data(&authority.pubkey(), 3);
+    data.hidden_settings = Some(hidden_settings(&reveal_settings()));
+    let candy_machine = create_unlocked_candy_machine(&mut context, &authority, data).await;
+
+    // the hidden settings hash could change after the mint otherwise
+    let error = enable_reveal(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::RevealNeedsConfigLock);
+}
+
+#[tokio::test]
+async fn enable_reveal_needs_mutable_metadata() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    for (is_mutable, retain_authority) in [(false, true), (true, false)] {
+        let mut data = candy_machine_data(&authority.pubkey(), 3);
+        data.hidden_settings = Some(hidden_settings(&reveal_settings()));
+        data.is_mutable = is_mutable;
+        data.retain_authority = retain_authority;
+        let candy_machine = create_candy_machine(&mut context, &authority, data).await;
+
+        let error = enable_reveal(&mut context, &candy_machine, &authority)
+            .await
+            .unwrap_err();
+        assert_candy_error(error, ErrorCode::RevealNeedsMutableMetadata);
+    }
+}
+
+#[tokio::test]
+async fn reveal_minted_nft() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let mut data = candy_machine_data(&authority.pubkey(), 3);
+    data.hidden_settings = Some(hidden_settings(&reveal_settings()));
+    let candy_machine = create_candy_machine(&mut context, &authority, data).await;
+    enable_reveal(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap();
+
+    let error = mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![],
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::MissingMintIndex);
+
+    let nft = create_nft_mint(&mut context, &minter).await.unwrap();
+    let (mint_index, _) = find_mint_index_address(&candy_machine, &nft.mint.pubkey());
+    let instruction = mint_nft_instruction(
+        &candy_machine,
+        &authority.pubkey(),
+        &minter.pubkey(),
+        &nft,
+        MintArgs::default(),
+        vec![AccountMeta::new(mint_index, false)],
+    );
+    process_instructions(&mut context, &[instruction], &minter, &[])
+        .await
+        .unwrap();
+    let mint_index_data: MintIndex = get_account_data(&mut context, &mint_index).await;
+    assert_eq!(mint_index_data.index, 0);
+    let metadata = get_metadata(&mut context, &nft.metadata).await;
+    assert!(metadata
+        .data
+        .uri
+        .starts_with("https://example.com/hidden.json"));
+
+    let error = reveal(
+        &mut context,
+        &candy_machine,
+        &authority,
+        &nft,
+        RevealSettings {
+            uri_prefix: "https://example.org/".to_string(),
+            ..reveal_settings()
+        },
+        content_proof(0),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::RevealSettingsHashMismatch);
+
+    // the content of another index does not prove the content at index 0
+    let (_, proof) = content_proof(0);
+    let error = reveal(
+        &mut context,
+        &candy_machine,
+        &authority,
+        &nft,
+        reveal_settings(),
+        (content_hash(1), proof),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::InvalidRevealProof);
+    let error = reveal(
+        &mut context,
+        &candy_machine,
+        &authority,
+        &nft,
+        reveal_settings(),
+        content_proof(1),
+    )
+    .await
+    .unwrap_err();
+    assert_candy_error(error, ErrorCode::InvalidRevealProof);
+
+    reveal(
+        &mut context,
+        &candy_machine,
+        &authority,
+        &nft,
+        reveal_settings(),
+        content_proof(0),
+    )
+    .await
+    .unwrap();
+    let metadata = get_metadata(&mut context, &nft.metadata).await;
+    assert!(metadata.data.uri.starts_with("https://example.com/0.json"));
+    let mint_index_data: MintIndex = get_account_data(&mut context, &mint_index).await;
+    assert!(mint_index_data.revealed);
+    assert_eq!(mint_index_data.content_hash, content_hash(0));
+}
+
+#[tokio::test]
+async fn enable_reveal_before_the_first_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let minter = funded_keypair(&mut context).await;
+    let mut data = candy_machine_data(&authority.pubkey(), 3);
+    data.hidden_settings = Some(hidden_settings(&reveal_settings()));
+    let candy_machine = create_candy_machine(&mut context, &authority, data).await;
+    mint_nft(
+        &mut context,
+        &candy_machine,
+        &authority.pubkey(),
+        &minter,
+        MintArgs::default(),
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    let error = enable_reveal(&mut context, &candy_machine, &authority)
+        .await
+        .unwrap_err();
+    assert_candy_error(error, ErrorCode::RevealEnabledAfterMint);
+}
@@ -0,0 +1,950 @@
+use anchor_lang::{AccountDeserialize, AnchorSerialize, InstructionData, ToAccountMetas};
+use mpl_candy_machine::{
+    guards::{CandyGuardData, ALLOW_LIST, CANDY_GUARD, MINT_LIMIT},
+    CandyMachineData, ConfigLine, Creator, ErrorCode, HiddenSettings, MintArgs, MintSettingsData,
+    RevealSettings, CONFIG_ARRAY_START, CONFIG_LINE_SIZE, CONFIG_LOCK, MINT_INDEX, MINT_SETTINGS,
+    PENDING_MINT,
+};
+use mpl_token_metadata::{
+    error::MetadataError,
+    pda::{find_collection_authority_account, find_master_edition_account, find_metadata_account},
+    state::Metadata,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    borsh::try_from_slice_unchecked,
+    clock::Clock,
+    hash::hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    keccak,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+
+pub const PRICE: u64 = 100_000_000;
+
+pub fn candy_machine_program_test() -> ProgramTest {
+    let mut program = ProgramTest::new("mpl_candy_machine", mpl_candy_machine::id(), None);
+    program.add_program("mpl_token_metadata", mpl_token_metadata::id(), None);
+    program
+}
+
+/// Asserts that a transaction failed with a candy machine error.
+pub fn assert_candy_error(error: TransportError, expected: ErrorCode) {
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        )) => {
+            // anchor offsets the custom error codes by 6000
+            assert_eq!(code, expected as u32 + 6000, "expected {}", expected)
+        }
+        error => panic!("expected {}, got {:?}", expected, error),
+    }
+}
+
+/// Asserts that a transaction failed with a token metadata error, which the candy machine also
+/// raises from the token metadata helpers it uses.
+pub fn assert_metadata_error(error: TransportError, expected: MetadataError) {
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        )) => assert_eq!(code, expected as u32),
+        error => panic!("expected {:?}, got {:?}", expected, error),
+    }
+}
+
+pub async fn process_instructions(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> Result<(), TransportError> {
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+/// Warps one slot at a time, so the SlotHashes sysvar has every slot, until `slot` has passed.
+pub async fn warp_past_slot(context: &mut ProgramTestContext, slot: u64) {
+    loop {
+        let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+        if clock.slot > slot {
+            break;
+        }
+        context.warp_to_slot(clock.slot + 1).unwrap();
+    }
+    // a transaction that failed before the warp can then be sent again
+    context.last_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+}
+
+pub async fn funded_keypair(context: &mut ProgramTestContext) -> Keypair {
+    let keypair = Keypair::new();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            &keypair.pubkey(),
+            10_000_000_000,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+    keypair
+}
+
+pub async fn get_lamports(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    context
+        .banks_client
+        .get_balance(*address)
+        .await
+        .expect("getting balance")
+}
+
+pub async fn account_exists(context: &mut ProgramTestContext, address: &Pubkey) -> bool {
+    context
+        .banks_client
+        .get_account(*address)
+        .await
+        .expect("getting account")
+        .is_some()
+}
+
+/// Reads an anchor account of the candy machine program.
+pub async fn get_account_data<T: AccountDeserialize>(
+    context: &mut ProgramTestContext,
+    address: &Pubkey,
+) -> T {
+    let account = context
+        .banks_client
+        .get_account(*address)
+        .await
+        .expect("getting account")
+        .expect("empty account");
+    T::try_deserialize(&mut &account.data[..]).unwrap()
+}
+
+pub async fn get_metadata(context: &mut ProgramTestContext, address: &Pubkey) -> Metadata {
+    let account = context
+        .banks_client
+        .get_account(*address)
+        .await
+        .expect("getting metadata")
+        .expect("empty metadata");
+    try_from_slice_unchecked(&account.data).unwrap()
+}
+
+pub fn find_candy_machine_creator_address(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"candy_machine", candy_machine.as_ref()],
+        &mpl_candy_machine::id(),
+    )
+}
+
+pub fn find_collection_pda_address(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"collection", candy_machine.as_ref()],
+        &mpl_candy_machine::id(),
+    )
+}
+
+pub fn find_config_lock_address(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CONFIG_LOCK.as_bytes(), candy_machine.as_ref()],
+        &mpl_candy_machine::id(),
+    )
+}
+
+pub fn find_mint_settings_address(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MINT_SETTINGS.as_bytes(), candy_machine.as_ref()],
+        &mpl_candy_machine::id(),
+    )
+}
+
+pub fn find_candy_guard_address(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CANDY_GUARD.as_bytes(), candy_machine.as_ref()],
+        &mpl_candy_machine::id(),
+    )
+}
+
+pub fn find_mint_index_address(candy_machine: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MINT_INDEX.as_bytes(), candy_machine.as_ref(), mint.as_ref()],
+        &mpl_candy_machine::id(),
+    )
+}
+
+pub fn find_pending_mint_address(candy_machine: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PENDING_MINT.as_bytes(),
+            candy_machine.as_ref(),
+            mint.as_ref(),
+        ],
+        &mpl_candy_machine::id(),
+    )
+}
+
+pub fn find_mint_limit_counter_address(
+    id: u8,
+    minter: &Pubkey,
+    candy_machine: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MINT_LIMIT.as_bytes(),
+            &[id],
+            minter.as_ref(),
+            candy_machine.as_ref(),
+        ],
+        &mpl_candy_machine::id(),
+    )
+}
+
+pub fn find_allow_list_counter_address(
+    merkle_root: &[u8; 32],
+    minter: &Pubkey,
+    candy_machine: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ALLOW_LIST.as_bytes(),
+            merkle_root,
+            minter.as_ref(),
+            candy_machine.as_ref(),
+        ],
+        &mpl_candy_machine::id(),
+    )
+}
+
+/// Candy machine data paid in SOL, live since the epoch, with `authority` as its only creator.
+pub fn candy_machine_data(authority: &Pubkey, items_available: u64) -> CandyMachineData {
+    CandyMachineData {
+        uuid: "ABCDEF".to_string(),
+        price: PRICE,
+        symbol: "CANDY".to_string(),
+        seller_fee_basis_points: 500,
+        max_supply: 0,
+        is_mutable: true,
+        retain_authority: true,
+        go_live_date: Some(0),
+        end_settings: None,
+        creators: vec![Creator {
+            address: *authority,
+            verified: false,
+            share: 100,
+        }],
+        hidden_settings: None,
+        whitelist_mint_settings: None,
+        items_available,
+        gatekeeper: None,
+    }
+}
+
+/// Number of NFTs in the content tree of [`reveal_settings`].
+pub const REVEAL_ITEMS: u64 = 3;
+
+/// Hash of the revealed content of the NFT at `index`.
+pub fn content_hash(index: u64) -> [u8; 32] {
+    hash(format!("content {}", index).as_bytes()).to_bytes()
+}
+
+/// Levels of the content tree of [`reveal_settings`], from the leaves to the root.
+fn content_tree() -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![(0..REVEAL_ITEMS)
+        .map(|index| keccak::hashv(&[&[0x00], &index.to_le_bytes(), &content_hash(index)]).0)
+        .collect::<Vec<_>>()];
+    while levels.last().unwrap().len() > 1 {
+        let level = levels.last().unwrap();
+        let parents = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    let (left, right) = if left <= right {
+                        (left, right)
+                    } else {
+                        (right, left)
+                    };
+                    keccak::hashv(&[&[0x01], left, right]).0
+                }
+                [node] => *node,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(parents);
+    }
+    levels
+}
+
+/// Content hash of the NFT at `index` and its proof in the content tree of [`reveal_settings`].
+pub fn content_proof(index: u64) -> ([u8; 32], Vec<[u8; 32]>) {
+    let levels = content_tree();
+    let mut position = index as usize;
+    let mut proof = vec![];
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(position ^ 1) {
+            proof.push(*sibling);
+        }
+        position /= 2;
+    }
+    (content_hash(index), proof)
+}
+
+pub fn reveal_settings() -> RevealSettings {
+    RevealSettings {
+        uri_prefix: "https://example.com/".to_string(),
+        uri_suffix: ".json".to_string(),
+        content_root: content_tree().last().unwrap()[0],
+    }
+}
+
+/// Hidden settings committing to `reveal_settings`.
+pub fn hidden_settings(reveal_settings: &RevealSettings) -> HiddenSettings {
+    HiddenSettings {
+        name: "Hidden".to_string(),
+        uri: "https://example.com/hidden.json".to_string(),
+        hash: hash(&reveal_settings.try_to_vec().unwrap()).to_bytes(),
+    }
+}
+
+pub fn config_line(index: u32) -> ConfigLine {
+    ConfigLine {
+        name: format!("Item #{}", index),
+        uri: format!("https://example.com/{}.json", index),
+    }
+}
+
+fn candy_machine_space(data: &CandyMachineData) -> usize {
+    if data.hidden_settings.is_some() {
+        CONFIG_ARRAY_START
+    } else {
+        let items_available = data.items_available as usize;
+        CONFIG_ARRAY_START
+            + 4
+            + items_available * CONFIG_LINE_SIZE
+            + 8
+            + 2 * (items_available / 8 + 1)
+    }
+}
+
+pub async fn initialize_candy_machine(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    data: CandyMachineData,
+) -> Result<Pubkey, TransportError> {
+    let candy_machine = Keypair::new();
+    let space = candy_machine_space(&data);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let accounts = mpl_candy_machine::accounts::InitializeCandyMachine {
+        candy_machine: candy_machine.pubkey(),
+        wallet: authority.pubkey(),
+        authority: authority.pubkey(),
+        payer: authority.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    let instructions = [
+        system_instruction::create_account(
+            &authority.pubkey(),
+            &candy_machine.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &mpl_candy_machine::id(),
+        ),
+        Instruction {
+            program_id: mpl_candy_machine::id(),
+            data: mpl_candy_machine::instruction::InitializeCandyMachine { data }.data(),
+            accounts,
+        },
+    ];
+
+    process_instructions(context, &instructions, authority, &[&candy_machine]).await?;
+    Ok(candy_machine.pubkey())
+}
+
+pub async fn update_candy_machine(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+    data: CandyMachineData,
+) -> Result<(), TransportError> {
+    let accounts = mpl_candy_machine::accounts::UpdateCandyMachine {
+        candy_machine: *candy_machine,
+        authority: authority.pubkey(),
+        wallet: authority.pubkey(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::UpdateCandyMachine { data }.data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await
+}
+
+/// Adds `count` config lines, made by [`config_line`], from `index` on.
+pub async fn add_config_lines(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+    index: u32,
+    count: u32,
+) -> Result<(), TransportError> {
+    let accounts = mpl_candy_machine::accounts::AddConfigLines {
+        candy_machine: *candy_machine,
+        authority: authority.pubkey(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::AddConfigLines {
+            index,
+            config_lines: (index..index + count).map(config_line).collect(),
+        }
+        .data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await
+}
+
+pub async fn lock_config(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+) -> Result<Pubkey, TransportError> {
+    let (config_lock, _) = find_config_lock_address(candy_machine);
+    let accounts = mpl_candy_machine::accounts::LockConfig {
+        candy_machine: *candy_machine,
+        config_lock,
+        authority: authority.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::LockConfig {}.data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await?;
+    Ok(config_lock)
+}
+
+/// Creates a candy machine with every config line added, unless it has hidden settings.
+pub async fn create_unlocked_candy_machine(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    data: CandyMachineData,
+) -> Pubkey {
+    let has_config_lines = data.hidden_settings.is_none();
+    let items_available = data.items_available as u32;
+    let candy_machine = initialize_candy_machine(context, authority, data)
+        .await
+        .unwrap();
+    if has_config_lines {
+        add_config_lines(context, &candy_machine, authority, 0, items_available)
+            .await
+            .unwrap();
+    }
+    candy_machine
+}
+
+/// Creates a candy machine ready to mint.
+pub async fn create_candy_machine(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    data: CandyMachineData,
+) -> Pubkey {
+    let candy_machine = create_unlocked_candy_machine(context, authority, data).await;
+    lock_config(context, &candy_machine, authority)
+        .await
+        .unwrap();
+    candy_machine
+}
+
+pub async fn initialize_candy_guard(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+    data: CandyGuardData,
+) -> Result<Pubkey, TransportError> {
+    let (candy_guard, _) = find_candy_guard_address(candy_machine);
+    let accounts = mpl_candy_machine::accounts::InitializeCandyGuard {
+        candy_machine: *candy_machine,
+        candy_guard,
+        authority: authority.pubkey(),
+        payer: authority.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::InitializeCandyGuard { data }.data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await?;
+    Ok(candy_guard)
+}
+
+pub async fn close_candy_guard(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+) -> Result<(), TransportError> {
+    let (candy_guard, _) = find_candy_guard_address(candy_machine);
+    let accounts = mpl_candy_machine::accounts::CloseCandyGuard {
+        candy_machine: *candy_machine,
+        candy_guard,
+        authority: authority.pubkey(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::CloseCandyGuard {}.data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await
+}
+
+/// Mint, metadata, master edition and token account of an NFT.
+#[derive(Debug)]
+pub struct Nft {
+    pub mint: Keypair,
+    pub metadata: Pubkey,
+    pub master_edition: Pubkey,
+    pub token: Pubkey,
+}
+
+/// Creates a mint with `owner` as its authority and mints its only token to `owner`, the way
+/// a wallet prepares a candy machine mint.
+pub async fn create_nft_mint(
+    context: &mut ProgramTestContext,
+    owner: &Keypair,
+) -> Result<Nft, TransportError> {
+    let mint = Keypair::new();
+    let (metadata, _) = find_metadata_account(&mint.pubkey());
+    let (master_edition, _) = find_master_edition_account(&mint.pubkey());
+    let token = get_associated_token_address(&owner.pubkey(), &mint.pubkey());
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &owner.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &owner.pubkey(),
+            Some(&owner.pubkey()),
+            0,
+        )
+        .unwrap(),
+        create_associated_token_account(&owner.pubkey(), &owner.pubkey(), &mint.pubkey()),
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &token,
+            &owner.pubkey(),
+            &[],
+            1,
+        )
+        .unwrap(),
+    ];
+
+    process_instructions(context, &instructions, owner, &[&mint]).await?;
+    Ok(Nft {
+        mint,
+        metadata,
+        master_edition,
+        token,
+    })
+}
+
+/// Creates a fungible mint and gives `amount` of it to the associated token account of `owner`.
+pub async fn create_token(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    amount: u64,
+) -> Result<Pubkey, TransportError> {
+    let mint = Keypair::new();
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    let token = get_associated_token_address(owner, &mint.pubkey());
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &payer.pubkey(),
+            None,
+            0,
+        )
+        .unwrap(),
+        create_associated_token_account(&payer.pubkey(), owner, &mint.pubkey()),
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &token,
+            &payer.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap(),
+    ];
+
+    process_instructions(context, &instructions, &payer, &[&mint]).await?;
+    Ok(mint.pubkey())
+}
+
+/// Creates a collection NFT with `authority` as its update authority.
+pub async fn create_collection(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+) -> Result<Nft, TransportError> {
+    let collection = create_nft_mint(context, authority).await?;
+    let instructions = [
+        mpl_token_metadata::instruction::create_metadata_accounts_v2(
+            mpl_token_metadata::id(),
+            collection.metadata,
+            collection.mint.pubkey(),
+            authority.pubkey(),
+            authority.pubkey(),
+            authority.pubkey(),
+            "Collection".to_string(),
+            "COL".to_string(),
+            "https://example.com/collection.json".to_string(),
+            None,
+            0,
+            true,
+            true,
+            None,
+            None,
+        ),
+        mpl_token_metadata::instruction::create_master_edition_v3(
+            mpl_token_metadata::id(),
+            collection.master_edition,
+            collection.mint.pubkey(),
+            authority.pubkey(),
+            authority.pubkey(),
+            collection.metadata,
+            authority.pubkey(),
+            Some(0),
+        ),
+    ];
+
+    process_instructions(context, &instructions, authority, &[]).await?;
+    Ok(collection)
+}
+
+/// Makes the candy machine verify `collection` on every mint.
+pub async fn set_collection(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+    collection: &Nft,
+) -> Result<(), TransportError> {
+    let (collection_pda, _) = find_collection_pda_address(candy_machine);
+    let (collection_authority_record, _) =
+        find_collection_authority_account(&collection.mint.pubkey(), &collection_pda);
+    let accounts = mpl_candy_machine::accounts::SetCollection {
+        candy_machine: *candy_machine,
+        authority: authority.pubkey(),
+        collection_pda,
+        payer: authority.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        metadata: collection.metadata,
+        mint: collection.mint.pubkey(),
+        edition: collection.master_edition,
+        collection_authority_record,
+        token_metadata_program: mpl_token_metadata::id(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::SetCollection {}.data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await
+}
+
+/// The set_collection_during_mint instruction following the mint of `nft` by `minter`.
+pub fn set_collection_during_mint_instruction(
+    candy_machine: &Pubkey,
+    authority: &Pubkey,
+    minter: &Pubkey,
+    nft: &Nft,
+    collection: &Nft,
+) -> Instruction {
+    let (collection_pda, _) = find_collection_pda_address(candy_machine);
+    let (collection_authority_record, _) =
+        find_collection_authority_account(&collection.mint.pubkey(), &collection_pda);
+    let accounts = mpl_candy_machine::accounts::SetCollectionDuringMint {
+        candy_machine: *candy_machine,
+        metadata: nft.metadata,
+        payer: *minter,
+        collection_pda,
+        token_metadata_program: mpl_token_metadata::id(),
+        instructions: sysvar::instructions::id(),
+        collection_mint: collection.mint.pubkey(),
+        collection_metadata: collection.metadata,
+        collection_master_edition: collection.master_edition,
+        authority: *authority,
+        collection_authority_record,
+    }
+    .to_account_metas(None);
+
+    Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::SetCollectionDuringMint {}.data(),
+        accounts,
+    }
+}
+
+/// The mint of `nft` by `minter`, which pays and holds its token. `remaining_accounts` are
+/// passed in the order the mint reads them.
+pub fn mint_nft_instruction(
+    candy_machine: &Pubkey,
+    wallet: &Pubkey,
+    minter: &Pubkey,
+    nft: &Nft,
+    args: MintArgs,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let (candy_machine_creator, creator_bump) = find_candy_machine_creator_address(candy_machine);
+    let mut accounts = mpl_candy_machine::accounts::MintNFT {
+        candy_machine: *candy_machine,
+        candy_machine_creator,
+        payer: *minter,
+        wallet: *wallet,
+        metadata: nft.metadata,
+        mint: nft.mint.pubkey(),
+        mint_authority: *minter,
+        update_authority: *minter,
+        master_edition: nft.master_edition,
+        token_metadata_program: mpl_token_metadata::id(),
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        clock: sysvar::clock::id(),
+        recent_blockhashes: sysvar::slot_hashes::id(),
+        instruction_sysvar_account: sysvar::instructions::id(),
+    }
+    .to_account_metas(None);
+    accounts.extend(remaining_accounts);
+
+    Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::MintNftV2 { creator_bump, args }.data(),
+        accounts,
+    }
+}
+
+/// Mints an NFT from a candy machine whose wallet is `wallet`.
+pub async fn mint_nft(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    wallet: &Pubkey,
+    minter: &Keypair,
+    args: MintArgs,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Result<Nft, TransportError> {
+    let nft = create_nft_mint(context, minter).await?;
+    let instruction = mint_nft_instruction(
+        candy_machine,
+        wallet,
+        &minter.pubkey(),
+        &nft,
+        args,
+        remaining_accounts,
+    );
+
+    process_instructions(context, &[instruction], minter, &[]).await?;
+    Ok(nft)
+}
+
+pub async fn set_mint_settings(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+    data: MintSettingsData,
+) -> Result<Pubkey, TransportError> {
+    let (mint_settings, _) = find_mint_settings_address(candy_machine);
+    let accounts = mpl_candy_machine::accounts::SetMintSettings {
+        candy_machine: *candy_machine,
+        mint_settings,
+        authority: authority.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::SetMintSettings { data }.data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await?;
+    Ok(mint_settings)
+}
+
+pub async fn enable_reveal(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+) -> Result<(), TransportError> {
+    let accounts = mpl_candy_machine::accounts::EnableReveal {
+        candy_machine: *candy_machine,
+        authority: authority.pubkey(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::EnableReveal {}.data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await
+}
+
+pub async fn reveal(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+    nft: &Nft,
+    reveal_settings: RevealSettings,
+    (content_hash, proof): ([u8; 32], Vec<[u8; 32]>),
+) -> Result<(), TransportError> {
+    let (mint_index, _) = find_mint_index_address(candy_machine, &nft.mint.pubkey());
+    let accounts = mpl_candy_machine::accounts::Reveal {
+        candy_machine: *candy_machine,
+        authority: authority.pubkey(),
+        mint_index,
+        mint: nft.mint.pubkey(),
+        metadata: nft.metadata,
+        token_metadata_program: mpl_token_metadata::id(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::Reveal {
+            reveal_settings,
+            content_hash,
+            proof,
+        }
+        .data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await
+}
+
+pub async fn enable_commit_reveal(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+) -> Result<(), TransportError> {
+    let (collection_pda, _) = find_collection_pda_address(candy_machine);
+    let accounts = mpl_candy_machine::accounts::EnableCommitReveal {
+        candy_machine: *candy_machine,
+        authority: authority.pubkey(),
+        collection_pda,
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::EnableCommitReveal {}.data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await
+}
+
+/// Settles the pending mint of `nft`, `payer` paid for the pending mint and signs.
+pub async fn settle_pending_mint(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    payer: &Keypair,
+    nft: &Nft,
+) -> Result<(), TransportError> {
+    let (candy_machine_creator, _) = find_candy_machine_creator_address(candy_machine);
+    let (pending_mint, _) = find_pending_mint_address(candy_machine, &nft.mint.pubkey());
+    let accounts = mpl_candy_machine::accounts::SettlePendingMint {
+        candy_machine: *candy_machine,
+        candy_machine_creator,
+        pending_mint,
+        mint: nft.mint.pubkey(),
+        metadata: nft.metadata,
+        payer: payer.pubkey(),
+        token_metadata_program: mpl_token_metadata::id(),
+        recent_slothashes: sysvar::slot_hashes::id(),
+        clock: sysvar::clock::id(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::SettlePendingMint {}.data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], payer, &[]).await
+}
+
+/// Closes a candy machine, `remaining_accounts` are the config lock, mint settings and
+/// collection accounts it needs.
+pub async fn close_candy_machine(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+    force: bool,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Result<(), TransportError> {
+    let (collection_pda, _) = find_collection_pda_address(candy_machine);
+    let mut accounts = mpl_candy_machine::accounts::CloseCandyMachine {
+        candy_machine: *candy_machine,
+        authority: authority.pubkey(),
+        collection_pda,
+    }
+    .to_account_metas(None);
+    accounts.extend(remaining_accounts);
+    let instruction = Instruction {
+        program_id: mpl_candy_machine::id(),
+        data: mpl_candy_machine::instruction::CloseCandyMachine { force }.data(),
+        accounts,
+    };
+
+    process_instructions(context, &[instruction], authority, &[]).await
+}
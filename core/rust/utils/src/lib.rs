@@ -0,0 +1,2 @@
+//! Helpers shared by several Metaplex programs, so they behave the same way in each of them.
+pub mod merkle_proof;
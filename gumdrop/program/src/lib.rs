@@ -10,10 +10,15 @@ use solana_program::{
 };
 use std::io::Write;
 
-pub mod merkle_proof;
+pub use mpl_utils::merkle_proof;
 
 declare_id!("gdrpGjVffourzkdDRrQmySw4aTHr8a3xmQzzxSwFD1a");
 
+/// The v2 candy machine program claimed from by `claim_candy_v2`.
+pub mod candy_machine_v2 {
+    anchor_lang::declare_id!("cndy3Z4yapfJBmL3ShUp5exZKqR3z33thTzeNMm2gRZ");
+}
+
 fn get_or_create_claim_count<'a>(
     distributor: &Account<'a, MerkleDistributor>,
     claim_count: &AccountInfo<'a>,
@@ -416,6 +421,211 @@ pub mod merkle_distributor {
         Ok(())
     }
 
+    /// Claims NFTs directly from a v2 candy machine through the [MerkleDistributor]. Accounts
+    /// the candy machine needs for its settings and guards are passed as remaining accounts and
+    /// forwarded to `mint_nft_v2` with `mint_args`. The payer is charged what the mint cost in
+    /// SOL, candy machines paid in tokens are not supported. A mint charged the bot tax of the
+    /// candy machine does not count as a claim.
+    pub fn claim_candy_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimCandyV2<'info>>,
+        _wallet_bump: u8,
+        _claim_bump: u8,
+        index: u64,
+        amount: u64,
+        claimant_secret: Pubkey,
+        proof: Vec<[u8; 32]>,
+        creator_bump: u8,
+        mint_args: CandyMachineMintArgs,
+    ) -> ProgramResult {
+        let distributor = &ctx.accounts.distributor;
+        let mut claim_count = get_or_create_claim_count(
+            &ctx.accounts.distributor,
+            &ctx.accounts.claim_count,
+            &ctx.accounts.temporal,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            _claim_bump,
+            index,
+            claimant_secret,
+        )?;
+        require!(*claim_count.to_account_info().owner == ID, OwnerMismatch);
+
+        // Verify the merkle proof.
+        let node = solana_program::keccak::hashv(&[
+            &[0x00],
+            &index.to_le_bytes(),
+            &claimant_secret.to_bytes(),
+            &ctx.accounts.candy_machine.key.to_bytes(),
+            &amount.to_le_bytes(),
+        ]);
+        require!(
+            merkle_proof::verify(proof, distributor.root, node.0),
+            InvalidProof
+        );
+
+        // This user is whitelisted to mint at most `amount` NFTs from the candy machine
+        require!(claim_count.count < amount, DropAlreadyClaimed);
+
+        // Transfer the SOL the mint is expected to cost from the payer. Guards can change the
+        // price, so the payer is charged what the mint actually cost once it is done.
+        let required_lamports;
+        {
+            let rent = &Rent::get()?;
+            let candy_machine_data = ctx.accounts.candy_machine.try_borrow_data()?;
+            let header = CandyMachineV2Header::deserialize(&mut &candy_machine_data[8..])?;
+            // the distributor wallet pays for the mint and holds no tokens
+            require!(header.token_mint.is_none(), UnsupportedTokenPayment);
+            // the candy machine counts a mint paid by its authority against the mint authority,
+            // the claimant, a mint paid by any other wallet would count against the distributor
+            require!(
+                header.authority == ctx.accounts.distributor_wallet.key(),
+                DistributorNotCandyMachineAuthority
+            );
+            required_lamports = header
+                .price
+                .checked_add(rent.minimum_balance(mpl_token_metadata::state::MAX_METADATA_LEN))
+                .and_then(|lamports| {
+                    lamports.checked_add(
+                        rent.minimum_balance(mpl_token_metadata::state::MAX_MASTER_EDITION_LEN),
+                    )
+                })
+                .ok_or(ErrorCode::NumericalOverflow)?;
+        }
+        let wallet_lamports = ctx.accounts.distributor_wallet.lamports();
+        msg!(
+            "Transferring {} lamports to distributor wallet for candy machine mint",
+            required_lamports,
+        );
+        invoke(
+            &system_instruction::transfer(
+                ctx.accounts.payer.key,
+                ctx.accounts.distributor_wallet.key,
+                required_lamports,
+            ),
+            &[
+                ctx.accounts.payer.to_account_info().clone(),
+                ctx.accounts.distributor_wallet.clone(),
+                ctx.accounts.system_program.to_account_info().clone(),
+            ],
+        )?;
+
+        let wallet_seeds = [
+            b"Wallet".as_ref(),
+            &distributor.key().to_bytes(),
+            &[_wallet_bump],
+        ];
+
+        // The distributor wallet is the candy machine authority and pays for the mint, the
+        // claimant signs as mint authority so the candy machine can tell claimants apart.
+        let mut accounts = vec![
+            AccountMeta::new(*ctx.accounts.candy_machine.key, false),
+            AccountMeta::new_readonly(*ctx.accounts.candy_machine_creator.key, false),
+            AccountMeta::new(*ctx.accounts.distributor_wallet.key, true),
+            AccountMeta::new(*ctx.accounts.candy_machine_wallet.key, false),
+            AccountMeta::new(*ctx.accounts.candy_machine_metadata.key, false),
+            AccountMeta::new(*ctx.accounts.candy_machine_mint.key, false),
+            AccountMeta::new(*ctx.accounts.payer.key, true),
+            AccountMeta::new(*ctx.accounts.payer.key, true),
+            AccountMeta::new(*ctx.accounts.candy_machine_master_edition.key, false),
+            AccountMeta::new_readonly(*ctx.accounts.token_metadata_program.key, false),
+            AccountMeta::new_readonly(*ctx.accounts.token_program.key, false),
+            AccountMeta::new_readonly(*ctx.accounts.system_program.key, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(*ctx.accounts.recent_slothashes.key, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ];
+        let mut candy_machine_infos = vec![
+            ctx.accounts.candy_machine.clone(),
+            ctx.accounts.candy_machine_creator.clone(),
+            ctx.accounts.distributor_wallet.clone(),
+            ctx.accounts.candy_machine_wallet.clone(),
+            ctx.accounts.candy_machine_metadata.clone(),
+            ctx.accounts.candy_machine_mint.clone(),
+            ctx.accounts.payer.to_account_info().clone(),
+            ctx.accounts.candy_machine_master_edition.clone(),
+            ctx.accounts.token_metadata_program.clone(),
+            ctx.accounts.token_program.to_account_info().clone(),
+            ctx.accounts.system_program.to_account_info().clone(),
+            ctx.accounts.rent.to_account_info().clone(),
+            ctx.accounts.clock.to_account_info().clone(),
+            ctx.accounts.recent_slothashes.clone(),
+            ctx.accounts.instruction_sysvar_account.clone(),
+        ];
+        for account in ctx.remaining_accounts {
+            accounts.push(if account.is_writable {
+                AccountMeta::new(*account.key, account.is_signer)
+            } else {
+                AccountMeta::new_readonly(*account.key, account.is_signer)
+            });
+            candy_machine_infos.push(account.clone());
+        }
+
+        // global::mint_nft_v2 instruction followed by the creator bump and the mint args
+        let mut data = vec![0x05, 0x0f, 0xa4, 0xae, 0xc3, 0x9c, 0x6b, 0xf4];
+        data.push(creator_bump);
+        data.extend(mint_args.try_to_vec()?);
+
+        invoke_signed(
+            &Instruction {
+                program_id: *ctx.accounts.candy_machine_program.key,
+                accounts,
+                data,
+            },
+            &candy_machine_infos,
+            &[&wallet_seeds],
+        )?;
+
+        // Settle the difference between what was transferred and what the mint cost
+        let wallet_lamports_after = ctx.accounts.distributor_wallet.lamports();
+        if wallet_lamports_after > wallet_lamports {
+            invoke_signed(
+                &system_instruction::transfer(
+                    ctx.accounts.distributor_wallet.key,
+                    ctx.accounts.payer.key,
+                    wallet_lamports_after - wallet_lamports,
+                ),
+                &[
+                    ctx.accounts.distributor_wallet.clone(),
+                    ctx.accounts.payer.to_account_info().clone(),
+                    ctx.accounts.system_program.to_account_info().clone(),
+                ],
+                &[&wallet_seeds],
+            )?;
+        } else if wallet_lamports_after < wallet_lamports {
+            invoke(
+                &system_instruction::transfer(
+                    ctx.accounts.payer.key,
+                    ctx.accounts.distributor_wallet.key,
+                    wallet_lamports - wallet_lamports_after,
+                ),
+                &[
+                    ctx.accounts.payer.to_account_info().clone(),
+                    ctx.accounts.distributor_wallet.clone(),
+                    ctx.accounts.system_program.to_account_info().clone(),
+                ],
+            )?;
+        }
+
+        // A mint charged the bot tax succeeds without creating the metadata. The claim count is
+        // still written so a new one keeps its claimant.
+        if ctx.accounts.candy_machine_metadata.data_is_empty() {
+            msg!("Candy machine charged the bot tax, the claim is not counted");
+        } else {
+            // Mark it claimed
+            claim_count.count += 1;
+        }
+
+        // reserialize claim_count
+        {
+            let mut claim_count_data: &mut [u8] =
+                &mut ctx.accounts.claim_count.try_borrow_mut_data()?;
+            claim_count.try_serialize(&mut claim_count_data)?;
+        }
+
+        Ok(())
+    }
+
     /// Claims NFTs by calling MintNewEditionFromMasterEditionViaToken
     pub fn claim_edition(
         ctx: Context<ClaimEdition>,
@@ -739,6 +949,92 @@ pub struct ClaimCandy<'info> {
     clock: Sysvar<'info, Clock>,
 }
 
+/// [merkle_distributor::claim_candy_v2] accounts.
+#[derive(Accounts)]
+#[instruction(_wallet_bump: u8, _claim_bump: u8, index: u64)]
+pub struct ClaimCandyV2<'info> {
+    /// The [MerkleDistributor].
+    #[account(mut)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    /// The [MerkleDistributor] wallet
+    #[account(
+        seeds = [
+            b"Wallet".as_ref(),
+            distributor.key().to_bytes().as_ref()
+        ],
+        bump = _wallet_bump,
+        mut
+    )]
+    pub distributor_wallet: AccountInfo<'info>,
+
+    /// Status of the claim. Created on first invocation of this function
+    #[account(
+        seeds = [
+            b"ClaimCount".as_ref(),
+            index.to_le_bytes().as_ref(),
+            distributor.key().to_bytes().as_ref()
+        ],
+        bump = _claim_bump,
+        mut,
+    )]
+    pub claim_count: AccountInfo<'info>,
+
+    /// Extra signer expected for claims
+    pub temporal: Signer<'info>,
+
+    /// Payer of the claim. Will be `mint_authority` for `candy_machine_mint` and
+    /// `update_authority` for `candy_machine_metadata`
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Candy-Machine. Verified through CPI
+    #[account(mut)]
+    pub candy_machine: AccountInfo<'info>,
+
+    /// Candy-Machine creator PDA. Verified through CPI
+    pub candy_machine_creator: AccountInfo<'info>,
+
+    /// Candy-Machine-Wallet. Verified through CPI
+    #[account(mut)]
+    pub candy_machine_wallet: AccountInfo<'info>,
+
+    /// Generated mint
+    #[account(mut)]
+    pub candy_machine_mint: AccountInfo<'info>,
+
+    /// PDA of `candy_machine_mint`
+    #[account(mut)]
+    pub candy_machine_metadata: AccountInfo<'info>,
+
+    /// PDA of `candy_machine_mint`
+    #[account(mut)]
+    pub candy_machine_master_edition: AccountInfo<'info>,
+
+    /// The [System] program.
+    pub system_program: Program<'info, System>,
+
+    /// SPL [Token] program.
+    pub token_program: Program<'info, Token>,
+
+    /// SPL [TokenMetadata] program.
+    pub token_metadata_program: AccountInfo<'info>,
+
+    /// SPL [CandyMachine] program.
+    #[account(address = candy_machine_v2::id())]
+    pub candy_machine_program: AccountInfo<'info>,
+
+    rent: Sysvar<'info, Rent>,
+    clock: Sysvar<'info, Clock>,
+
+    /// SlotHashes sysvar. Verified through CPI
+    pub recent_slothashes: AccountInfo<'info>,
+
+    /// Instructions sysvar
+    #[account(address = sysvar::instructions::id())]
+    pub instruction_sysvar_account: AccountInfo<'info>,
+}
+
 /// [merkle_distributor::claim_edition] accounts. Wrapper around
 /// MintNewEditionFromMasterEditionViaToken
 #[derive(Accounts)]
@@ -895,6 +1191,32 @@ pub struct CandyMachineData {
     pub go_live_date: Option<i64>,
 }
 
+/// Arguments of the v2 candy machine `mint_nft_v2`, the guard group to mint with and the proof
+/// for its allow list guard.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct CandyMachineMintArgs {
+    pub group: Option<String>,
+    pub allow_list_proof: Option<CandyMachineAllowListProof>,
+}
+
+/// Proof that the claimant is a leaf of the allow list of a v2 candy machine guard.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct CandyMachineAllowListProof {
+    pub allocation: Option<u16>,
+    pub proof: Vec<[u8; 32]>,
+}
+
+/// Leading fields of a v2 candy machine, after its discriminator. Enough to price a claim.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct CandyMachineV2Header {
+    pub authority: Pubkey,
+    pub wallet: Pubkey,
+    pub token_mint: Option<Pubkey>,
+    pub items_redeemed: u64,
+    pub uuid: String,
+    pub price: u64,
+}
+
 #[error]
 pub enum ErrorCode {
     #[msg("Invalid Merkle proof.")]
@@ -907,4 +1229,10 @@ pub enum ErrorCode {
     OwnerMismatch,
     #[msg("Temporal signer did not match distributor")]
     TemporalMismatch,
+    #[msg("Candy machines paid in tokens cannot be claimed through the distributor")]
+    UnsupportedTokenPayment,
+    #[msg("Numerical overflow")]
+    NumericalOverflow,
+    #[msg("The distributor wallet has to be the candy machine authority")]
+    DistributorNotCandyMachineAuthority,
 }
@@ -5,15 +5,23 @@ use {
     clap::{crate_description, crate_name, crate_version, App, Arg, ArgMatches, SubCommand},
     mpl_token_metadata::{
         instruction::{
-            create_master_edition, create_metadata_accounts,
-            mint_new_edition_from_master_edition_via_token, puff_metadata_account,
-            update_metadata_accounts,
+            approve_use_authority, create_master_edition, create_metadata_accounts,
+            freeze_delegated_account, mint_new_edition_from_master_edition_via_token,
+            puff_metadata_account, set_and_verify_collection, sign_metadata,
+            update_metadata_accounts, update_primary_sale_happened_via_token, utilize,
+            verify_collection,
+        },
+        pda::{
+            find_master_edition_account, find_metadata_account, find_program_as_burner_account,
+            find_use_authority_account,
         },
         state::{
-            get_reservation_list, Data, Edition, Key, MasterEditionV1, MasterEditionV2, Metadata,
-            EDITION, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH, PREFIX,
+            get_reservation_list, CollectionDetails, Data, Edition, Key, MasterEditionV1,
+            MasterEditionV2, Metadata, EDITION, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH,
+            PREFIX,
         },
     },
+    serde_json::{json, Value},
     solana_clap_utils::{
         input_parsers::pubkey_of,
         input_validators::{is_url, is_valid_pubkey, is_valid_signer},
@@ -23,6 +31,7 @@ use {
         account_info::AccountInfo, borsh::try_from_slice_unchecked, program_pack::Pack,
     },
     solana_sdk::{
+        instruction::Instruction,
         pubkey::Pubkey,
         signature::{read_keypair_file, Keypair, Signer},
         system_instruction::create_account,
@@ -36,7 +45,76 @@ use {
 };
 
 const TOKEN_PROGRAM_PUBKEY: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
-fn puff_unpuffed_metadata(_app_matches: &ArgMatches, payer: Keypair, client: RpcClient) {
+
+fn instruction_to_json(instruction: &Instruction) -> Value {
+    json!({
+        "program_id": instruction.program_id.to_string(),
+        "accounts": instruction
+            .accounts
+            .iter()
+            .map(|account| {
+                json!({
+                    "pubkey": account.pubkey.to_string(),
+                    "is_signer": account.is_signer,
+                    "is_writable": account.is_writable,
+                })
+            })
+            .collect::<Vec<_>>(),
+        "data": bs58::encode(&instruction.data).into_string(),
+    })
+}
+
+/// Signs and sends the instructions in one transaction. With --dry-run the serialized
+/// instructions are printed instead and the process exits without sending anything.
+fn send_instructions(
+    app_matches: &ArgMatches,
+    client: &RpcClient,
+    payer: &Keypair,
+    signers: &[&Keypair],
+    instructions: &[Instruction],
+) {
+    if app_matches.is_present("dry_run") {
+        let instructions: Vec<Value> = instructions.iter().map(instruction_to_json).collect();
+        println!("{}", serde_json::to_string_pretty(&instructions).unwrap());
+        std::process::exit(0);
+    }
+
+    let mut transaction = Transaction::new_with_payer(instructions, Some(&payer.pubkey()));
+    let recent_blockhash = client.get_latest_blockhash().unwrap();
+
+    transaction.sign(signers, recent_blockhash);
+    client.send_and_confirm_transaction(&transaction).unwrap();
+}
+
+fn find_token_account_holding(client: &RpcClient, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::from_str(
+        &client
+            .get_token_accounts_by_owner(owner, TokenAccountsFilter::Mint(*mint))
+            .unwrap()
+            .iter()
+            .find(|x| {
+                client
+                    .get_token_account_balance(&Pubkey::from_str(&x.pubkey).unwrap())
+                    .unwrap()
+                    .amount
+                    != "0"
+            })
+            .unwrap()
+            .pubkey,
+    )
+    .unwrap()
+}
+
+fn read_keypair_or_default(app_matches: &ArgMatches, name: &str) -> Keypair {
+    read_keypair_file(
+        app_matches
+            .value_of(name)
+            .unwrap_or_else(|| app_matches.value_of("keypair").unwrap()),
+    )
+    .unwrap()
+}
+
+fn puff_unpuffed_metadata(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) {
     let metadata_accounts = client
         .get_program_accounts(&mpl_token_metadata::id())
         .unwrap();
@@ -62,6 +140,14 @@ fn puff_unpuffed_metadata(_app_matches: &ArgMatches, payer: Keypair, client: Rpc
     }
     println!("Found {} accounts needing puffing", needing_puffing.len());
 
+    if app_matches.is_present("dry_run") {
+        let instructions: Vec<Instruction> = needing_puffing
+            .iter()
+            .map(|pubkey| puff_metadata_account(mpl_token_metadata::id(), *pubkey))
+            .collect();
+        send_instructions(app_matches, &client, &payer, &[&payer], &instructions);
+    }
+
     let mut instructions = vec![];
     let mut i = 0;
     while i < needing_puffing.len() {
@@ -138,11 +224,7 @@ fn mint_coins(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) {
         )
         .unwrap(),
     );
-    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
-    let recent_blockhash = client.get_latest_blockhash().unwrap();
-
-    transaction.sign(&signers, recent_blockhash);
-    client.send_and_confirm_transaction(&transaction).unwrap();
+    send_instructions(app_matches, &client, &payer, &signers, &instructions);
 
     println!("Minted {:?} tokens to {:?}.", amount, destination_key);
 }
@@ -171,6 +253,80 @@ fn show_reservation_list(app_matches: &ArgMatches, _payer: Keypair, client: RpcC
     println!("supply snapshot: {:?}", res_list.supply_snapshot());
 }
 
+fn metadata_to_json(metadata_key: &Pubkey, metadata: &Metadata) -> Value {
+    let trimmed = |value: &str| value.trim_end_matches(char::from(0)).to_owned();
+    json!({
+        "key": metadata_key.to_string(),
+        "update_authority": metadata.update_authority.to_string(),
+        "mint": metadata.mint.to_string(),
+        "name": trimmed(&metadata.data.name),
+        "symbol": trimmed(&metadata.data.symbol),
+        "uri": trimmed(&metadata.data.uri),
+        "seller_fee_basis_points": metadata.data.seller_fee_basis_points,
+        "creators": metadata.data.creators.as_ref().map(|creators| {
+            creators
+                .iter()
+                .map(|creator| {
+                    json!({
+                        "address": creator.address.to_string(),
+                        "verified": creator.verified,
+                        "share": creator.share,
+                    })
+                })
+                .collect::<Vec<_>>()
+        }),
+        "primary_sale_happened": metadata.primary_sale_happened,
+        "is_mutable": metadata.is_mutable,
+        "edition_nonce": metadata.edition_nonce,
+        "token_standard": metadata.token_standard.as_ref().map(|standard| format!("{:?}", standard)),
+        "collection": metadata.collection.as_ref().map(|collection| {
+            json!({
+                "key": collection.key.to_string(),
+                "verified": collection.verified,
+            })
+        }),
+        "uses": metadata.uses.as_ref().map(|uses| {
+            json!({
+                "use_method": format!("{:?}", uses.use_method),
+                "remaining": uses.remaining,
+                "total": uses.total,
+            })
+        }),
+        "collection_size": metadata.collection_details.as_ref().map(|details| match details {
+            CollectionDetails::V1 { size } => *size,
+        }),
+        "rule_set": metadata.rule_set.map(|rule_set| rule_set.to_string()),
+    })
+}
+
+fn edition_to_json(edition_key: &Pubkey, data: &[u8]) -> Value {
+    if data[0] == Key::MasterEditionV1 as u8 {
+        let master_edition: MasterEditionV1 = try_from_slice_unchecked(data).unwrap();
+        json!({
+            "key": edition_key.to_string(),
+            "type": "MasterEditionV1",
+            "supply": master_edition.supply,
+            "max_supply": master_edition.max_supply,
+        })
+    } else if data[0] == Key::MasterEditionV2 as u8 {
+        let master_edition: MasterEditionV2 = try_from_slice_unchecked(data).unwrap();
+        json!({
+            "key": edition_key.to_string(),
+            "type": "MasterEditionV2",
+            "supply": master_edition.supply,
+            "max_supply": master_edition.max_supply,
+        })
+    } else {
+        let edition: Edition = try_from_slice_unchecked(data).unwrap();
+        json!({
+            "key": edition_key.to_string(),
+            "type": "Edition",
+            "parent": edition.parent.to_string(),
+            "edition": edition.edition,
+        })
+    }
+}
+
 fn show(app_matches: &ArgMatches, _payer: Keypair, client: RpcClient) {
     let program_key = mpl_token_metadata::id();
 
@@ -198,6 +354,19 @@ fn show(app_matches: &ArgMatches, _payer: Keypair, client: RpcClient) {
     let (master_edition_key, _) = Pubkey::find_program_address(master_edition_seeds, &program_key);
     let master_edition_account_res = client.get_account(&master_edition_key);
 
+    if app_matches.is_present("json") {
+        let edition = match master_edition_account_res {
+            Ok(master_edition_account) => {
+                edition_to_json(&master_edition_key, &master_edition_account.data)
+            }
+            Err(_) => Value::Null,
+        };
+        let mut output = metadata_to_json(&master_metadata_key, &master_metadata);
+        output["edition"] = edition;
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        return;
+    }
+
     println!("Metadata key: {:?}", master_metadata_key);
     println!("Metadata: {:#?}", master_metadata);
     println!("Update authority: {:?}", update_authority);
@@ -239,25 +408,8 @@ fn mint_edition_via_token_call(
     let token_key = Pubkey::from_str(TOKEN_PROGRAM_PUBKEY).unwrap();
 
     let mint_key = pubkey_of(app_matches, "mint").unwrap();
-    let existing_token_account = Pubkey::from_str(
-        &client
-            .get_token_accounts_by_owner(
-                &account_authority.pubkey(),
-                TokenAccountsFilter::Mint(mint_key),
-            )
-            .unwrap()
-            .iter()
-            .find(|x| {
-                client
-                    .get_token_account_balance(&Pubkey::from_str(&x.pubkey).unwrap())
-                    .unwrap()
-                    .amount
-                    != "0"
-            })
-            .unwrap()
-            .pubkey,
-    )
-    .unwrap();
+    let existing_token_account =
+        find_token_account_holding(&client, &account_authority.pubkey(), &mint_key);
 
     let new_mint_key = Keypair::new();
     let added_token_account = Keypair::new();
@@ -357,11 +509,7 @@ fn mint_edition_via_token_call(
         master_edition.supply + 1,
     ));
 
-    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
-    let recent_blockhash = client.get_latest_blockhash().unwrap();
-
-    transaction.sign(&signers, recent_blockhash);
-    client.send_and_confirm_transaction(&transaction).unwrap();
+    send_instructions(app_matches, &client, &payer, &signers, &instructions);
     let account = client.get_account(&edition_key).unwrap();
     let edition: Edition = try_from_slice_unchecked(&account.data).unwrap();
     (edition, edition_key, new_mint_key.pubkey())
@@ -458,11 +606,7 @@ fn master_edition_call(
         max_supply,
     ));
 
-    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
-    let recent_blockhash = client.get_latest_blockhash().unwrap();
-
-    transaction.sign(&signers, recent_blockhash);
-    client.send_and_confirm_transaction(&transaction).unwrap();
+    send_instructions(app_matches, &client, &payer, &signers, &instructions);
     let account = client.get_account(&master_edition_key).unwrap();
     let master_edition: MasterEditionV2 = try_from_slice_unchecked(&account.data).unwrap();
     (master_edition, master_edition_key)
@@ -516,12 +660,8 @@ fn update_metadata_account_call(
         None,
     )];
 
-    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
-    let recent_blockhash = client.get_latest_blockhash().unwrap();
     let signers = vec![&update_authority];
-
-    transaction.sign(&signers, recent_blockhash);
-    client.send_and_confirm_transaction(&transaction).unwrap();
+    send_instructions(app_matches, &client, &payer, &signers, &instructions);
     let metadata_account = client.get_account(&metadata_key).unwrap();
     let metadata: Metadata = try_from_slice_unchecked(&metadata_account.data).unwrap();
     (metadata, metadata_key)
@@ -598,8 +738,6 @@ fn create_metadata_account_call(
 
     instructions.push(new_metadata_instruction);
 
-    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
-    let recent_blockhash = client.get_latest_blockhash().unwrap();
     let mut signers = vec![&payer];
     if create_new_mint {
         signers.push(&new_mint);
@@ -607,13 +745,212 @@ fn create_metadata_account_call(
     if update_authority.pubkey() != payer.pubkey() {
         signers.push(&update_authority)
     }
-    transaction.sign(&signers, recent_blockhash);
-    client.send_and_confirm_transaction(&transaction).unwrap();
+    send_instructions(app_matches, &client, &payer, &signers, &instructions);
     let account = client.get_account(&metadata_key).unwrap();
     let metadata: Metadata = try_from_slice_unchecked(&account.data).unwrap();
     (metadata, metadata_key)
 }
 
+fn verify_collection_call(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) -> Pubkey {
+    let collection_authority = read_keypair_or_default(app_matches, "update_authority");
+    let program_key = mpl_token_metadata::id();
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let collection_mint_key = pubkey_of(app_matches, "collection_mint").unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+    let (collection_key, _) = find_metadata_account(&collection_mint_key);
+    let (collection_master_edition_key, _) = find_master_edition_account(&collection_mint_key);
+
+    let instructions = [verify_collection(
+        program_key,
+        metadata_key,
+        collection_authority.pubkey(),
+        payer.pubkey(),
+        collection_mint_key,
+        collection_key,
+        collection_master_edition_key,
+        None,
+    )];
+
+    send_instructions(
+        app_matches,
+        &client,
+        &payer,
+        &[&payer, &collection_authority],
+        &instructions,
+    );
+    metadata_key
+}
+
+fn set_and_verify_collection_call(
+    app_matches: &ArgMatches,
+    payer: Keypair,
+    client: RpcClient,
+) -> Pubkey {
+    let update_authority = read_keypair_or_default(app_matches, "update_authority");
+    let collection_authority = read_keypair_or_default(app_matches, "collection_authority");
+    let program_key = mpl_token_metadata::id();
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let collection_mint_key = pubkey_of(app_matches, "collection_mint").unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+    let (collection_key, _) = find_metadata_account(&collection_mint_key);
+    let (collection_master_edition_key, _) = find_master_edition_account(&collection_mint_key);
+
+    let instructions = [set_and_verify_collection(
+        program_key,
+        metadata_key,
+        collection_authority.pubkey(),
+        payer.pubkey(),
+        update_authority.pubkey(),
+        collection_mint_key,
+        collection_key,
+        collection_master_edition_key,
+        None,
+    )];
+
+    send_instructions(
+        app_matches,
+        &client,
+        &payer,
+        &[&payer, &update_authority, &collection_authority],
+        &instructions,
+    );
+    metadata_key
+}
+
+fn approve_use_authority_call(
+    app_matches: &ArgMatches,
+    payer: Keypair,
+    client: RpcClient,
+) -> Pubkey {
+    let program_key = mpl_token_metadata::id();
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let user_key = pubkey_of(app_matches, "user").unwrap();
+    let number_of_uses = app_matches
+        .value_of("number_of_uses")
+        .unwrap()
+        .parse::<u64>()
+        .unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+    let (use_authority_record_key, _) = find_use_authority_account(&mint_key, &user_key);
+    let (burner_key, _) = find_program_as_burner_account();
+    let token_account = find_token_account_holding(&client, &payer.pubkey(), &mint_key);
+
+    let instructions = [approve_use_authority(
+        program_key,
+        use_authority_record_key,
+        user_key,
+        payer.pubkey(),
+        payer.pubkey(),
+        token_account,
+        metadata_key,
+        mint_key,
+        burner_key,
+        number_of_uses,
+    )];
+
+    send_instructions(app_matches, &client, &payer, &[&payer], &instructions);
+    use_authority_record_key
+}
+
+fn utilize_call(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) -> Metadata {
+    let program_key = mpl_token_metadata::id();
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let number_of_uses = app_matches
+        .value_of("number_of_uses")
+        .unwrap()
+        .parse::<u64>()
+        .unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+
+    // Using someone else's asset goes through the use authority record approved for us.
+    let (owner_key, use_authority_record_key, burner_key) = match pubkey_of(app_matches, "owner") {
+        Some(owner_key) => (
+            owner_key,
+            Some(find_use_authority_account(&mint_key, &payer.pubkey()).0),
+            Some(find_program_as_burner_account().0),
+        ),
+        None => (payer.pubkey(), None, None),
+    };
+    let token_account = find_token_account_holding(&client, &owner_key, &mint_key);
+
+    let instructions = [utilize(
+        program_key,
+        metadata_key,
+        token_account,
+        mint_key,
+        use_authority_record_key,
+        payer.pubkey(),
+        owner_key,
+        burner_key,
+        number_of_uses,
+    )];
+
+    send_instructions(app_matches, &client, &payer, &[&payer], &instructions);
+    let metadata_account = client.get_account(&metadata_key).unwrap();
+    try_from_slice_unchecked(&metadata_account.data).unwrap()
+}
+
+fn sign_metadata_call(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) -> Pubkey {
+    let creator = read_keypair_or_default(app_matches, "creator");
+    let program_key = mpl_token_metadata::id();
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+
+    let instructions = [sign_metadata(program_key, metadata_key, creator.pubkey())];
+
+    send_instructions(
+        app_matches,
+        &client,
+        &payer,
+        &[&payer, &creator],
+        &instructions,
+    );
+    metadata_key
+}
+
+fn freeze_delegated_account_call(
+    app_matches: &ArgMatches,
+    payer: Keypair,
+    client: RpcClient,
+) -> Pubkey {
+    let program_key = mpl_token_metadata::id();
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let token_account = pubkey_of(app_matches, "token_account").unwrap();
+    let (edition_key, _) = find_master_edition_account(&mint_key);
+
+    let instructions = [freeze_delegated_account(
+        program_key,
+        payer.pubkey(),
+        token_account,
+        edition_key,
+        mint_key,
+    )];
+
+    send_instructions(app_matches, &client, &payer, &[&payer], &instructions);
+    token_account
+}
+
+fn update_primary_sale_happened_via_token_call(
+    app_matches: &ArgMatches,
+    payer: Keypair,
+    client: RpcClient,
+) -> Pubkey {
+    let program_key = mpl_token_metadata::id();
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+    let token_account = find_token_account_holding(&client, &payer.pubkey(), &mint_key);
+
+    let instructions = [update_primary_sale_happened_via_token(
+        program_key,
+        metadata_key,
+        payer.pubkey(),
+        token_account,
+    )];
+
+    send_instructions(app_matches, &client, &payer, &[&payer], &instructions);
+    metadata_key
+}
+
 fn main() {
     let app_matches = App::new(crate_name!())
         .about(crate_description!())
@@ -644,6 +981,13 @@ fn main() {
                 .global(true)
                 .help("Update authority filepath or url to keypair besides yourself, defaults to normal keypair"),
         )
+        .arg(
+            Arg::with_name("dry_run")
+                .long("dry-run")
+                .takes_value(false)
+                .global(true)
+                .help("Print the serialized instructions as JSON instead of sending them"),
+        )
         .subcommand(
      SubCommand::with_name("create_metadata_accounts")
                 .about("Create Metadata Accounts")
@@ -763,6 +1107,13 @@ fn main() {
                         .takes_value(true)
                         .help("Metadata mint"),
                 )
+                .arg(
+                    Arg::with_name("json")
+                        .long("json")
+                        .takes_value(false)
+                        .required(false)
+                        .help("Print the metadata and edition as JSON"),
+                )
         ).subcommand(
             SubCommand::with_name("show_reservation_list")
                 .about("Show Reservation List")
@@ -839,6 +1190,169 @@ fn main() {
                                 .help("Account's authority, defaults to you"),
                         )
 
+        ).subcommand(
+            SubCommand::with_name("verify_collection")
+                .about("Verify an NFT as a member of a collection, signed by the collection update authority")
+                .arg(
+                    Arg::with_name("mint")
+                        .long("mint")
+                        .value_name("MINT")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Mint of the NFT to verify"),
+                )
+                .arg(
+                    Arg::with_name("collection_mint")
+                        .long("collection_mint")
+                        .value_name("COLLECTION_MINT")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Mint of the collection parent NFT"),
+                )
+        ).subcommand(
+            SubCommand::with_name("set_and_verify_collection")
+                .about("Set the collection of an NFT and verify it in one go")
+                .arg(
+                    Arg::with_name("mint")
+                        .long("mint")
+                        .value_name("MINT")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Mint of the NFT to add to the collection"),
+                )
+                .arg(
+                    Arg::with_name("collection_mint")
+                        .long("collection_mint")
+                        .value_name("COLLECTION_MINT")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Mint of the collection parent NFT"),
+                )
+                .arg(
+                    Arg::with_name("collection_authority")
+                        .long("collection_authority")
+                        .value_name("COLLECTION_AUTHORITY")
+                        .validator(is_valid_signer)
+                        .takes_value(true)
+                        .required(false)
+                        .help("Filepath or URL to a keypair representing the collection update authority, defaults to you"),
+                )
+        ).subcommand(
+            SubCommand::with_name("approve_use_authority")
+                .about("Approve a use authority for an NFT you hold")
+                .arg(
+                    Arg::with_name("mint")
+                        .long("mint")
+                        .value_name("MINT")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Mint of the NFT"),
+                )
+                .arg(
+                    Arg::with_name("user")
+                        .long("user")
+                        .value_name("USER")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Use authority to approve"),
+                )
+                .arg(
+                    Arg::with_name("number_of_uses")
+                        .long("number_of_uses")
+                        .value_name("NUMBER_OF_USES")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Number of uses"),
+                )
+        ).subcommand(
+            SubCommand::with_name("utilize")
+                .about("Use an NFT, as its holder or as an approved use authority")
+                .arg(
+                    Arg::with_name("mint")
+                        .long("mint")
+                        .value_name("MINT")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Mint of the NFT"),
+                )
+                .arg(
+                    Arg::with_name("number_of_uses")
+                        .long("number_of_uses")
+                        .value_name("NUMBER_OF_USES")
+                        .required(true)
+                        .takes_value(true)
+                        .help("Number of uses"),
+                )
+                .arg(
+                    Arg::with_name("owner")
+                        .long("owner")
+                        .value_name("OWNER")
+                        .required(false)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Holder of the NFT when using it as an approved use authority, defaults to you"),
+                )
+        ).subcommand(
+            SubCommand::with_name("sign_metadata")
+                .about("Verify yourself as a creator of the metadata")
+                .arg(
+                    Arg::with_name("mint")
+                        .long("mint")
+                        .value_name("MINT")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Metadata mint"),
+                )
+                .arg(
+                    Arg::with_name("creator")
+                        .long("creator")
+                        .value_name("CREATOR")
+                        .validator(is_valid_signer)
+                        .takes_value(true)
+                        .required(false)
+                        .help("Filepath or URL to a keypair representing the creator, defaults to you"),
+                )
+        ).subcommand(
+            SubCommand::with_name("freeze_delegated_account")
+                .about("Freeze a token account you are the delegate of")
+                .arg(
+                    Arg::with_name("mint")
+                        .long("mint")
+                        .value_name("MINT")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Mint of the NFT"),
+                )
+                .arg(
+                    Arg::with_name("token_account")
+                        .long("token_account")
+                        .value_name("TOKEN_ACCOUNT")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Token account to freeze"),
+                )
+        ).subcommand(
+            SubCommand::with_name("update_primary_sale_happened_via_token")
+                .about("Mark the primary sale of an NFT you hold as happened")
+                .arg(
+                    Arg::with_name("mint")
+                        .long("mint")
+                        .value_name("MINT")
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Mint of the NFT"),
+                )
         ).subcommand(
                 SubCommand::with_name("puff_unpuffed_metadata")
                         .about("Take metadata that still have variable length name, symbol, and uri fields and stretch them out with null symbols so they can be searched more easily by RPC.")).get_matches();
@@ -893,6 +1407,38 @@ fn main() {
         ("mint_coins", Some(arg_matches)) => {
             mint_coins(arg_matches, payer, client);
         }
+        ("verify_collection", Some(arg_matches)) => {
+            let metadata_key = verify_collection_call(arg_matches, payer, client);
+            println!("Verified collection on metadata {:?}", metadata_key);
+        }
+        ("set_and_verify_collection", Some(arg_matches)) => {
+            let metadata_key = set_and_verify_collection_call(arg_matches, payer, client);
+            println!("Set and verified collection on metadata {:?}", metadata_key);
+        }
+        ("approve_use_authority", Some(arg_matches)) => {
+            let record_key = approve_use_authority_call(arg_matches, payer, client);
+            println!("Approved use authority with record {:?}", record_key);
+        }
+        ("utilize", Some(arg_matches)) => {
+            let metadata = utilize_call(arg_matches, payer, client);
+            println!("Used metadata, uses are now {:?}", metadata.uses);
+        }
+        ("sign_metadata", Some(arg_matches)) => {
+            let metadata_key = sign_metadata_call(arg_matches, payer, client);
+            println!("Signed metadata {:?}", metadata_key);
+        }
+        ("freeze_delegated_account", Some(arg_matches)) => {
+            let token_account = freeze_delegated_account_call(arg_matches, payer, client);
+            println!("Froze token account {:?}", token_account);
+        }
+        ("update_primary_sale_happened_via_token", Some(arg_matches)) => {
+            let metadata_key =
+                update_primary_sale_happened_via_token_call(arg_matches, payer, client);
+            println!(
+                "Marked primary sale as happened on metadata {:?}",
+                metadata_key
+            );
+        }
         ("puff_unpuffed_metadata", Some(arg_matches)) => {
             puff_unpuffed_metadata(arg_matches, payer, client);
         }
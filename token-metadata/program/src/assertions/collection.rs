@@ -8,9 +8,18 @@ use crate::{
 
 pub fn assert_collection_update_is_valid(
     edition: bool,
-    _existing: &Option<Collection>,
+    existing: &Option<Collection>,
     incoming: &Option<Collection>,
 ) -> Result<(), ProgramError> {
+    // Passing the current collection back unchanged is not an update, even if it is verified.
+    if existing == incoming {
+        return Ok(());
+    }
+    // Removing or replacing a verified collection would leave its size out of date, this has to
+    // go through unverify_collection first.
+    if existing.as_ref().map_or(false, |collection| collection.verified) && !edition {
+        return Err(MetadataError::VerifiedCollectionMustBeUnverifiedFirst.into());
+    }
     if incoming.is_some() && incoming.as_ref().unwrap().verified == true && !edition {
         // Never allow a collection to be verified outside of verify_collection instruction
         return Err(MetadataError::CollectionCannotBeVerifiedInThisInstruction.into());
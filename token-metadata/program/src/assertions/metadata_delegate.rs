@@ -0,0 +1,64 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    error::MetadataError,
+    pda::find_metadata_delegate_account,
+    state::{DataV2, Metadata, MetadataDelegatePermissions, MetadataDelegateRecord},
+    utils::assert_owned_by,
+};
+
+pub fn assert_is_metadata_delegate(
+    program_id: &Pubkey,
+    metadata_delegate_record: &AccountInfo,
+    delegate: &Pubkey,
+    mint: &Pubkey,
+    update_authority: &Pubkey,
+) -> Result<MetadataDelegateRecord, ProgramError> {
+    let (pda, bump) = find_metadata_delegate_account(mint, delegate);
+    if pda != *metadata_delegate_record.key {
+        return Err(MetadataError::DerivedKeyInvalid.into());
+    }
+    if metadata_delegate_record.data_is_empty() {
+        return Err(MetadataError::MetadataDelegateRecordDoesNotExist.into());
+    }
+    assert_owned_by(metadata_delegate_record, program_id)?;
+
+    let record = MetadataDelegateRecord::from_account_info(metadata_delegate_record)?;
+    if record.bump != bump {
+        return Err(MetadataError::DerivedKeyInvalid.into());
+    }
+    if record.update_authority != *update_authority {
+        return Err(MetadataError::MetadataDelegateApprovedByPreviousAuthority.into());
+    }
+    Ok(record)
+}
+
+/// Stored strings are puffed out with null characters, incoming ones usually are not.
+fn same_string(existing: &str, incoming: &str) -> bool {
+    existing.trim_end_matches(char::from(0)) == incoming.trim_end_matches(char::from(0))
+}
+
+pub fn assert_metadata_delegate_update_is_valid(
+    permissions: &MetadataDelegatePermissions,
+    existing: &Metadata,
+    incoming: &DataV2,
+) -> Result<(), ProgramError> {
+    // Delegates can never touch the fields that make up the identity and royalties of the asset.
+    if !same_string(&existing.data.name, &incoming.name)
+        || !same_string(&existing.data.symbol, &incoming.symbol)
+        || existing.data.seller_fee_basis_points != incoming.seller_fee_basis_points
+        || existing.data.creators != incoming.creators
+    {
+        return Err(MetadataError::MetadataDelegateScopeViolation.into());
+    }
+    if !permissions.uri && !same_string(&existing.data.uri, &incoming.uri) {
+        return Err(MetadataError::MetadataDelegateScopeViolation.into());
+    }
+    if !permissions.collection && existing.collection != incoming.collection {
+        return Err(MetadataError::MetadataDelegateScopeViolation.into());
+    }
+    if !permissions.uses && existing.uses != incoming.uses {
+        return Err(MetadataError::MetadataDelegateScopeViolation.into());
+    }
+    Ok(())
+}
@@ -1,2 +1,3 @@
 pub mod collection;
+pub mod metadata_delegate;
 pub mod uses;
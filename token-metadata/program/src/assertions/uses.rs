@@ -2,7 +2,7 @@ use solana_program::{account_info::AccountInfo, program_error::ProgramError, pub
 
 use crate::{
     error::MetadataError,
-    state::{UseAuthorityRecord, UseMethod, Uses, PREFIX, USER},
+    state::{UseAuthorityRecord, UseMethod, UseRestrictions, Uses, PREFIX, USER},
     utils::assert_derivation,
 };
 
@@ -35,6 +35,43 @@ pub fn assert_valid_use(
     };
 }
 
+pub fn assert_valid_use_restrictions(restrictions: &UseRestrictions) -> Result<(), ProgramError> {
+    if let (Some(valid_from), Some(valid_until)) = (restrictions.valid_from, restrictions.valid_until)
+    {
+        if valid_from >= valid_until {
+            return Err(MetadataError::InvalidUseRestrictions.into());
+        }
+    }
+    if let Some(cooldown) = restrictions.cooldown {
+        if cooldown < 0 {
+            return Err(MetadataError::InvalidUseRestrictions.into());
+        }
+    }
+    Ok(())
+}
+
+pub fn assert_use_allowed_at(restrictions: &UseRestrictions, now: i64) -> Result<(), ProgramError> {
+    if let Some(valid_from) = restrictions.valid_from {
+        if now < valid_from {
+            return Err(MetadataError::UseNotYetValid.into());
+        }
+    }
+    if let Some(valid_until) = restrictions.valid_until {
+        if now > valid_until {
+            return Err(MetadataError::UseExpired.into());
+        }
+    }
+    if let (Some(cooldown), Some(last_used_at)) = (restrictions.cooldown, restrictions.last_used_at) {
+        let next_use_at = last_used_at
+            .checked_add(cooldown)
+            .ok_or(MetadataError::NumericalOverflowError)?;
+        if now < next_use_at {
+            return Err(MetadataError::UseCooldownActive.into());
+        }
+    }
+    Ok(())
+}
+
 pub fn assert_valid_bump(
     canonical_bump: u8,
     use_authority_record: &UseAuthorityRecord,
@@ -7,7 +7,6 @@ use crate::{
         CreateMetadataAccountsLogicArgs,
     },
 };
-use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
@@ -104,6 +103,6 @@ pub fn process_deprecated_update_metadata_accounts(
 
     puff_out_data_fields(&mut metadata);
 
-    metadata.serialize(&mut *metadata_account_info.data.borrow_mut())?;
+    metadata.save(metadata_account_info)?;
     Ok(())
 }
@@ -374,7 +374,94 @@ pub enum MetadataError {
     CannotWipeVerifiedCreators,
 
     #[error("Not allowed to change seller fee basis points.")]
-    NotAllowedToChangeSellerFeeBasisPoints
+    NotAllowedToChangeSellerFeeBasisPoints,
+
+    #[error("Master edition has printed editions outstanding and cannot be burned.")]
+    MasterEditionHasPrints,
+
+    #[error("Print edition does not belong to the given master edition.")]
+    PrintEditionDoesNotMatchMasterEdition,
+
+    #[error("Edition is not marked as printed in the edition marker.")]
+    EditionNotPrinted,
+
+    #[error("This collection already has its size set.")]
+    CollectionAlreadySized,
+
+    #[error("Collection metadata account is required for an item verified in a collection.")]
+    MissingCollectionMetadata,
+
+    #[error("This instruction is not supported for the token standard of the asset.")]
+    InvalidTokenStandard,
+
+    #[error("Rule set account does not match the rule set of the asset.")]
+    InvalidRuleSet,
+
+    #[error("The calling program is not allowed by the rule set of the asset.")]
+    ProgramNotAllowedByRuleSet,
+
+    #[error("Too many programs in the rule set allow-list.")]
+    TooManyRuleSetPrograms,
+
+    #[error("Metadata account is already on the latest schema version.")]
+    MetadataAlreadyMigrated,
+
+    #[error("This metadata delegate record already exists.")]
+    MetadataDelegateRecordAlreadyExists,
+
+    #[error("This metadata delegate record does not exist.")]
+    MetadataDelegateRecordDoesNotExist,
+
+    #[error("The metadata delegate is not allowed to make this change.")]
+    MetadataDelegateScopeViolation,
+
+    #[error("This master edition has a print policy, its accounts must be passed to mint a print.")]
+    MissingPrintPolicyAccounts,
+
+    #[error("Print policy account does not match the master edition.")]
+    InvalidPrintPolicy,
+
+    #[error("Print window has not started yet.")]
+    PrintWindowNotStarted,
+
+    #[error("Print window has ended.")]
+    PrintWindowEnded,
+
+    #[error("This recipient has reached the print limit of the print policy.")]
+    PrintLimitReached,
+
+    #[error("Print policy start time must be before its end time.")]
+    InvalidPrintWindow,
+
+    #[error("Metadata account must be migrated with MigrateMetadata first.")]
+    MetadataNotMigrated,
+
+    #[error("Use restrictions need a valid_from before valid_until and a non-negative cooldown.")]
+    InvalidUseRestrictions,
+
+    #[error("This asset cannot be used yet.")]
+    UseNotYetValid,
+
+    #[error("The uses of this asset have expired.")]
+    UseExpired,
+
+    #[error("The cooldown since the last use has not passed yet.")]
+    UseCooldownActive,
+
+    #[error("Max supply cannot be lower than the number of editions already printed.")]
+    MaxSupplyBelowSupply,
+
+    #[error("Max supply cannot be raised once the primary sale has happened.")]
+    CannotRaiseMaxSupplyAfterPrimarySale,
+
+    #[error("A verified collection must be unverified before it can be removed or replaced.")]
+    VerifiedCollectionMustBeUnverifiedFirst,
+
+    #[error("The rule set of the asset does not allow transfers outside of an allowed program.")]
+    DirectTransferNotAllowedByRuleSet,
+
+    #[error("This metadata delegate was approved by a previous update authority.")]
+    MetadataDelegateApprovedByPreviousAuthority,
 }
 
 impl PrintProgramError for MetadataError {
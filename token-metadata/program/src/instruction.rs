@@ -1,6 +1,9 @@
 use crate::{
     deprecated_instruction::{MintPrintingTokensViaTokenArgs, SetReservationListArgs},
-    state::{Collection, Creator, Data, DataV2, Uses, EDITION, EDITION_MARKER_BIT_SIZE, PREFIX},
+    state::{
+        Collection, Creator, Data, DataV2, MetadataDelegatePermissions, Uses, EDITION,
+        EDITION_MARKER_BIT_SIZE, METADATA_DELEGATE, PREFIX, PRINT_POLICY, RULE_SET,
+    },
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use shank::ShankInstruction;
@@ -74,6 +77,60 @@ pub struct UtilizeArgs {
     pub number_of_uses: u64,
 }
 
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetCollectionSizeArgs {
+    pub size: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ApproveMetadataDelegateArgs {
+    pub permissions: MetadataDelegatePermissions,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SignMetadataBatchArgs {
+    /// true to verify the creator on every metadata account, false to remove the verification.
+    pub verified: bool,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetRuleSetArgs {
+    /// Programs allowed to transfer programmable assets using this rule set through CPI.
+    pub allowed_programs: Vec<Pubkey>,
+    /// Whether owners can transfer programmable assets using this rule set directly.
+    pub allow_direct_transfers: bool,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetPrintPolicyArgs {
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    pub max_per_recipient: Option<u64>,
+    /// Lamports paid per print, split between the creators of the master metadata by share.
+    pub price: Option<u64>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetUseRestrictionsArgs {
+    pub valid_from: Option<i64>,
+    pub valid_until: Option<i64>,
+    /// Minimum number of seconds between two uses.
+    pub cooldown: Option<i64>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct UpdateMasterEditionSupplyArgs {
+    /// New max supply of the master edition, None for an unlimited supply.
+    pub max_supply: Option<u64>,
+}
+
 /// Instructions supported by the Metadata program.
 #[derive(BorshSerialize, BorshDeserialize, Clone, ShankInstruction)]
 #[rustfmt::skip]
@@ -227,6 +284,10 @@ pub enum MetadataInstruction {
     #[account(11, name="token_program", desc="Token program")]
     #[account(12, name="system_program", desc="System program")]
     #[account(13, name="rent", desc="Rent info")]
+    #[account(14, optional, name="print_policy", desc="Print policy (pda of ['metadata', program id, master metadata mint id, 'edition', 'print_policy']), required if the master edition has one")]
+    #[account(15, optional, name="new_token_account", desc="Token account holding the token of the new mint, required with the print policy")]
+    #[account(16, optional, writable, name="print_recipient_record", desc="Print recipient record of the new token owner (pda of ['metadata', program id, master metadata mint id, 'edition', 'print_policy', new token owner]), required with the print policy")]
+    #[account(17, optional, writable, name="creators", desc="Creators of the master metadata in order, required when the print policy has a price")]
     MintNewEditionFromMasterEditionViaToken(MintNewEditionFromMasterEditionViaTokenArgs),
 
     /// Converts the Master Edition V1 to a Master Edition V2, draining lamports from the two printing mints
@@ -255,6 +316,10 @@ pub enum MetadataInstruction {
     #[account(14, name="token_vault_program", desc="Token vault program")]
     #[account(15, name="system_program", desc="System program")]
     #[account(16, name="rent", desc="Rent info")]
+    #[account(17, optional, name="print_policy", desc="Print policy (pda of ['metadata', program id, master metadata mint id, 'edition', 'print_policy']), required if the master edition has one")]
+    #[account(18, optional, name="new_token_account", desc="Token account holding the token of the new mint, required with the print policy")]
+    #[account(19, optional, writable, name="print_recipient_record", desc="Print recipient record of the new token owner (pda of ['metadata', program id, master metadata mint id, 'edition', 'print_policy', new token owner]), required with the print policy")]
+    #[account(20, optional, writable, name="creators", desc="Creators of the master metadata in order, required when the print policy has a price")]
     MintNewEditionFromMasterEditionViaVaultProxy(MintNewEditionFromMasterEditionViaTokenArgs),
 
     /// Puff a Metadata - make all of it's variable length fields (name/uri/symbol) a fixed length using a null character
@@ -262,9 +327,12 @@ pub enum MetadataInstruction {
     #[account(0, writable, name="metadata", desc="Metadata account")]
     PuffMetadata,
 
-    /// Update a Metadata with is_mutable as a parameter
+    /// Update a Metadata with is_mutable as a parameter.
+    /// When a Metadata Delegate Record is passed, the signer is a delegate that can only change the
+    /// fields it was approved for through [ApproveMetadataDelegate].
     #[account(0, writable, name="metadata", desc="Metadata account")]
-    #[account(1, signer, name="update_authority", desc="Update authority key")]
+    #[account(1, signer, name="update_authority", desc="Update authority key, or the delegate when a Metadata Delegate Record is given")]
+    #[account(2, optional, name="metadata_delegate_record", desc="Metadata Delegate Record PDA")]
     UpdateMetadataAccountV2(UpdateMetadataAccountArgsV2),
 
     /// Create Metadata object.
@@ -292,11 +360,12 @@ pub enum MetadataInstruction {
     CreateMasterEditionV3(CreateMasterEditionArgs),
 
     /// If a MetadataAccount Has a Collection allow the UpdateAuthority of the Collection to Verify the NFT Belongs in the Collection.
+    /// If the Collection is sized, its size is incremented.
     #[account(0, writable, name="metadata", desc="Metadata account")]
     #[account(1, signer, name="collection_authority", desc="Collection Update authority")]
     #[account(2, signer, name="payer", desc="payer")]
     #[account(3, name="collection_mint", desc="Mint of the Collection")]
-    #[account(4, name="collection", desc="Metadata Account of the Collection")]
+    #[account(4, writable, name="collection", desc="Metadata Account of the Collection")]
     #[account(5, name="collection_master_edition_account", desc="MasterEdition2 Account of the Collection Token")]
     VerifyCollection,
 
@@ -342,10 +411,11 @@ pub enum MetadataInstruction {
     RevokeUseAuthority,
 
     /// If a MetadataAccount Has a Collection allow an Authority of the Collection to unverify an NFT in a Collection.
+    /// If the Collection is sized, its size is decremented.
     #[account(0, writable, name="metadata", desc="Metadata account")]
     #[account(1, signer, name="collection_authority", desc="Collection Authority")]
     #[account(2, name="collection_mint", desc="Mint of the Collection")]
-    #[account(3, name="collection", desc="Metadata Account of the Collection")]
+    #[account(3, writable, name="collection", desc="Metadata Account of the Collection")]
     #[account(4, name="collection_master_edition_account", desc="MasterEdition2 Account of the Collection Token")]
     #[account(5, optional, name="collection_authority_record", desc="Collection Authority Record PDA")]
     UnverifyCollection,
@@ -375,7 +445,7 @@ pub enum MetadataInstruction {
     #[account(2, signer, name="payer", desc="Payer")]
     #[account(3, name="update_authority", desc="Update Authority of Collection NFT and NFT")]
     #[account(4, name="collection_mint", desc="Mint of the Collection")]
-    #[account(5, name="collection", desc="Metadata Account of the Collection")]
+    #[account(5, writable, name="collection", desc="Metadata Account of the Collection")]
     #[account(6, name="collection_master_edition_account", desc="MasterEdition2 Account of the Collection Token")]
     #[account(7, optional, name="collection_authority_record", desc="Collection Authority Record PDA")]
     SetAndVerifyCollection,
@@ -399,7 +469,141 @@ pub enum MetadataInstruction {
     /// Remove Creator Verificaton.
     #[account(0, writable, name="metadata", desc="Metadata (pda of ['metadata', program id, mint id])")]
     #[account(1, signer, name="creator", desc="Creator")]
-    RemoveCreatorVerification
+    RemoveCreatorVerification,
+
+    /// Completely burn a NFT, including closing the metadata account.
+    #[account(0, writable, name="metadata", desc="Metadata (pda of ['metadata', program id, mint id])")]
+    #[account(1, signer, writable, name="owner", desc="NFT owner")]
+    #[account(2, writable, name="mint", desc="Mint of the NFT")]
+    #[account(3, writable, name="token_account", desc="Token account to close")]
+    #[account(4, writable, name="master_edition_account", desc="MasterEdition2 of the NFT")]
+    #[account(5, name="spl_token_program", desc="SPL Token Program")]
+    #[account(6, optional, writable, name="collection_metadata", desc="Metadata of the Collection, required if the NFT is a verified member of a Collection")]
+    BurnNft,
+
+    /// Completely burn a print edition NFT, closing its metadata and edition accounts and
+    /// clearing its bit in the edition marker of the master edition. The marker stays open and the
+    /// master edition supply is left as is, so the burned edition number can be printed again.
+    #[account(0, writable, name="metadata", desc="Metadata (pda of ['metadata', program id, mint id])")]
+    #[account(1, signer, writable, name="owner", desc="NFT owner")]
+    #[account(2, writable, name="print_edition_mint", desc="Mint of the print edition NFT")]
+    #[account(3, name="master_edition_mint", desc="Mint of the original/master NFT")]
+    #[account(4, writable, name="print_edition_token_account", desc="Token account the print edition NFT is in")]
+    #[account(5, name="master_edition_account", desc="MasterEdition2 of the original NFT")]
+    #[account(6, writable, name="print_edition_account", desc="Print Edition account of the NFT")]
+    #[account(7, writable, name="edition_marker_account", desc="Edition Marker PDA of the NFT")]
+    #[account(8, name="spl_token_program", desc="SPL Token Program")]
+    #[account(9, optional, writable, name="collection_metadata", desc="Metadata of the Collection, required if the print edition is a verified member of a Collection")]
+    BurnEditionNft,
+
+    /// Set the size of an existing Collection, turning it into a sized Collection whose size is
+    /// kept up to date by [verify_collection], [unverify_collection] and [set_and_verify_collection].
+    /// Can only be called once, on a Collection that is not sized yet.
+    #[account(0, writable, name="collection_metadata", desc="Collection Metadata account")]
+    #[account(1, signer, writable, name="collection_authority", desc="Collection Update authority")]
+    #[account(2, name="collection_mint", desc="Mint of the Collection")]
+    #[account(3, optional, name="collection_authority_record", desc="Collection Authority Record PDA")]
+    SetCollectionSize(SetCollectionSizeArgs),
+
+    /// Create or update a rule set, the allow-list of programs that can transfer
+    /// programmable assets pointing at it and whether owners can transfer them directly.
+    #[account(0, writable, name="rule_set", desc="Rule set (pda of ['metadata', program id, 'rule_set', authority])")]
+    #[account(1, signer, name="authority", desc="Authority of the rule set")]
+    #[account(2, signer, writable, name="payer", desc="Payer")]
+    #[account(3, name="system_program", desc="System program")]
+    #[account(4, name="rent", desc="Rent info")]
+    SetRuleSet(SetRuleSetArgs),
+
+    /// Turn a master edition into a programmable asset. Its token account is frozen and can from
+    /// then on only be moved with [Transfer], subject to the given rule set.
+    #[account(0, writable, name="metadata", desc="Metadata (pda of ['metadata', program id, mint id])")]
+    #[account(1, name="edition", desc="MasterEdition2 of the asset, freeze authority of the mint")]
+    #[account(2, name="mint", desc="Mint of the asset")]
+    #[account(3, writable, name="token_account", desc="Token account holding the asset")]
+    #[account(4, signer, name="owner", desc="Owner of the token account")]
+    #[account(5, signer, name="update_authority", desc="Update authority of the metadata")]
+    #[account(6, name="rule_set", desc="Rule set to enforce on transfers")]
+    #[account(7, name="token_program", desc="Token program")]
+    ConvertToProgrammable,
+
+    /// Transfer a programmable asset. When invoked through CPI the calling program must be
+    /// on the allow-list of the asset's rule set, direct calls need the rule set to allow them.
+    /// The destination is left frozen, the emptied source is left thawed so it can be closed.
+    #[account(0, name="metadata", desc="Metadata (pda of ['metadata', program id, mint id])")]
+    #[account(1, signer, name="owner", desc="Owner of the source token account")]
+    #[account(2, writable, name="source", desc="Token account holding the asset")]
+    #[account(3, writable, name="destination", desc="Token account receiving the asset")]
+    #[account(4, name="mint", desc="Mint of the asset")]
+    #[account(5, name="edition", desc="MasterEdition2 of the asset, freeze authority of the mint")]
+    #[account(6, name="rule_set", desc="Rule set of the asset")]
+    #[account(7, name="instructions", desc="Instructions sysvar")]
+    #[account(8, name="token_program", desc="Token program")]
+    Transfer,
+
+    /// Reallocate a Metadata account to the latest versioned layout and stamp its schema version.
+    /// The payer covers the extra rent. Readers checking for exactly MAX_METADATA_LEN no longer
+    /// recognize a migrated account, which is why only the update authority can migrate it.
+    /// Programs built against mpl-token-metadata 1.2.5 or earlier, or metaplex-token-metadata,
+    /// are such readers and fail on migrated NFTs until they read through this version of
+    /// Metadata::from_account_info, as the auction house and candy machine here already do.
+    #[account(0, writable, name="metadata", desc="Metadata (pda of ['metadata', program id, mint id])")]
+    #[account(1, signer, name="update_authority", desc="Update authority of the Metadata")]
+    #[account(2, signer, writable, name="payer", desc="Payer of the extra rent")]
+    #[account(3, name="system_program", desc="System program")]
+    MigrateMetadata,
+
+    /// Approve another account to update a subset of the Metadata fields through [UpdateMetadataAccountV2].
+    /// The approval lapses when the update authority changes, the new one can approve the delegate again.
+    #[account(0, writable, name="metadata_delegate_record", desc="Metadata Delegate Record PDA (pda of ['metadata', program id, mint id, 'metadata_delegate', delegate])")]
+    #[account(1, name="delegate", desc="Metadata delegate")]
+    #[account(2, signer, writable, name="update_authority", desc="Update authority of the Metadata")]
+    #[account(3, signer, writable, name="payer", desc="Payer")]
+    #[account(4, name="metadata", desc="Metadata account")]
+    #[account(5, name="mint", desc="Mint of the Metadata")]
+    #[account(6, name="system_program", desc="System program")]
+    #[account(7, name="rent", desc="Rent info")]
+    ApproveMetadataDelegate(ApproveMetadataDelegateArgs),
+
+    /// Revoke a metadata delegate, closing its record.
+    #[account(0, writable, name="metadata_delegate_record", desc="Metadata Delegate Record PDA")]
+    #[account(1, name="delegate", desc="Metadata delegate")]
+    #[account(2, signer, writable, name="update_authority", desc="Update authority of the Metadata")]
+    #[account(3, name="metadata", desc="Metadata account")]
+    #[account(4, name="mint", desc="Mint of the Metadata")]
+    RevokeMetadataDelegate,
+
+    /// Verify or unverify the signing creator on many metadata accounts at once, see [SignMetadata]
+    /// and [RemoveCreatorVerification]. Fails as a whole with the error of the first metadata account
+    /// that fails, such as `CreatorNotFound`, and logs the index of that account.
+    #[account(0, signer, name="creator", desc="Creator")]
+    #[account(1, writable, name="metadata", desc="Metadata accounts (pda of ['metadata', program id, mint id]), any number of them from here on")]
+    SignMetadataBatch(SignMetadataBatchArgs),
+
+    /// Create, replace or remove the print policy of a master edition. Once set, every print minted
+    /// from the master edition is checked against its mint window and per-recipient cap, and pays its
+    /// price to the creators. Passing no rules closes the print policy account.
+    #[account(0, writable, name="print_policy", desc="Print policy (pda of ['metadata', program id, master metadata mint id, 'edition', 'print_policy'])")]
+    #[account(1, writable, name="master_edition", desc="Master Record Edition V2 (pda of ['metadata', program id, master metadata mint id, 'edition'])")]
+    #[account(2, name="metadata", desc="Master record metadata account")]
+    #[account(3, signer, name="update_authority", desc="Update authority of the master metadata")]
+    #[account(4, signer, writable, name="payer", desc="Payer")]
+    #[account(5, name="system_program", desc="System program")]
+    #[account(6, name="rent", desc="Rent info")]
+    SetPrintPolicy(SetPrintPolicyArgs),
+
+    /// Set the validity window and cooldown that [Utilize] enforces on the uses of an asset, for the
+    /// owner and use authorities alike. Passing no restrictions clears them. The Metadata must be
+    /// migrated with [MigrateMetadata] first.
+    #[account(0, writable, name="metadata", desc="Metadata (pda of ['metadata', program id, mint id])")]
+    #[account(1, signer, name="update_authority", desc="Update authority of the Metadata")]
+    SetUseRestrictions(SetUseRestrictionsArgs),
+
+    /// Change the max supply of a master edition. The new max supply cannot be lower than the
+    /// current supply, and can only be raised or removed before the primary sale has happened.
+    #[account(0, writable, name="master_edition", desc="Master Record Edition V2 (pda of ['metadata', program id, master metadata mint id, 'edition'])")]
+    #[account(1, name="metadata", desc="Master record metadata account")]
+    #[account(2, signer, name="update_authority", desc="Update authority of the master metadata")]
+    UpdateMasterEditionSupply(UpdateMasterEditionSupplyArgs),
 }
 
 /// Creates an CreateMetadataAccounts instruction
@@ -546,6 +750,41 @@ pub fn update_metadata_accounts_v2(
     }
 }
 
+///# Update Metadata Accounts V2 As Delegate
+///
+///Update the fields of a Metadata a delegate was approved for. All other fields of `data` must
+///match the current Metadata.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[signer]` Metadata delegate
+///   2. `[]` Metadata Delegate Record PDA
+pub fn update_metadata_accounts_v2_as_delegate(
+    program_id: Pubkey,
+    metadata_account: Pubkey,
+    delegate: Pubkey,
+    metadata_delegate_record: Pubkey,
+    data: DataV2,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata_account, false),
+            AccountMeta::new_readonly(delegate, true),
+            AccountMeta::new_readonly(metadata_delegate_record, false),
+        ],
+        data: MetadataInstruction::UpdateMetadataAccountV2(UpdateMetadataAccountArgsV2 {
+            data: Some(data),
+            update_authority: None,
+            primary_sale_happened: None,
+            is_mutable: None,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
 /// puff metadata account instruction
 pub fn puff_metadata_account(program_id: Pubkey, metadata_account: Pubkey) -> Instruction {
     Instruction {
@@ -816,7 +1055,7 @@ pub fn mint_edition_from_master_edition_via_vault_proxy(
 ///   1. `[signer]` Collection Update authority
 ///   2. `[signer]` payer
 ///   3. `[]` Mint of the Collection
-///   4. `[]` Metadata Account of the Collection
+///   4. `[writable]` Metadata Account of the Collection
 ///   5. `[]` MasterEdition2 Account of the Collection Token
 #[allow(clippy::too_many_arguments)]
 pub fn verify_collection(
@@ -834,7 +1073,7 @@ pub fn verify_collection(
         AccountMeta::new(collection_authority, true),
         AccountMeta::new(payer, true),
         AccountMeta::new_readonly(collection_mint, false),
-        AccountMeta::new_readonly(collection, false),
+        AccountMeta::new(collection, false),
         AccountMeta::new_readonly(collection_master_edition_account, false),
     ];
 
@@ -861,7 +1100,7 @@ pub fn verify_collection(
 ///   1. `[signer]` Collection Authority
 ///   2. `[signer]` payer
 ///   3. `[]` Mint of the Collection
-///   4. `[]` Metadata Account of the Collection
+///   4. `[writable]` Metadata Account of the Collection
 ///   5. `[]` MasterEdition2 Account of the Collection Token
 #[allow(clippy::too_many_arguments)]
 pub fn unverify_collection(
@@ -877,7 +1116,7 @@ pub fn unverify_collection(
         AccountMeta::new(metadata, false),
         AccountMeta::new(collection_authority, true),
         AccountMeta::new_readonly(collection_mint, false),
-        AccountMeta::new_readonly(collection, false),
+        AccountMeta::new(collection, false),
         AccountMeta::new_readonly(collection_master_edition_account, false),
     ];
 
@@ -1141,7 +1380,7 @@ pub fn revoke_collection_authority(
 ///   2. `[signer]` payer
 ///   3. `[] Update Authority of Collection NFT and NFT
 ///   3. `[]` Mint of the Collection
-///   4. `[]` Metadata Account of the Collection
+///   4. `[writable]` Metadata Account of the Collection
 ///   5. `[]` MasterEdition2 Account of the Collection Token
 #[allow(clippy::too_many_arguments)]
 pub fn set_and_verify_collection(
@@ -1161,7 +1400,7 @@ pub fn set_and_verify_collection(
         AccountMeta::new(payer, true),
         AccountMeta::new_readonly(update_authority, false),
         AccountMeta::new_readonly(collection_mint, false),
-        AccountMeta::new_readonly(collection, false),
+        AccountMeta::new(collection, false),
         AccountMeta::new_readonly(collection_master_edition_account, false),
     ];
 
@@ -1243,3 +1482,626 @@ pub fn thaw_delegated_account(
             .unwrap(),
     }
 }
+
+///# Burn NFT
+///
+///Burn an NFT, closing its token account, metadata account and master edition account.
+///Lamports of the closed accounts are returned to the owner.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[signer, writable]` NFT owner
+///   2. `[writable]` Mint of the NFT
+///   3. `[writable]` Token account to close
+///   4. `[writable]` MasterEdition2 of the NFT
+///   5. `[]` SPL Token Program
+///   6. Optional `[writable]` Metadata of the Collection, required if the NFT is a verified member of a Collection
+#[allow(clippy::too_many_arguments)]
+pub fn burn_nft(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    owner: Pubkey,
+    mint: Pubkey,
+    token: Pubkey,
+    edition: Pubkey,
+    spl_token: Pubkey,
+    collection_metadata: Option<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(metadata, false),
+        AccountMeta::new(owner, true),
+        AccountMeta::new(mint, false),
+        AccountMeta::new(token, false),
+        AccountMeta::new(edition, false),
+        AccountMeta::new_readonly(spl_token, false),
+    ];
+
+    if let Some(collection_metadata) = collection_metadata {
+        accounts.push(AccountMeta::new(collection_metadata, false));
+    }
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::BurnNft.try_to_vec().unwrap(),
+    }
+}
+
+///# Burn Edition NFT
+///
+///Burn a print edition NFT, closing its token account, metadata account and edition account and
+///clearing the edition's bit in the edition marker. Lamports of the closed accounts are returned to
+///the owner. The edition marker stays open and the supply of the master edition is unchanged, so the
+///burned edition number can be printed again.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[signer, writable]` NFT owner
+///   2. `[writable]` Mint of the print edition NFT
+///   3. `[]` Mint of the original/master NFT
+///   4. `[writable]` Token account the print edition NFT is in
+///   5. `[]` MasterEdition2 of the original NFT
+///   6. `[writable]` Print Edition account of the NFT
+///   7. `[writable]` Edition Marker PDA of the NFT
+///   8. `[]` SPL Token Program
+///   9. Optional `[writable]` Metadata of the Collection, required if the print edition is a verified member of a Collection
+#[allow(clippy::too_many_arguments)]
+pub fn burn_edition_nft(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    owner: Pubkey,
+    print_edition_mint: Pubkey,
+    master_edition_mint: Pubkey,
+    print_edition_token: Pubkey,
+    master_edition: Pubkey,
+    print_edition: Pubkey,
+    edition_marker: Pubkey,
+    spl_token: Pubkey,
+    collection_metadata: Option<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(metadata, false),
+        AccountMeta::new(owner, true),
+        AccountMeta::new(print_edition_mint, false),
+        AccountMeta::new_readonly(master_edition_mint, false),
+        AccountMeta::new(print_edition_token, false),
+        AccountMeta::new_readonly(master_edition, false),
+        AccountMeta::new(print_edition, false),
+        AccountMeta::new(edition_marker, false),
+        AccountMeta::new_readonly(spl_token, false),
+    ];
+
+    if let Some(collection_metadata) = collection_metadata {
+        accounts.push(AccountMeta::new(collection_metadata, false));
+    }
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::BurnEditionNft.try_to_vec().unwrap(),
+    }
+}
+
+///# Set Collection Size
+///
+///Set the size of an existing Collection, turning it into a sized Collection. Can only be called once.
+///
+///### Accounts:
+///
+///   0. `[writable]` Collection Metadata account
+///   1. `[signer, writable]` Collection Update authority
+///   2. `[]` Mint of the Collection
+///   3. Optional `[]` Collection Authority Record PDA
+#[allow(clippy::too_many_arguments)]
+pub fn set_collection_size(
+    program_id: Pubkey,
+    metadata_account: Pubkey,
+    update_authority: Pubkey,
+    mint: Pubkey,
+    collection_authority_record: Option<Pubkey>,
+    size: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(metadata_account, false),
+        AccountMeta::new(update_authority, true),
+        AccountMeta::new_readonly(mint, false),
+    ];
+
+    if let Some(record) = collection_authority_record {
+        accounts.push(AccountMeta::new_readonly(record, false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::SetCollectionSize(SetCollectionSizeArgs { size })
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Set Rule Set
+///
+///Create or update the rule set of an authority, the allow-list of programs that can transfer
+///programmable assets pointing at it and whether owners can transfer them directly.
+///
+///### Accounts:
+///
+///   0. `[writable]` Rule set PDA
+///   1. `[signer]` Authority of the rule set
+///   2. `[signer, writable]` Payer
+///   3. `[]` System program
+///   4. `[]` Rent info
+pub fn set_rule_set(
+    program_id: Pubkey,
+    authority: Pubkey,
+    payer: Pubkey,
+    allowed_programs: Vec<Pubkey>,
+    allow_direct_transfers: bool,
+) -> Instruction {
+    let (rule_set, _) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            RULE_SET.as_bytes(),
+            authority.as_ref(),
+        ],
+        &program_id,
+    );
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(rule_set, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: MetadataInstruction::SetRuleSet(SetRuleSetArgs {
+            allowed_programs,
+            allow_direct_transfers,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+///# Convert To Programmable
+///
+///Turn a master edition into a programmable asset, freezing its token account.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[]` MasterEdition2 of the asset
+///   2. `[]` Mint of the asset
+///   3. `[writable]` Token account holding the asset
+///   4. `[signer]` Owner of the token account
+///   5. `[signer]` Update authority of the metadata
+///   6. `[]` Rule set to enforce on transfers
+///   7. `[]` Token program
+#[allow(clippy::too_many_arguments)]
+pub fn convert_to_programmable(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    edition: Pubkey,
+    mint: Pubkey,
+    token_account: Pubkey,
+    owner: Pubkey,
+    update_authority: Pubkey,
+    rule_set: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata, false),
+            AccountMeta::new_readonly(edition, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(token_account, false),
+            AccountMeta::new_readonly(owner, true),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new_readonly(rule_set, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: MetadataInstruction::ConvertToProgrammable
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Transfer
+///
+///Transfer a programmable asset. When invoked through CPI, the calling program must be on the
+///allow-list of the asset's rule set. When called directly, the rule set must allow direct transfers.
+///
+///### Accounts:
+///
+///   0. `[]` Metadata account
+///   1. `[signer]` Owner of the source token account
+///   2. `[writable]` Token account holding the asset
+///   3. `[writable]` Token account receiving the asset
+///   4. `[]` Mint of the asset
+///   5. `[]` MasterEdition2 of the asset
+///   6. `[]` Rule set of the asset
+///   7. `[]` Instructions sysvar
+///   8. `[]` Token program
+#[allow(clippy::too_many_arguments)]
+pub fn transfer(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    owner: Pubkey,
+    source: Pubkey,
+    destination: Pubkey,
+    mint: Pubkey,
+    edition: Pubkey,
+    rule_set: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(owner, true),
+            AccountMeta::new(source, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(edition, false),
+            AccountMeta::new_readonly(rule_set, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: MetadataInstruction::Transfer.try_to_vec().unwrap(),
+    }
+}
+
+///# Migrate Metadata
+///
+///Reallocate a Metadata account to the latest versioned layout, the payer covers the extra rent.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[signer]` Update authority
+///   2. `[signer, writable]` Payer
+///   3. `[]` System program
+pub fn migrate_metadata(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: MetadataInstruction::MigrateMetadata.try_to_vec().unwrap(),
+    }
+}
+
+///# Approve Metadata Delegate
+///
+///Approve another account to update a subset of the Metadata fields through [update_metadata_accounts_v2_as_delegate]
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata Delegate Record PDA
+///   1. `[]` Metadata delegate
+///   2. `[signer, writable]` Update authority of the Metadata
+///   3. `[signer, writable]` Payer
+///   4. `[]` Metadata account
+///   5. `[]` Mint of the Metadata
+///   6. `[]` System program
+///   7. `[]` Rent info
+#[allow(clippy::too_many_arguments)]
+pub fn approve_metadata_delegate(
+    program_id: Pubkey,
+    delegate: Pubkey,
+    update_authority: Pubkey,
+    payer: Pubkey,
+    metadata: Pubkey,
+    mint: Pubkey,
+    permissions: MetadataDelegatePermissions,
+) -> Instruction {
+    let (metadata_delegate_record, _) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            mint.as_ref(),
+            METADATA_DELEGATE.as_bytes(),
+            delegate.as_ref(),
+        ],
+        &program_id,
+    );
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata_delegate_record, false),
+            AccountMeta::new_readonly(delegate, false),
+            AccountMeta::new(update_authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: MetadataInstruction::ApproveMetadataDelegate(ApproveMetadataDelegateArgs {
+            permissions,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+///# Revoke Metadata Delegate
+///
+///Revoke a metadata delegate, closing its record and returning the rent to the update authority
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata Delegate Record PDA
+///   1. `[]` Metadata delegate
+///   2. `[signer, writable]` Update authority of the Metadata
+///   3. `[]` Metadata account
+///   4. `[]` Mint of the Metadata
+pub fn revoke_metadata_delegate(
+    program_id: Pubkey,
+    metadata_delegate_record: Pubkey,
+    delegate: Pubkey,
+    update_authority: Pubkey,
+    metadata: Pubkey,
+    mint: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata_delegate_record, false),
+            AccountMeta::new_readonly(delegate, false),
+            AccountMeta::new(update_authority, true),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(mint, false),
+        ],
+        data: MetadataInstruction::RevokeMetadataDelegate
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Sign Metadata Batch
+///
+///Verify or unverify the signing creator on every given metadata account
+///
+///### Accounts:
+///
+///   0. `[signer]` Creator
+///   1..n. `[writable]` Metadata accounts
+pub fn sign_metadata_batch(
+    program_id: Pubkey,
+    creator: Pubkey,
+    metadata_accounts: Vec<Pubkey>,
+    verified: bool,
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(creator, true)];
+    accounts.extend(
+        metadata_accounts
+            .into_iter()
+            .map(|metadata| AccountMeta::new(metadata, false)),
+    );
+
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::SignMetadataBatch(SignMetadataBatchArgs { verified })
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Set Print Policy
+///
+///Create, replace or remove the print policy of a master edition, passing no rules removes it
+///
+///### Accounts:
+///
+///   0. `[writable]` Print Policy PDA
+///   1. `[writable]` Master Edition V2
+///   2. `[]` Master Metadata account
+///   3. `[signer]` Update authority of the master metadata
+///   4. `[signer, writable]` Payer
+///   5. `[]` System program
+///   6. `[]` Rent info
+#[allow(clippy::too_many_arguments)]
+pub fn set_print_policy(
+    program_id: Pubkey,
+    master_edition: Pubkey,
+    metadata: Pubkey,
+    metadata_mint: Pubkey,
+    update_authority: Pubkey,
+    payer: Pubkey,
+    start_time: Option<i64>,
+    end_time: Option<i64>,
+    max_per_recipient: Option<u64>,
+    price: Option<u64>,
+) -> Instruction {
+    let (print_policy, _) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            metadata_mint.as_ref(),
+            EDITION.as_bytes(),
+            PRINT_POLICY.as_bytes(),
+        ],
+        &program_id,
+    );
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(print_policy, false),
+            AccountMeta::new(master_edition, false),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: MetadataInstruction::SetPrintPolicy(SetPrintPolicyArgs {
+            start_time,
+            end_time,
+            max_per_recipient,
+            price,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+///# Mint New Edition From Master Edition Via Token With Print Policy
+///
+///Same as [mint_new_edition_from_master_edition_via_token], for master editions with a print policy
+///
+///### Accounts:
+///
+///   0-13. Same as [mint_new_edition_from_master_edition_via_token]
+///   14. `[]` Print Policy PDA
+///   15. `[]` Token account holding the token of the new mint
+///   16. `[writable]` Print Recipient Record PDA of the new token owner
+///   17..n. `[writable]` Creators of the master metadata, in order
+#[allow(clippy::too_many_arguments)]
+pub fn mint_new_edition_from_master_edition_via_token_with_print_policy(
+    program_id: Pubkey,
+    new_metadata: Pubkey,
+    new_edition: Pubkey,
+    master_edition: Pubkey,
+    new_mint: Pubkey,
+    new_mint_authority: Pubkey,
+    payer: Pubkey,
+    token_account_owner: Pubkey,
+    token_account: Pubkey,
+    new_metadata_update_authority: Pubkey,
+    metadata: Pubkey,
+    metadata_mint: Pubkey,
+    edition: u64,
+    new_token_account: Pubkey,
+    new_token_owner: Pubkey,
+    creators: Vec<Pubkey>,
+) -> Instruction {
+    let mut instruction = mint_new_edition_from_master_edition_via_token(
+        program_id,
+        new_metadata,
+        new_edition,
+        master_edition,
+        new_mint,
+        new_mint_authority,
+        payer,
+        token_account_owner,
+        token_account,
+        new_metadata_update_authority,
+        metadata,
+        metadata_mint,
+        edition,
+    );
+    let (print_policy, _) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            metadata_mint.as_ref(),
+            EDITION.as_bytes(),
+            PRINT_POLICY.as_bytes(),
+        ],
+        &program_id,
+    );
+    let (print_recipient_record, _) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            metadata_mint.as_ref(),
+            EDITION.as_bytes(),
+            PRINT_POLICY.as_bytes(),
+            new_token_owner.as_ref(),
+        ],
+        &program_id,
+    );
+
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(print_policy, false));
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(new_token_account, false));
+    instruction
+        .accounts
+        .push(AccountMeta::new(print_recipient_record, false));
+    instruction.accounts.extend(
+        creators
+            .into_iter()
+            .map(|creator| AccountMeta::new(creator, false)),
+    );
+
+    instruction
+}
+
+///# Set Use Restrictions
+///
+///Set the validity window and cooldown of the uses of an asset
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[signer]` Update authority of the Metadata
+pub fn set_use_restrictions(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+    valid_from: Option<i64>,
+    valid_until: Option<i64>,
+    cooldown: Option<i64>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+        ],
+        data: MetadataInstruction::SetUseRestrictions(SetUseRestrictionsArgs {
+            valid_from,
+            valid_until,
+            cooldown,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+///# Update Master Edition Supply
+///
+///Change the max supply of a master edition
+///
+///### Accounts:
+///
+///   0. `[writable]` Master Edition V2
+///   1. `[]` Master Metadata account
+///   2. `[signer]` Update authority of the master metadata
+pub fn update_master_edition_supply(
+    program_id: Pubkey,
+    master_edition: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+    max_supply: Option<u64>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(master_edition, false),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+        ],
+        data: MetadataInstruction::UpdateMasterEditionSupply(UpdateMasterEditionSupplyArgs {
+            max_supply,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
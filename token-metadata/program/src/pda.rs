@@ -1,6 +1,8 @@
 use solana_program::pubkey::Pubkey;
 
-use crate::state::{BURN, COLLECTION_AUTHORITY, EDITION, PREFIX, USER};
+use crate::state::{
+    BURN, COLLECTION_AUTHORITY, EDITION, METADATA_DELEGATE, PREFIX, PRINT_POLICY, RULE_SET, USER,
+};
 
 pub fn find_edition_account(mint: &Pubkey, edition_number: String) -> (Pubkey, u8) {
     Pubkey::find_program_address(
@@ -65,3 +67,55 @@ pub fn find_program_as_burner_account() -> (Pubkey, u8) {
         &crate::id(),
     )
 }
+
+pub fn find_rule_set_account(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            crate::id().as_ref(),
+            RULE_SET.as_bytes(),
+            authority.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_metadata_delegate_account(mint: &Pubkey, delegate: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            crate::id().as_ref(),
+            mint.as_ref(),
+            METADATA_DELEGATE.as_bytes(),
+            delegate.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_print_policy_account(master_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            crate::id().as_ref(),
+            master_mint.as_ref(),
+            EDITION.as_bytes(),
+            PRINT_POLICY.as_bytes(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_print_recipient_record_account(master_mint: &Pubkey, recipient: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            crate::id().as_ref(),
+            master_mint.as_ref(),
+            EDITION.as_bytes(),
+            PRINT_POLICY.as_bytes(),
+            recipient.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
@@ -5,7 +5,11 @@ use crate::{
             assert_collection_update_is_valid, assert_collection_verify_is_valid,
             assert_has_collection_authority,
         },
-        uses::process_use_authority_validation,
+        metadata_delegate::{assert_is_metadata_delegate, assert_metadata_delegate_update_is_valid},
+        uses::{
+            assert_use_allowed_at, assert_valid_use, assert_valid_use_restrictions,
+            process_use_authority_validation,
+        },
     },
     deprecated_processor::{
         process_deprecated_create_metadata_accounts, process_deprecated_update_metadata_accounts,
@@ -13,10 +17,14 @@ use crate::{
     error::MetadataError,
     instruction::MetadataInstruction,
     state::{
-        Collection, CollectionAuthorityRecord, DataV2, Key, MasterEditionV1, MasterEditionV2,
-        Metadata, TokenStandard, UseAuthorityRecord, UseMethod, Uses, BURN, COLLECTION_AUTHORITY,
-        COLLECTION_AUTHORITY_RECORD_SIZE, EDITION, MAX_MASTER_EDITION_LEN, PREFIX, USER,
-        USE_AUTHORITY_RECORD_SIZE, EDITION_MARKER_BIT_SIZE
+        Collection, CollectionAuthorityRecord, CollectionDetails, DataV2, Edition, EditionMarker, Key,
+        MasterEditionV1, MasterEditionV2, Metadata, MetadataDelegatePermissions,
+        MetadataDelegateRecord, PrintPolicy, RuleSet, TokenStandard, UseAuthorityRecord, UseMethod,
+        UseRestrictions, Uses, BURN, COLLECTION_AUTHORITY, COLLECTION_AUTHORITY_RECORD_SIZE, EDITION,
+        MAX_MASTER_EDITION_LEN, MAX_METADATA_LEN_V1, MAX_RULE_SET_LEN, MAX_RULE_SET_PROGRAMS,
+        MAX_URI_LENGTH, METADATA_DELEGATE, METADATA_DELEGATE_RECORD_SIZE, METADATA_SCHEMA_VERSION,
+        PREFIX, PRINT_POLICY, PRINT_POLICY_SIZE, RULE_SET, USER, USE_AUTHORITY_RECORD_SIZE,
+        EDITION_MARKER_BIT_SIZE
     },
     solana_program::{
         program_memory::{ sol_memset},
@@ -25,7 +33,8 @@ use crate::{
         assert_currently_holding, assert_data_valid, assert_derivation, assert_initialized,
         assert_mint_authority_matches_mint, assert_owned_by, assert_signer, assert_delegated_tokens,
         assert_token_program_matches_package, assert_update_authority_is_correct, assert_freeze_authority_matches_mint,
-        create_or_allocate_account_raw, get_owner_from_token_account,
+        close_program_account, create_or_allocate_account_raw, decrement_collection_size, freeze,
+        get_owner_from_token_account, increment_collection_size, thaw,
         process_create_metadata_accounts_logic,
         process_mint_new_edition_from_master_edition_via_token_logic, puff_out_data_fields,
         spl_token_burn, transfer_mint_authority, CreateMetadataAccountsLogicArgs,
@@ -42,6 +51,8 @@ use solana_program::{
     program::{invoke, invoke_signed},
     program_error::ProgramError,
     pubkey::Pubkey,
+    system_instruction,
+    sysvar::{self, clock::Clock, instructions::get_instruction_relative, rent::Rent, Sysvar},
 };
 use spl_token::{
     instruction::{approve, revoke, freeze_account, thaw_account},
@@ -207,6 +218,76 @@ pub fn process_instruction<'a>(
             msg!("Instruction: Thaw Delegated Account");
             process_thaw_delegated_account(program_id, accounts)
         }
+        MetadataInstruction::BurnNft => {
+            msg!("Instruction: Burn NFT");
+            process_burn_nft(program_id, accounts)
+        }
+        MetadataInstruction::BurnEditionNft => {
+            msg!("Instruction: Burn Edition NFT");
+            process_burn_edition_nft(program_id, accounts)
+        }
+        MetadataInstruction::SetCollectionSize(args) => {
+            msg!("Instruction: Set Collection Size");
+            process_set_collection_size(program_id, accounts, args.size)
+        }
+        MetadataInstruction::SetRuleSet(args) => {
+            msg!("Instruction: Set Rule Set");
+            process_set_rule_set(
+                program_id,
+                accounts,
+                args.allowed_programs,
+                args.allow_direct_transfers,
+            )
+        }
+        MetadataInstruction::ConvertToProgrammable => {
+            msg!("Instruction: Convert To Programmable");
+            process_convert_to_programmable(program_id, accounts)
+        }
+        MetadataInstruction::Transfer => {
+            msg!("Instruction: Transfer");
+            process_transfer(program_id, accounts)
+        }
+        MetadataInstruction::MigrateMetadata => {
+            msg!("Instruction: Migrate Metadata");
+            process_migrate_metadata(program_id, accounts)
+        }
+        MetadataInstruction::ApproveMetadataDelegate(args) => {
+            msg!("Instruction: Approve Metadata Delegate");
+            process_approve_metadata_delegate(program_id, accounts, args.permissions)
+        }
+        MetadataInstruction::RevokeMetadataDelegate => {
+            msg!("Instruction: Revoke Metadata Delegate");
+            process_revoke_metadata_delegate(program_id, accounts)
+        }
+        MetadataInstruction::SignMetadataBatch(args) => {
+            msg!("Instruction: Sign Metadata Batch");
+            process_sign_metadata_batch(program_id, accounts, args.verified)
+        }
+        MetadataInstruction::SetPrintPolicy(args) => {
+            msg!("Instruction: Set Print Policy");
+            process_set_print_policy(
+                program_id,
+                accounts,
+                args.start_time,
+                args.end_time,
+                args.max_per_recipient,
+                args.price,
+            )
+        }
+        MetadataInstruction::SetUseRestrictions(args) => {
+            msg!("Instruction: Set Use Restrictions");
+            process_set_use_restrictions(
+                program_id,
+                accounts,
+                args.valid_from,
+                args.valid_until,
+                args.cooldown,
+            )
+        }
+        MetadataInstruction::UpdateMasterEditionSupply(args) => {
+            msg!("Instruction: Update Master Edition Supply");
+            process_update_master_edition_supply(program_id, accounts, args.max_supply)
+        }
     }
 }
 
@@ -258,9 +339,27 @@ pub fn process_update_metadata_accounts_v2(
 
     let metadata_account_info = next_account_info(account_info_iter)?;
     let update_authority_info = next_account_info(account_info_iter)?;
+    let metadata_delegate_record_info = next_account_info(account_info_iter).ok();
     let mut metadata = Metadata::from_account_info(metadata_account_info)?;
 
     assert_owned_by(metadata_account_info, program_id)?;
+
+    if let Some(metadata_delegate_record_info) = metadata_delegate_record_info {
+        // Delegates only get to change the data fields they were approved for.
+        if update_authority.is_some() || primary_sale_happened.is_some() || is_mutable.is_some() {
+            return Err(MetadataError::MetadataDelegateScopeViolation.into());
+        }
+        let data = optional_data.ok_or(MetadataError::MetadataDelegateScopeViolation)?;
+        return update_metadata_as_delegate(
+            program_id,
+            metadata_account_info,
+            update_authority_info,
+            metadata_delegate_record_info,
+            metadata,
+            data,
+        );
+    }
+
     assert_update_authority_is_correct(&metadata, update_authority_info)?;
 
     if let Some(data) = optional_data {
@@ -304,9 +403,46 @@ pub fn process_update_metadata_accounts_v2(
 
     puff_out_data_fields(&mut metadata);
 
-    metadata.serialize(&mut *metadata_account_info.try_borrow_mut_data()?)?;
+    metadata.save(metadata_account_info)?;
+    Ok(())
+}
+fn update_metadata_as_delegate(
+    program_id: &Pubkey,
+    metadata_account_info: &AccountInfo,
+    delegate_info: &AccountInfo,
+    metadata_delegate_record_info: &AccountInfo,
+    mut metadata: Metadata,
+    data: DataV2,
+) -> ProgramResult {
+    assert_signer(delegate_info)?;
+    let record = assert_is_metadata_delegate(
+        program_id,
+        metadata_delegate_record_info,
+        delegate_info.key,
+        &metadata.mint,
+        &metadata.update_authority,
+    )?;
+
+    if !metadata.is_mutable {
+        return Err(MetadataError::DataIsImmutable.into());
+    }
+    assert_metadata_delegate_update_is_valid(&record.permissions, &metadata, &data)?;
+
+    if data.uri.len() > MAX_URI_LENGTH {
+        return Err(MetadataError::UriTooLong.into());
+    }
+    metadata.data.uri = data.uri;
+    assert_collection_update_is_valid(false, &metadata.collection, &data.collection)?;
+    metadata.collection = data.collection;
+    assert_valid_use(&data.uses, &metadata.uses)?;
+    metadata.uses = data.uses;
+
+    puff_out_data_fields(&mut metadata);
+
+    metadata.save(metadata_account_info)?;
     Ok(())
 }
+
 pub fn process_update_primary_sale_happened_via_token(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -340,7 +476,7 @@ pub fn process_update_primary_sale_happened_via_token(
     }
 
     metadata.primary_sale_happened = true;
-    metadata.serialize(&mut *metadata_account_info.try_borrow_mut_data()?)?;
+    metadata.save(metadata_account_info)?;
 
     Ok(())
 }
@@ -352,28 +488,7 @@ pub fn process_sign_metadata(program_id: &Pubkey, accounts: &[AccountInfo]) -> P
     let creator_info = next_account_info(account_info_iter)?;
 
     assert_signer(creator_info)?;
-    assert_owned_by(metadata_info, program_id)?;
-
-    let mut metadata = Metadata::from_account_info(metadata_info)?;
-
-    if let Some(creators) = &mut metadata.data.creators {
-        let mut found = false;
-        for creator in creators {
-            if creator.address == *creator_info.key {
-                creator.verified = true;
-                found = true;
-                break;
-            }
-        }
-        if !found {
-            return Err(MetadataError::CreatorNotFound.into());
-        }
-    } else {
-        return Err(MetadataError::NoCreatorsPresentOnMetadata.into());
-    }
-    metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
-
-    Ok(())
+    set_creator_verification(program_id, metadata_info, creator_info, true)
 }
 
 pub fn process_remove_creator_verification(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
@@ -383,6 +498,45 @@ pub fn process_remove_creator_verification(program_id: &Pubkey, accounts: &[Acco
     let creator_info = next_account_info(account_info_iter)?;
 
     assert_signer(creator_info)?;
+    set_creator_verification(program_id, metadata_info, creator_info, false)
+}
+
+pub fn process_sign_metadata_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    verified: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let creator_info = next_account_info(account_info_iter)?;
+    assert_signer(creator_info)?;
+
+    let metadata_infos = account_info_iter.as_slice();
+    if metadata_infos.is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    for (index, metadata_info) in metadata_infos.iter().enumerate() {
+        if let Err(err) = set_creator_verification(program_id, metadata_info, creator_info, verified)
+        {
+            msg!(
+                "Failed to update creator verification of metadata {} at index {}",
+                metadata_info.key,
+                index
+            );
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+fn set_creator_verification(
+    program_id: &Pubkey,
+    metadata_info: &AccountInfo,
+    creator_info: &AccountInfo,
+    verified: bool,
+) -> ProgramResult {
     assert_owned_by(metadata_info, program_id)?;
 
     let mut metadata = Metadata::from_account_info(metadata_info)?;
@@ -391,7 +545,7 @@ pub fn process_remove_creator_verification(program_id: &Pubkey, accounts: &[Acco
         let mut found = false;
         for creator in creators {
             if creator.address == *creator_info.key {
-                creator.verified = false;
+                creator.verified = verified;
                 found = true;
                 break;
             }
@@ -402,7 +556,7 @@ pub fn process_remove_creator_verification(program_id: &Pubkey, accounts: &[Acco
     } else {
         return Err(MetadataError::NoCreatorsPresentOnMetadata.into());
     }
-    metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
+    metadata.save(metadata_info)?;
 
     Ok(())
 }
@@ -485,7 +639,7 @@ pub fn process_create_master_edition(
     if metadata_account_info.is_writable {
         let mut metadata_mut = Metadata::from_account_info(metadata_account_info)?;
         metadata_mut.token_standard = Some(TokenStandard::NonFungible);
-        metadata_mut.serialize(&mut *metadata_account_info.try_borrow_mut_data()?)?;
+        metadata_mut.save(metadata_account_info)?;
     }
 
     // While you can't mint any more of your master record, you can
@@ -541,6 +695,7 @@ pub fn process_mint_new_edition_from_master_edition_via_token<'a>(
             token_program_account_info,
             system_account_info,
             rent_info,
+            print_policy_accounts: account_info_iter.as_slice(),
         },
         edition,
         ignore_owner_signer,
@@ -699,6 +854,7 @@ pub fn process_mint_new_edition_from_master_edition_via_vault_proxy<'a>(
         token_program_account_info,
         system_account_info,
         rent_info,
+        print_policy_accounts: account_info_iter.as_slice(),
     };
 
     process_mint_new_edition_from_master_edition_via_token_logic(program_id, args, edition, true)
@@ -728,7 +884,7 @@ pub fn process_puff_metadata_account(
     let (_, edition_bump_seed) = Pubkey::find_program_address(edition_seeds, program_id);
     metadata.edition_nonce = Some(edition_bump_seed);
 
-    metadata.serialize(&mut *metadata_account_info.try_borrow_mut_data()?)?;
+    metadata.save(metadata_account_info)?;
     Ok(())
 }
 
@@ -750,7 +906,7 @@ pub fn verify_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progr
     assert_owned_by(edition_account_info, program_id)?;
 
     let mut metadata = Metadata::from_account_info(metadata_info)?;
-    let collection_data = Metadata::from_account_info(collection_info)?;
+    let mut collection_data = Metadata::from_account_info(collection_info)?;
     assert_collection_verify_is_valid(
         &metadata,
         &collection_data,
@@ -775,8 +931,13 @@ pub fn verify_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progr
         )?;
     }
     if let Some(collection) = &mut metadata.collection {
+        let already_verified = collection.verified;
         collection.verified = true;
-        metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
+        metadata.save(metadata_info)?;
+
+        if !already_verified {
+            increment_collection_size(&mut collection_data, collection_info)?;
+        }
     }
     Ok(())
 }
@@ -797,7 +958,7 @@ pub fn unverify_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
     assert_owned_by(edition_account_info, program_id)?;
 
     let mut metadata = Metadata::from_account_info(metadata_info)?;
-    let collection_data = Metadata::from_account_info(collection_info)?;
+    let mut collection_data = Metadata::from_account_info(collection_info)?;
     assert_collection_verify_is_valid(
         &metadata,
         &collection_data,
@@ -820,10 +981,16 @@ pub fn unverify_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
             None,
         )?;
     }
+    let mut was_verified = false;
     if let Some(collection) = &mut metadata.collection {
+        was_verified = collection.verified;
         collection.verified = false;
     }
-    metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
+    metadata.save(metadata_info)?;
+
+    if was_verified {
+        decrement_collection_size(&mut collection_data, collection_info)?;
+    }
     Ok(())
 }
 
@@ -861,6 +1028,15 @@ pub fn process_approve_use_authority(
         mint_info,
         token_account_info,
     )?;
+    if let Some(valid_until) = metadata
+        .use_restrictions
+        .as_ref()
+        .and_then(|restrictions| restrictions.valid_until)
+    {
+        if Clock::get()?.unix_timestamp > valid_until {
+            return Err(MetadataError::UseExpired.into());
+        }
+    }
     let metadata_uses = metadata.uses.unwrap();
     let bump_seed = assert_use_authority_derivation(
         program_id,
@@ -1028,6 +1204,11 @@ pub fn process_utilize(
         token_account_info,
     )?;
     let mut metadata = Metadata::from_account_info(metadata_info)?;
+    if let Some(restrictions) = metadata.use_restrictions.as_mut() {
+        let now = Clock::get()?.unix_timestamp;
+        assert_use_allowed_at(restrictions, now)?;
+        restrictions.last_used_at = Some(now);
+    }
     let metadata_uses = metadata.uses.unwrap();
     let must_burn = metadata_uses.use_method == UseMethod::Burn;
     if number_of_uses > metadata_uses.total || number_of_uses > metadata_uses.remaining {
@@ -1071,7 +1252,7 @@ pub fn process_utilize(
             .ok_or(MetadataError::NotEnoughUses)?;
         record.serialize(data)?;
     }
-    metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
+    metadata.save(metadata_info)?;
     if remaining_uses <= 0 && must_burn {
         if approved_authority_is_using {
             let burn_path = &[PREFIX.as_bytes(), program_id.as_ref(), BURN.as_bytes()];
@@ -1228,7 +1409,7 @@ pub fn set_and_verify_collection(program_id: &Pubkey, accounts: &[AccountInfo])
     assert_owned_by(edition_account_info, program_id)?;
 
     let mut metadata = Metadata::from_account_info(metadata_info)?;
-    let collection_data = Metadata::from_account_info(collection_info)?;
+    let mut collection_data = Metadata::from_account_info(collection_info)?;
     if metadata.update_authority != *update_authority.key
         || metadata.update_authority != collection_data.update_authority
     {
@@ -1251,6 +1432,13 @@ pub fn set_and_verify_collection(program_id: &Pubkey, accounts: &[AccountInfo])
             None,
         )?;
     }
+    let already_verified = match &metadata.collection {
+        Some(collection) if collection.verified && collection.key != *collection_mint.key => {
+            return Err(MetadataError::VerifiedCollectionMustBeUnverifiedFirst.into());
+        }
+        Some(collection) => collection.verified,
+        None => false,
+    };
     metadata.collection = Some(Collection {
         key: *collection_mint.key,
         verified: true,
@@ -1261,7 +1449,11 @@ pub fn set_and_verify_collection(program_id: &Pubkey, accounts: &[AccountInfo])
         collection_mint,
         edition_account_info,
     )?;
-    metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
+    metadata.save(metadata_info)?;
+
+    if !already_verified {
+        increment_collection_size(&mut collection_data, collection_info)?;
+    }
     Ok(())
 }
 
@@ -1384,3 +1576,861 @@ pub fn process_thaw_delegated_account(
     )?;
     Ok(())
 }
+
+pub fn process_burn_nft(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_info = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let spl_token_program_info = next_account_info(account_info_iter)?;
+    let collection_metadata_info = next_account_info(account_info_iter).ok();
+
+    assert_signer(owner_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    assert_owned_by(edition_info, program_id)?;
+    assert_token_program_matches_package(spl_token_program_info)?;
+
+    let metadata = Metadata::from_account_info(metadata_info)?;
+    assert_currently_holding(
+        program_id,
+        owner_info,
+        metadata_info,
+        &metadata,
+        mint_info,
+        token_info,
+    )?;
+
+    let edition_bump = assert_derivation(
+        program_id,
+        edition_info,
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            mint_info.key.as_ref(),
+            EDITION.as_bytes(),
+        ],
+    )?;
+
+    // Print editions have to go through BurnEditionNft so the master edition stays in sync.
+    let master_edition = MasterEditionV2::from_account_info(edition_info)?;
+    if master_edition.supply > 0 {
+        return Err(MetadataError::MasterEditionHasPrints.into());
+    }
+
+    // Programmable assets are always frozen, the owner burning it is the one exception.
+    if metadata.token_standard == Some(TokenStandard::ProgrammableNonFungible) {
+        thaw(
+            mint_info,
+            token_info,
+            edition_info,
+            spl_token_program_info,
+            &[
+                PREFIX.as_bytes(),
+                program_id.as_ref(),
+                mint_info.key.as_ref(),
+                EDITION.as_bytes(),
+                &[edition_bump],
+            ],
+        )?;
+    }
+
+    spl_token_burn(TokenBurnParams {
+        mint: mint_info.clone(),
+        source: token_info.clone(),
+        authority: owner_info.clone(),
+        token_program: spl_token_program_info.clone(),
+        amount: 1,
+        authority_signer_seeds: None,
+    })?;
+
+    invoke(
+        &close_account(
+            spl_token_program_info.key,
+            token_info.key,
+            owner_info.key,
+            owner_info.key,
+            &[],
+        )?,
+        &[
+            token_info.clone(),
+            owner_info.clone(),
+            spl_token_program_info.clone(),
+        ],
+    )?;
+
+    remove_from_verified_collection(program_id, &metadata, collection_metadata_info)?;
+
+    close_program_account(metadata_info, owner_info)?;
+    close_program_account(edition_info, owner_info)?;
+
+    Ok(())
+}
+
+pub fn process_burn_edition_nft(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let print_edition_mint_info = next_account_info(account_info_iter)?;
+    let master_edition_mint_info = next_account_info(account_info_iter)?;
+    let print_edition_token_info = next_account_info(account_info_iter)?;
+    let master_edition_info = next_account_info(account_info_iter)?;
+    let print_edition_info = next_account_info(account_info_iter)?;
+    let edition_marker_info = next_account_info(account_info_iter)?;
+    let spl_token_program_info = next_account_info(account_info_iter)?;
+    let collection_metadata_info = next_account_info(account_info_iter).ok();
+
+    assert_signer(owner_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    assert_owned_by(master_edition_info, program_id)?;
+    assert_owned_by(print_edition_info, program_id)?;
+    assert_owned_by(edition_marker_info, program_id)?;
+    assert_token_program_matches_package(spl_token_program_info)?;
+
+    let metadata = Metadata::from_account_info(metadata_info)?;
+    assert_currently_holding(
+        program_id,
+        owner_info,
+        metadata_info,
+        &metadata,
+        print_edition_mint_info,
+        print_edition_token_info,
+    )?;
+
+    assert_derivation(
+        program_id,
+        print_edition_info,
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            print_edition_mint_info.key.as_ref(),
+            EDITION.as_bytes(),
+        ],
+    )?;
+    assert_derivation(
+        program_id,
+        master_edition_info,
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            master_edition_mint_info.key.as_ref(),
+            EDITION.as_bytes(),
+        ],
+    )?;
+
+    let print_edition = Edition::from_account_info(print_edition_info)?;
+    if print_edition.parent != *master_edition_info.key {
+        return Err(MetadataError::PrintEditionDoesNotMatchMasterEdition.into());
+    }
+
+    let edition_number = print_edition
+        .edition
+        .checked_div(EDITION_MARKER_BIT_SIZE)
+        .ok_or(MetadataError::NumericalOverflowError)?;
+    let as_string = edition_number.to_string();
+    assert_derivation(
+        program_id,
+        edition_marker_info,
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            master_edition_mint_info.key.as_ref(),
+            EDITION.as_bytes(),
+            as_string.as_bytes(),
+        ],
+    )?;
+
+    let mut edition_marker = EditionMarker::from_account_info(edition_marker_info)?;
+    if !edition_marker.edition_taken(print_edition.edition)? {
+        return Err(MetadataError::EditionNotPrinted.into());
+    }
+    // The supply is the highest edition number printed so far and stays as is, the cleared bit
+    // lets the burned number be printed again.
+    edition_marker.remove_edition(print_edition.edition)?;
+    edition_marker.serialize(&mut *edition_marker_info.try_borrow_mut_data()?)?;
+
+    spl_token_burn(TokenBurnParams {
+        mint: print_edition_mint_info.clone(),
+        source: print_edition_token_info.clone(),
+        authority: owner_info.clone(),
+        token_program: spl_token_program_info.clone(),
+        amount: 1,
+        authority_signer_seeds: None,
+    })?;
+
+    invoke(
+        &close_account(
+            spl_token_program_info.key,
+            print_edition_token_info.key,
+            owner_info.key,
+            owner_info.key,
+            &[],
+        )?,
+        &[
+            print_edition_token_info.clone(),
+            owner_info.clone(),
+            spl_token_program_info.clone(),
+        ],
+    )?;
+
+    remove_from_verified_collection(program_id, &metadata, collection_metadata_info)?;
+
+    close_program_account(metadata_info, owner_info)?;
+    close_program_account(print_edition_info, owner_info)?;
+
+    Ok(())
+}
+
+/// Verified collection items need to be removed from the collection size before they go away.
+/// Nothing is tracked anymore once the collection parent has been burned.
+fn remove_from_verified_collection(
+    program_id: &Pubkey,
+    metadata: &Metadata,
+    collection_metadata_info: Option<&AccountInfo>,
+) -> ProgramResult {
+    if let Some(collection) = &metadata.collection {
+        if collection.verified {
+            let collection_metadata_info =
+                collection_metadata_info.ok_or(MetadataError::MissingCollectionMetadata)?;
+            assert_derivation(
+                program_id,
+                collection_metadata_info,
+                &[
+                    PREFIX.as_bytes(),
+                    program_id.as_ref(),
+                    collection.key.as_ref(),
+                ],
+            )?;
+            if collection_metadata_info.data_is_empty() {
+                return Ok(());
+            }
+            assert_owned_by(collection_metadata_info, program_id)?;
+            let mut collection_metadata = Metadata::from_account_info(collection_metadata_info)?;
+            decrement_collection_size(&mut collection_metadata, collection_metadata_info)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn process_set_collection_size(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    size: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let collection_metadata_info = next_account_info(account_info_iter)?;
+    let collection_authority_info = next_account_info(account_info_iter)?;
+    let collection_mint_info = next_account_info(account_info_iter)?;
+    let using_delegated_collection_authority = accounts.len() == 4;
+
+    assert_signer(collection_authority_info)?;
+    assert_owned_by(collection_metadata_info, program_id)?;
+    assert_owned_by(collection_mint_info, &spl_token::id())?;
+
+    let mut metadata = Metadata::from_account_info(collection_metadata_info)?;
+    if metadata.mint != *collection_mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+
+    if using_delegated_collection_authority {
+        let collection_authority_record = next_account_info(account_info_iter)?;
+        assert_has_collection_authority(
+            collection_authority_info,
+            &metadata,
+            collection_mint_info.key,
+            Some(collection_authority_record),
+        )?;
+    } else {
+        assert_has_collection_authority(
+            collection_authority_info,
+            &metadata,
+            collection_mint_info.key,
+            None,
+        )?;
+    }
+
+    // Only unsized collections can be migrated, afterwards the size is tracked on verification.
+    if metadata.collection_details.is_some() {
+        return Err(MetadataError::CollectionAlreadySized.into());
+    }
+
+    metadata.collection_details = Some(CollectionDetails::V1 { size });
+    metadata.save(collection_metadata_info)?;
+    Ok(())
+}
+
+pub fn process_set_rule_set(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    allowed_programs: Vec<Pubkey>,
+    allow_direct_transfers: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let rule_set_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    assert_signer(authority_info)?;
+    assert_signer(payer_info)?;
+
+    if allowed_programs.len() > MAX_RULE_SET_PROGRAMS {
+        return Err(MetadataError::TooManyRuleSetPrograms.into());
+    }
+
+    let rule_set_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        RULE_SET.as_bytes(),
+        authority_info.key.as_ref(),
+    ]);
+    let rule_set_bump_seed = &[assert_derivation(program_id, rule_set_info, &rule_set_path)?];
+
+    if rule_set_info.try_data_is_empty()? {
+        let mut rule_set_seeds = rule_set_path.clone();
+        rule_set_seeds.push(rule_set_bump_seed);
+        create_or_allocate_account_raw(
+            *program_id,
+            rule_set_info,
+            rent_info,
+            system_account_info,
+            payer_info,
+            MAX_RULE_SET_LEN,
+            &rule_set_seeds,
+        )?;
+    } else {
+        assert_owned_by(rule_set_info, program_id)?;
+    }
+
+    let mut rule_set = RuleSet::from_account_info(rule_set_info)?;
+    rule_set.key = Key::RuleSet;
+    rule_set.authority = *authority_info.key;
+    rule_set.bump = rule_set_bump_seed[0];
+    rule_set.allowed_programs = allowed_programs;
+    rule_set.allow_direct_transfers = allow_direct_transfers;
+    // Clear out the old allow-list first since the new one may be shorter.
+    sol_memset(*rule_set_info.try_borrow_mut_data()?, 0, MAX_RULE_SET_LEN);
+    rule_set.serialize(&mut *rule_set_info.try_borrow_mut_data()?)?;
+    Ok(())
+}
+
+pub fn process_convert_to_programmable(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let rule_set_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(owner_info)?;
+    assert_owned_by(edition_info, program_id)?;
+    assert_owned_by(rule_set_info, program_id)?;
+    assert_token_program_matches_package(token_program_info)?;
+
+    let mut metadata = Metadata::from_account_info(metadata_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    assert_currently_holding(
+        program_id,
+        owner_info,
+        metadata_info,
+        &metadata,
+        mint_info,
+        token_account_info,
+    )?;
+
+    // Only master editions can be converted, prints and fungibles keep their own rules.
+    if metadata.token_standard != Some(TokenStandard::NonFungible) {
+        return Err(MetadataError::InvalidTokenStandard.into());
+    }
+    // Make sure the rule set deserializes before pointing the asset at it.
+    RuleSet::from_account_info(rule_set_info)?;
+
+    let edition_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        EDITION.as_bytes(),
+    ]);
+    let edition_bump_seed = &[assert_derivation(program_id, edition_info, &edition_path)?];
+    let mut edition_seeds = edition_path.clone();
+    edition_seeds.push(edition_bump_seed);
+
+    let mint: Mint = assert_initialized(mint_info)?;
+    assert_freeze_authority_matches_mint(&mint.freeze_authority, edition_info)?;
+
+    // A lingering delegate could otherwise thaw the account through ThawDelegatedAccount.
+    let token_account: Account = assert_initialized(token_account_info)?;
+    if token_account.delegate.is_some() {
+        return Err(MetadataError::InvalidDelegate.into());
+    }
+
+    if !token_account.is_frozen() {
+        freeze(
+            mint_info,
+            token_account_info,
+            edition_info,
+            token_program_info,
+            &edition_seeds,
+        )?;
+    }
+
+    metadata.token_standard = Some(TokenStandard::ProgrammableNonFungible);
+    metadata.rule_set = Some(*rule_set_info.key);
+    metadata.save(metadata_info)?;
+    Ok(())
+}
+
+pub fn process_transfer(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let rule_set_info = next_account_info(account_info_iter)?;
+    let instructions_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(owner_info)?;
+    assert_owned_by(edition_info, program_id)?;
+    assert_owned_by(rule_set_info, program_id)?;
+    assert_token_program_matches_package(token_program_info)?;
+
+    let metadata = Metadata::from_account_info(metadata_info)?;
+    if metadata.token_standard != Some(TokenStandard::ProgrammableNonFungible) {
+        return Err(MetadataError::InvalidTokenStandard.into());
+    }
+    if metadata.rule_set != Some(*rule_set_info.key) {
+        return Err(MetadataError::InvalidRuleSet.into());
+    }
+    assert_currently_holding(
+        program_id,
+        owner_info,
+        metadata_info,
+        &metadata,
+        mint_info,
+        source_info,
+    )?;
+
+    // When invoked through CPI the top level instruction belongs to the calling program. A direct
+    // call can sit next to any other instruction of the transaction, so the rule set has to opt in.
+    if *instructions_info.key != sysvar::instructions::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let current_instruction = get_instruction_relative(0, instructions_info)?;
+    let rule_set = RuleSet::from_account_info(rule_set_info)?;
+    if current_instruction.program_id == *program_id {
+        if !rule_set.allow_direct_transfers {
+            return Err(MetadataError::DirectTransferNotAllowedByRuleSet.into());
+        }
+    } else if !rule_set.is_program_allowed(&current_instruction.program_id) {
+        return Err(MetadataError::ProgramNotAllowedByRuleSet.into());
+    }
+
+    let destination: Account = assert_initialized(destination_info)?;
+    assert_owned_by(destination_info, &spl_token::id())?;
+    if destination.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+    // Same as on conversion, the receiving account must not carry a delegate that could thaw it.
+    if destination.delegate.is_some() {
+        return Err(MetadataError::InvalidDelegate.into());
+    }
+
+    let edition_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        EDITION.as_bytes(),
+    ]);
+    let edition_bump_seed = &[assert_derivation(program_id, edition_info, &edition_path)?];
+    let mut edition_seeds = edition_path.clone();
+    edition_seeds.push(edition_bump_seed);
+
+    thaw(
+        mint_info,
+        source_info,
+        edition_info,
+        token_program_info,
+        &edition_seeds,
+    )?;
+    if source_info.key != destination_info.key && destination.is_frozen() {
+        thaw(
+            mint_info,
+            destination_info,
+            edition_info,
+            token_program_info,
+            &edition_seeds,
+        )?;
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(
+            token_program_info.key,
+            source_info.key,
+            destination_info.key,
+            owner_info.key,
+            &[],
+            1,
+        )?,
+        &[
+            source_info.clone(),
+            destination_info.clone(),
+            owner_info.clone(),
+            token_program_info.clone(),
+        ],
+    )?;
+
+    // An emptied source is left thawed so that its owner can close it.
+    let source: Account = assert_initialized(source_info)?;
+    if source.amount > 0 {
+        freeze(
+            mint_info,
+            source_info,
+            edition_info,
+            token_program_info,
+            &edition_seeds,
+        )?;
+    }
+    if source_info.key != destination_info.key {
+        freeze(
+            mint_info,
+            destination_info,
+            edition_info,
+            token_program_info,
+            &edition_seeds,
+        )?;
+    }
+    Ok(())
+}
+
+pub fn process_migrate_metadata(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    assert_signer(payer_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    let mut metadata = Metadata::from_account_info(metadata_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+
+    // Go by the account size, legacy accounts can have stale bytes where the version now lives.
+    if metadata_info.data_len() == MAX_METADATA_LEN_V1 {
+        return Err(MetadataError::MetadataAlreadyMigrated.into());
+    }
+
+    let rent = Rent::get()?;
+    let required_lamports = rent
+        .minimum_balance(MAX_METADATA_LEN_V1)
+        .saturating_sub(metadata_info.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer_info.key, metadata_info.key, required_lamports),
+            &[
+                payer_info.clone(),
+                metadata_info.clone(),
+                system_account_info.clone(),
+            ],
+        )?;
+    }
+    metadata_info.realloc(MAX_METADATA_LEN_V1, true)?;
+    // Clear the leftovers of earlier writes so that only this version byte can be read back.
+    sol_memset(*metadata_info.try_borrow_mut_data()?, 0, MAX_METADATA_LEN_V1);
+
+    metadata.schema_version = METADATA_SCHEMA_VERSION;
+    metadata.save(metadata_info)?;
+    Ok(())
+}
+
+pub fn process_approve_metadata_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    permissions: MetadataDelegatePermissions,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_delegate_record_info = next_account_info(account_info_iter)?;
+    let delegate_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let metadata = Metadata::from_account_info(metadata_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    assert_owned_by(mint_info, &spl_token::id())?;
+    assert_signer(payer_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    if metadata.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+
+    let metadata_delegate_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        METADATA_DELEGATE.as_bytes(),
+        delegate_info.key.as_ref(),
+    ]);
+    let metadata_delegate_bump_seed = &[assert_derivation(
+        program_id,
+        metadata_delegate_record_info,
+        &metadata_delegate_path,
+    )?];
+    // A record approved by a previous update authority has lapsed and is taken over as is.
+    if metadata_delegate_record_info.try_data_is_empty()? {
+        let mut metadata_delegate_seeds = metadata_delegate_path.clone();
+        metadata_delegate_seeds.push(metadata_delegate_bump_seed);
+        create_or_allocate_account_raw(
+            *program_id,
+            metadata_delegate_record_info,
+            rent_info,
+            system_account_info,
+            payer_info,
+            METADATA_DELEGATE_RECORD_SIZE,
+            &metadata_delegate_seeds,
+        )?;
+    } else {
+        assert_owned_by(metadata_delegate_record_info, program_id)?;
+        let record = MetadataDelegateRecord::from_account_info(metadata_delegate_record_info)?;
+        if record.update_authority == metadata.update_authority {
+            return Err(MetadataError::MetadataDelegateRecordAlreadyExists.into());
+        }
+    }
+
+    let mut record = MetadataDelegateRecord::from_account_info(metadata_delegate_record_info)?;
+    record.key = Key::MetadataDelegateRecord;
+    record.bump = metadata_delegate_bump_seed[0];
+    record.permissions = permissions;
+    record.update_authority = metadata.update_authority;
+    record.serialize(&mut *metadata_delegate_record_info.try_borrow_mut_data()?)?;
+    Ok(())
+}
+
+pub fn process_revoke_metadata_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_delegate_record_info = next_account_info(account_info_iter)?;
+    let delegate_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+
+    let metadata = Metadata::from_account_info(metadata_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    assert_owned_by(mint_info, &spl_token::id())?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    if metadata.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+
+    assert_is_metadata_delegate(
+        program_id,
+        metadata_delegate_record_info,
+        delegate_info.key,
+        mint_info.key,
+        &metadata.update_authority,
+    )?;
+    close_program_account(metadata_delegate_record_info, update_authority_info)?;
+    Ok(())
+}
+
+pub fn process_set_print_policy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    start_time: Option<i64>,
+    end_time: Option<i64>,
+    max_per_recipient: Option<u64>,
+    price: Option<u64>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let print_policy_info = next_account_info(account_info_iter)?;
+    let master_edition_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    assert_signer(payer_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    assert_owned_by(master_edition_info, program_id)?;
+
+    let metadata = Metadata::from_account_info(metadata_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    assert_derivation(
+        program_id,
+        master_edition_info,
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            metadata.mint.as_ref(),
+            EDITION.as_bytes(),
+        ],
+    )?;
+    // Only V2 master editions can carry a print policy.
+    MasterEditionV2::from_account_info(master_edition_info)?;
+
+    if let (Some(start_time), Some(end_time)) = (start_time, end_time) {
+        if start_time >= end_time {
+            return Err(MetadataError::InvalidPrintWindow.into());
+        }
+    }
+
+    let print_policy_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        metadata.mint.as_ref(),
+        EDITION.as_bytes(),
+        PRINT_POLICY.as_bytes(),
+    ]);
+    let print_policy_bump_seed = &[assert_derivation(
+        program_id,
+        print_policy_info,
+        &print_policy_path,
+    )?];
+
+    // The flag on the master edition is what mints check, it is only set while the policy exists.
+    if start_time.is_none() && end_time.is_none() && max_per_recipient.is_none() && price.is_none()
+    {
+        if !print_policy_info.try_data_is_empty()? {
+            assert_owned_by(print_policy_info, program_id)?;
+            close_program_account(print_policy_info, payer_info)?;
+        }
+        MasterEditionV2::set_has_print_policy(master_edition_info, false);
+        return Ok(());
+    }
+
+    if print_policy_info.try_data_is_empty()? {
+        let mut print_policy_seeds = print_policy_path.clone();
+        print_policy_seeds.push(print_policy_bump_seed);
+        create_or_allocate_account_raw(
+            *program_id,
+            print_policy_info,
+            rent_info,
+            system_account_info,
+            payer_info,
+            PRINT_POLICY_SIZE,
+            &print_policy_seeds,
+        )?;
+    } else {
+        assert_owned_by(print_policy_info, program_id)?;
+    }
+
+    let mut print_policy = PrintPolicy::from_account_info(print_policy_info)?;
+    print_policy.key = Key::PrintPolicy;
+    print_policy.bump = print_policy_bump_seed[0];
+    print_policy.start_time = start_time;
+    print_policy.end_time = end_time;
+    print_policy.max_per_recipient = max_per_recipient;
+    print_policy.price = price;
+    print_policy.serialize(&mut *print_policy_info.try_borrow_mut_data()?)?;
+
+    MasterEditionV2::set_has_print_policy(master_edition_info, true);
+    Ok(())
+}
+
+pub fn process_set_use_restrictions(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    valid_from: Option<i64>,
+    valid_until: Option<i64>,
+    cooldown: Option<i64>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(metadata_info, program_id)?;
+    let mut metadata = Metadata::from_account_info(metadata_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+
+    if !metadata.is_mutable {
+        return Err(MetadataError::DataIsImmutable.into());
+    }
+    if metadata.uses.is_none() {
+        return Err(MetadataError::Unusable.into());
+    }
+    if metadata.schema_version < METADATA_SCHEMA_VERSION {
+        return Err(MetadataError::MetadataNotMigrated.into());
+    }
+
+    if valid_from.is_none() && valid_until.is_none() && cooldown.is_none() {
+        metadata.use_restrictions = None;
+    } else {
+        let restrictions = UseRestrictions {
+            valid_from,
+            valid_until,
+            cooldown,
+            last_used_at: metadata
+                .use_restrictions
+                .and_then(|restrictions| restrictions.last_used_at),
+        };
+        assert_valid_use_restrictions(&restrictions)?;
+        metadata.use_restrictions = Some(restrictions);
+    }
+
+    metadata.save(metadata_info)?;
+    Ok(())
+}
+
+pub fn process_update_master_edition_supply(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_supply: Option<u64>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let master_edition_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(master_edition_info, program_id)?;
+    assert_owned_by(metadata_info, program_id)?;
+
+    let metadata = Metadata::from_account_info(metadata_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    assert_derivation(
+        program_id,
+        master_edition_info,
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            metadata.mint.as_ref(),
+            EDITION.as_bytes(),
+        ],
+    )?;
+
+    let mut master_edition = MasterEditionV2::from_account_info(master_edition_info)?;
+    if let Some(max_supply) = max_supply {
+        if max_supply < master_edition.supply {
+            return Err(MetadataError::MaxSupplyBelowSupply.into());
+        }
+    }
+
+    let is_raise = match (master_edition.max_supply, max_supply) {
+        (Some(current), Some(new)) => new > current,
+        (Some(_), None) => true,
+        (None, _) => false,
+    };
+    if is_raise && metadata.primary_sale_happened {
+        return Err(MetadataError::CannotRaiseMaxSupplyAfterPrimarySale.into());
+    }
+
+    master_edition.max_supply = max_supply;
+    master_edition.serialize(&mut *master_edition_info.try_borrow_mut_data()?)?;
+    Ok(())
+}
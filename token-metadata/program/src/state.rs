@@ -1,9 +1,12 @@
-use crate::{error::MetadataError, utils::try_from_slice_checked};
+use crate::{
+    error::MetadataError,
+    utils::{try_from_slice_checked, try_from_slice_checked_versioned},
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 use shank::ShankAccount;
 use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
-    pubkey::Pubkey,
+    program_memory::sol_memset, pubkey::Pubkey,
 };
 /// prefix used for PDAs to avoid certain collision attacks (https://en.wikipedia.org/wiki/Collision_attack#Chosen-prefix_collision_attack)
 pub const PREFIX: &str = "metadata";
@@ -19,6 +22,12 @@ pub const BURN: &str = "burn";
 
 pub const COLLECTION_AUTHORITY: &str = "collection_authority";
 
+pub const RULE_SET: &str = "rule_set";
+
+pub const METADATA_DELEGATE: &str = "metadata_delegate";
+
+pub const PRINT_POLICY: &str = "print_policy";
+
 pub const MAX_NAME_LENGTH: usize = 32;
 
 pub const MAX_SYMBOL_LENGTH: usize = 10;
@@ -36,7 +45,26 @@ pub const MAX_METADATA_LEN: usize =
 + 34 // collection
 + 18 // uses
 + 2 // token standard
-+ 118; // Padding
++ 10 // collection details
++ 33 // rule set
++ 1 // schema version
++ 1 // use restrictions, only the None tag fits here, see MAX_METADATA_LEN_V1
++ 72 // Padding
++ 1; // extension marker, see METADATA_EXTENSION_MARKER
+
+/// Written by Metadata::save into the last byte of the legacy layout, which earlier program versions
+/// never serialized that far. Only accounts carrying it hold the fields after the uses, for the
+/// others these bytes can be the leftovers of an earlier, longer serialization.
+pub const METADATA_EXTENSION_MARKER: u8 = 1;
+
+pub const METADATA_EXTENSION_MARKER_OFFSET: usize = MAX_METADATA_LEN - 1;
+
+/// Schema version written by MigrateMetadata. Legacy accounts read as version 0.
+pub const METADATA_SCHEMA_VERSION: u8 = 1;
+
+/// Size of a metadata account migrated to schema version 1. Fields added from this version onwards
+/// go into the extra space instead of the legacy padding.
+pub const MAX_METADATA_LEN_V1: usize = MAX_METADATA_LEN + 256;
 
 pub const MAX_DATA_SIZE: usize = 4
     + MAX_NAME_LENGTH
@@ -51,10 +79,28 @@ pub const MAX_DATA_SIZE: usize = 4
 
 pub const MAX_EDITION_LEN: usize = 1 + 32 + 8 + 200;
 
+/// Printing mint and one time printing authorization mint of MasterEditionV1, left in place in
+/// MasterEditionV2 accounts converted from V1.
+pub const MASTER_EDITION_V1_MINTS_LEN: usize = 64;
+
+/// Print policy flag of MasterEditionV2, see [MASTER_EDITION_PRINT_POLICY_OFFSET].
+pub const MASTER_EDITION_PRINT_POLICY_FLAG_LEN: usize = 1;
+
+/// Unused tail of a master edition account.
+pub const MASTER_EDITION_PADDING: usize = 199;
+
 // Large buffer because the older master editions have two pubkeys in them,
 // need to keep two versions same size because the conversion process actually changes the same account
 // by rewriting it.
-pub const MAX_MASTER_EDITION_LEN: usize = 1 + 9 + 8 + 264;
+pub const MAX_MASTER_EDITION_LEN: usize = 1
+    + 9
+    + 8
+    + MASTER_EDITION_V1_MINTS_LEN
+    + MASTER_EDITION_PRINT_POLICY_FLAG_LEN
+    + MASTER_EDITION_PADDING;
+
+// Master edition accounts already exist at this size, reserving bytes must come out of the padding.
+const _: [(); 282] = [(); MAX_MASTER_EDITION_LEN];
 
 pub const MAX_CREATOR_LIMIT: usize = 5;
 
@@ -76,6 +122,21 @@ pub const USE_AUTHORITY_RECORD_SIZE: usize = 18; //8 byte padding
 
 pub const COLLECTION_AUTHORITY_RECORD_SIZE: usize = 11; //10 byte padding
 
+pub const METADATA_DELEGATE_RECORD_SIZE: usize = 48; //11 byte padding
+
+pub const MAX_RULE_SET_PROGRAMS: usize = 20;
+
+pub const MAX_RULE_SET_LEN: usize = 1 + 32 + 1 + 4 + MAX_RULE_SET_PROGRAMS * 32 + 1 + 49; //49 byte padding
+
+pub const PRINT_POLICY_SIZE: usize = 1 + 1 + 9 + 9 + 9 + 9 + 26; //26 byte padding
+
+pub const PRINT_RECIPIENT_RECORD_SIZE: usize = 1 + 1 + 8 + 14; //14 byte padding
+
+/// MasterEditionV2 accounts converted from V1 still carry the old printing mints after the V2 fields,
+/// so the print policy flag lives in the byte reserved for it past the largest V1 layout instead of
+/// in borsh. SetPrintPolicy keeps it set exactly while the print policy account exists.
+pub const MASTER_EDITION_PRINT_POLICY_OFFSET: usize = 1 + 9 + 8 + MASTER_EDITION_V1_MINTS_LEN;
+
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Copy)]
@@ -89,7 +150,11 @@ pub enum Key {
     MasterEditionV2,
     EditionMarker,
     UseAuthorityRecord,
-    CollectionAuthorityRecord
+    CollectionAuthorityRecord,
+    RuleSet,
+    MetadataDelegateRecord,
+    PrintPolicy,
+    PrintRecipientRecord,
 }
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
@@ -154,6 +219,19 @@ pub struct Uses { // 17 bytes + Option byte
     pub total: u64, //8
 }
 
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct UseRestrictions { // 36 bytes + Option byte
+    /// Unix timestamp before which the asset cannot be used.
+    pub valid_from: Option<i64>, //9
+    /// Unix timestamp after which the asset cannot be used.
+    pub valid_until: Option<i64>, //9
+    /// Minimum number of seconds between two uses.
+    pub cooldown: Option<i64>, //9
+    /// Unix timestamp of the last use, set by Utilize.
+    pub last_used_at: Option<i64>, //9
+}
+
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub enum TokenStandard {
@@ -161,6 +239,7 @@ pub enum TokenStandard {
     FungibleAsset, // A token with metadata that can also have attrributes
     Fungible,     // A token with simple metadata
     NonFungibleEdition,      // This is a limited edition
+    ProgrammableNonFungible, // A master edition whose token stays frozen, only movable via Transfer
 }
 
 #[repr(C)]
@@ -210,6 +289,63 @@ impl CollectionAuthorityRecord {
     }
 }
 
+/// Fields of a Metadata a delegate is allowed to change through UpdateMetadataAccountV2.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct MetadataDelegatePermissions {
+    pub uri: bool,
+    pub collection: bool,
+    pub uses: bool,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, ShankAccount)]
+pub struct MetadataDelegateRecord {
+    pub key: Key, //1
+    pub bump: u8, //1
+    pub permissions: MetadataDelegatePermissions, //3
+    /// Update authority that approved the delegate, the record lapses once it changes.
+    pub update_authority: Pubkey, //32
+}
+
+impl MetadataDelegateRecord {
+    pub fn from_account_info(a: &AccountInfo) -> Result<MetadataDelegateRecord, ProgramError> {
+        let md: MetadataDelegateRecord = try_from_slice_checked(
+            &a.data.borrow_mut(),
+            Key::MetadataDelegateRecord,
+            METADATA_DELEGATE_RECORD_SIZE,
+        )?;
+
+        Ok(md)
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, ShankAccount)]
+pub struct RuleSet {
+    pub key: Key, //1
+    pub authority: Pubkey, //32
+    pub bump: u8, //1
+    /// Programs allowed to move a programmable asset by invoking Transfer through CPI.
+    pub allowed_programs: Vec<Pubkey>, //4 + 32 * MAX_RULE_SET_PROGRAMS
+    /// Whether the owner can call Transfer directly, outside of an allowed program. Such a transfer
+    /// can share its transaction with any other instruction, including a payment to the owner.
+    pub allow_direct_transfers: bool, //1
+}
+
+impl RuleSet {
+    pub fn from_account_info(a: &AccountInfo) -> Result<RuleSet, ProgramError> {
+        let rs: RuleSet =
+            try_from_slice_checked(&a.data.borrow_mut(), Key::RuleSet, MAX_RULE_SET_LEN)?;
+
+        Ok(rs)
+    }
+
+    pub fn is_program_allowed(&self, program_id: &Pubkey) -> bool {
+        self.allowed_programs.contains(program_id)
+    }
+}
+
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct Collection {
@@ -218,7 +354,13 @@ pub struct Collection {
 }
 
 #[repr(C)]
-#[derive(Clone, BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub enum CollectionDetails {
+    V1 { size: u64 },
+}
+
+#[repr(C)]
+#[derive(Clone, BorshSerialize, Debug, ShankAccount)]
 pub struct Metadata {
     pub key: Key,
     pub update_authority: Pubkey,
@@ -236,15 +378,92 @@ pub struct Metadata {
     pub collection: Option<Collection>,
     /// Uses
     pub uses: Option<Uses>,
+    /// Only set on the parent NFT of a sized collection, tracks how many items are verified under it.
+    pub collection_details: Option<CollectionDetails>,
+    /// Rule set gating transfers, only set on programmable assets.
+    pub rule_set: Option<Pubkey>,
+    /// Layout version of the account, 0 for legacy accounts of MAX_METADATA_LEN.
+    pub schema_version: u8,
+    /// Validity window and cooldown of the uses, only set on migrated accounts.
+    pub use_restrictions: Option<UseRestrictions>,
+}
+
+impl BorshDeserialize for Metadata {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let extended =
+            buf.get(METADATA_EXTENSION_MARKER_OFFSET) == Some(&METADATA_EXTENSION_MARKER);
+        let key = BorshDeserialize::deserialize(buf)?;
+        let update_authority = BorshDeserialize::deserialize(buf)?;
+        let mint = BorshDeserialize::deserialize(buf)?;
+        let data = BorshDeserialize::deserialize(buf)?;
+        let primary_sale_happened = BorshDeserialize::deserialize(buf)?;
+        let is_mutable = BorshDeserialize::deserialize(buf)?;
+        let edition_nonce = BorshDeserialize::deserialize(buf)?;
+        let token_standard = BorshDeserialize::deserialize(buf)?;
+        let collection = BorshDeserialize::deserialize(buf)?;
+        let uses = BorshDeserialize::deserialize(buf)?;
+        // Writes of earlier program versions never cleared the account, so past the uses an
+        // account they wrote last can hold the leftovers of a longer serialization instead of
+        // zeroes, such as the tail of a creators list that shrank. Those bytes only hold fields
+        // once this version saved the account and left its marker.
+        let (collection_details, rule_set, schema_version, use_restrictions) = if extended {
+            (
+                BorshDeserialize::deserialize(buf)?,
+                BorshDeserialize::deserialize(buf)?,
+                BorshDeserialize::deserialize(buf)?,
+                BorshDeserialize::deserialize(buf)?,
+            )
+        } else {
+            (None, None, 0, None)
+        };
+
+        Ok(Metadata {
+            key,
+            update_authority,
+            mint,
+            data,
+            primary_sale_happened,
+            is_mutable,
+            edition_nonce,
+            token_standard,
+            collection,
+            uses,
+            collection_details,
+            rule_set,
+            schema_version,
+            use_restrictions,
+        })
+    }
 }
 
 impl Metadata {
     pub fn from_account_info(a: &AccountInfo) -> Result<Metadata, ProgramError> {
-        let md: Metadata =
-            try_from_slice_checked(&a.data.borrow_mut(), Key::MetadataV1, MAX_METADATA_LEN)?;
+        let mut md: Metadata = try_from_slice_checked_versioned(
+            &a.data.borrow_mut(),
+            Key::MetadataV1,
+            &[MAX_METADATA_LEN, MAX_METADATA_LEN_V1],
+        )?;
+        // Legacy accounts predate the version byte, whatever is stored there is leftover padding.
+        if a.data_len() == MAX_METADATA_LEN {
+            md.schema_version = 0;
+            md.use_restrictions = None;
+        }
 
         Ok(md)
     }
+
+    /// Write the metadata to its account and zero the rest of the account, so that a shorter
+    /// serialization leaves no leftovers to be read back as the fields at the end, then set the
+    /// extension marker that lets those fields be read back at all.
+    pub fn save(&self, account: &AccountInfo) -> ProgramResult {
+        let mut data = account.try_borrow_mut_data()?;
+        let mut remaining: &mut [u8] = &mut data[..];
+        self.serialize(&mut remaining)?;
+        let remaining_len = remaining.len();
+        sol_memset(remaining, 0, remaining_len);
+        data[METADATA_EXTENSION_MARKER_OFFSET] = METADATA_EXTENSION_MARKER;
+        Ok(())
+    }
 }
 
 pub trait MasterEdition {
@@ -309,6 +528,58 @@ impl MasterEditionV2 {
 
         Ok(me)
     }
+
+    pub fn has_print_policy(a: &AccountInfo) -> bool {
+        a.data.borrow().get(MASTER_EDITION_PRINT_POLICY_OFFSET) == Some(&1)
+    }
+
+    pub fn set_has_print_policy(a: &AccountInfo, has_print_policy: bool) {
+        a.data.borrow_mut()[MASTER_EDITION_PRINT_POLICY_OFFSET] = has_print_policy as u8;
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, ShankAccount)]
+pub struct PrintPolicy {
+    pub key: Key, //1
+    pub bump: u8, //1
+    /// Unix timestamp before which no prints can be minted.
+    pub start_time: Option<i64>, //9
+    /// Unix timestamp after which no prints can be minted.
+    pub end_time: Option<i64>, //9
+    /// Maximum number of prints minted to a single owner.
+    pub max_per_recipient: Option<u64>, //9
+    /// Lamports paid by the payer for each print, split between the master's creators by share.
+    pub price: Option<u64>, //9
+}
+
+impl PrintPolicy {
+    pub fn from_account_info(a: &AccountInfo) -> Result<PrintPolicy, ProgramError> {
+        let pp: PrintPolicy =
+            try_from_slice_checked(&a.data.borrow_mut(), Key::PrintPolicy, PRINT_POLICY_SIZE)?;
+
+        Ok(pp)
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, ShankAccount)]
+pub struct PrintRecipientRecord {
+    pub key: Key, //1
+    pub bump: u8, //1
+    pub count: u64, //8
+}
+
+impl PrintRecipientRecord {
+    pub fn from_account_info(a: &AccountInfo) -> Result<PrintRecipientRecord, ProgramError> {
+        let pr: PrintRecipientRecord = try_from_slice_checked(
+            &a.data.borrow_mut(),
+            Key::PrintRecipientRecord,
+            PRINT_RECIPIENT_RECORD_SIZE,
+        )?;
+
+        Ok(pr)
+    }
 }
 
 #[repr(C)]
@@ -748,4 +1019,11 @@ impl EditionMarker {
         self.ledger[index] = self.ledger[index] | mask;
         Ok(())
     }
+
+    pub fn remove_edition(&mut self, edition: u64) -> ProgramResult {
+        let (index, mask) = EditionMarker::get_index_and_mask(edition)?;
+        // bitwise and with the inverted mask to clear our position only
+        self.ledger[index] = self.ledger[index] & !mask;
+        Ok(())
+    }
 }
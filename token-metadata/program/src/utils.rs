@@ -2,29 +2,32 @@ use crate::{
     assertions::{collection::assert_collection_update_is_valid, uses::assert_valid_use},
     error::MetadataError,
     state::{
-        get_reservation_list, Data, DataV2, EditionMarker, Key, MasterEditionV1, Metadata,
+        get_reservation_list, CollectionDetails, Data, DataV2, EditionMarker, Key,
+        MasterEditionV1, MasterEditionV2, Metadata, PrintPolicy, PrintRecipientRecord,
         TokenStandard, Uses, EDITION, EDITION_MARKER_BIT_SIZE, MAX_CREATOR_LIMIT, MAX_EDITION_LEN,
         MAX_EDITION_MARKER_SIZE, MAX_MASTER_EDITION_LEN, MAX_METADATA_LEN, MAX_NAME_LENGTH,
-        MAX_SYMBOL_LENGTH, MAX_URI_LENGTH, PREFIX,
+        MAX_SYMBOL_LENGTH, MAX_URI_LENGTH, PREFIX, PRINT_POLICY, PRINT_RECIPIENT_RECORD_SIZE,
     },
 };
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
-    account_info::AccountInfo,
+    account_info::{next_account_info, AccountInfo},
     borsh::try_from_slice_unchecked,
+    clock::Clock,
     entrypoint::ProgramResult,
     msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
     program_option::COption,
+    program_memory::sol_memset,
     program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
     system_instruction,
     sysvar::{rent::Rent, Sysvar},
 };
 use spl_token::{
-    instruction::{set_authority, AuthorityType},
+    instruction::{freeze_account, set_authority, thaw_account, AuthorityType},
     state::{Account, Mint},
 };
 use std::convert::TryInto;
@@ -803,6 +806,35 @@ pub fn try_from_slice_checked<T: BorshDeserialize>(
     Ok(result)
 }
 
+/// Like try_from_slice_checked, but for accounts whose layout grew over time. `data_sizes` lists
+/// every known layout size, oldest first. Older layouts are zero-extended to the latest size before
+/// deserializing, so fields they do not have yet read as zero, None or empty.
+pub fn try_from_slice_checked_versioned<T: BorshDeserialize>(
+    data: &[u8],
+    data_type: Key,
+    data_sizes: &[usize],
+) -> Result<T, ProgramError> {
+    if (data[0] != data_type as u8 && data[0] != Key::Uninitialized as u8)
+        || !data_sizes.contains(&data.len())
+    {
+        return Err(MetadataError::DataTypeMismatch.into());
+    }
+
+    let latest_size = data_sizes
+        .iter()
+        .max()
+        .ok_or(MetadataError::DataTypeMismatch)?;
+    let result: T = if data.len() < *latest_size {
+        let mut extended = data.to_vec();
+        extended.resize(*latest_size, 0);
+        try_from_slice_unchecked(&extended)?
+    } else {
+        try_from_slice_unchecked(data)?
+    };
+
+    Ok(result)
+}
+
 pub struct CreateMetadataAccountsLogicArgs<'a> {
     pub metadata_account_info: &'a AccountInfo<'a>,
     pub mint_info: &'a AccountInfo<'a>,
@@ -936,7 +968,7 @@ pub fn process_create_metadata_accounts_logic(
     ];
     let (_, edition_bump_seed) = Pubkey::find_program_address(edition_seeds, program_id);
     metadata.edition_nonce = Some(edition_bump_seed);
-    metadata.serialize(&mut *metadata_account_info.data.borrow_mut())?;
+    metadata.save(metadata_account_info)?;
 
     Ok(())
 }
@@ -987,6 +1019,9 @@ pub struct MintNewEditionFromMasterEditionViaTokenLogicArgs<'a> {
     pub token_program_account_info: &'a AccountInfo<'a>,
     pub system_account_info: &'a AccountInfo<'a>,
     pub rent_info: &'a AccountInfo<'a>,
+    /// Print policy, new token account, print recipient record and creator accounts, only read
+    /// when the master edition has a print policy.
+    pub print_policy_accounts: &'a [AccountInfo<'a>],
 }
 
 pub fn process_mint_new_edition_from_master_edition_via_token_logic<'a>(
@@ -1010,6 +1045,7 @@ pub fn process_mint_new_edition_from_master_edition_via_token_logic<'a>(
         token_program_account_info,
         system_account_info,
         rent_info,
+        print_policy_accounts,
     } = accounts;
 
     assert_token_program_matches_package(token_program_account_info)?;
@@ -1045,6 +1081,18 @@ pub fn process_mint_new_edition_from_master_edition_via_token_logic<'a>(
         return Err(MetadataError::AlreadyInitialized.into());
     }
 
+    if MasterEditionV2::has_print_policy(master_edition_account_info) {
+        apply_print_policy(
+            program_id,
+            &master_metadata,
+            mint_info,
+            payer_account_info,
+            print_policy_accounts,
+            system_account_info,
+            rent_info,
+        )?;
+    }
+
     let edition_number = edition.checked_div(EDITION_MARKER_BIT_SIZE).unwrap();
     let as_string = edition_number.to_string();
 
@@ -1108,6 +1156,144 @@ pub fn process_mint_new_edition_from_master_edition_via_token_logic<'a>(
     )?;
     Ok(())
 }
+
+/// Checks a print against the print policy of its master edition. The owner of the new token is the
+/// recipient whose print count is tracked against the cap, the payer pays the print price to the
+/// master's creators.
+pub fn apply_print_policy<'a>(
+    program_id: &Pubkey,
+    master_metadata: &Metadata,
+    mint_info: &'a AccountInfo<'a>,
+    payer_account_info: &'a AccountInfo<'a>,
+    print_policy_accounts: &'a [AccountInfo<'a>],
+    system_account_info: &'a AccountInfo<'a>,
+    rent_info: &'a AccountInfo<'a>,
+) -> ProgramResult {
+    let account_info_iter = &mut print_policy_accounts.iter();
+    let print_policy_info = next_account_info(account_info_iter)
+        .map_err(|_| MetadataError::MissingPrintPolicyAccounts)?;
+    let new_token_account_info = next_account_info(account_info_iter)
+        .map_err(|_| MetadataError::MissingPrintPolicyAccounts)?;
+    let print_recipient_record_info = next_account_info(account_info_iter)
+        .map_err(|_| MetadataError::MissingPrintPolicyAccounts)?;
+
+    assert_owned_by(print_policy_info, program_id)?;
+    assert_derivation(
+        program_id,
+        print_policy_info,
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            master_metadata.mint.as_ref(),
+            EDITION.as_bytes(),
+            PRINT_POLICY.as_bytes(),
+        ],
+    )
+    .map_err(|_| MetadataError::InvalidPrintPolicy)?;
+    let print_policy = PrintPolicy::from_account_info(print_policy_info)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if let Some(start_time) = print_policy.start_time {
+        if now < start_time {
+            return Err(MetadataError::PrintWindowNotStarted.into());
+        }
+    }
+    if let Some(end_time) = print_policy.end_time {
+        if now > end_time {
+            return Err(MetadataError::PrintWindowEnded.into());
+        }
+    }
+
+    if let Some(max_per_recipient) = print_policy.max_per_recipient {
+        // The payer can be anyone, the cap follows whoever holds the print.
+        assert_owned_by(new_token_account_info, &spl_token::id())?;
+        let new_token_account: Account = assert_initialized(new_token_account_info)?;
+        if new_token_account.mint != *mint_info.key {
+            return Err(MetadataError::TokenAccountMintMismatchV2.into());
+        }
+        if new_token_account.amount != 1 {
+            return Err(MetadataError::EditionsMustHaveExactlyOneToken.into());
+        }
+
+        let print_recipient_record_path = Vec::from([
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            master_metadata.mint.as_ref(),
+            EDITION.as_bytes(),
+            PRINT_POLICY.as_bytes(),
+            new_token_account.owner.as_ref(),
+        ]);
+        let print_recipient_record_bump_seed = &[assert_derivation(
+            program_id,
+            print_recipient_record_info,
+            &print_recipient_record_path,
+        )?];
+
+        if print_recipient_record_info.data_is_empty() {
+            let mut print_recipient_record_seeds = print_recipient_record_path.clone();
+            print_recipient_record_seeds.push(print_recipient_record_bump_seed);
+            create_or_allocate_account_raw(
+                *program_id,
+                print_recipient_record_info,
+                rent_info,
+                system_account_info,
+                payer_account_info,
+                PRINT_RECIPIENT_RECORD_SIZE,
+                &print_recipient_record_seeds,
+            )?;
+        } else {
+            assert_owned_by(print_recipient_record_info, program_id)?;
+        }
+
+        let mut record = PrintRecipientRecord::from_account_info(print_recipient_record_info)?;
+        record.key = Key::PrintRecipientRecord;
+        record.bump = print_recipient_record_bump_seed[0];
+        record.count = record
+            .count
+            .checked_add(1)
+            .ok_or(MetadataError::NumericalOverflowError)?;
+        if record.count > max_per_recipient {
+            return Err(MetadataError::PrintLimitReached.into());
+        }
+        record.serialize(&mut *print_recipient_record_info.try_borrow_mut_data()?)?;
+    }
+
+    if let Some(price) = print_policy.price {
+        let creators = master_metadata
+            .data
+            .creators
+            .as_ref()
+            .ok_or(MetadataError::NoCreatorsPresentOnMetadata)?;
+
+        for creator in creators {
+            let creator_info = next_account_info(account_info_iter)
+                .map_err(|_| MetadataError::MissingPrintPolicyAccounts)?;
+            if *creator_info.key != creator.address {
+                return Err(MetadataError::CreatorNotFound.into());
+            }
+
+            // Whatever does not divide evenly between the shares stays with the payer.
+            let amount = price
+                .checked_mul(creator.share as u64)
+                .ok_or(MetadataError::NumericalOverflowError)?
+                .checked_div(100)
+                .ok_or(MetadataError::NumericalOverflowError)?;
+            if amount > 0 {
+                invoke(
+                    &system_instruction::transfer(payer_account_info.key, creator_info.key, amount),
+                    &[
+                        payer_account_info.clone(),
+                        creator_info.clone(),
+                        system_account_info.clone(),
+                    ],
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn assert_currently_holding(
     program_id: &Pubkey,
     owner_info: &AccountInfo,
@@ -1141,6 +1327,62 @@ pub fn assert_currently_holding(
     Ok(())
 }
 
+pub fn increment_collection_size(
+    metadata: &mut Metadata,
+    metadata_info: &AccountInfo,
+) -> ProgramResult {
+    if let Some(ref details) = metadata.collection_details {
+        match details {
+            CollectionDetails::V1 { size } => {
+                metadata.collection_details = Some(CollectionDetails::V1 {
+                    size: size
+                        .checked_add(1)
+                        .ok_or(MetadataError::NumericalOverflowError)?,
+                });
+                metadata.save(metadata_info)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn decrement_collection_size(
+    metadata: &mut Metadata,
+    metadata_info: &AccountInfo,
+) -> ProgramResult {
+    if let Some(ref details) = metadata.collection_details {
+        match details {
+            CollectionDetails::V1 { size } => {
+                // SetCollectionSize can be set below the actual count, which must not lock the
+                // remaining items out of unverifying or burning.
+                metadata.collection_details = Some(CollectionDetails::V1 {
+                    size: size.saturating_sub(1),
+                });
+                metadata.save(metadata_info)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Closes a program owned account by draining its lamports into the destination
+/// and zeroing out its data so it is garbage collected at the end of the transaction.
+pub fn close_program_account(
+    account_info: &AccountInfo,
+    funds_dest_account_info: &AccountInfo,
+) -> ProgramResult {
+    let lamports = account_info.lamports();
+    **account_info.try_borrow_mut_lamports()? = 0;
+    **funds_dest_account_info.try_borrow_mut_lamports()? = funds_dest_account_info
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(MetadataError::NumericalOverflowError)?;
+
+    let data_len = account_info.data_len();
+    sol_memset(*account_info.try_borrow_mut_data()?, 0, data_len);
+    Ok(())
+}
+
 pub fn assert_freeze_authority_matches_mint(
     freeze_authority: &COption<Pubkey>,
     freeze_authority_info: &AccountInfo,
@@ -1182,3 +1424,53 @@ pub fn assert_delegated_tokens(
     }
     Ok(())
 }
+
+/// Freezes a token account using the master edition PDA, which is the freeze authority of the mint.
+pub fn freeze<'a>(
+    mint_info: &AccountInfo<'a>,
+    token_account_info: &AccountInfo<'a>,
+    edition_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    edition_seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(
+        &freeze_account(
+            token_program_info.key,
+            token_account_info.key,
+            mint_info.key,
+            edition_info.key,
+            &[],
+        )?,
+        &[
+            token_account_info.clone(),
+            mint_info.clone(),
+            edition_info.clone(),
+        ],
+        &[edition_seeds],
+    )
+}
+
+/// Thaws a token account using the master edition PDA, which is the freeze authority of the mint.
+pub fn thaw<'a>(
+    mint_info: &AccountInfo<'a>,
+    token_account_info: &AccountInfo<'a>,
+    edition_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    edition_seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(
+        &thaw_account(
+            token_program_info.key,
+            token_account_info.key,
+            mint_info.key,
+            edition_info.key,
+            &[],
+        )?,
+        &[
+            token_account_info.clone(),
+            mint_info.clone(),
+            edition_info.clone(),
+        ],
+        &[edition_seeds],
+    )
+}
@@ -45,6 +45,10 @@ mod puff_out_test {
             collection: None,
             uses: None,
             token_standard: None,
+            collection_details: None,
+            rule_set: None,
+            schema_version: 0,
+            use_restrictions: None,
         };
 
         puff_out_data_fields(&mut metadata);
@@ -64,3 +68,321 @@ mod puff_out_test {
         assert_eq!(creators, None);
     }
 }
+
+mod versioned_layout_test {
+    use borsh::BorshSerialize;
+    use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+    use crate::{
+        state::{
+            Collection, CollectionDetails, Creator, Data, Key, Metadata, TokenStandard, UseMethod,
+            UseRestrictions, Uses, MAX_CREATOR_LIMIT, MAX_METADATA_LEN, MAX_METADATA_LEN_V1,
+            METADATA_EXTENSION_MARKER, METADATA_EXTENSION_MARKER_OFFSET, METADATA_SCHEMA_VERSION,
+        },
+        utils::{puff_out_data_fields, try_from_slice_checked, try_from_slice_checked_versioned},
+    };
+
+    fn metadata(schema_version: u8) -> Metadata {
+        let mut metadata = Metadata {
+            key: Key::MetadataV1,
+            update_authority: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            data: Data {
+                name: "Garfield".to_string(),
+                symbol: "GARF".to_string(),
+                uri: "https://garfiel.de".to_string(),
+                seller_fee_basis_points: 0,
+                creators: None,
+            },
+            primary_sale_happened: false,
+            is_mutable: false,
+            edition_nonce: None,
+            collection: None,
+            uses: None,
+            token_standard: None,
+            collection_details: None,
+            rule_set: None,
+            schema_version,
+            use_restrictions: None,
+        };
+        puff_out_data_fields(&mut metadata);
+        metadata
+    }
+
+    /// Account data as an earlier program version wrote it, without the extension marker.
+    fn serialize_into(metadata: &Metadata, len: usize) -> Vec<u8> {
+        let mut data = vec![0; len];
+        metadata.serialize(&mut data.as_mut_slice()).unwrap();
+        data
+    }
+
+    /// Account data as Metadata::save writes it.
+    fn save_into(metadata: &Metadata, len: usize) -> Vec<u8> {
+        let mut data = serialize_into(metadata, len);
+        data[METADATA_EXTENSION_MARKER_OFFSET] = METADATA_EXTENSION_MARKER;
+        data
+    }
+
+    #[test]
+    fn reads_legacy_and_migrated_layouts() {
+        let sizes = &[MAX_METADATA_LEN, MAX_METADATA_LEN_V1];
+
+        let legacy = metadata(0);
+        let read: Metadata = try_from_slice_checked_versioned(
+            &serialize_into(&legacy, MAX_METADATA_LEN),
+            Key::MetadataV1,
+            sizes,
+        )
+        .unwrap();
+        assert_eq!(read.mint, legacy.mint);
+        assert_eq!(read.schema_version, 0);
+
+        let mut migrated = metadata(METADATA_SCHEMA_VERSION);
+        migrated.use_restrictions = Some(UseRestrictions {
+            valid_from: Some(1),
+            valid_until: Some(2),
+            cooldown: Some(3),
+            last_used_at: Some(4),
+        });
+        let read: Metadata = try_from_slice_checked_versioned(
+            &save_into(&migrated, MAX_METADATA_LEN_V1),
+            Key::MetadataV1,
+            sizes,
+        )
+        .unwrap();
+        assert_eq!(read.mint, migrated.mint);
+        assert_eq!(read.schema_version, METADATA_SCHEMA_VERSION);
+        assert_eq!(read.use_restrictions, migrated.use_restrictions);
+    }
+
+    #[test]
+    fn rejects_unknown_layout_size() {
+        let data = serialize_into(&metadata(0), MAX_METADATA_LEN + 1);
+        let result: Result<Metadata, _> = try_from_slice_checked_versioned(
+            &data,
+            Key::MetadataV1,
+            &[MAX_METADATA_LEN, MAX_METADATA_LEN_V1],
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn reads_legacy_account_as_unmigrated_whatever_its_leftovers() {
+        let mut previous = metadata(0);
+        previous.collection = Some(Collection {
+            verified: false,
+            key: Pubkey::new_from_array([7; 32]),
+        });
+        let mut data = serialize_into(&previous, MAX_METADATA_LEN);
+
+        // Dropping the collection leaves its bytes behind the shorter serialization, here they
+        // read as a version byte followed by no use restrictions.
+        let mut current = previous.clone();
+        current.collection = None;
+        let legacy_len = current.try_to_vec().unwrap().len() - 2;
+        current.serialize(&mut data.as_mut_slice()).unwrap();
+        data[legacy_len] = METADATA_SCHEMA_VERSION;
+        data[legacy_len + 1] = 0;
+
+        let key = Pubkey::new_unique();
+        let owner = crate::id();
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        let read = Metadata::from_account_info(&account).unwrap();
+        assert_eq!(read.collection, None);
+        assert_eq!(read.schema_version, 0);
+        assert_eq!(read.use_restrictions, None);
+    }
+
+    #[test]
+    fn reads_leftover_creators_as_absent_fields() {
+        let creator = Creator {
+            address: Pubkey::new_from_array([7; 32]),
+            verified: false,
+            share: 20,
+        };
+        let mut previous = metadata(0);
+        previous.data.creators = Some(vec![creator; MAX_CREATOR_LIMIT]);
+        let mut data = serialize_into(&previous, MAX_METADATA_LEN);
+
+        // An earlier program wrote everything up to the uses, the dropped creators stay behind.
+        let mut current = previous.clone();
+        current.data.creators = current.data.creators.map(|creators| creators[..1].to_vec());
+        let current_data = current.try_to_vec().unwrap();
+        // Collection details, rule set, schema version and use restrictions are one byte each.
+        let uses_end = current_data.len() - 4;
+        data[..uses_end].copy_from_slice(&current_data[..uses_end]);
+        assert_eq!(data[uses_end], 7);
+
+        let read: Metadata =
+            try_from_slice_checked(&data, Key::MetadataV1, MAX_METADATA_LEN).unwrap();
+        assert_eq!(read.data.creators, current.data.creators);
+        assert_eq!(read.collection_details, None);
+        assert_eq!(read.rule_set, None);
+        assert_eq!(read.schema_version, 0);
+        assert_eq!(read.use_restrictions, None);
+    }
+
+    #[test]
+    fn ignores_garbage_after_the_uses_of_legacy_accounts() {
+        let legacy = metadata(0);
+        let mut data = serialize_into(&legacy, MAX_METADATA_LEN);
+
+        // Leftovers that happen to read back as collection details and a rule set.
+        let uses_end = legacy.try_to_vec().unwrap().len() - 4;
+        let mut garbage = Some(CollectionDetails::V1 { size: 7 })
+            .try_to_vec()
+            .unwrap();
+        garbage.extend(Some(Pubkey::new_from_array([7; 32])).try_to_vec().unwrap());
+        garbage.extend([METADATA_SCHEMA_VERSION, 0]);
+        data[uses_end..uses_end + garbage.len()].copy_from_slice(&garbage);
+
+        let read: Metadata =
+            try_from_slice_checked(&data, Key::MetadataV1, MAX_METADATA_LEN).unwrap();
+        assert_eq!(read.mint, legacy.mint);
+        assert_eq!(read.collection_details, None);
+        assert_eq!(read.rule_set, None);
+        assert_eq!(read.schema_version, 0);
+
+        // The same bytes are fields once the current program saved them.
+        data[METADATA_EXTENSION_MARKER_OFFSET] = METADATA_EXTENSION_MARKER;
+        let read: Metadata =
+            try_from_slice_checked(&data, Key::MetadataV1, MAX_METADATA_LEN).unwrap();
+        assert_eq!(
+            read.collection_details,
+            Some(CollectionDetails::V1 { size: 7 })
+        );
+        assert_eq!(read.rule_set, Some(Pubkey::new_from_array([7; 32])));
+    }
+
+    #[test]
+    fn save_clears_leftovers_of_a_longer_write() {
+        let mut previous = metadata(0);
+        previous.data.creators = Some(vec![
+            Creator {
+                address: Pubkey::new_from_array([1; 32]),
+                verified: false,
+                share: 20,
+            };
+            MAX_CREATOR_LIMIT
+        ]);
+        let mut data = serialize_into(&previous, MAX_METADATA_LEN);
+
+        let mut current = previous.clone();
+        current.data.creators = current.data.creators.map(|creators| creators[..1].to_vec());
+        let current_len = current.try_to_vec().unwrap().len();
+
+        let key = Pubkey::new_unique();
+        let owner = crate::id();
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        current.save(&account).unwrap();
+        assert!(
+            account.data.borrow()[current_len..METADATA_EXTENSION_MARKER_OFFSET]
+                .iter()
+                .all(|byte| *byte == 0)
+        );
+        assert_eq!(
+            account.data.borrow()[METADATA_EXTENSION_MARKER_OFFSET],
+            METADATA_EXTENSION_MARKER
+        );
+
+        let read = Metadata::from_account_info(&account).unwrap();
+        assert_eq!(read.data.creators, current.data.creators);
+        assert_eq!(read.collection_details, None);
+        assert_eq!(read.rule_set, None);
+    }
+
+    #[test]
+    fn largest_migrated_metadata_fits() {
+        let creator = Creator {
+            address: Pubkey::new_unique(),
+            verified: true,
+            share: 20,
+        };
+        let mut largest = metadata(METADATA_SCHEMA_VERSION);
+        largest.data.creators = Some(vec![creator; MAX_CREATOR_LIMIT]);
+        largest.edition_nonce = Some(255);
+        largest.token_standard = Some(TokenStandard::ProgrammableNonFungible);
+        largest.collection = Some(Collection {
+            verified: true,
+            key: Pubkey::new_unique(),
+        });
+        largest.uses = Some(Uses {
+            use_method: UseMethod::Multiple,
+            remaining: 1,
+            total: 1,
+        });
+        largest.collection_details = Some(CollectionDetails::V1 { size: 1 });
+        largest.rule_set = Some(Pubkey::new_unique());
+        largest.use_restrictions = Some(UseRestrictions {
+            valid_from: Some(1),
+            valid_until: Some(2),
+            cooldown: Some(3),
+            last_used_at: Some(4),
+        });
+
+        // The extension marker stays clear of every field.
+        assert!(largest.try_to_vec().unwrap().len() <= METADATA_EXTENSION_MARKER_OFFSET);
+
+        // Legacy accounts have room for everything but the use restrictions.
+        largest.use_restrictions = None;
+        assert!(largest.try_to_vec().unwrap().len() <= METADATA_EXTENSION_MARKER_OFFSET);
+    }
+}
+
+mod use_restrictions_test {
+    use crate::{
+        assertions::uses::{assert_use_allowed_at, assert_valid_use_restrictions},
+        state::UseRestrictions,
+    };
+
+    fn restrictions(last_used_at: Option<i64>) -> UseRestrictions {
+        UseRestrictions {
+            valid_from: Some(100),
+            valid_until: Some(200),
+            cooldown: Some(10),
+            last_used_at,
+        }
+    }
+
+    #[test]
+    fn enforces_window_and_cooldown() {
+        assert!(assert_use_allowed_at(&restrictions(None), 99).is_err());
+        assert!(assert_use_allowed_at(&restrictions(None), 100).is_ok());
+        assert!(assert_use_allowed_at(&restrictions(None), 201).is_err());
+        assert!(assert_use_allowed_at(&restrictions(Some(150)), 159).is_err());
+        assert!(assert_use_allowed_at(&restrictions(Some(150)), 160).is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_restrictions() {
+        let mut inverted = restrictions(None);
+        inverted.valid_from = Some(200);
+        assert!(assert_valid_use_restrictions(&inverted).is_err());
+
+        let mut negative_cooldown = restrictions(None);
+        negative_cooldown.cooldown = Some(-1);
+        assert!(assert_valid_use_restrictions(&negative_cooldown).is_err());
+
+        assert!(assert_valid_use_restrictions(&restrictions(None)).is_ok());
+    }
+}
@@ -0,0 +1,407 @@
+#![cfg(feature = "test-bpf")]
+mod utils;
+
+use mpl_token_metadata::{error::MetadataError, id, instruction};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use utils::*;
+
+mod burn_nft {
+    use super::*;
+
+    #[tokio::test]
+    async fn success() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(&mut context, Some(0))
+            .await
+            .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_nft(
+                id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                test_master_edition.pubkey,
+                spl_token::id(),
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let metadata_account = context
+            .banks_client
+            .get_account(test_metadata.pubkey)
+            .await
+            .unwrap();
+        let edition_account = context
+            .banks_client
+            .get_account(test_master_edition.pubkey)
+            .await
+            .unwrap();
+        let token_account = context
+            .banks_client
+            .get_account(test_metadata.token.pubkey())
+            .await
+            .unwrap();
+
+        assert!(metadata_account.is_none());
+        assert!(edition_account.is_none());
+        assert!(token_account.is_none());
+    }
+
+    #[tokio::test]
+    async fn fail_not_owner() {
+        let mut context = program_test().start_with_context().await;
+        let not_owner = Keypair::new();
+        airdrop(&mut context, &not_owner.pubkey(), 1_000_000_000)
+            .await
+            .unwrap();
+
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(&mut context, Some(0))
+            .await
+            .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_nft(
+                id(),
+                test_metadata.pubkey,
+                not_owner.pubkey(),
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                test_master_edition.pubkey,
+                spl_token::id(),
+                None,
+            )],
+            Some(&not_owner.pubkey()),
+            &[&not_owner],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::InvalidOwner);
+    }
+
+    #[tokio::test]
+    async fn fail_master_edition_has_prints() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(&mut context, Some(10))
+            .await
+            .unwrap();
+
+        let test_edition_marker = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        test_edition_marker.create(&mut context).await.unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_nft(
+                id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                test_master_edition.pubkey,
+                spl_token::id(),
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MasterEditionHasPrints);
+    }
+}
+
+mod burn_edition_nft {
+    use super::*;
+
+    #[tokio::test]
+    async fn success() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(&mut context, Some(10))
+            .await
+            .unwrap();
+
+        let first_print = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        first_print.create(&mut context).await.unwrap();
+        let second_print = EditionMarker::new(&test_metadata, &test_master_edition, 2);
+        second_print.create(&mut context).await.unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_edition_nft(
+                id(),
+                second_print.new_metadata_pubkey,
+                context.payer.pubkey(),
+                second_print.mint.pubkey(),
+                test_metadata.mint.pubkey(),
+                second_print.token.pubkey(),
+                test_master_edition.pubkey,
+                second_print.new_edition_pubkey,
+                second_print.pubkey,
+                spl_token::id(),
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let metadata_account = context
+            .banks_client
+            .get_account(second_print.new_metadata_pubkey)
+            .await
+            .unwrap();
+        let edition_account = context
+            .banks_client
+            .get_account(second_print.new_edition_pubkey)
+            .await
+            .unwrap();
+        assert!(metadata_account.is_none());
+        assert!(edition_account.is_none());
+
+        // The supply is the highest edition number printed, burning leaves it as is.
+        let master_edition = test_master_edition.get_data(&mut context).await;
+        assert_eq!(master_edition.supply, 2);
+
+        // Only the bit for the first print is left set.
+        let edition_marker = first_print.get_data(&mut context).await;
+        assert_eq!(edition_marker.ledger[0], 64);
+    }
+
+    #[tokio::test]
+    async fn success_reprint_burned_edition() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(&mut context, Some(1))
+            .await
+            .unwrap();
+
+        let print = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        print.create(&mut context).await.unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_edition_nft(
+                id(),
+                print.new_metadata_pubkey,
+                context.payer.pubkey(),
+                print.mint.pubkey(),
+                test_metadata.mint.pubkey(),
+                print.token.pubkey(),
+                test_master_edition.pubkey,
+                print.new_edition_pubkey,
+                print.pubkey,
+                spl_token::id(),
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        // The emptied marker stays open with the bit of the burned print cleared.
+        let edition_marker = print.get_data(&mut context).await;
+        assert!(edition_marker.ledger.iter().all(|byte| *byte == 0));
+
+        // The burned number is free again, even with the max supply already reached.
+        let reprint = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        reprint.create(&mut context).await.unwrap();
+
+        let master_edition = test_master_edition.get_data(&mut context).await;
+        assert_eq!(master_edition.supply, 1);
+        let edition_marker = reprint.get_data(&mut context).await;
+        assert_eq!(edition_marker.ledger[0], 64);
+    }
+
+    #[tokio::test]
+    async fn fail_wrong_master_edition() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(&mut context, Some(10))
+            .await
+            .unwrap();
+
+        let other_metadata = Metadata::new();
+        other_metadata
+            .create_v2(
+                &mut context,
+                "Other".to_string(),
+                "OTH".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let other_master_edition = MasterEditionV2::new(&other_metadata);
+        other_master_edition
+            .create_v3(&mut context, Some(10))
+            .await
+            .unwrap();
+
+        let print = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        print.create(&mut context).await.unwrap();
+        let other_print = EditionMarker::new(&other_metadata, &other_master_edition, 1);
+        other_print.create(&mut context).await.unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_edition_nft(
+                id(),
+                print.new_metadata_pubkey,
+                context.payer.pubkey(),
+                print.mint.pubkey(),
+                other_metadata.mint.pubkey(),
+                print.token.pubkey(),
+                other_master_edition.pubkey,
+                print.new_edition_pubkey,
+                other_print.pubkey,
+                spl_token::id(),
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::PrintEditionDoesNotMatchMasterEdition);
+    }
+}
@@ -0,0 +1,535 @@
+#![cfg(feature = "test-bpf")]
+mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    id, instruction,
+    state::{Collection, CollectionDetails},
+};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+    transport::{self, TransportError},
+};
+use utils::*;
+
+mod collection_size {
+    use super::*;
+
+    async fn create_collection(context: &mut ProgramTestContext) -> (Metadata, MasterEditionV2) {
+        let test_collection = Metadata::new();
+        test_collection
+            .create_v2(
+                context,
+                "Collection".to_string(),
+                "COL".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let collection_master_edition_account = MasterEditionV2::new(&test_collection);
+        collection_master_edition_account
+            .create_v3(context, Some(0))
+            .await
+            .unwrap();
+
+        (test_collection, collection_master_edition_account)
+    }
+
+    async fn set_collection_size(
+        context: &mut ProgramTestContext,
+        collection: &Metadata,
+        size: u64,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_collection_size(
+                id(),
+                collection.pubkey,
+                context.payer.pubkey(),
+                collection.mint.pubkey(),
+                None,
+                size,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn create_item(context: &mut ProgramTestContext, collection_mint: Pubkey) -> Metadata {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                Some(Collection {
+                    key: collection_mint,
+                    verified: false,
+                }),
+                None,
+            )
+            .await
+            .unwrap();
+
+        test_metadata
+    }
+
+    #[tokio::test]
+    async fn success_verify_and_unverify_updates_size() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, collection_master_edition_account) =
+            create_collection(&mut context).await;
+        set_collection_size(&mut context, &test_collection, 0)
+            .await
+            .unwrap();
+
+        let test_metadata = create_item(&mut context, test_collection.mint.pubkey()).await;
+        let kp = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        test_metadata
+            .verify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let collection_data = test_collection.get_data(&mut context).await;
+        assert_eq!(
+            collection_data.collection_details,
+            Some(CollectionDetails::V1 { size: 1 })
+        );
+
+        test_metadata
+            .unverify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let collection_data = test_collection.get_data(&mut context).await;
+        assert_eq!(
+            collection_data.collection_details,
+            Some(CollectionDetails::V1 { size: 0 })
+        );
+    }
+
+    #[tokio::test]
+    async fn success_set_and_verify_updates_size() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, collection_master_edition_account) =
+            create_collection(&mut context).await;
+        set_collection_size(&mut context, &test_collection, 41)
+            .await
+            .unwrap();
+
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let kp = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        test_metadata
+            .set_and_verify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                kp.pubkey(),
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let collection_data = test_collection.get_data(&mut context).await;
+        assert_eq!(
+            collection_data.collection_details,
+            Some(CollectionDetails::V1 { size: 42 })
+        );
+    }
+
+    #[tokio::test]
+    async fn success_unsized_collection_is_untouched() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, collection_master_edition_account) =
+            create_collection(&mut context).await;
+
+        let test_metadata = create_item(&mut context, test_collection.mint.pubkey()).await;
+        let kp = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        test_metadata
+            .verify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let collection_data = test_collection.get_data(&mut context).await;
+        assert_eq!(collection_data.collection_details, None);
+    }
+
+    #[tokio::test]
+    async fn success_burn_edition_updates_size() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, collection_master_edition_account) =
+            create_collection(&mut context).await;
+        set_collection_size(&mut context, &test_collection, 10)
+            .await
+            .unwrap();
+
+        let test_metadata = create_item(&mut context, test_collection.mint.pubkey()).await;
+        let kp = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        test_metadata
+            .verify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(&mut context, Some(10))
+            .await
+            .unwrap();
+        // The print copies the verified collection of its master edition.
+        let print = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        print.create(&mut context).await.unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_edition_nft(
+                id(),
+                print.new_metadata_pubkey,
+                context.payer.pubkey(),
+                print.mint.pubkey(),
+                test_metadata.mint.pubkey(),
+                print.token.pubkey(),
+                test_master_edition.pubkey,
+                print.new_edition_pubkey,
+                print.pubkey,
+                spl_token::id(),
+                Some(test_collection.pubkey),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let collection_data = test_collection.get_data(&mut context).await;
+        assert_eq!(
+            collection_data.collection_details,
+            Some(CollectionDetails::V1 { size: 10 })
+        );
+    }
+
+    #[tokio::test]
+    async fn success_burn_item_after_collection_burned() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, collection_master_edition_account) =
+            create_collection(&mut context).await;
+        set_collection_size(&mut context, &test_collection, 0)
+            .await
+            .unwrap();
+
+        let test_metadata = create_item(&mut context, test_collection.mint.pubkey()).await;
+        let kp = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        test_metadata
+            .verify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(&mut context, Some(0))
+            .await
+            .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_nft(
+                id(),
+                test_collection.pubkey,
+                context.payer.pubkey(),
+                test_collection.mint.pubkey(),
+                test_collection.token.pubkey(),
+                collection_master_edition_account.pubkey,
+                spl_token::id(),
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_nft(
+                id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                test_master_edition.pubkey,
+                spl_token::id(),
+                Some(test_collection.pubkey),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let metadata_account = context
+            .banks_client
+            .get_account(test_metadata.pubkey)
+            .await
+            .unwrap();
+        assert!(metadata_account.is_none());
+    }
+
+    #[tokio::test]
+    async fn success_unverify_below_zero_keeps_size_at_zero() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, collection_master_edition_account) =
+            create_collection(&mut context).await;
+
+        let test_metadata = create_item(&mut context, test_collection.mint.pubkey()).await;
+        let kp = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        test_metadata
+            .verify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+        // The size is set after the fact and misses the item verified above.
+        set_collection_size(&mut context, &test_collection, 0)
+            .await
+            .unwrap();
+
+        test_metadata
+            .unverify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let collection_data = test_collection.get_data(&mut context).await;
+        assert_eq!(
+            collection_data.collection_details,
+            Some(CollectionDetails::V1 { size: 0 })
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_set_and_verify_moves_verified_item() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, collection_master_edition_account) =
+            create_collection(&mut context).await;
+        set_collection_size(&mut context, &test_collection, 0)
+            .await
+            .unwrap();
+        let (other_collection, other_master_edition_account) =
+            create_collection(&mut context).await;
+
+        let test_metadata = create_item(&mut context, test_collection.mint.pubkey()).await;
+        let kp = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        test_metadata
+            .verify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let err = test_metadata
+            .set_and_verify_collection(
+                &mut context,
+                other_collection.pubkey,
+                &kp,
+                kp.pubkey(),
+                other_collection.mint.pubkey(),
+                other_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::VerifiedCollectionMustBeUnverifiedFirst);
+        let collection_data = test_collection.get_data(&mut context).await;
+        assert_eq!(
+            collection_data.collection_details,
+            Some(CollectionDetails::V1 { size: 1 })
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_update_removes_verified_collection() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, collection_master_edition_account) =
+            create_collection(&mut context).await;
+        set_collection_size(&mut context, &test_collection, 0)
+            .await
+            .unwrap();
+
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                Some(Collection {
+                    key: test_collection.mint.pubkey(),
+                    verified: false,
+                }),
+                None,
+            )
+            .await
+            .unwrap();
+        let kp = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        test_metadata
+            .verify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let err = test_metadata
+            .update_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::VerifiedCollectionMustBeUnverifiedFirst);
+    }
+
+    #[tokio::test]
+    async fn fail_set_size_twice() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, _) = create_collection(&mut context).await;
+        set_collection_size(&mut context, &test_collection, 10)
+            .await
+            .unwrap();
+
+        let err = set_collection_size(&mut context, &test_collection, 20)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::CollectionAlreadySized);
+    }
+
+    #[tokio::test]
+    async fn fail_set_size_wrong_authority() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, _) = create_collection(&mut context).await;
+        let fake_authority = Keypair::new();
+        airdrop(&mut context, &fake_authority.pubkey(), 1_000_000_000)
+            .await
+            .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_collection_size(
+                id(),
+                test_collection.pubkey,
+                fake_authority.pubkey(),
+                test_collection.mint.pubkey(),
+                None,
+                10,
+            )],
+            Some(&fake_authority.pubkey()),
+            &[&fake_authority],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::InvalidCollectionUpdateAuthority);
+    }
+}
@@ -0,0 +1,451 @@
+#![cfg(feature = "test-bpf")]
+mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    id, instruction,
+    pda::find_metadata_delegate_account,
+    state::{Collection, DataV2, MetadataDelegatePermissions},
+    utils::puffed_out_string,
+};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+    transport::{self, TransportError},
+};
+use utils::*;
+
+mod metadata_delegate {
+    use super::*;
+
+    async fn create_metadata(context: &mut ProgramTestContext) -> Metadata {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        test_metadata
+    }
+
+    async fn approve(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        delegate: &Keypair,
+        permissions: MetadataDelegatePermissions,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::approve_metadata_delegate(
+                id(),
+                delegate.pubkey(),
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                permissions,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn update_as_delegate(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        delegate: &Keypair,
+        data: DataV2,
+    ) -> transport::Result<()> {
+        let (record, _) =
+            find_metadata_delegate_account(&test_metadata.mint.pubkey(), &delegate.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::update_metadata_accounts_v2_as_delegate(
+                id(),
+                test_metadata.pubkey,
+                delegate.pubkey(),
+                record,
+                data,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, delegate],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    fn data(name: &str, uri: &str) -> DataV2 {
+        DataV2 {
+            name: name.to_string(),
+            symbol: "TST".to_string(),
+            uri: uri.to_string(),
+            seller_fee_basis_points: 10,
+            creators: None,
+            collection: None,
+            uses: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn success_update_uri() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_metadata(&mut context).await;
+        let delegate = Keypair::new();
+        approve(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            MetadataDelegatePermissions {
+                uri: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        update_as_delegate(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            data("Test", "https://refreshed.uri"),
+        )
+        .await
+        .unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            metadata.data.uri,
+            puffed_out_string(&"https://refreshed.uri".to_string(), 200)
+        );
+        assert_eq!(metadata.update_authority, context.payer.pubkey());
+    }
+
+    #[tokio::test]
+    async fn success_update_uri_in_verified_collection() {
+        let mut context = program_test().start_with_context().await;
+        let test_collection = Metadata::new();
+        test_collection
+            .create_v2(
+                &mut context,
+                "Collection".to_string(),
+                "COL".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let collection_master_edition_account = MasterEditionV2::new(&test_collection);
+        collection_master_edition_account
+            .create_v3(&mut context, Some(0))
+            .await
+            .unwrap();
+
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                Some(Collection {
+                    key: test_collection.mint.pubkey(),
+                    verified: false,
+                }),
+                None,
+            )
+            .await
+            .unwrap();
+        let kp = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        test_metadata
+            .verify_collection(
+                &mut context,
+                test_collection.pubkey,
+                &kp,
+                test_collection.mint.pubkey(),
+                collection_master_edition_account.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let delegate = Keypair::new();
+        approve(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            MetadataDelegatePermissions {
+                uri: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let collection = Some(Collection {
+            key: test_collection.mint.pubkey(),
+            verified: true,
+        });
+        update_as_delegate(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            DataV2 {
+                collection: collection.clone(),
+                ..data("Test", "https://refreshed.uri")
+            },
+        )
+        .await
+        .unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            metadata.data.uri,
+            puffed_out_string(&"https://refreshed.uri".to_string(), 200)
+        );
+        assert_eq!(metadata.collection, collection);
+    }
+
+    #[tokio::test]
+    async fn fail_update_outside_scope() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_metadata(&mut context).await;
+        let delegate = Keypair::new();
+        approve(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            MetadataDelegatePermissions {
+                uri: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let err = update_as_delegate(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            data("Renamed", "uri"),
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MetadataDelegateScopeViolation);
+    }
+
+    #[tokio::test]
+    async fn fail_update_uri_without_permission() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_metadata(&mut context).await;
+        let delegate = Keypair::new();
+        approve(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            MetadataDelegatePermissions {
+                uses: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let err = update_as_delegate(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            data("Test", "https://refreshed.uri"),
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MetadataDelegateScopeViolation);
+    }
+
+    #[tokio::test]
+    async fn fail_update_after_revoke() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_metadata(&mut context).await;
+        let delegate = Keypair::new();
+        approve(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            MetadataDelegatePermissions {
+                uri: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let (record, _) =
+            find_metadata_delegate_account(&test_metadata.mint.pubkey(), &delegate.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::revoke_metadata_delegate(
+                id(),
+                record,
+                delegate.pubkey(),
+                context.payer.pubkey(),
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let err = update_as_delegate(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            data("Test", "https://refreshed.uri"),
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MetadataDelegateRecordDoesNotExist);
+    }
+
+    #[tokio::test]
+    async fn fail_approve_twice() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_metadata(&mut context).await;
+        let delegate = Keypair::new();
+        approve(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            MetadataDelegatePermissions {
+                uri: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let err = approve(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            MetadataDelegatePermissions {
+                uri: true,
+                collection: true,
+                uses: true,
+            },
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MetadataDelegateRecordAlreadyExists);
+    }
+
+    #[tokio::test]
+    async fn fail_update_after_update_authority_changed() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_metadata(&mut context).await;
+        let delegate = Keypair::new();
+        approve(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            MetadataDelegatePermissions {
+                uri: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let new_update_authority = Keypair::new();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::update_metadata_accounts_v2(
+                id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                Some(new_update_authority.pubkey()),
+                None,
+                None,
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let err = update_as_delegate(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            data("Test", "https://refreshed.uri"),
+        )
+        .await
+        .unwrap_err();
+        assert_custom_error!(
+            err,
+            MetadataError::MetadataDelegateApprovedByPreviousAuthority
+        );
+
+        // The new update authority can approve the same delegate again.
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::approve_metadata_delegate(
+                id(),
+                delegate.pubkey(),
+                new_update_authority.pubkey(),
+                context.payer.pubkey(),
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                MetadataDelegatePermissions {
+                    uri: true,
+                    ..Default::default()
+                },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &new_update_authority],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        update_as_delegate(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            data("Test", "https://refreshed.uri"),
+        )
+        .await
+        .unwrap();
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            metadata.data.uri,
+            puffed_out_string(&"https://refreshed.uri".to_string(), 200)
+        );
+    }
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "test-bpf")]
+mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    id, instruction,
+    state::{MAX_METADATA_LEN, MAX_METADATA_LEN_V1, METADATA_SCHEMA_VERSION},
+    utils::puffed_out_string,
+};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+    transport::{self, TransportError},
+};
+use utils::*;
+
+mod migrate_metadata {
+    use super::*;
+
+    async fn migrate(context: &mut ProgramTestContext, metadata: &Metadata) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::migrate_metadata(
+                id(),
+                metadata.pubkey,
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let account = get_account(&mut context, &test_metadata.pubkey).await;
+        assert_eq!(account.data.len(), MAX_METADATA_LEN);
+
+        migrate(&mut context, &test_metadata).await.unwrap();
+
+        let account = get_account(&mut context, &test_metadata.pubkey).await;
+        assert_eq!(account.data.len(), MAX_METADATA_LEN_V1);
+        let rent = context.banks_client.get_rent().await.unwrap();
+        assert!(account.lamports >= rent.minimum_balance(MAX_METADATA_LEN_V1));
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(metadata.schema_version, METADATA_SCHEMA_VERSION);
+        assert_eq!(metadata.mint, test_metadata.mint.pubkey());
+        assert_eq!(metadata.data.name, puffed_out_string(&"Test".to_string(), 32));
+
+        // Migrated accounts keep working with the existing instructions.
+        test_metadata
+            .update_v2(
+                &mut context,
+                "Updated".to_string(),
+                "UPD".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            metadata.data.name,
+            puffed_out_string(&"Updated".to_string(), 32)
+        );
+        assert_eq!(metadata.schema_version, METADATA_SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn fail_already_migrated() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        migrate(&mut context, &test_metadata).await.unwrap();
+        context.warp_to_slot(100).unwrap();
+        let err = migrate(&mut context, &test_metadata).await.unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MetadataAlreadyMigrated);
+    }
+
+    #[tokio::test]
+    async fn fail_invalid_update_authority() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let fake_update_authority = Keypair::new();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::migrate_metadata(
+                id(),
+                test_metadata.pubkey,
+                fake_update_authority.pubkey(),
+                context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &fake_update_authority],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UpdateAuthorityIncorrect);
+        let account = get_account(&mut context, &test_metadata.pubkey).await;
+        assert_eq!(account.data.len(), MAX_METADATA_LEN);
+    }
+}
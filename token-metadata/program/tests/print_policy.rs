@@ -0,0 +1,304 @@
+#![cfg(feature = "test-bpf")]
+mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    id, instruction,
+    pda::{find_print_policy_account, find_print_recipient_record_account},
+    state::{Creator, PrintPolicy, PrintRecipientRecord},
+};
+use num_traits::FromPrimitive;
+use solana_program::borsh::try_from_slice_unchecked;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::{self, TransportError},
+};
+use utils::*;
+
+mod print_policy {
+    use super::*;
+
+    async fn create_master_edition(
+        context: &mut ProgramTestContext,
+        creators: Option<Vec<Creator>>,
+    ) -> (Metadata, MasterEditionV2) {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                creators,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(context, Some(10))
+            .await
+            .unwrap();
+
+        (test_metadata, test_master_edition)
+    }
+
+    async fn set_print_policy(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        test_master_edition: &MasterEditionV2,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        max_per_recipient: Option<u64>,
+        price: Option<u64>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_print_policy(
+                id(),
+                test_master_edition.pubkey,
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                start_time,
+                end_time,
+                max_per_recipient,
+                price,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success_per_recipient_cap() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context, None).await;
+        set_print_policy(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            None,
+            None,
+            Some(1),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (policy, _) = find_print_policy_account(&test_metadata.mint.pubkey());
+        let account = get_account(&mut context, &policy).await;
+        let policy: PrintPolicy = try_from_slice_unchecked(&account.data).unwrap();
+        assert_eq!(policy.max_per_recipient, Some(1));
+
+        let payer = context.payer.pubkey();
+        let first_print = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        first_print
+            .create_with_print_policy(&mut context, &payer, vec![])
+            .await
+            .unwrap();
+
+        let (record, _) = find_print_recipient_record_account(&test_metadata.mint.pubkey(), &payer);
+        let account = get_account(&mut context, &record).await;
+        let record: PrintRecipientRecord = try_from_slice_unchecked(&account.data).unwrap();
+        assert_eq!(record.count, 1);
+
+        // The cap follows the owner of the print, not the payer.
+        let other_owner = Pubkey::new_unique();
+        let other_print = EditionMarker::new(&test_metadata, &test_master_edition, 2);
+        other_print
+            .create_with_print_policy(&mut context, &other_owner, vec![])
+            .await
+            .unwrap();
+
+        let second_print = EditionMarker::new(&test_metadata, &test_master_edition, 3);
+        let err = second_print
+            .create_with_print_policy(&mut context, &payer, vec![])
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::PrintLimitReached);
+    }
+
+    #[tokio::test]
+    async fn success_price_paid_to_creators() {
+        let mut context = program_test().start_with_context().await;
+        let other_creator = Pubkey::new_unique();
+        let creators = vec![
+            Creator {
+                address: context.payer.pubkey(),
+                verified: true,
+                share: 50,
+            },
+            Creator {
+                address: other_creator,
+                verified: false,
+                share: 50,
+            },
+        ];
+        let (test_metadata, test_master_edition) =
+            create_master_edition(&mut context, Some(creators)).await;
+        set_print_policy(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            None,
+            None,
+            None,
+            Some(10_000_000),
+        )
+        .await
+        .unwrap();
+
+        let payer = context.payer.pubkey();
+        let print = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        print
+            .create_with_print_policy(&mut context, &payer, vec![payer, other_creator])
+            .await
+            .unwrap();
+
+        let account = get_account(&mut context, &other_creator).await;
+        assert_eq!(account.lamports, 5_000_000);
+    }
+
+    #[tokio::test]
+    async fn fail_print_without_policy_accounts() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context, None).await;
+        set_print_policy(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            None,
+            None,
+            Some(5),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let print = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        let err = print.create(&mut context).await.unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MissingPrintPolicyAccounts);
+    }
+
+    #[tokio::test]
+    async fn fail_print_window_not_started() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context, None).await;
+        set_print_policy(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            Some(i64::MAX - 1),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let payer = context.payer.pubkey();
+        let print = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        let err = print
+            .create_with_print_policy(&mut context, &payer, vec![])
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::PrintWindowNotStarted);
+    }
+
+    #[tokio::test]
+    async fn fail_print_window_ended() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context, None).await;
+        set_print_policy(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            None,
+            Some(1),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let payer = context.payer.pubkey();
+        let print = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        let err = print
+            .create_with_print_policy(&mut context, &payer, vec![])
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::PrintWindowEnded);
+    }
+
+    #[tokio::test]
+    async fn fail_set_invalid_window() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context, None).await;
+
+        let err = set_print_policy(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            Some(100),
+            Some(100),
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::InvalidPrintWindow);
+    }
+
+    #[tokio::test]
+    async fn success_remove_print_policy() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context, None).await;
+        set_print_policy(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            None,
+            None,
+            Some(1),
+            None,
+        )
+        .await
+        .unwrap();
+        context.warp_to_slot(100).unwrap();
+        set_print_policy(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (policy, _) = find_print_policy_account(&test_metadata.mint.pubkey());
+        let account = context.banks_client.get_account(policy).await.unwrap();
+        assert!(account.is_none());
+
+        let print = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        print.create(&mut context).await.unwrap();
+    }
+}
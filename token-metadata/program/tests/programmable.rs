@@ -0,0 +1,433 @@
+#![cfg(feature = "test-bpf")]
+mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    id, instruction,
+    pda::find_rule_set_account,
+    state::{RuleSet, TokenStandard, MAX_RULE_SET_PROGRAMS},
+};
+use num_traits::FromPrimitive;
+use solana_program::{borsh::try_from_slice_unchecked, program_pack::Pack};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+    transport::{self, TransportError},
+};
+use spl_token::state::{Account, AccountState};
+use utils::*;
+
+mod programmable {
+    use super::*;
+
+    async fn create_master_edition(context: &mut ProgramTestContext) -> (Metadata, MasterEditionV2) {
+        let freeze_authority = context.payer.pubkey();
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                Some(&freeze_authority),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(context, Some(0))
+            .await
+            .unwrap();
+
+        (test_metadata, test_master_edition)
+    }
+
+    async fn set_rule_set(
+        context: &mut ProgramTestContext,
+        allowed_programs: Vec<Pubkey>,
+        allow_direct_transfers: bool,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_rule_set(
+                id(),
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                allowed_programs,
+                allow_direct_transfers,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn convert(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        test_master_edition: &MasterEditionV2,
+        rule_set: Pubkey,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::convert_to_programmable(
+                id(),
+                test_metadata.pubkey,
+                test_master_edition.pubkey,
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                rule_set,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn get_token_account(context: &mut ProgramTestContext, pubkey: &Pubkey) -> Account {
+        let account = get_account(context, pubkey).await;
+        Account::unpack(&account.data).unwrap()
+    }
+
+    #[tokio::test]
+    async fn success_set_rule_set() {
+        let mut context = program_test().start_with_context().await;
+        let allowed_program = Pubkey::new_unique();
+        set_rule_set(
+            &mut context,
+            vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            true,
+        )
+        .await
+        .unwrap();
+        set_rule_set(&mut context, vec![allowed_program], false)
+            .await
+            .unwrap();
+
+        let (rule_set, _) = find_rule_set_account(&context.payer.pubkey());
+        let account = get_account(&mut context, &rule_set).await;
+        let rule_set: RuleSet = try_from_slice_unchecked(&account.data).unwrap();
+        assert_eq!(rule_set.authority, context.payer.pubkey());
+        assert_eq!(rule_set.allowed_programs, vec![allowed_program]);
+        assert_eq!(rule_set.allow_direct_transfers, false);
+    }
+
+    #[tokio::test]
+    async fn fail_set_rule_set_too_many_programs() {
+        let mut context = program_test().start_with_context().await;
+        let allowed_programs = (0..MAX_RULE_SET_PROGRAMS + 1)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+
+        let err = set_rule_set(&mut context, allowed_programs, false)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::TooManyRuleSetPrograms);
+    }
+
+    #[tokio::test]
+    async fn success_convert_and_transfer() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context).await;
+        set_rule_set(&mut context, vec![], true).await.unwrap();
+        let (rule_set, _) = find_rule_set_account(&context.payer.pubkey());
+
+        convert(&mut context, &test_metadata, &test_master_edition, rule_set)
+            .await
+            .unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            metadata.token_standard,
+            Some(TokenStandard::ProgrammableNonFungible)
+        );
+        assert_eq!(metadata.rule_set, Some(rule_set));
+        let source = get_token_account(&mut context, &test_metadata.token.pubkey()).await;
+        assert_eq!(source.state, AccountState::Frozen);
+
+        let new_owner = Keypair::new();
+        let destination = Keypair::new();
+        create_token_account(
+            &mut context,
+            &destination,
+            &test_metadata.mint.pubkey(),
+            &new_owner.pubkey(),
+        )
+        .await
+        .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::transfer(
+                id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                test_metadata.token.pubkey(),
+                destination.pubkey(),
+                test_metadata.mint.pubkey(),
+                test_master_edition.pubkey,
+                rule_set,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let source = get_token_account(&mut context, &test_metadata.token.pubkey()).await;
+        let destination = get_token_account(&mut context, &destination.pubkey()).await;
+        assert_eq!(source.amount, 0);
+        assert_eq!(source.state, AccountState::Initialized);
+        assert_eq!(destination.amount, 1);
+        assert_eq!(destination.state, AccountState::Frozen);
+    }
+
+    #[tokio::test]
+    async fn success_close_source_after_transfer() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context).await;
+        set_rule_set(&mut context, vec![], true).await.unwrap();
+        let (rule_set, _) = find_rule_set_account(&context.payer.pubkey());
+        convert(&mut context, &test_metadata, &test_master_edition, rule_set)
+            .await
+            .unwrap();
+
+        let new_owner = Keypair::new();
+        let destination = Keypair::new();
+        create_token_account(
+            &mut context,
+            &destination,
+            &test_metadata.mint.pubkey(),
+            &new_owner.pubkey(),
+        )
+        .await
+        .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                instruction::transfer(
+                    id(),
+                    test_metadata.pubkey,
+                    context.payer.pubkey(),
+                    test_metadata.token.pubkey(),
+                    destination.pubkey(),
+                    test_metadata.mint.pubkey(),
+                    test_master_edition.pubkey,
+                    rule_set,
+                ),
+                spl_token::instruction::close_account(
+                    &spl_token::id(),
+                    &test_metadata.token.pubkey(),
+                    &context.payer.pubkey(),
+                    &context.payer.pubkey(),
+                    &[],
+                )
+                .unwrap(),
+            ],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let source = context
+            .banks_client
+            .get_account(test_metadata.token.pubkey())
+            .await
+            .unwrap();
+        assert!(source.is_none());
+    }
+
+    #[tokio::test]
+    async fn fail_spl_transfer_after_convert() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context).await;
+        set_rule_set(&mut context, vec![], true).await.unwrap();
+        let (rule_set, _) = find_rule_set_account(&context.payer.pubkey());
+        convert(&mut context, &test_metadata, &test_master_edition, rule_set)
+            .await
+            .unwrap();
+
+        let destination = Keypair::new();
+        create_token_account(
+            &mut context,
+            &destination,
+            &test_metadata.mint.pubkey(),
+            &Keypair::new().pubkey(),
+        )
+        .await
+        .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[spl_token::instruction::transfer(
+                &spl_token::id(),
+                &test_metadata.token.pubkey(),
+                &destination.pubkey(),
+                &context.payer.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        assert!(context.banks_client.process_transaction(tx).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn fail_convert_with_delegate() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context).await;
+        set_rule_set(&mut context, vec![], true).await.unwrap();
+        let (rule_set, _) = find_rule_set_account(&context.payer.pubkey());
+
+        let approve_tx = Transaction::new_signed_with_payer(
+            &[spl_token::instruction::approve(
+                &spl_token::id(),
+                &test_metadata.token.pubkey(),
+                &Keypair::new().pubkey(),
+                &context.payer.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context
+            .banks_client
+            .process_transaction(approve_tx)
+            .await
+            .unwrap();
+
+        let err = convert(&mut context, &test_metadata, &test_master_edition, rule_set)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::InvalidDelegate);
+    }
+
+    #[tokio::test]
+    async fn fail_transfer_wrong_rule_set() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context).await;
+        set_rule_set(&mut context, vec![], true).await.unwrap();
+        let (rule_set, _) = find_rule_set_account(&context.payer.pubkey());
+        convert(&mut context, &test_metadata, &test_master_edition, rule_set)
+            .await
+            .unwrap();
+
+        let other_authority = Keypair::new();
+        airdrop(&mut context, &other_authority.pubkey(), 1_000_000_000)
+            .await
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_rule_set(
+                id(),
+                other_authority.pubkey(),
+                other_authority.pubkey(),
+                vec![],
+                true,
+            )],
+            Some(&other_authority.pubkey()),
+            &[&other_authority],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+        let (other_rule_set, _) = find_rule_set_account(&other_authority.pubkey());
+
+        let destination = Keypair::new();
+        create_token_account(
+            &mut context,
+            &destination,
+            &test_metadata.mint.pubkey(),
+            &Keypair::new().pubkey(),
+        )
+        .await
+        .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::transfer(
+                id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                test_metadata.token.pubkey(),
+                destination.pubkey(),
+                test_metadata.mint.pubkey(),
+                test_master_edition.pubkey,
+                other_rule_set,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::InvalidRuleSet);
+    }
+
+    #[tokio::test]
+    async fn fail_direct_transfer_not_allowed() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context).await;
+        set_rule_set(&mut context, vec![Pubkey::new_unique()], false)
+            .await
+            .unwrap();
+        let (rule_set, _) = find_rule_set_account(&context.payer.pubkey());
+        convert(&mut context, &test_metadata, &test_master_edition, rule_set)
+            .await
+            .unwrap();
+
+        let destination = Keypair::new();
+        create_token_account(
+            &mut context,
+            &destination,
+            &test_metadata.mint.pubkey(),
+            &Keypair::new().pubkey(),
+        )
+        .await
+        .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::transfer(
+                id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                test_metadata.token.pubkey(),
+                destination.pubkey(),
+                test_metadata.mint.pubkey(),
+                test_master_edition.pubkey,
+                rule_set,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::DirectTransferNotAllowedByRuleSet);
+    }
+}
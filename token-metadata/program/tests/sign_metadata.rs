@@ -95,3 +95,111 @@ mod sign_metadata {
         assert_eq!(after_remove.data.creators.unwrap()[1].verified, false);
     }
 }
+
+mod sign_metadata_batch {
+    use solana_sdk::transaction::Transaction;
+
+    use mpl_token_metadata::instruction::sign_metadata_batch;
+    use mpl_token_metadata::state::Creator;
+
+    use super::*;
+
+    async fn create_with_creator(context: &mut ProgramTestContext, creator: &Keypair) -> Metadata {
+        let ua_creator = context.payer.pubkey().clone();
+        let test_meta = Metadata::new();
+        test_meta
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                Some(vec![
+                    Creator {
+                        address: ua_creator,
+                        verified: true,
+                        share: 0,
+                    },
+                    Creator {
+                        address: creator.pubkey(),
+                        verified: false,
+                        share: 100,
+                    },
+                ]),
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        test_meta
+    }
+
+    #[tokio::test]
+    async fn success_verify_unverify_creator() {
+        let mut context = program_test().start_with_context().await;
+        let creator = Keypair::new();
+        let first = create_with_creator(&mut context, &creator).await;
+        let second = create_with_creator(&mut context, &creator).await;
+
+        let sign_tx = Transaction::new_signed_with_payer(
+            &[sign_metadata_batch(
+                mpl_token_metadata::id(),
+                creator.pubkey(),
+                vec![first.pubkey, second.pubkey],
+                true,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&creator, &context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(sign_tx).await.unwrap();
+        assert_eq!(first.get_data(&mut context).await.data.creators.unwrap()[1].verified, true);
+        assert_eq!(second.get_data(&mut context).await.data.creators.unwrap()[1].verified, true);
+
+        let remove_tx = Transaction::new_signed_with_payer(
+            &[sign_metadata_batch(
+                mpl_token_metadata::id(),
+                creator.pubkey(),
+                vec![first.pubkey, second.pubkey],
+                false,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&creator, &context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(remove_tx).await.unwrap();
+        assert_eq!(first.get_data(&mut context).await.data.creators.unwrap()[1].verified, false);
+        assert_eq!(second.get_data(&mut context).await.data.creators.unwrap()[1].verified, false);
+    }
+
+    #[tokio::test]
+    async fn fail_creator_missing_on_one_metadata() {
+        let mut context = program_test().start_with_context().await;
+        let creator = Keypair::new();
+        let first = create_with_creator(&mut context, &creator).await;
+        let other = create_with_creator(&mut context, &Keypair::new()).await;
+
+        let sign_tx = Transaction::new_signed_with_payer(
+            &[sign_metadata_batch(
+                mpl_token_metadata::id(),
+                creator.pubkey(),
+                vec![first.pubkey, other.pubkey],
+                true,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&creator, &context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(sign_tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::CreatorNotFound);
+        // Nothing is applied when one of the accounts fails.
+        assert_eq!(first.get_data(&mut context).await.data.creators.unwrap()[1].verified, false);
+    }
+}
@@ -0,0 +1,184 @@
+#![cfg(feature = "test-bpf")]
+mod utils;
+
+use mpl_token_metadata::{error::MetadataError, id, instruction};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+    transport::{self, TransportError},
+};
+use utils::*;
+
+mod update_master_edition_supply {
+    use super::*;
+
+    async fn create_master_edition(
+        context: &mut ProgramTestContext,
+        max_supply: Option<u64>,
+    ) -> (Metadata, MasterEditionV2) {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(context, max_supply)
+            .await
+            .unwrap();
+
+        (test_metadata, test_master_edition)
+    }
+
+    async fn update_supply(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        test_master_edition: &MasterEditionV2,
+        update_authority: &Keypair,
+        max_supply: Option<u64>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::update_master_edition_supply(
+                id(),
+                test_master_edition.pubkey,
+                test_metadata.pubkey,
+                update_authority.pubkey(),
+                max_supply,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, update_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success_lower_and_cap() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master_edition(&mut context, None).await;
+        let update_authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+        update_supply(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            &update_authority,
+            Some(10),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            test_master_edition.get_data(&mut context).await.max_supply,
+            Some(10)
+        );
+
+        context.warp_to_slot(100).unwrap();
+        update_supply(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            &update_authority,
+            Some(5),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            test_master_edition.get_data(&mut context).await.max_supply,
+            Some(5)
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_below_supply() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) =
+            create_master_edition(&mut context, Some(10)).await;
+        let update_authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        for edition in 1..=2 {
+            EditionMarker::new(&test_metadata, &test_master_edition, edition)
+                .create(&mut context)
+                .await
+                .unwrap();
+        }
+
+        let err = update_supply(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            &update_authority,
+            Some(1),
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MaxSupplyBelowSupply);
+    }
+
+    #[tokio::test]
+    async fn fail_raise_after_primary_sale() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) =
+            create_master_edition(&mut context, Some(10)).await;
+        let update_authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::update_primary_sale_happened_via_token(
+                id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                test_metadata.token.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let err = update_supply(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            &update_authority,
+            None,
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::CannotRaiseMaxSupplyAfterPrimarySale);
+    }
+
+    #[tokio::test]
+    async fn fail_wrong_update_authority() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) =
+            create_master_edition(&mut context, Some(10)).await;
+        let fake_authority = Keypair::new();
+
+        let err = update_supply(
+            &mut context,
+            &test_metadata,
+            &test_master_edition,
+            &fake_authority,
+            Some(5),
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UpdateAuthorityIncorrect);
+    }
+}
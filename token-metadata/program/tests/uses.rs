@@ -501,3 +501,218 @@ mod uses {
         assert_eq!(token_account_after_burn_data.amount, 0);
     }
 }
+
+mod use_restrictions {
+    use mpl_token_metadata::{
+        error::MetadataError,
+        id, instruction,
+        pda::{find_program_as_burner_account, find_use_authority_account},
+    };
+    use solana_sdk::{signature::Keypair, transport};
+
+    use super::*;
+
+    async fn create_usable(context: &mut ProgramTestContext, migrate: bool) -> Metadata {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+                Some(Uses {
+                    use_method: UseMethod::Multiple,
+                    total: 5,
+                    remaining: 5,
+                }),
+            )
+            .await
+            .unwrap();
+
+        if migrate {
+            let tx = Transaction::new_signed_with_payer(
+                &[instruction::migrate_metadata(
+                    id(),
+                    test_metadata.pubkey,
+                    context.payer.pubkey(),
+                    context.payer.pubkey(),
+                )],
+                Some(&context.payer.pubkey()),
+                &[&context.payer],
+                context.last_blockhash,
+            );
+            context.banks_client.process_transaction(tx).await.unwrap();
+        }
+
+        test_metadata
+    }
+
+    async fn set_use_restrictions(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        valid_from: Option<i64>,
+        valid_until: Option<i64>,
+        cooldown: Option<i64>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_use_restrictions(
+                id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                valid_from,
+                valid_until,
+                cooldown,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn utilize(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::utilize(
+                id(),
+                test_metadata.pubkey,
+                test_metadata.token.pubkey(),
+                test_metadata.mint.pubkey(),
+                None,
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                None,
+                1,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success_use_records_last_used_at() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_usable(&mut context, true).await;
+        set_use_restrictions(&mut context, &test_metadata, Some(1), None, None)
+            .await
+            .unwrap();
+
+        utilize(&mut context, &test_metadata).await.unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(metadata.uses.unwrap().remaining, 4);
+        let restrictions = metadata.use_restrictions.unwrap();
+        assert_eq!(restrictions.valid_from, Some(1));
+        assert!(restrictions.last_used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn fail_use_expired() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_usable(&mut context, true).await;
+        set_use_restrictions(&mut context, &test_metadata, None, Some(1), None)
+            .await
+            .unwrap();
+
+        let err = utilize(&mut context, &test_metadata).await.unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UseExpired);
+    }
+
+    #[tokio::test]
+    async fn fail_use_during_cooldown() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_usable(&mut context, true).await;
+        set_use_restrictions(&mut context, &test_metadata, None, None, Some(86_400))
+            .await
+            .unwrap();
+
+        utilize(&mut context, &test_metadata).await.unwrap();
+        context.warp_to_slot(100).unwrap();
+        let err = utilize(&mut context, &test_metadata).await.unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UseCooldownActive);
+    }
+
+    #[tokio::test]
+    async fn fail_delegated_use_not_yet_valid() {
+        let mut context = program_test().start_with_context().await;
+        let use_authority = Keypair::new();
+        airdrop(&mut context, &use_authority.pubkey(), 10000000)
+            .await
+            .unwrap();
+        let test_metadata = create_usable(&mut context, true).await;
+        set_use_restrictions(&mut context, &test_metadata, Some(i64::MAX - 1), None, None)
+            .await
+            .unwrap();
+
+        let (record, _) =
+            find_use_authority_account(&test_metadata.mint.pubkey(), &use_authority.pubkey());
+        let (burner, _) = find_program_as_burner_account();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::approve_use_authority(
+                id(),
+                record,
+                use_authority.pubkey(),
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                test_metadata.token.pubkey(),
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                burner,
+                1,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::utilize(
+                id(),
+                test_metadata.pubkey,
+                test_metadata.token.pubkey(),
+                test_metadata.mint.pubkey(),
+                Some(record),
+                use_authority.pubkey(),
+                context.payer.pubkey(),
+                Some(burner),
+                1,
+            )],
+            Some(&use_authority.pubkey()),
+            &[&use_authority],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UseNotYetValid);
+    }
+
+    #[tokio::test]
+    async fn fail_set_on_legacy_metadata() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_usable(&mut context, false).await;
+
+        let err = set_use_restrictions(&mut context, &test_metadata, None, Some(1), None)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MetadataNotMigrated);
+    }
+}